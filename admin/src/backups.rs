@@ -5,14 +5,18 @@ use common::api::models::journalist_id::JournalistIdentity;
 use common::api::models::untrusted_keys_and_journalist_profiles::UntrustedKeysAndJournalistProfiles;
 use common::aws::s3::client::S3Client;
 use common::backup::get_backup_data_s3::get_latest_journalist_backup_from_s3;
+use common::backup::secret_sharing_settings::{
+    SecretSharingSettings, SignedSecretSharingSettings, SECRET_SHARING_SETTINGS_FILENAME,
+};
 use common::clap::Stage;
+use common::crypto::ShareCount;
 use common::protocol::backup::{
     coverup_finish_restore_step, coverup_initiate_restore_step, BackupRestorationInProgress,
     WrappedSecretShare,
 };
 use common::protocol::backup_data::BackupDataWithSignature;
 use common::protocol::keys::{
-    load_anchor_org_pks, load_backup_id_key_pairs, load_backup_msg_key_pairs,
+    load_anchor_org_pks, load_backup_id_key_pairs, load_backup_msg_key_pairs, LatestKey as _,
 };
 use common::time;
 use common::time::now;
@@ -23,6 +27,57 @@ use std::path::{Path, PathBuf};
 use time::format_timestamp_for_filename;
 use tokio::fs;
 
+/// Signs the k-of-n secret sharing settings used by Sentinel's automated backups with the latest
+/// backup identity key pair (offline). Returns the path to the signed settings which should be
+/// posted to the API using `post_backup_secret_sharing_settings`.
+pub fn sign_backup_secret_sharing_settings(
+    keys_path: &Path,
+    k: ShareCount,
+    n: ShareCount,
+    now: DateTime<Utc>,
+) -> anyhow::Result<PathBuf> {
+    let settings = SecretSharingSettings::new(k, n)?;
+
+    let org_pks = load_anchor_org_pks(keys_path, now)?;
+    if org_pks.is_empty() {
+        anyhow::bail!(
+            "No organization public keys found in '{}'. Cannot sign secret sharing settings.",
+            keys_path.display()
+        );
+    }
+
+    let backup_id_key_pair =
+        load_backup_id_key_pairs(keys_path, &org_pks, now)?.into_latest_key_required()?;
+
+    let signed_settings = SignedSecretSharingSettings::new(settings, &backup_id_key_pair, now);
+
+    let output_file = keys_path.join(SECRET_SHARING_SETTINGS_FILENAME);
+    std::fs::write(&output_file, serde_json::to_string(&signed_settings)?)?;
+    info!(
+        "Wrote signed secret sharing settings to disk: {:?}",
+        output_file
+    );
+
+    Ok(output_file)
+}
+
+/// Posts signed secret sharing settings created by `sign_backup_secret_sharing_settings` to the
+/// API (online).
+pub async fn post_backup_secret_sharing_settings(
+    api_url: Url,
+    settings_path: &Path,
+) -> anyhow::Result<SecretSharingSettings> {
+    let api_client = ApiClient::new(api_url);
+
+    let signed_settings = load_bundle::<SignedSecretSharingSettings>(settings_path).await?;
+
+    api_client
+        .post_backup_secret_sharing_settings(&signed_settings)
+        .await?;
+
+    Ok(signed_settings.settings)
+}
+
 /// Bundle structure for the response step of backup restoration.
 /// See `backup_initiate_restore_submit` function.
 #[derive(Serialize, Deserialize)]
@@ -124,7 +179,7 @@ pub async fn backup_initiate_restore_finalize(
         backup_id_key_pairs.len()
     );
 
-    let hierarchy = response_bundle.hierarchy.into_trusted(&org_pks, now);
+    let hierarchy = response_bundle.hierarchy.into_trusted(&org_pks, now)?;
     let journalist_id_key = hierarchy
         .keys
         .verify_journalist_id_key(
@@ -260,11 +315,22 @@ pub async fn backup_complete_restore(
         backup_msg_key_pairs.len()
     );
 
-    debug!("Found {} recovery share(s)", shares.len());
+    debug!(
+        "Found {} recovery share(s), {} needed to restore",
+        shares.len(),
+        in_progress_bundle.k
+    );
+
+    if shares.len() < in_progress_bundle.k as usize {
+        anyhow::bail!(
+            "The backup needs {} recovery shares to restore but only {} were provided",
+            in_progress_bundle.k,
+            shares.len()
+        );
+    }
 
-    let k = 1;
     let restored_encrypted_vault =
-        coverup_finish_restore_step(in_progress_bundle.clone(), shares, &backup_msg_key_pairs, k)
+        coverup_finish_restore_step(in_progress_bundle.clone(), shares, &backup_msg_key_pairs)
             .with_context(|| "Failed to complete restore step")?;
 
     // Save the restored encrypted vault to disk
@@ -8,6 +8,7 @@ use common::aws::ssm::prefix::ParameterPrefix;
 use common::clap::AwsConfig;
 use common::clap::Stage;
 use common::client::JournalistStatus;
+use common::crypto::ShareCount;
 use reqwest::Url;
 
 #[derive(Parser)]
@@ -356,6 +357,16 @@ pub enum Commands {
         #[clap(long)]
         keys_path: PathBuf,
     },
+    /// Must be run offline.
+    /// Create a new key pair for signing the tree heads of the API's key transparency log.
+    #[clap(name = "generate-key-transparency-log-key-pair")]
+    GenerateKeyTransparencyLogKeyPair {
+        /// The directory you wish to create the key files in
+        /// and the path to the directory containing the organization's
+        /// public and secret keys
+        #[clap(long)]
+        keys_path: PathBuf,
+    },
     /// Run online.
     /// Post the backup identity key form to the API.
     #[clap(name = "post-backup-identity-key-form")]
@@ -386,6 +397,33 @@ pub enum Commands {
         #[clap(long)]
         form_path: PathBuf,
     },
+    /// Must be run offline.
+    /// Sign the k-of-n secret sharing settings used by Sentinel's automated backups
+    /// with the latest backup identity key pair.
+    #[clap(name = "sign-backup-secret-sharing-settings")]
+    SignBackupSecretSharingSettings {
+        /// The path to the directory containing the backup identity key pair and the
+        /// public org keys. The signed settings are written to this directory.
+        #[clap(long)]
+        keys_path: PathBuf,
+        /// The number of recovery contacts needed to restore a backup
+        #[clap(long)]
+        k: ShareCount,
+        /// The number of recovery contacts each backup is shared between
+        #[clap(long)]
+        n: ShareCount,
+    },
+    /// Run online.
+    /// Post the signed backup secret sharing settings to the API.
+    #[clap(name = "post-backup-secret-sharing-settings")]
+    PostBackupSecretSharingSettings {
+        /// The address of the CoverDrop API server
+        #[clap(long)]
+        api_url: Url,
+        /// The path to the signed secret sharing settings
+        #[clap(long)]
+        settings_path: PathBuf,
+    },
     /// Update system status. API consumers will be able to get the system
     /// status information using the GET endpoint /v1/status
     UpdateSystemStatus {
@@ -401,6 +439,20 @@ pub enum Commands {
         #[clap(long)]
         description: String,
     },
+    /// Run online.
+    /// Update the longest journalists may keep messages in their vaults, regardless of the
+    /// retention policy they have chosen for a conversation.
+    UpdateMessageRetentionLimit {
+        /// The address of the CoverDrop API server
+        #[clap(long)]
+        api_url: Url,
+        /// The path to the directory containing the admin key pair and the public org keys
+        #[clap(long)]
+        keys_path: PathBuf,
+        /// The maximum number of days a message can be kept for
+        #[clap(long)]
+        max_retention_days: u16,
+    },
     PostReloadLoggingForm {
         /// URL of the service
         #[clap(long)]
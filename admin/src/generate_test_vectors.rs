@@ -6,14 +6,40 @@ use common::crypto::keys::signing::UnsignedSigningKeyPair;
 use common::crypto::{AnonymousBox, MultiAnonymousBox, Signable, TwoPartyBox};
 use common::FixedSizeMessageText;
 
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use common::api::models::dead_drops::DeadDropId;
 use common::api::models::dead_drops::UserToJournalistDeadDropMessages;
+use common::api::models::journalist_id::JournalistIdentity;
 use common::api::models::messages::covernode_to_journalist_message::{
     CoverNodeToJournalistMessage, EncryptedCoverNodeToJournalistMessage,
 };
+use common::api::models::messages::journalist_to_covernode_message::{
+    EncryptedJournalistToCoverNodeMessage, JournalistToCoverNodeMessage,
+};
+use common::api::models::messages::journalist_to_user_message::{
+    EncryptedJournalistToUserMessage, JournalistToUserMessage,
+};
+use common::api::models::messages::user_to_covernode_message::{
+    EncryptedUserToCoverNodeMessage, UserToCoverNodeMessage,
+};
+use common::backup::roles::{BackupId, BackupMsg};
+use common::client::mailbox::user_mailbox::UserMailbox;
+use common::client::private_sending_queue::{
+    PrivateSendingQueue, PrivateSendingQueueSecret, PRIVATE_SENDING_QUEUE_ITEM_LEN,
+};
 use common::crypto::keys::key_certificate_data::KeyCertificateData;
+use common::crypto::keys::role::Role;
+use common::crypto::keys::{Ed25519SigningKeyPair, X25519PublicKey, X25519SecretKey};
+use common::crypto::ShareCount;
+use common::protocol::backup::{sentinel_create_backup, RecoveryContact};
+use common::protocol::constants::X25519_SECRET_KEY_LEN;
+use common::protocol::recipient_tag::RecipientTag;
+use common::protocol::roles::{AnchorOrganization, JournalistId, JournalistProvisioning};
 use common::protocol::roles::{CoverNodeMessaging, JournalistMessaging, User};
-use rand::RngCore;
+use rand::distributions::Alphanumeric;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::Serialize;
 use std::path::Path;
 use std::{fs, io};
 
@@ -24,6 +50,14 @@ pub fn generate_test_vectors(path: &Path) -> anyhow::Result<()> {
     generate_test_vectors_journalist_dead_drop(path.join("journalist_dead_drop").as_path())?;
     generate_test_vectors_signature(path.join("signature").as_path())?;
     generate_test_vectors_certificate_data(path.join("certificate_data").as_path())?;
+    generate_test_vectors_private_sending_queue(path.join("private_sending_queue").as_path())?;
+    generate_test_vectors_padded_compressed_string(
+        path.join("padded_compressed_string").as_path(),
+    )?;
+    generate_test_vectors_user_to_journalist_flow(path.join("user_to_journalist_flow").as_path())?;
+    generate_test_vectors_journalist_to_user_flow(path.join("journalist_to_user_flow").as_path())?;
+    generate_test_vectors_user_mailbox(path.join("user_mailbox").as_path())?;
+    generate_test_vectors_backup(path.join("backup").as_path())?;
     Ok(())
 }
 
@@ -260,8 +294,426 @@ fn generate_test_vectors_certificate_data(path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn generate_test_vectors_private_sending_queue(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!("Creating private sending queue test vectors in {:?}", &path);
+
+    let secret = PrivateSendingQueueSecret::generate();
+    write_bytes(path, "01_secret", secret.as_bytes())?;
+
+    let mut queue = PrivateSendingQueue::new_coverdrop(|| Ok(generate_random_queue_item()))?;
+    write_bytes(path, "02_initial_queue", &queue.to_bytes())?;
+
+    let item_1 = generate_random_queue_item();
+    write_bytes(path, "03_item_1", &item_1)?;
+
+    let hint_1 = queue.enqueue(&secret, item_1)?;
+    write_bytes(path, "04_hint_1", hint_1.as_bytes())?;
+    write_bytes(path, "05_queue_after_enqueue_1", &queue.to_bytes())?;
+
+    let item_2 = generate_random_queue_item();
+    write_bytes(path, "06_item_2", &item_2)?;
+
+    let hint_2 = queue.enqueue(&secret, item_2)?;
+    write_bytes(path, "07_hint_2", hint_2.as_bytes())?;
+    write_bytes(path, "08_queue_after_enqueue_2", &queue.to_bytes())?;
+
+    Ok(())
+}
+
+fn generate_test_vectors_padded_compressed_string(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!(
+        "Creating padded compressed string test vectors in {:?}",
+        &path
+    );
+
+    let mut rng = seeded_rng(path, SEED_PADDED_COMPRESSED_STRING)?;
+
+    let cases = [
+        ("01_empty", String::new()),
+        ("02_ascii", "Hello, world!".to_owned()),
+        (
+            "03_unicode",
+            "こんにちは, Здравствуйте, مرحبا 👋".to_owned(),
+        ),
+        ("04_compressible", "a".repeat(1_000)),
+    ];
+
+    for (name, text) in cases {
+        let padded = FixedSizeMessageText::new(&text)?;
+        write_bytes(path, &format!("{name}_text"), text.as_bytes())?;
+        write_bytes(path, &format!("{name}_padded"), padded.as_bytes())?;
+    }
+
+    // Random alphanumeric text barely compresses so we can search for the longest text
+    // which still fits and the shortest text which no longer does
+    let text = seeded_text(&mut rng, 2 * FixedSizeMessageText::TOTAL_LEN);
+    let longest_len = (0..=text.len())
+        .rev()
+        .find(|len| FixedSizeMessageText::new(&text[..*len]).is_ok())
+        .ok_or_else(|| anyhow::anyhow!("No prefix of the text fits in a padded string"))?;
+    anyhow::ensure!(
+        longest_len < text.len(),
+        "Text is too short to exceed the padded string length"
+    );
+
+    let longest = FixedSizeMessageText::new(&text[..longest_len])?;
+    write_bytes(path, "05_longest_text", &text.as_bytes()[..longest_len])?;
+    write_bytes(path, "05_longest_padded", longest.as_bytes())?;
+
+    write_bytes(
+        path,
+        "06_too_long_text",
+        &text.as_bytes()[..longest_len + 1],
+    )?;
+
+    // Text which compresses too well can be encoded but is rejected when decoding
+    let too_compressible = FixedSizeMessageText::new(&"a".repeat(10_000))?;
+    write_bytes(
+        path,
+        "07_too_compressible_padded",
+        too_compressible.as_bytes(),
+    )?;
+
+    Ok(())
+}
+
+fn generate_test_vectors_user_to_journalist_flow(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!(
+        "Creating user to journalist flow test vectors in {:?}",
+        &path
+    );
+
+    let mut rng = seeded_rng(path, SEED_USER_TO_JOURNALIST_FLOW)?;
+
+    let user_key_pair = seeded_encryption_key_pair::<User>(&mut rng);
+    let journalist_key_pair = seeded_encryption_key_pair::<JournalistMessaging>(&mut rng);
+    let covernode_1_key_pair = seeded_encryption_key_pair::<CoverNodeMessaging>(&mut rng);
+    let covernode_2_key_pair = seeded_encryption_key_pair::<CoverNodeMessaging>(&mut rng);
+
+    write_encryption_key_pair(path, "01_user", &user_key_pair)?;
+    write_encryption_key_pair(path, "02_journalist", &journalist_key_pair)?;
+    write_encryption_key_pair(path, "03_covernode_1", &covernode_1_key_pair)?;
+    write_encryption_key_pair(path, "04_covernode_2", &covernode_2_key_pair)?;
+
+    let journalist_id = JournalistIdentity::new("journalist_0")?;
+    write_bytes(path, "05_journalist_id", journalist_id.as_bytes())?;
+
+    let text = seeded_text(&mut rng, 64);
+    write_bytes(path, "06_message_text", text.as_bytes())?;
+
+    // The user encrypts the message for the journalist...
+    let message = UserToJournalistMessage::new(
+        FixedSizeMessageText::new(&text)?,
+        user_key_pair.public_key(),
+    );
+    let message = message.serialize();
+    write_bytes(path, "07_user_to_journalist_message", &message.bytes)?;
+
+    let message =
+        EncryptedUserToJournalistMessage::encrypt(journalist_key_pair.public_key(), message)?;
+    write_bytes(
+        path,
+        "08_encrypted_user_to_journalist_message",
+        message.as_bytes(),
+    )?;
+
+    // ...then tags it with the journalist's recipient tag and wraps it for the CoverNodes
+    let recipient_tag = RecipientTag::from_journalist_id(&journalist_id);
+    write_bytes(path, "09_recipient_tag", recipient_tag.as_ref())?;
+
+    let message = UserToCoverNodeMessage::new_real_message(recipient_tag, message);
+    let message = message.serialize();
+    write_bytes(path, "10_user_to_covernode_message", &message.bytes)?;
+
+    let message = EncryptedUserToCoverNodeMessage::encrypt(
+        [
+            covernode_1_key_pair.public_key(),
+            covernode_2_key_pair.public_key(),
+        ],
+        message,
+    )?;
+    write_bytes(
+        path,
+        "11_encrypted_user_to_covernode_message",
+        message.as_bytes(),
+    )?;
+
+    Ok(())
+}
+
+fn generate_test_vectors_journalist_to_user_flow(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!(
+        "Creating journalist to user flow test vectors in {:?}",
+        &path
+    );
+
+    let mut rng = seeded_rng(path, SEED_JOURNALIST_TO_USER_FLOW)?;
+
+    let user_key_pair = seeded_encryption_key_pair::<User>(&mut rng);
+    let journalist_key_pair = seeded_encryption_key_pair::<JournalistMessaging>(&mut rng);
+    let covernode_1_key_pair = seeded_encryption_key_pair::<CoverNodeMessaging>(&mut rng);
+    let covernode_2_key_pair = seeded_encryption_key_pair::<CoverNodeMessaging>(&mut rng);
+
+    write_encryption_key_pair(path, "01_user", &user_key_pair)?;
+    write_encryption_key_pair(path, "02_journalist", &journalist_key_pair)?;
+    write_encryption_key_pair(path, "03_covernode_1", &covernode_1_key_pair)?;
+    write_encryption_key_pair(path, "04_covernode_2", &covernode_2_key_pair)?;
+
+    let text = seeded_text(&mut rng, 64);
+    write_bytes(path, "05_message_text", text.as_bytes())?;
+
+    let hand_over_journalist_id = JournalistIdentity::new("journalist_1")?;
+    write_bytes(
+        path,
+        "06_hand_over_journalist_id",
+        hand_over_journalist_id.as_bytes(),
+    )?;
+
+    let messages = [
+        (
+            "07_message",
+            JournalistToUserMessage::new_with_message(FixedSizeMessageText::new(&text)?),
+        ),
+        (
+            "08_hand_over",
+            JournalistToUserMessage::new_with_hand_over(hand_over_journalist_id),
+        ),
+    ];
+
+    for (name, message) in messages {
+        let message = message.serialize();
+        write_bytes(
+            path,
+            &format!("{name}_journalist_to_user_message"),
+            &message.bytes,
+        )?;
+
+        // The journalist encrypts the message for the user...
+        let message = EncryptedJournalistToUserMessage::encrypt(
+            user_key_pair.public_key(),
+            journalist_key_pair.secret_key(),
+            message,
+        )?;
+        write_bytes(
+            path,
+            &format!("{name}_encrypted_journalist_to_user_message"),
+            message.as_bytes(),
+        )?;
+
+        // ...and wraps it for the CoverNodes
+        let message = JournalistToCoverNodeMessage::new_real_message(message);
+        let message = message.serialize();
+        write_bytes(
+            path,
+            &format!("{name}_journalist_to_covernode_message"),
+            &message.bytes,
+        )?;
+
+        let message = EncryptedJournalistToCoverNodeMessage::encrypt(
+            [
+                covernode_1_key_pair.public_key(),
+                covernode_2_key_pair.public_key(),
+            ],
+            message,
+        )?;
+        write_bytes(
+            path,
+            &format!("{name}_encrypted_journalist_to_covernode_message"),
+            message.as_bytes(),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn generate_test_vectors_user_mailbox(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!("Creating user mailbox test vectors in {:?}", &path);
+
+    let mut rng = seeded_rng(path, SEED_USER_MAILBOX)?;
+
+    let password = seeded_text(&mut rng, 32);
+    write_bytes(path, "01_password", password.as_bytes())?;
+
+    let user_key_pair = seeded_encryption_key_pair::<User>(&mut rng);
+    write_encryption_key_pair(path, "02_user", &user_key_pair)?;
+
+    let org_key_pair = seeded_signing_key_pair::<AnchorOrganization>(&mut rng)
+        .to_self_signed_key_pair(test_vectors_not_valid_after());
+    write_bytes(path, "03_org_pk", org_key_pair.public_key().as_bytes())?;
+
+    let journalist_id = JournalistIdentity::new("journalist_0")?;
+    write_bytes(path, "04_journalist_id", journalist_id.as_bytes())?;
+
+    let message_to_journalist = seeded_text(&mut rng, 64);
+    write_bytes(
+        path,
+        "05_message_to_journalist_text",
+        message_to_journalist.as_bytes(),
+    )?;
+
+    let message_from_journalist = seeded_text(&mut rng, 64);
+    write_bytes(
+        path,
+        "06_message_from_journalist_text",
+        message_from_journalist.as_bytes(),
+    )?;
+
+    let max_dead_drop_id: DeadDropId = rng.gen_range(1..1000);
+    write_bytes(path, "07_max_dead_drop_id", &max_dead_drop_id.to_be_bytes())?;
+
+    let mut mailbox = UserMailbox::new_with_keys(
+        &password,
+        user_key_pair,
+        vec![org_key_pair.public_key().clone()],
+        path.join("08_mailbox"),
+    )?;
+
+    mailbox.add_message_to_journalist_from_user(
+        &journalist_id,
+        &FixedSizeMessageText::new(&message_to_journalist)?,
+    );
+    mailbox.add_message_to_user_from_journalist(
+        &journalist_id,
+        &FixedSizeMessageText::new(&message_from_journalist)?,
+    );
+    mailbox.set_max_dead_drop_id(max_dead_drop_id);
+
+    mailbox.save()?;
+
+    // The dead drop cache is disposable and isn't part of the test vectors. The mailbox saves
+    // itself when dropped so drop it before removing the cache.
+    let dead_drop_cache_path = mailbox.dead_drop_cache_path();
+    drop(mailbox);
+    fs::remove_file(dead_drop_cache_path)?;
+
+    Ok(())
+}
+
+fn generate_test_vectors_backup(path: &Path) -> anyhow::Result<()> {
+    ensure_dir(path);
+    println!("Creating backup test vectors in {:?}", &path);
+
+    let mut rng = seeded_rng(path, SEED_BACKUP)?;
+    let not_valid_after = test_vectors_not_valid_after();
+
+    let provisioning_key_pair = seeded_signing_key_pair::<JournalistProvisioning>(&mut rng);
+    write_bytes(
+        path,
+        "01_journalist_provisioning_pk",
+        provisioning_key_pair.public_key().as_bytes(),
+    )?;
+
+    let journalist_id = JournalistIdentity::new("journalist_0")?;
+    write_bytes(path, "02_journalist_id", journalist_id.as_bytes())?;
+
+    let id_key_pair = seeded_signing_key_pair::<JournalistId>(&mut rng)
+        .to_signed_key_pair(&provisioning_key_pair, not_valid_after);
+    write_json(
+        path,
+        "03_journalist_id_key_pair.json",
+        &id_key_pair.to_untrusted(),
+    )?;
+
+    let backup_id_key_pair = seeded_signing_key_pair::<BackupId>(&mut rng);
+    write_bytes(
+        path,
+        "04_backup_id_pk",
+        backup_id_key_pair.public_key().as_bytes(),
+    )?;
+
+    let backup_msg_key_pair = seeded_encryption_key_pair::<BackupMsg>(&mut rng)
+        .to_signed_key_pair(&backup_id_key_pair, not_valid_after);
+    write_json(
+        path,
+        "05_backup_msg_key_pair.json",
+        &backup_msg_key_pair.to_untrusted(),
+    )?;
+
+    // For simplicity the recovery contacts' messaging keys are signed directly by the
+    // provisioning key rather than by their own identity keys
+    let mut recovery_contacts = vec![];
+    for i in 1..=BACKUP_RECOVERY_CONTACT_COUNT {
+        let identity = JournalistIdentity::new(&format!("journalist_{i}"))?;
+        let msg_key_pair = seeded_encryption_key_pair::<JournalistMessaging>(&mut rng)
+            .to_signed_key_pair(&provisioning_key_pair, not_valid_after);
+
+        write_bytes(
+            path,
+            &format!("06_recovery_contact_{i}_id"),
+            identity.as_bytes(),
+        )?;
+        write_json(
+            path,
+            &format!("06_recovery_contact_{i}_msg_key_pair.json"),
+            &msg_key_pair.to_untrusted(),
+        )?;
+
+        recovery_contacts.push(RecoveryContact {
+            identity,
+            latest_messaging_key: msg_key_pair.public_key().clone(),
+        });
+    }
+
+    write_bytes(path, "07_k", &[BACKUP_K])?;
+
+    let mut encrypted_vault = vec![0; LEN_MESSAGE];
+    rng.fill_bytes(&mut encrypted_vault);
+    write_bytes(path, "08_encrypted_vault", &encrypted_vault)?;
+
+    let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let backup = sentinel_create_backup(
+        encrypted_vault,
+        journalist_id,
+        id_key_pair,
+        backup_msg_key_pair.public_key().clone(),
+        recovery_contacts,
+        BACKUP_K,
+        created_at,
+    )?;
+
+    // Stored in the same layout as the S3 object, i.e. the raw CBOR body with the signature
+    // and signing key as JSON metadata, rather than as one very large JSON document
+    write_bytes(
+        path,
+        "09_backup_data_bytes",
+        backup.backup_data_bytes.as_bytes(),
+    )?;
+    write_json(
+        path,
+        "10_backup_data_signature.json",
+        &backup.backup_data_signature,
+    )?;
+    write_json(
+        path,
+        "11_signed_with.json",
+        &backup.signed_with.to_untrusted(),
+    )?;
+
+    Ok(())
+}
+
+fn generate_random_queue_item() -> Vec<u8> {
+    let mut item = vec![0; PRIVATE_SENDING_QUEUE_ITEM_LEN];
+    rand::thread_rng().fill_bytes(&mut item);
+    item
+}
+
 const LEN_MESSAGE: usize = 997;
 
+const SEED_PADDED_COMPRESSED_STRING: u64 = 1;
+const SEED_USER_TO_JOURNALIST_FLOW: u64 = 2;
+const SEED_JOURNALIST_TO_USER_FLOW: u64 = 3;
+const SEED_USER_MAILBOX: u64 = 4;
+const SEED_BACKUP: u64 = 5;
+
+const BACKUP_RECOVERY_CONTACT_COUNT: usize = 3;
+const BACKUP_K: ShareCount = 2;
+
 fn generate_random_message() -> Vec<u8> {
     let mut message: [u8; LEN_MESSAGE] = [0; LEN_MESSAGE];
     let mut rng = rand::thread_rng();
@@ -269,6 +721,62 @@ fn generate_random_message() -> Vec<u8> {
     message.to_vec()
 }
 
+/// The protocol flow vectors derive their keys and plaintexts from a fixed seed, which is stored
+/// next to them, so that regenerating them only changes the output of the randomised encryption.
+fn seeded_rng(path: &Path, seed: u64) -> io::Result<StdRng> {
+    write_bytes(path, "00_seed", &seed.to_be_bytes())?;
+    Ok(StdRng::seed_from_u64(seed))
+}
+
+fn seeded_text(rng: &mut StdRng, len: usize) -> String {
+    rng.sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn seeded_encryption_key_pair<R: Role>(rng: &mut StdRng) -> UnsignedEncryptionKeyPair<R> {
+    let sk = X25519SecretKey::from(rng.gen::<[u8; X25519_SECRET_KEY_LEN]>());
+    let pk = X25519PublicKey::from(&sk);
+
+    UnsignedEncryptionKeyPair::from_raw_keys(pk, sk)
+}
+
+fn seeded_signing_key_pair<R: Role>(rng: &mut StdRng) -> UnsignedSigningKeyPair<R> {
+    let sk = Ed25519SigningKeyPair::from_bytes(&rng.gen());
+
+    let pk = common::crypto::keys::signing::PublicSigningKey::new(sk.verifying_key());
+
+    UnsignedSigningKeyPair::new(pk, sk)
+}
+
+/// Keys in the test vectors must remain valid for as long as the vectors are checked in
+fn test_vectors_not_valid_after() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap()
+}
+
+fn write_encryption_key_pair<R: Role>(
+    base_path: &Path,
+    prefix: &str,
+    key_pair: &UnsignedEncryptionKeyPair<R>,
+) -> io::Result<()> {
+    write_bytes(
+        base_path,
+        &format!("{prefix}_pk"),
+        &key_pair.raw_public_key().to_bytes(),
+    )?;
+    write_bytes(
+        base_path,
+        &format!("{prefix}_sk"),
+        &key_pair.secret_key().to_bytes(),
+    )
+}
+
+fn write_json(base_path: &Path, filename: &str, value: &impl Serialize) -> anyhow::Result<()> {
+    write_bytes(base_path, filename, &serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
 fn write_bytes(base_path: &Path, filename: &str, bytes: &[u8]) -> io::Result<()> {
     let path = base_path.join(filename);
     fs::write(path.as_path(), bytes)
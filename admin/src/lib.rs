@@ -10,10 +10,12 @@ mod post_keys;
 mod post_log_config_form;
 mod reseed_journalist_vault_id_key_pair;
 mod update_journalist;
+mod update_message_retention_limit;
 mod update_system_status;
 
 pub use backups::{
     backup_complete_restore, backup_initiate_restore, backup_initiate_restore_finalize,
+    post_backup_secret_sharing_settings, sign_backup_secret_sharing_settings,
 };
 pub use ceremony::{
     api_has_anchor_org_pk, public_key_forms_bundle, read_bundle_from_disk, run_key_ceremony,
@@ -34,4 +36,5 @@ pub use post_keys::{post_covernode_provisioning_key_pair, post_journalist_provis
 pub use post_log_config_form::post_log_config_form;
 pub use reseed_journalist_vault_id_key_pair::reseed_journalist_vault_id_key_pair;
 pub use update_journalist::update_journalist;
+pub use update_message_retention_limit::update_message_retention_limit;
 pub use update_system_status::update_system_status;
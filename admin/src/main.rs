@@ -12,13 +12,15 @@ use admin::reseed_journalist_vault_id_key_pair;
 use admin::run_key_ceremony;
 use admin::submit_delete_journalist_form;
 use admin::update_journalist;
+use admin::update_message_retention_limit;
 use admin::update_system_status;
 use admin::upload_keys_to_api;
 use admin::AssumeYes;
 use admin::CeremonyType;
 use admin::{
     backup_complete_restore, backup_initiate_restore, backup_initiate_restore_finalize,
-    delete_journalist_form,
+    delete_journalist_form, post_backup_secret_sharing_settings,
+    sign_backup_secret_sharing_settings,
 };
 use admin::{
     generate_covernode_identity_key_pair, generate_covernode_messaging_key_pair,
@@ -40,6 +42,7 @@ use common::crypto::keys::public_key::PublicKey;
 use common::crypto::keys::serde::StorableKeyMaterial;
 use common::crypto::pbkdf::DEFAULT_PASSPHRASE_WORDS;
 use common::generators::PasswordGenerator;
+use common::key_transparency::keys::generate_key_transparency_log_key_pair;
 use common::protocol::backup::WrappedSecretShare;
 use common::protocol::keys::load_anchor_org_pks;
 use common::protocol::keys::load_backup_id_key_pairs;
@@ -348,6 +351,18 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Commands::GenerateKeyTransparencyLogKeyPair { keys_path } => {
+            let org_key_pair = load_latest_org_key_pair(&keys_path, time::now())?;
+            let log_key_pair = generate_key_transparency_log_key_pair(&org_key_pair, time::now());
+            let log_key_pair_path = log_key_pair.to_untrusted().save_to_disk(&keys_path)?;
+
+            println!(
+                "Key transparency log key pair saved to {:?}. Copy it into the API's keys directory.",
+                log_key_pair_path
+            );
+
+            Ok(())
+        }
         Commands::PostBackupIdentityKeyForm { api_url, form_path } => {
             let api_client = ApiClient::new(api_url);
 
@@ -410,6 +425,29 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Commands::SignBackupSecretSharingSettings { keys_path, k, n } => {
+            let settings_path = sign_backup_secret_sharing_settings(&keys_path, k, n, time::now())?;
+
+            println!("Secret sharing settings saved to {:?}.", settings_path);
+            println!(
+                "Move this to an online machine and post it to the api with post-backup-secret-sharing-settings."
+            );
+
+            Ok(())
+        }
+        Commands::PostBackupSecretSharingSettings {
+            api_url,
+            settings_path,
+        } => {
+            let settings = post_backup_secret_sharing_settings(api_url, &settings_path).await?;
+
+            println!(
+                "Automated backups will now be shared between {} recovery contacts, {} of which are needed to restore.",
+                settings.n, settings.k
+            );
+
+            Ok(())
+        }
         Commands::UpdateSystemStatus {
             keys_path,
             api_url,
@@ -422,6 +460,28 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Commands::UpdateMessageRetentionLimit {
+            api_url,
+            keys_path,
+            max_retention_days,
+        } => {
+            let api_client = ApiClient::new(api_url);
+
+            let limit = update_message_retention_limit(
+                keys_path,
+                &api_client,
+                max_retention_days,
+                time::now(),
+            )
+            .await?;
+
+            println!(
+                "Journalists can now keep messages for up to {} days.",
+                limit.max_retention_days
+            );
+
+            Ok(())
+        }
         Commands::PostReloadLoggingForm {
             service_url,
             keys_path,
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use common::{
+    api::api_client::ApiClient,
+    protocol::keys::{load_anchor_org_pks, LatestKey},
+    system::{
+        keys::load_admin_key_pair,
+        message_retention::{MessageRetentionLimit, SignedMessageRetentionLimit},
+    },
+};
+
+/// Sign the longest journalists may keep messages for with the latest admin key pair and post it
+/// to the API. Sentinel picks this up the next time it refreshes the public keys.
+pub async fn update_message_retention_limit(
+    keys_path: impl AsRef<Path>,
+    api_client: &ApiClient,
+    max_retention_days: u16,
+    now: DateTime<Utc>,
+) -> anyhow::Result<MessageRetentionLimit> {
+    let limit = MessageRetentionLimit::new(max_retention_days)?;
+
+    let org_pks = load_anchor_org_pks(&keys_path, now)?;
+    let admin_key_pair =
+        load_admin_key_pair(&keys_path, &org_pks, now)?.into_latest_key_required()?;
+
+    let signed_limit = SignedMessageRetentionLimit::new(limit, &admin_key_pair, now);
+
+    api_client
+        .post_message_retention_limit(&signed_limit)
+        .await?;
+
+    Ok(limit)
+}
@@ -0,0 +1,19 @@
+-- An append-only log of the leaf hashes of every public key that has been
+-- returned from the public keys endpoint. The position of a leaf in the
+-- Merkle tree is given by the order of the `id` column, so rows must never
+-- be updated or deleted.
+CREATE TABLE key_transparency_log (
+    id BIGSERIAL PRIMARY KEY,
+    leaf_hash BYTEA NOT NULL UNIQUE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW ()
+);
+
+CREATE FUNCTION key_transparency_log_is_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'key_transparency_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER key_transparency_log_append_only
+    BEFORE UPDATE OR DELETE ON key_transparency_log
+    FOR EACH ROW EXECUTE FUNCTION key_transparency_log_is_append_only();
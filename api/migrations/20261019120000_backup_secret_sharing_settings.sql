@@ -0,0 +1,8 @@
+-- Secret sharing settings for automated Sentinel backups, signed by a backup
+-- identity key. Clients verify the signature themselves so the most recent
+-- row is only a suggestion from the API.
+CREATE TABLE backup_secret_sharing_settings (
+    id            INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    created_at    TIMESTAMPTZ NOT NULL,
+    settings_json JSONB NOT NULL
+);
@@ -0,0 +1,8 @@
+-- The longest journalists may keep messages in their vaults, signed by an
+-- admin key. Clients verify the signature themselves so the most recent row
+-- is only a suggestion from the API.
+CREATE TABLE message_retention_limits (
+    id         INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    created_at TIMESTAMPTZ NOT NULL,
+    limit_json JSONB NOT NULL
+);
@@ -0,0 +1,8 @@
+-- The signed tree head and inclusion proofs for the published keys, stored
+-- whenever keys are appended to the key transparency log so that the public
+-- keys endpoint can serve them without rebuilding the tree.
+CREATE TABLE key_transparency_proofs (
+    tree_size  BIGINT PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL,
+    proof_json JSONB NOT NULL
+);
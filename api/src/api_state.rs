@@ -5,6 +5,7 @@ use axum::extract::FromRef;
 use common::api::models::journalist_id::JournalistIdentity;
 use common::aws::kinesis::client::KinesisClient;
 use common::aws::s3::client::S3Client;
+use common::key_transparency::keys::KeyTransparencyLogKeyPair;
 use common::tracing::TracingReloadHandle;
 
 #[derive(Clone, FromRef)]
@@ -16,9 +17,11 @@ pub struct ApiState {
     pub default_journalist_id: Option<JournalistIdentity>,
     pub tracing_reload_handle: TracingReloadHandle,
     pub dead_drop_limits: DeadDropLimits,
+    pub key_transparency_log_key_pair: Option<KeyTransparencyLogKeyPair>,
 }
 
 impl ApiState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         anchor_org_pks: AnchorOrganizationPublicKeyCache,
         db: Database,
@@ -27,6 +30,7 @@ impl ApiState {
         default_journalist_id: Option<JournalistIdentity>,
         tracing_reload_handle: TracingReloadHandle,
         dead_drop_limits: DeadDropLimits,
+        key_transparency_log_key_pair: Option<KeyTransparencyLogKeyPair>,
     ) -> Self {
         ApiState {
             anchor_org_pks,
@@ -36,6 +40,7 @@ impl ApiState {
             default_journalist_id,
             tracing_reload_handle,
             dead_drop_limits,
+            key_transparency_log_key_pair,
         }
     }
 }
@@ -56,6 +56,11 @@ pub struct Cli {
     #[clap(long)]
     pub anchor_organization_public_key_polling_period_seconds: Option<i64>,
 
+    /// The amount of time in seconds to wait between checking that the key transparency log
+    /// covers the published keys. Must be more than 1.
+    #[clap(long)]
+    pub key_transparency_log_polling_period_seconds: Option<i64>,
+
     #[command(flatten)]
     pub key_location: KeyLocation,
 
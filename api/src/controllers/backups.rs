@@ -16,6 +16,7 @@ use common::backup::forms::retrieve_upload_url::{
     RetrieveUploadUrlForm, RetrieveUploadUrlWithMetadataForm,
 };
 use common::backup::keys::{verify_backup_id_pk, verify_backup_msg_pk};
+use common::backup::secret_sharing_settings::SignedSecretSharingSettings;
 use common::clap::Stage;
 use common::protocol::backup::get_backup_bucket_name;
 use common::time;
@@ -189,3 +190,41 @@ pub async fn post_backup_encryption_pk(
 
     Ok(())
 }
+
+pub async fn post_backup_secret_sharing_settings(
+    State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
+    State(db): State<Database>,
+    Json(body): Json<SignedSecretSharingSettings>,
+) -> Result<(), AppError> {
+    let now = time::now();
+    let (keys, _max_epoch) = db
+        .hierarchy_queries
+        .key_hierarchy(&anchor_org_pks.get().await, now)
+        .await?;
+
+    let settings = body.verify(keys.backup_id_pk_iter(), now).map_err(|e| {
+        tracing::error!("Failed to verify secret sharing settings {:?}", e);
+        AppError::SignatureVerificationFailed
+    })?;
+
+    let current = db
+        .backup_settings_queries
+        .latest_secret_sharing_settings()
+        .await?;
+
+    if !body.supersedes(current.as_ref()) {
+        return Err(AppError::SignedSettingsOutdated);
+    }
+
+    db.backup_settings_queries
+        .insert_secret_sharing_settings(&body)
+        .await?;
+
+    tracing::info!(
+        "Backup secret sharing settings updated to {}-of-{}",
+        settings.k,
+        settings.n
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::env;
 
 use crate::{
+    anchor_org_pk_cache::AnchorOrganizationPublicKeyCache,
     cache_control::{add_cache_control_header, HEALTHCHECK_TTL, STATUS_TTL},
     error::AppError,
     services::database::Database,
@@ -14,7 +15,7 @@ use common::{
     },
     aws::ses::client::{SendEmailConfig, SesClient},
     healthcheck::HealthCheck,
-    system::forms::PostLogConfigForm,
+    system::{forms::PostLogConfigForm, message_retention::SignedMessageRetentionLimit},
     time,
     tracing::TracingReloadHandle,
 };
@@ -113,6 +114,40 @@ pub async fn post_status_event(
     Ok(())
 }
 
+pub async fn post_message_retention_limit(
+    State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
+    State(db): State<Database>,
+    Json(body): Json<SignedMessageRetentionLimit>,
+) -> Result<(), AppError> {
+    let now = time::now();
+    let (keys, _max_epoch) = db
+        .hierarchy_queries
+        .key_hierarchy(&anchor_org_pks.get().await, now)
+        .await?;
+
+    let limit = body.verify(keys.org_pk_iter(), now).map_err(|e| {
+        tracing::error!("Failed to verify message retention limit {:?}", e);
+        AppError::SignatureVerificationFailed
+    })?;
+
+    let current = db.system_queries.latest_message_retention_limit().await?;
+
+    if !body.supersedes(current.as_ref()) {
+        return Err(AppError::SignedSettingsOutdated);
+    }
+
+    db.system_queries
+        .insert_message_retention_limit(&body)
+        .await?;
+
+    tracing::info!(
+        "Message retention limit updated to {} days",
+        limit.max_retention_days
+    );
+
+    Ok(())
+}
+
 pub async fn post_reload_tracing(
     State(db): State<Database>,
     State(tracing_reload_handle): State<TracingReloadHandle>,
@@ -23,6 +23,9 @@ use common::{
         forms::post_rotate_journalist_id::RotateJournalistIdPublicKeyBody,
         models::UntrustedJournalistIdPublicKeyWithEpoch,
     },
+    key_transparency::{
+        key_transparency_leaves, keys::KeyTransparencyLogKeyPair, merkle, merkle::MerkleHash,
+    },
     protocol::{
         constants::{
             COVERNODE_ID_KEY_ROTATE_AFTER, COVERNODE_MSG_KEY_ROTATE_AFTER,
@@ -53,6 +56,7 @@ pub async fn get_public_keys(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
     State(default_journalist_id): State<Option<JournalistIdentity>>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
 ) -> Result<(HeaderMap, Json<UntrustedKeysAndJournalistProfiles>), AppError> {
     let (keys, max_epoch) = {
         let anchor_org_pks = anchor_org_pks.get().await;
@@ -71,6 +75,24 @@ pub async fn get_public_keys(
             .any(|existing_journalist_id| existing_journalist_id == default_journalist_id)
     });
 
+    // The log is updated when keys are uploaded and by a background task, so this can be
+    // missing briefly after the org keys or the log key change
+    let key_transparency = match &key_transparency_log_key_pair {
+        Some(log_key_pair) => db
+            .key_transparency_queries
+            .latest_proof()
+            .await?
+            .and_then(|proof| proof.for_published(log_key_pair, &key_transparency_leaves(&keys))),
+        None => None,
+    };
+
+    let backup_secret_sharing = db
+        .backup_settings_queries
+        .latest_secret_sharing_settings()
+        .await?;
+
+    let message_retention_limit = db.system_queries.latest_message_retention_limit().await?;
+
     let keys = keys.to_untrusted();
 
     let mut headers = HeaderMap::new();
@@ -78,15 +100,82 @@ pub async fn get_public_keys(
 
     Ok((
         headers,
-        Json(UntrustedKeysAndJournalistProfiles::new(
-            journalist_profiles,
-            default_journalist_id,
-            keys,
-            max_epoch,
-        )),
+        Json(
+            UntrustedKeysAndJournalistProfiles::new(
+                journalist_profiles,
+                default_journalist_id,
+                keys,
+                max_epoch,
+            )
+            .with_key_transparency(key_transparency)
+            .with_backup_secret_sharing(backup_secret_sharing)
+            .with_message_retention_limit(message_retention_limit),
+        ),
     ))
 }
 
+/// Prove that the key transparency log of size `first` is a prefix of the log of size `second`
+pub async fn get_key_transparency_consistency_proof(
+    State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
+    Path((first, second)): Path<(u64, u64)>,
+) -> Result<(HeaderMap, Json<Vec<MerkleHash>>), AppError> {
+    if key_transparency_log_key_pair.is_none() {
+        return Err(AppError::KeyTransparencyNotEnabled);
+    }
+
+    if first > second {
+        return Err(AppError::InvalidConsistencyProofRange(first, second));
+    }
+
+    let second_size =
+        i64::try_from(second).map_err(|_| AppError::InvalidConsistencyProofRange(first, second))?;
+    let leaves = db.key_transparency_queries.leaves(second_size).await?;
+
+    if (leaves.len() as u64) < second {
+        return Err(AppError::InvalidConsistencyProofRange(first, second));
+    }
+
+    let proof = merkle::consistency_proof(&leaves, first as usize);
+
+    // The log is append-only so the proof for a given range never changes
+    let mut headers = HeaderMap::new();
+    add_cache_control_header(&mut headers, PUBLIC_KEYS_TTL);
+
+    Ok((headers, Json(proof)))
+}
+
+/// Append newly uploaded keys to the key transparency log so the proof served by
+/// [`get_public_keys`] is ready before anyone fetches them. Failures are only logged since the
+/// key has already been stored and the `UpdateKeyTransparencyLogTask` will catch the log up.
+async fn update_key_transparency_log(
+    db: &Database,
+    anchor_org_pks: &AnchorOrganizationPublicKeyCache,
+    key_transparency_log_key_pair: &Option<KeyTransparencyLogKeyPair>,
+) {
+    let Some(log_key_pair) = key_transparency_log_key_pair else {
+        return;
+    };
+
+    let now = time::now();
+
+    let result = async {
+        let (keys, _max_epoch) = db
+            .hierarchy_queries
+            .key_hierarchy(&anchor_org_pks.get().await, now)
+            .await?;
+
+        db.key_transparency_queries
+            .append_leaves_and_store_proof(log_key_pair, &key_transparency_leaves(&keys), now)
+            .await
+    }
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to update key transparency log: {e:?}");
+    }
+}
+
 pub async fn post_journalist(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
@@ -216,6 +305,7 @@ fn warn_if_key_rotation_too_recent<R: Role>(
 pub async fn post_covernode_provisioning_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostCoverNodeProvisioningPublicKeyForm>,
 ) -> Result<(), AppError> {
     let (keys, _max_epoch) = db
@@ -254,12 +344,15 @@ pub async fn post_covernode_provisioning_key(
 
     metrics::counter!("CoverNodeProvisioningPksAdded").increment(1);
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(())
 }
 
 pub async fn post_covernode_id_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostCoverNodeIdPublicKeyForm>,
 ) -> Result<Json<Epoch>, AppError> {
     let (keys, _max_epoch) = db
@@ -319,12 +412,15 @@ pub async fn post_covernode_id_key(
         )
         .await?;
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(Json(epoch))
 }
 
 pub async fn post_covernode_msg_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostCoverNodeMessagingPublicKeyForm>,
 ) -> Result<Json<Epoch>, AppError> {
     let (keys, _max_epoch) = db
@@ -376,12 +472,15 @@ pub async fn post_covernode_msg_key(
         .insert_covernode_msg_pk(covernode_id, &new_msg_pk, key_signing_id_pk, time::now())
         .await?;
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(Json(epoch))
 }
 
 pub async fn post_journalist_provisioning_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostJournalistProvisioningPublicKeyForm>,
 ) -> Result<(), AppError> {
     let (keys, _max_epoch) = db
@@ -420,6 +519,8 @@ pub async fn post_journalist_provisioning_key(
 
     metrics::counter!("JournalistProvisioningPksAdded").increment(1);
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(())
 }
 
@@ -484,6 +585,7 @@ pub async fn get_journalist_id_pk_rotation_forms(
 pub async fn post_journalist_id_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostJournalistIdPublicKeyForm>,
 ) -> Result<Json<Epoch>, AppError> {
     let (keys, _max_epoch) = db
@@ -549,6 +651,8 @@ pub async fn post_journalist_id_key(
         )
         .await?;
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(Json(epoch))
 }
 
@@ -580,6 +684,7 @@ pub async fn get_journalist_id_pk_with_epoch(
 pub async fn post_journalist_msg_key(
     State(anchor_org_pks): State<AnchorOrganizationPublicKeyCache>,
     State(db): State<Database>,
+    State(key_transparency_log_key_pair): State<Option<KeyTransparencyLogKeyPair>>,
     Json(form): Json<PostJournalistMessagingPublicKeyForm>,
 ) -> Result<Json<Epoch>, AppError> {
     let (keys, _max_epoch) = db
@@ -632,6 +737,8 @@ pub async fn post_journalist_msg_key(
         .insert_journalist_msg_pk(journalist_id, new_msg_pk, key_signing_id_pk, time::now())
         .await?;
 
+    update_key_transparency_log(&db, &anchor_org_pks, &key_transparency_log_key_pair).await;
+
     Ok(Json(epoch))
 }
 
@@ -61,6 +61,12 @@ pub enum AppError {
     BackupDataNotFound(JournalistIdentity),
     #[error("Incorrect Stage found: {0}")]
     IncorrectStageFound(String),
+    #[error("key transparency log is not enabled")]
+    KeyTransparencyNotEnabled,
+    #[error("invalid key transparency consistency proof range {0}..{1}")]
+    InvalidConsistencyProofRange(u64, u64),
+    #[error("signed settings are not newer than the current settings")]
+    SignedSettingsOutdated,
 }
 
 impl IntoResponse for AppError {
@@ -139,6 +145,18 @@ impl IntoResponse for AppError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Incorrect Stage found on server".into(),
             ),
+            Self::KeyTransparencyNotEnabled => (
+                StatusCode::NOT_FOUND,
+                "Key transparency log is not enabled".into(),
+            ),
+            Self::InvalidConsistencyProofRange(_, _) => (
+                StatusCode::BAD_REQUEST,
+                "Invalid consistency proof range".into(),
+            ),
+            Self::SignedSettingsOutdated => (
+                StatusCode::BAD_REQUEST,
+                "Signed settings are not newer than the current settings".into(),
+            ),
         };
 
         tracing::error!("Error from API: {:?}", self);
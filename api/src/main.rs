@@ -3,28 +3,31 @@ use api::api_state::ApiState;
 use api::cli::Cli;
 #[allow(deprecated)]
 use api::controllers::backups::{
-    post_backup_encryption_pk, post_backup_signing_pk, retrieve_upload_url,
-    retrieve_upload_url_with_metadata,
+    post_backup_encryption_pk, post_backup_secret_sharing_settings, post_backup_signing_pk,
+    retrieve_upload_url, retrieve_upload_url_with_metadata,
 };
 use api::controllers::dead_drops::{
     get_journalist_dead_drops, get_journalist_recent_dead_drop_summary, get_user_dead_drops,
     get_user_recent_dead_drop_summary, post_journalist_dead_drops, post_user_dead_drops,
 };
 use api::controllers::general::{
-    get_healthcheck, get_latest_status, post_reload_tracing, post_status_event,
+    get_healthcheck, get_latest_status, post_message_retention_limit, post_reload_tracing,
+    post_status_event,
 };
 use api::controllers::journalist_message::post_forward_journalist_to_covernode_msg;
 use api::controllers::journalist_status::patch_journalist_status;
 use api::controllers::keys::{
     delete_journalist, get_journalist_id_pk_rotation_forms, get_journalist_id_pk_with_epoch,
-    get_public_keys, patch_journalist, post_admin_key, post_covernode_id_key,
-    post_covernode_msg_key, post_covernode_provisioning_key, post_journalist,
-    post_journalist_id_key, post_journalist_id_pk_rotation_form, post_journalist_msg_key,
-    post_journalist_provisioning_key,
+    get_key_transparency_consistency_proof, get_public_keys, patch_journalist, post_admin_key,
+    post_covernode_id_key, post_covernode_msg_key, post_covernode_provisioning_key,
+    post_journalist, post_journalist_id_key, post_journalist_id_pk_rotation_form,
+    post_journalist_msg_key, post_journalist_provisioning_key,
 };
 use api::dead_drop_limits::DeadDropLimits;
 use api::services::database::Database;
-use api::services::tasks::{AnchorOrganizationPublicKeyPollTask, DeleteOldDeadDropsTask};
+use api::services::tasks::{
+    AnchorOrganizationPublicKeyPollTask, DeleteOldDeadDropsTask, UpdateKeyTransparencyLogTask,
+};
 use api::DEFAULT_PORT;
 use axum::routing::{delete, get, patch, post};
 use axum::Router;
@@ -32,8 +35,11 @@ use chrono::Duration;
 use clap::Parser;
 use common::aws::kinesis::client::KinesisClient;
 use common::aws::s3::client::S3Client;
+use common::key_transparency::keys::load_key_transparency_log_key_pairs;
 use common::metrics::{init_metrics, API_NAMESPACE};
+use common::protocol::keys::load_anchor_org_pks;
 use common::task::TaskRunner;
+use common::time;
 use common::tracing::init_tracing_with_reload_handle;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use tokio::net::TcpListener;
@@ -79,6 +85,11 @@ async fn main() -> anyhow::Result<()> {
         Duration::minutes(1),
     );
 
+    let key_transparency_log_polling_period = polling_seconds_to_duration(
+        cli.key_transparency_log_polling_period_seconds,
+        Duration::minutes(1),
+    );
+
     //
     // Set up services
     //
@@ -94,6 +105,24 @@ async fn main() -> anyhow::Result<()> {
 
     let s3_client = S3Client::new(cli.aws_config, cli.s3_endpoint_url).await;
 
+    //
+    // Load the key used to sign the key transparency log tree heads, if there is one
+    //
+    let key_transparency_log_key_pair = match &cli.key_location.keys_path {
+        Some(keys_path) => {
+            let anchor_org_pks = load_anchor_org_pks(keys_path, time::now())?;
+
+            load_key_transparency_log_key_pairs(keys_path, &anchor_org_pks, time::now())?
+                .into_iter()
+                .max_by_key(|key_pair| key_pair.public_key().not_valid_after)
+        }
+        None => None,
+    };
+
+    if key_transparency_log_key_pair.is_none() {
+        tracing::info!("No key transparency log key pair found, key transparency is disabled");
+    }
+
     //
     // Track the current trusted org pks in memory
     //
@@ -114,6 +143,17 @@ async fn main() -> anyhow::Result<()> {
         runner.add_task(delete_old_dead_drops_task).await;
         runner.add_task(anchor_org_pk_poll_task).await;
 
+        if let Some(log_key_pair) = key_transparency_log_key_pair.clone() {
+            let update_key_transparency_log_task = UpdateKeyTransparencyLogTask::new(
+                key_transparency_log_polling_period,
+                anchor_org_pks.clone(),
+                db.clone(),
+                log_key_pair,
+            );
+
+            runner.add_task(update_key_transparency_log_task).await;
+        }
+
         async move {
             runner.run().await;
         }
@@ -136,6 +176,7 @@ async fn main() -> anyhow::Result<()> {
         cli.default_journalist_id,
         tracing_reload_handle,
         dead_drop_limits,
+        key_transparency_log_key_pair,
     );
 
     #[allow(deprecated)]
@@ -145,8 +186,16 @@ async fn main() -> anyhow::Result<()> {
         .route("/status", get(get_latest_status).post(post_status_event))
         .route("/status/public-key", post(post_admin_key))
         .route("/logging", post(post_reload_tracing))
+        .route(
+            "/message-retention-limit",
+            post(post_message_retention_limit),
+        )
         // Public key infrastructure
         .route("/public-keys", get(get_public_keys))
+        .route(
+            "/public-keys/key-transparency/consistency-proof/{first}/{second}",
+            get(get_key_transparency_consistency_proof),
+        )
         .route("/public-keys/journalists", post(post_journalist))
         .route("/public-keys/journalists/delete", delete(delete_journalist))
         .route(
@@ -216,6 +265,10 @@ async fn main() -> anyhow::Result<()> {
             "/backups/encryption-public-key",
             post(post_backup_encryption_pk),
         )
+        .route(
+            "/backups/secret-sharing-settings",
+            post(post_backup_secret_sharing_settings),
+        )
         // deprecated endpoint which does not include metadata in the presigned URL
         // TODO: remove this endpoint once there are no Sentinel versions which rely on it
         .route("/backups/retrieve-upload-url", post(retrieve_upload_url))
@@ -8,10 +8,12 @@ use sqlx::{
 #[derive(Clone)]
 pub struct Database {
     pub backup_key_queries: BackupKeyQueries,
+    pub backup_settings_queries: BackupSettingsQueries,
     pub covernode_key_queries: CoverNodeKeyQueries,
     pub dead_drop_queries: DeadDropQueries,
     pub hierarchy_queries: HierarchyQueries,
     pub journalist_queries: JournalistQueries,
+    pub key_transparency_queries: KeyTransparencyQueries,
     pub organization_key_queries: OrganizationKeyQueries,
     pub system_key_queries: SystemKeyQueries,
     pub system_queries: SystemQueries,
@@ -31,10 +33,12 @@ impl Database {
 
         Ok(Database {
             backup_key_queries: BackupKeyQueries::new(pool.clone()),
+            backup_settings_queries: BackupSettingsQueries::new(pool.clone()),
             covernode_key_queries: CoverNodeKeyQueries::new(pool.clone()),
             dead_drop_queries: DeadDropQueries::new(pool.clone()),
             hierarchy_queries: HierarchyQueries::new(pool.clone()),
             journalist_queries: JournalistQueries::new(pool.clone()),
+            key_transparency_queries: KeyTransparencyQueries::new(pool.clone()),
             organization_key_queries: OrganizationKeyQueries::new(pool.clone()),
             system_key_queries: SystemKeyQueries::new(pool.clone()),
             system_queries: SystemQueries::new(pool),
@@ -0,0 +1,55 @@
+use common::backup::secret_sharing_settings::SignedSecretSharingSettings;
+use serde_json::Value;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct BackupSettingsQueries {
+    pool: PgPool,
+}
+
+impl BackupSettingsQueries {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert_secret_sharing_settings(
+        &self,
+        settings: &SignedSecretSharingSettings,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO backup_secret_sharing_settings (created_at, settings_json)
+                    VALUES ($1, $2)
+            "#,
+            settings.created_at,
+            serde_json::to_value(settings)?,
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recently created settings. These have already been verified when they were
+    /// posted but clients must verify them again since they cannot trust the API.
+    pub async fn latest_secret_sharing_settings(
+        &self,
+    ) -> anyhow::Result<Option<SignedSecretSharingSettings>> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+                SELECT settings_json AS "settings_json: Value"
+                FROM backup_secret_sharing_settings
+                ORDER BY created_at DESC
+                LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| Ok(serde_json::from_value(row.settings_json)?))
+        .transpose()
+    }
+}
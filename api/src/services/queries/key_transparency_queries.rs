@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use common::key_transparency::{
+    keys::KeyTransparencyLogKeyPair, merkle::MerkleHash, UntrustedKeyTransparencyProof,
+};
+use serde_json::Value;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct KeyTransparencyQueries {
+    pool: PgPool,
+}
+
+impl KeyTransparencyQueries {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append any of the `published` leaves which are not yet in the log, then build and store
+    /// the proof that every one of them is included in the new tree.
+    pub async fn append_leaves_and_store_proof(
+        &self,
+        log_key_pair: &KeyTransparencyLogKeyPair,
+        published: &[MerkleHash],
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<UntrustedKeyTransparencyProof> {
+        let mut tx = self.pool.begin().await?;
+
+        // Appends must be serialized, otherwise a reader could see a later leaf before an
+        // earlier one has been committed and the tree would change underneath it.
+        sqlx::query!("LOCK TABLE key_transparency_log IN EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        for leaf_hash in published {
+            sqlx::query!(
+                r#"
+                    INSERT INTO key_transparency_log (leaf_hash)
+                    VALUES ($1)
+                    ON CONFLICT (leaf_hash) DO NOTHING
+                "#,
+                leaf_hash.as_ref()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let log = sqlx::query!(
+            r#"
+                SELECT leaf_hash
+                FROM key_transparency_log
+                ORDER BY id ASC
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|row| MerkleHash::from_bytes(&row.leaf_hash))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let proof = UntrustedKeyTransparencyProof::new(log_key_pair, &log, published, now)?;
+
+        let tree_size = i64::try_from(log.len())?;
+        let proof_json = serde_json::to_value(&proof)?;
+
+        // The same tree can be proven for a different set of published keys, e.g. after a key
+        // has expired, so keep the most recent
+        sqlx::query!(
+            r#"
+                INSERT INTO key_transparency_proofs (tree_size, created_at, proof_json)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (tree_size) DO UPDATE
+                SET created_at = EXCLUDED.created_at, proof_json = EXCLUDED.proof_json
+            "#,
+            tree_size,
+            now,
+            proof_json
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(proof)
+    }
+
+    /// The proof stored for the largest tree
+    pub async fn latest_proof(&self) -> anyhow::Result<Option<UntrustedKeyTransparencyProof>> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+                SELECT proof_json AS "proof_json: Value"
+                FROM key_transparency_proofs
+                ORDER BY tree_size DESC
+                LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| Ok(serde_json::from_value(row.proof_json)?))
+        .transpose()
+    }
+
+    /// Get the first `tree_size` leaves of the log
+    pub async fn leaves(&self, tree_size: i64) -> anyhow::Result<Vec<MerkleHash>> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+                SELECT leaf_hash
+                FROM key_transparency_log
+                ORDER BY id ASC
+                LIMIT $1
+            "#,
+            tree_size
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| MerkleHash::from_bytes(&row.leaf_hash))
+        .collect()
+    }
+}
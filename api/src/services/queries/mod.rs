@@ -1,17 +1,21 @@
 mod backup_key_queries;
+mod backup_settings_queries;
 mod covernode_key_queries;
 mod dead_drop_queries;
 mod hierarchy_queries;
 mod journalist_queries;
+mod key_transparency_queries;
 mod organization_key_queries;
 mod system_key_queries;
 mod system_queries;
 
 pub use backup_key_queries::BackupKeyQueries;
+pub use backup_settings_queries::BackupSettingsQueries;
 pub use covernode_key_queries::CoverNodeKeyQueries;
 pub use dead_drop_queries::DeadDropQueries;
 pub use hierarchy_queries::HierarchyQueries;
 pub use journalist_queries::JournalistQueries;
+pub use key_transparency_queries::KeyTransparencyQueries;
 pub use organization_key_queries::OrganizationKeyQueries;
 pub use system_key_queries::SystemKeyQueries;
 pub use system_queries::SystemQueries;
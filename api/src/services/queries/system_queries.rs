@@ -1,9 +1,13 @@
-use common::api::{
-    forms::PostSystemStatusEventBody,
-    models::general::{StatusEvent, SystemStatus},
+use common::{
+    api::{
+        forms::PostSystemStatusEventBody,
+        models::general::{StatusEvent, SystemStatus},
+    },
+    system::message_retention::SignedMessageRetentionLimit,
 };
 
 use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::PgPool;
 
 #[derive(Clone)]
@@ -68,4 +72,45 @@ impl SystemQueries {
 
         Ok(())
     }
+
+    pub async fn insert_message_retention_limit(
+        &self,
+        limit: &SignedMessageRetentionLimit,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO message_retention_limits (created_at, limit_json)
+            VALUES ($1, $2)
+            "#,
+            limit.created_at,
+            serde_json::to_value(limit)?,
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recently created limit. This has already been verified when it was posted but
+    /// clients must verify it again since they cannot trust the API.
+    pub async fn latest_message_retention_limit(
+        &self,
+    ) -> anyhow::Result<Option<SignedMessageRetentionLimit>> {
+        let mut connection = self.pool.acquire().await?;
+
+        sqlx::query!(
+            r#"
+            SELECT limit_json AS "limit_json: Value"
+            FROM message_retention_limits
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| Ok(serde_json::from_value(row.limit_json)?))
+        .transpose()
+    }
 }
@@ -1,5 +1,7 @@
 mod anchor_org_pk_poll_tasks;
 mod delete_old_dead_drops_task;
+mod update_key_transparency_log_task;
 
 pub use anchor_org_pk_poll_tasks::AnchorOrganizationPublicKeyPollTask;
 pub use delete_old_dead_drops_task::DeleteOldDeadDropsTask;
+pub use update_key_transparency_log_task::UpdateKeyTransparencyLogTask;
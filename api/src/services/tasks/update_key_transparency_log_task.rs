@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use common::{
+    key_transparency::{key_transparency_leaves, keys::KeyTransparencyLogKeyPair},
+    task::Task,
+    time,
+};
+
+use crate::{anchor_org_pk_cache::AnchorOrganizationPublicKeyCache, services::database::Database};
+
+/// Append the published keys to the key transparency log when the stored proof no longer covers
+/// them. Keys uploaded through the API are appended straight away, but the org keys aren't
+/// uploaded through the API and the log key can be replaced on deploy.
+pub struct UpdateKeyTransparencyLogTask {
+    interval: Duration,
+    anchor_org_pks: AnchorOrganizationPublicKeyCache,
+    db: Database,
+    log_key_pair: KeyTransparencyLogKeyPair,
+}
+
+impl UpdateKeyTransparencyLogTask {
+    pub fn new(
+        interval: Duration,
+        anchor_org_pks: AnchorOrganizationPublicKeyCache,
+        db: Database,
+        log_key_pair: KeyTransparencyLogKeyPair,
+    ) -> Self {
+        Self {
+            interval,
+            anchor_org_pks,
+            db,
+            log_key_pair,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for UpdateKeyTransparencyLogTask {
+    fn name(&self) -> &'static str {
+        "update_key_transparency_log"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        let now = time::now();
+
+        let (keys, _max_epoch) = self
+            .db
+            .hierarchy_queries
+            .key_hierarchy(&self.anchor_org_pks.get().await, now)
+            .await?;
+
+        let published = key_transparency_leaves(&keys);
+
+        let is_up_to_date = self
+            .db
+            .key_transparency_queries
+            .latest_proof()
+            .await?
+            .and_then(|proof| proof.for_published(&self.log_key_pair, &published))
+            .is_some();
+
+        if !is_up_to_date {
+            self.db
+                .key_transparency_queries
+                .append_leaves_and_store_proof(&self.log_key_pair, &published, now)
+                .await?;
+
+            metrics::counter!("KeyTransparencyLogUpdates").increment(1);
+        }
+
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+}
@@ -1,4 +1,7 @@
+use std::path::PathBuf;
+
 use clap::Subcommand;
+use common::api::models::journalist_id::JournalistIdentity;
 
 #[derive(Subcommand)]
 pub enum JournalistCommand {
@@ -18,8 +21,42 @@ pub enum JournalistCommand {
         /// The content of the message.
         message: String,
     },
+    /// Hand a conversation over to another journalist or desk, the user will send future messages to them
+    HandOver {
+        /// The public key of the user as retrieved by the `pull-dead-drops` command.
+        /// You may use a prefix of the public key, but if multiple different matching keys are found this will result in an error.
+        user_pk: String,
+        /// The ID of the journalist or desk taking over the conversation.
+        to: JournalistIdentity,
+    },
+    /// Tell a user which of their messages you have read
+    SendReadReceipt {
+        /// The public key of the user as retrieved by the `pull-dead-drops` command.
+        /// You may use a prefix of the public key, but if multiple different matching keys are found this will result in an error.
+        user_pk: String,
+    },
+    /// Export a conversation with a user, signed with your identity key and encrypted to the
+    /// recipient's key, so it can be handed over as evidence.
+    ExportConversation {
+        /// The public key of the user as retrieved by the `pull-dead-drops` command.
+        /// You may use a prefix of the public key, but if multiple different matching keys are found this will result in an error.
+        user_pk: String,
+        /// The path to the recipient's evidence public key
+        #[clap(long)]
+        recipient_pk_path: PathBuf,
+        /// Where to write the evidence bundle
+        #[clap(long)]
+        output_path: PathBuf,
+    },
     /// Download all dead drops to your vault
     PullDeadDrops,
+    /// Print the safety number for a conversation with a user, compare it with the user in person
+    /// to check that neither of your keys has been substituted.
+    SafetyNumber {
+        /// The public key of the user as retrieved by the `pull-dead-drops` command.
+        /// You may use a prefix of the public key, but if multiple different matching keys are found this will result in an error.
+        user_pk: String,
+    },
     /// Starts an auto-reply service for the given journalist; option not available for users (sources)
     StartAutoReplyService,
     /// Print the contents of a vault, passwords for the vault can be provided with either the password or
@@ -37,6 +37,26 @@ pub enum Command {
         #[clap(long)]
         password: Option<String>,
     },
+    /// Generate a key pair that journalists can export conversations to. Share the public key
+    /// with journalists and keep the key pair safe.
+    GenerateEvidenceKeyPair {
+        /// The directory to save the key pair and public key in
+        #[clap(long)]
+        output_directory: PathBuf,
+    },
+    /// Decrypt a conversation exported by a journalist, check that it was signed by their
+    /// identity key and print it.
+    ImportEvidenceBundle {
+        /// The path to the evidence bundle
+        #[clap(long)]
+        bundle_path: PathBuf,
+        /// The path to the key pair the bundle was encrypted to
+        #[clap(long)]
+        key_pair_path: PathBuf,
+        /// The stage the bundle was exported from
+        #[clap(long)]
+        stage: Stage,
+    },
     /// Subcommands as a user
     User {
         /// The path to the mailbox
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Subcommand;
 use common::api::models::journalist_id::JournalistIdentity;
 use reqwest::Url;
@@ -7,7 +9,8 @@ pub enum UserCommand {
     /// Print the contents of a mailbox, passwords for the mailbox can be provided with either the password or
     /// password-path flags. If neither is provided then you will be prompted for the password interactively.
     ReadMailbox,
-    /// Send cover traffic as a user
+    /// Send messages from the mailbox's private sending queue as a user. These are cover messages
+    /// unless real messages are still pending.
     SendCover {
         /// The address of the U2J service forwarding messages to Kinesis
         #[clap(long)]
@@ -23,13 +26,84 @@ pub enum UserCommand {
         #[clap(long)]
         messaging_url: Url,
 
-        /// The ID of the journalist's public key.
+        /// The ID of the journalist's public key. Defaults to the last journalist messaged, or
+        /// whoever they handed the conversation over to.
         #[clap(long)]
-        journalist_id: JournalistIdentity,
+        journalist_id: Option<JournalistIdentity>,
         /// The content of the message.
         #[clap(long)]
         message: String,
     },
+    /// List the journalists and desks you can message, like the recipient picker in the apps
+    Journalists {
+        /// Only show profiles whose name or description matches this search. If nothing matches
+        /// exactly a desk is recommended instead.
+        #[clap(long)]
+        search: Option<String>,
+        /// Only show desks
+        #[clap(long, conflicts_with = "individuals")]
+        desks: bool,
+        /// Only show individual journalists
+        #[clap(long)]
+        individuals: bool,
+        /// Also show profiles which are hidden from the recipient picker
+        #[clap(long)]
+        include_hidden: bool,
+    },
     /// Download all dead drops to your mailbox
     PullDeadDrops,
+    /// Tell a journalist which of their messages you have read. The read receipt is sent along
+    /// with your other queued messages.
+    SendReadReceipt {
+        /// The ID of the journalist. Defaults to the last journalist messaged, or whoever they
+        /// handed the conversation over to.
+        #[clap(long)]
+        journalist_id: Option<JournalistIdentity>,
+    },
+    /// Change the password used to unlock the mailbox
+    ChangePassword {
+        /// The new password for the mailbox
+        #[clap(long, required_unless_present = "new_password_path")]
+        new_password: Option<String>,
+        /// A file containing the new password for the mailbox
+        #[clap(long)]
+        new_password_path: Option<PathBuf>,
+    },
+    /// Replace your key pair and queue a message telling each journalist you've talked to about
+    /// the new public key. The messages are sent along with your other queued messages.
+    RotateKey,
+    /// Run many simulated users which open and close the app on a random schedule, sending cover
+    /// messages and downloading keys, dead drops and status like the mobile apps do. This is
+    /// useful for load testing with realistic traffic. The mailbox path is the directory the
+    /// simulated users' mailboxes are created in.
+    Simulate {
+        /// The address of the U2J service forwarding messages to Kinesis
+        #[clap(long)]
+        messaging_url: Url,
+        /// The number of simulated users
+        #[clap(long, default_value = "10")]
+        users: usize,
+        /// The average number of seconds the app is kept open for
+        #[clap(long, default_value = "120")]
+        mean_foreground_seconds: i64,
+        /// The average number of seconds between closing the app and opening it again
+        #[clap(long, default_value = "3600")]
+        mean_background_seconds: i64,
+        /// How many times faster than real time the users' schedules run
+        #[clap(long, default_value = "1")]
+        speed_up: u32,
+        /// Stop after this many seconds, otherwise run until interrupted
+        #[clap(long)]
+        run_for_seconds: Option<u64>,
+        /// Seed for the users' schedules, so a run can be repeated
+        #[clap(long)]
+        seed: Option<u64>,
+    },
+    /// Print the safety number for your conversation with a journalist, compare it with the
+    /// journalist in person to check that neither of your keys has been substituted.
+    SafetyNumber {
+        /// The ID of the journalist.
+        #[clap(long)]
+        journalist_id: JournalistIdentity,
+    },
 }
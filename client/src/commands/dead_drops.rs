@@ -12,12 +12,10 @@ pub async fn pull_user_dead_drops(
 
 pub fn print_user_dead_drops(mailbox: &UserMailbox) -> anyhow::Result<()> {
     println!("received_at\tfrom\tmessage");
-    for message in mailbox.messages().iter() {
+    for reassembled in mailbox.reassembled_messages()? {
         println!(
             "{}\t{}\t{}",
-            message.received_at,
-            message.from,
-            message.message.to_string()?
+            reassembled.message.received_at, reassembled.message.from, reassembled.text
         );
     }
     Ok(())
@@ -34,7 +32,11 @@ pub async fn print_journalist_dead_drops(vault: &JournalistVault) -> anyhow::Res
                 );
             }
             VaultMessage::J2U(msg) => {
-                println!("{}\t{:?}\t{}", msg.sent_at, msg.user_pk.key, msg.message);
+                let read = if msg.read_by_user { " (read)" } else { "" };
+                println!(
+                    "{}\t{:?}\t{}{}",
+                    msg.sent_at, msg.user_pk.key, msg.message, read
+                );
             }
         }
     }
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use common::{
+    api::api_client::ApiClient,
+    crypto::keys::{
+        encryption::{PublicEncryptionKey, UnsignedEncryptionKeyPair},
+        serde::StorableKeyMaterial,
+        untrusted::encryption::{UntrustedPublicEncryptionKey, UntrustedUnsignedEncryptionKeyPair},
+    },
+    protocol::{
+        evidence_bundle::{EvidenceBundle, ExportedConversation},
+        keys::AnchorOrganizationPublicKey,
+        roles::EvidenceRecipient,
+    },
+};
+
+/// Generate a key pair that journalists can export conversations to, saving both the key pair and
+/// the public key so that the latter can be shared.
+pub fn generate_evidence_key_pair(output_directory: impl AsRef<Path>) -> anyhow::Result<()> {
+    let key_pair = UnsignedEncryptionKeyPair::<EvidenceRecipient>::generate();
+
+    let key_pair_path = key_pair.to_untrusted().save_to_disk(&output_directory)?;
+    let public_key_path = key_pair
+        .public_key()
+        .to_untrusted()
+        .save_to_disk(&output_directory)?;
+
+    println!("Key pair: {}", key_pair_path.display());
+    println!("Public key: {}", public_key_path.display());
+
+    Ok(())
+}
+
+/// Decrypt an evidence bundle and check that it was signed by a journalist identity key from one
+/// of the trusted organizations, at the time the conversation was exported. The journalist
+/// identity is checked against the key hierarchy published by the API if the key is still there.
+pub async fn import_evidence_bundle(
+    bundle_path: impl AsRef<Path>,
+    key_pair_path: impl AsRef<Path>,
+    api_client: &ApiClient,
+    org_pks: &[AnchorOrganizationPublicKey],
+    now: DateTime<Utc>,
+) -> anyhow::Result<ExportedConversation> {
+    let key_pair =
+        UntrustedUnsignedEncryptionKeyPair::<EvidenceRecipient>::load_from_file(key_pair_path)?
+            .to_trusted();
+
+    let bundle_bytes = std::fs::read(bundle_path).context("Failed to read evidence bundle")?;
+    let signed_conversation =
+        EvidenceBundle::from_vec_unchecked(bundle_bytes).decrypt(&key_pair)?;
+
+    let signed_with = signed_conversation.signed_with().key;
+    let conversation = signed_conversation.to_verified(org_pks, now)?;
+
+    let keys = api_client
+        .get_public_keys()
+        .await?
+        .into_trusted(org_pks, now)?
+        .keys;
+
+    match keys.find_journalist_id_pk_from_raw_ed25519_pk(&signed_with) {
+        Some((journalist_identity, _)) if *journalist_identity != conversation.journalist_identity => {
+            anyhow::bail!(
+                "Evidence bundle claims to be from {} but was signed by {}",
+                conversation.journalist_identity,
+                journalist_identity
+            );
+        }
+        Some(_) => {}
+        None => println!(
+            "Warning: the signing key is no longer published, so the journalist identity {} could not be confirmed",
+            conversation.journalist_identity
+        ),
+    }
+
+    Ok(conversation)
+}
+
+pub fn print_exported_conversation(conversation: &ExportedConversation) {
+    println!("Journalist: {}", conversation.journalist_identity);
+    println!("User: {}", hex::encode(conversation.user_pk.key.as_bytes()));
+    if let Some(alias) = &conversation.alias {
+        println!("Alias: {alias}");
+    }
+    if let Some(description) = &conversation.description {
+        println!("Description: {description}");
+    }
+    println!("Exported at: {}", conversation.exported_at);
+    println!();
+
+    for message in &conversation.messages {
+        let from = if message.from_user {
+            "User"
+        } else {
+            "Journalist"
+        };
+
+        println!("From: {from}");
+        println!("Date: {}", message.timestamp);
+        if let Some(hand_over_to) = &message.hand_over_to {
+            println!("Handed over to: {hand_over_to}");
+        } else {
+            println!("Message: {}", message.text);
+        }
+        println!();
+    }
+}
+
+/// Load a recipient's public key, these are shared out of band so permissions aren't checked
+pub fn load_evidence_recipient_pk(
+    path: impl AsRef<Path>,
+) -> anyhow::Result<PublicEncryptionKey<EvidenceRecipient>> {
+    let pk = UntrustedPublicEncryptionKey::load_from_file_skip_permissions_check(path)?;
+
+    Ok(pk.to_trusted())
+}
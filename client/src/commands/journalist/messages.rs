@@ -1,12 +1,18 @@
 use chrono::{DateTime, Utc};
 use common::aws::kinesis::client::KinesisClient;
 use common::{
+    api::models::journalist_id::JournalistIdentity,
+    message_fragments::split_into_fragments,
     protocol::{
         self,
-        journalist::encrypt_real_message_from_journalist_to_user_via_covernode,
+        covernode::covernode_msg_pks_not_valid_after,
+        journalist::{
+            encrypt_hand_over_from_journalist_to_user_via_covernode,
+            encrypt_read_receipt_from_journalist_to_user_via_covernode,
+            encrypt_real_message_from_journalist_to_user_via_covernode,
+        },
         keys::{CoverDropPublicKeyHierarchy, UserPublicKey},
     },
-    FixedSizeMessageText,
 };
 use journalist_vault::JournalistVault;
 
@@ -18,18 +24,101 @@ pub async fn send_journalist_to_user_real_message(
     message: &str,
     now: DateTime<Utc>,
 ) -> anyhow::Result<()> {
-    // Save to mailbox
-    let message = FixedSizeMessageText::new(message)?;
+    let Some(journalist_msg_key_pair) = vault.latest_msg_key_pair(now).await? else {
+        anyhow::bail!("No messaging key in journalist vault");
+    };
+
+    let messages = split_into_fragments(message)?
+        .into_iter()
+        .map(|message| {
+            let msg = encrypt_real_message_from_journalist_to_user_via_covernode(
+                keys,
+                user_pk,
+                &journalist_msg_key_pair,
+                &message,
+            )?;
+
+            Ok((message, msg))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    for (_, msg) in &messages {
+        kinesis_client
+            .encode_and_put_journalist_message(msg.clone())
+            .await?;
+    }
+
+    // Save to mailbox, all the fragments of a message are saved together
+    vault
+        .add_messages_from_journalist_to_user_and_enqueue(
+            user_pk,
+            messages,
+            None,
+            covernode_msg_pks_not_valid_after(keys)?,
+            now,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn send_journalist_to_user_hand_over(
+    kinesis_client: &KinesisClient,
+    keys: &CoverDropPublicKeyHierarchy,
+    vault: &JournalistVault,
+    user_pk: &UserPublicKey,
+    to: &JournalistIdentity,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let Some(journalist_msg_key_pair) = vault.latest_msg_key_pair(now).await? else {
+        anyhow::bail!("No messaging key in journalist vault");
+    };
 
+    let msg = encrypt_hand_over_from_journalist_to_user_via_covernode(
+        keys,
+        user_pk,
+        &journalist_msg_key_pair,
+        to,
+    )?;
+
+    kinesis_client
+        .encode_and_put_journalist_message(msg.clone())
+        .await?;
+
+    vault
+        .add_hand_over_from_journalist_to_user_and_enqueue(
+            user_pk,
+            to,
+            msg,
+            covernode_msg_pks_not_valid_after(keys)?,
+            now,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn send_journalist_to_user_read_receipt(
+    kinesis_client: &KinesisClient,
+    keys: &CoverDropPublicKeyHierarchy,
+    vault: &JournalistVault,
+    user_pk: &UserPublicKey,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
     let Some(journalist_msg_key_pair) = vault.latest_msg_key_pair(now).await? else {
         anyhow::bail!("No messaging key in journalist vault");
     };
 
-    let msg = encrypt_real_message_from_journalist_to_user_via_covernode(
+    let read_receipt = vault.read_receipt_for_user(user_pk).await?;
+    if read_receipt.message_ids().is_empty() {
+        anyhow::bail!("No messages from this user to acknowledge");
+    }
+
+    let msg = encrypt_read_receipt_from_journalist_to_user_via_covernode(
         keys,
         user_pk,
         &journalist_msg_key_pair,
-        &message,
+        read_receipt,
     )?;
 
     kinesis_client
@@ -37,7 +126,7 @@ pub async fn send_journalist_to_user_real_message(
         .await?;
 
     vault
-        .add_message_from_journalist_to_user_and_enqueue(user_pk, &message, msg, now)
+        .enqueue_read_receipt_from_journalist_to_user(msg, covernode_msg_pks_not_valid_after(keys)?)
         .await?;
 
     Ok(())
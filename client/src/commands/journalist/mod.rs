@@ -8,12 +8,16 @@ use std::path::PathBuf;
 use common::aws::kinesis::client::KinesisClient;
 use common::clap::Stage;
 use common::{
-    api::api_client::ApiClient, crypto::keys::signing::traits::PublicSigningKey,
-    protocol::keys::UserPublicKey, time,
+    api::api_client::ApiClient,
+    crypto::keys::signing::traits::PublicSigningKey,
+    protocol::{keys::UserPublicKey, safety_number::SafetyNumber},
+    time,
 };
 use coverdrop_service::JournalistCoverDropService;
+use journalist_vault::JournalistVault;
 
 use crate::commands::dead_drops::print_journalist_dead_drops;
+use crate::commands::evidence::load_evidence_recipient_pk;
 use crate::{
     cli::JournalistCommand, commands::journalist::dead_drops::load_journalist_dead_drop_messages,
     error::Error,
@@ -21,7 +25,10 @@ use crate::{
 
 use self::{
     auto_reply::run_auto_reply_service,
-    messages::{send_journalist_to_user_cover_message, send_journalist_to_user_real_message},
+    messages::{
+        send_journalist_to_user_cover_message, send_journalist_to_user_hand_over,
+        send_journalist_to_user_read_receipt, send_journalist_to_user_real_message,
+    },
 };
 
 use super::load_journalist_vault_from_args;
@@ -43,7 +50,13 @@ pub async fn handle_journalist_command(
     let keys_and_profiles = api_client
         .get_public_keys()
         .await?
-        .into_trusted(&org_pks, time::now());
+        .into_trusted(&org_pks, time::now())?;
+
+    let mut known_tree_heads = vault.known_tree_heads().await?;
+    keys_and_profiles
+        .check_tree_head_with_api(&api_client, &mut known_tree_heads)
+        .await?;
+    vault.set_known_tree_heads(&known_tree_heads).await?;
 
     match command {
         JournalistCommand::SendCover { number } => {
@@ -54,37 +67,7 @@ pub async fn handle_journalist_command(
             Ok(())
         }
         JournalistCommand::ReplyToMessage { reply_to, message } => {
-            let reply_to_key_prefix = reply_to.to_lowercase();
-
-            let user_keys = vault.user_keys().await?;
-
-            // Given a key prefix we should find the possible users
-            let user_pk = {
-                let mut candidate_user_keys: Vec<UserPublicKey> = user_keys
-                    .into_iter()
-                    .map(|key| {
-                        let key_hex = hex::encode(key.key.as_bytes());
-                        (key_hex, key)
-                    })
-                    .filter_map(|(key_hex, key)| {
-                        if key_hex.starts_with(&reply_to_key_prefix) {
-                            Some(key)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                candidate_user_keys.dedup();
-
-                if candidate_user_keys.len() > 1 {
-                    Err(Error::MultiplePublicKeys)
-                } else if let Some(key) = candidate_user_keys.pop() {
-                    Ok(key)
-                } else {
-                    Err(Error::PublicKeyNotFound)
-                }
-            }?;
+            let user_pk = find_user_pk_by_prefix(&vault, &reply_to).await?;
 
             send_journalist_to_user_real_message(
                 &kinesis_client,
@@ -96,6 +79,64 @@ pub async fn handle_journalist_command(
             )
             .await
         }
+        JournalistCommand::HandOver { user_pk, to } => {
+            let user_pk = find_user_pk_by_prefix(&vault, &user_pk).await?;
+
+            send_journalist_to_user_hand_over(
+                &kinesis_client,
+                &keys_and_profiles.keys,
+                &vault,
+                &user_pk,
+                &to,
+                time::now(),
+            )
+            .await
+        }
+        JournalistCommand::SendReadReceipt { user_pk } => {
+            let user_pk = find_user_pk_by_prefix(&vault, &user_pk).await?;
+
+            send_journalist_to_user_read_receipt(
+                &kinesis_client,
+                &keys_and_profiles.keys,
+                &vault,
+                &user_pk,
+                time::now(),
+            )
+            .await
+        }
+        JournalistCommand::SafetyNumber { user_pk } => {
+            let user_pk = find_user_pk_by_prefix(&vault, &user_pk).await?;
+
+            let journalist_id = vault.journalist_id().await?;
+
+            let safety_number = SafetyNumber::for_latest_published(
+                &user_pk,
+                &journalist_id,
+                &keys_and_profiles.keys,
+            )
+            .ok_or(Error::PublicKeyNotFound)?;
+
+            println!("{}", safety_number.digits());
+            println!("{}", safety_number.words()?);
+
+            Ok(())
+        }
+        JournalistCommand::ExportConversation {
+            user_pk,
+            recipient_pk_path,
+            output_path,
+        } => {
+            let user_pk = find_user_pk_by_prefix(&vault, &user_pk).await?;
+            let recipient_pk = load_evidence_recipient_pk(recipient_pk_path)?;
+
+            let bundle = vault
+                .export_conversation(&user_pk, &recipient_pk, time::now())
+                .await?;
+
+            std::fs::write(&output_path, bundle.as_bytes())?;
+
+            Ok(())
+        }
         JournalistCommand::PullDeadDrops => {
             let max_dead_drop_id = vault.max_dead_drop_id().await?;
 
@@ -158,3 +199,38 @@ pub async fn handle_journalist_command(
         }
     }
 }
+
+/// Given a key prefix find the matching user public key in the vault
+async fn find_user_pk_by_prefix(
+    vault: &JournalistVault,
+    key_prefix: &str,
+) -> anyhow::Result<UserPublicKey> {
+    let key_prefix = key_prefix.to_lowercase();
+
+    let user_keys = vault.user_keys().await?;
+
+    let mut candidate_user_keys: Vec<UserPublicKey> = user_keys
+        .into_iter()
+        .map(|key| {
+            let key_hex = hex::encode(key.key.as_bytes());
+            (key_hex, key)
+        })
+        .filter_map(|(key_hex, key)| {
+            if key_hex.starts_with(&key_prefix) {
+                Some(key)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    candidate_user_keys.dedup();
+
+    if candidate_user_keys.len() > 1 {
+        Err(Error::MultiplePublicKeys.into())
+    } else if let Some(key) = candidate_user_keys.pop() {
+        Ok(key)
+    } else {
+        Err(Error::PublicKeyNotFound.into())
+    }
+}
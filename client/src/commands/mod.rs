@@ -1,4 +1,5 @@
 pub mod dead_drops;
+pub mod evidence;
 pub mod journalist;
 pub mod public_keys;
 pub mod user;
@@ -8,6 +9,7 @@ use std::path::{Path, PathBuf};
 use common::{
     clap::{validate_password_from_args, Stage},
     client::mailbox::{mailbox_message::MailboxMessage, user_mailbox::UserMailbox},
+    message_fragments::ReassembledMessage,
     time,
 };
 use journalist_vault::JournalistVault;
@@ -38,18 +40,50 @@ pub async fn load_journalist_vault_from_args(
     JournalistVault::open(&vault_path, &valid_password, trust_anchors).await
 }
 
-pub fn print_mailbox_messages<'a>(
-    messages: impl Iterator<Item = &'a MailboxMessage>,
+pub fn print_mailbox_messages(
+    messages: impl IntoIterator<Item = ReassembledMessage<MailboxMessage>>,
+    is_read_by_journalist: impl Fn(&MailboxMessage) -> bool,
 ) -> anyhow::Result<()> {
-    for mailbox_message in messages {
-        let text = mailbox_message.message.to_string()?;
+    for reassembled in messages {
+        let mailbox_message = &reassembled.message;
 
         println!("From: {}", mailbox_message.from);
 
         println!("Date: {}", mailbox_message.received_at);
-        println!("Message: {text}");
+        if reassembled.is_partial() {
+            println!(
+                "Message (partial, {} fragments missing): {}",
+                reassembled.missing_fragments.len(),
+                reassembled.text
+            );
+        } else {
+            println!("Message: {}", reassembled.text);
+        }
+        if is_read_by_journalist(mailbox_message) {
+            println!("Read by journalist");
+        }
         println!();
     }
 
     Ok(())
 }
+
+/// Warn that messages were removed from a full mailbox to make space for newer ones, whether the
+/// newer messages were sent by the user or received from a journalist.
+pub fn print_evicted_messages(mailbox: &mut UserMailbox) {
+    let evicted = mailbox.take_evicted_messages();
+    if evicted.is_empty() {
+        return;
+    }
+
+    println!(
+        "Warning: mailbox is full, removed {} old messages",
+        evicted.len()
+    );
+    for message in evicted {
+        println!(
+            "Removed message from {} at {}",
+            message.from, message.received_at
+        );
+    }
+}
@@ -9,5 +9,5 @@ pub async fn get_public_keys(
     client: &ApiClient,
     now: DateTime<Utc>,
 ) -> anyhow::Result<VerifiedKeysAndJournalistProfiles> {
-    Ok(client.get_public_keys().await?.into_trusted(org_pks, now))
+    Ok(client.get_public_keys().await?.into_trusted(org_pks, now)?)
 }
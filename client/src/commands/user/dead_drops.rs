@@ -2,7 +2,6 @@ use chrono::{DateTime, Utc};
 use common::api::models::dead_drops::UnverifiedJournalistToUserDeadDropsList;
 use common::api::models::messages::journalist_to_user_message::JournalistToUserMessage;
 use common::client::mailbox::user_mailbox::UserMailbox;
-use common::protocol::covernode::verify_journalist_to_user_dead_drop_list;
 use common::protocol::keys::CoverDropPublicKeyHierarchy;
 use common::protocol::user::get_decrypted_user_dead_drop_message;
 
@@ -17,7 +16,15 @@ pub fn load_user_dead_drop_messages(
         return Ok(messages_loaded);
     };
 
-    let verified_dead_drops = verify_journalist_to_user_dead_drop_list(keys, dead_drop_list, now);
+    // Dead drops are verified once when they're added to the cache and decrypted from there
+    mailbox.cache_dead_drops(keys, dead_drop_list, now);
+
+    let verified_dead_drops = mailbox
+        .dead_drop_cache()
+        .iter()
+        .filter(|dead_drop| dead_drop.id > mailbox.max_dead_drop_id())
+        .map(|dead_drop| dead_drop.to_verified())
+        .collect::<Vec<_>>();
 
     // POSSIBLE IMPROVEMENT:
     // We could keep a track of who the user has messages so we don't have to check every single key
@@ -31,17 +38,28 @@ pub fn load_user_dead_drop_messages(
 
     for dead_drop in verified_dead_drops {
         for msg in dead_drop.data.messages {
-            if let Ok(Some((journalist_id, message))) =
-                get_decrypted_user_dead_drop_message(mailbox.user_key_pair(), keys, &msg)
-            {
+            // Replies sent before a journalist learnt about a key rotation use the previous key
+            let decrypted = std::iter::once(mailbox.user_key_pair())
+                .chain(mailbox.previous_user_key_pair())
+                .find_map(|user_key_pair| {
+                    get_decrypted_user_dead_drop_message(user_key_pair, keys, &msg)
+                        .ok()
+                        .flatten()
+                });
+
+            if let Some((journalist_id, message)) = decrypted {
                 match message {
                     JournalistToUserMessage::Message(message) => {
                         mailbox.add_message_to_user_from_journalist(&journalist_id, &message);
                     }
-                    JournalistToUserMessage::HandOver(_) => {
+                    JournalistToUserMessage::HandOver(to) => {
                         // POSSIBLY TODO implement the optimisation that allows users to limit the number of journalist keys they check
                         // against dead drop messages. As it stands the Rust code doesn't do this since we only act as a user for
                         // test and debug purposes.
+                        mailbox.hand_over(&journalist_id, &to);
+                    }
+                    JournalistToUserMessage::ReadReceipt(read_receipt) => {
+                        mailbox.add_read_receipt(&journalist_id, &read_receipt);
                     }
                 }
                 messages_loaded += 1;
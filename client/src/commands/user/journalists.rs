@@ -0,0 +1,77 @@
+use common::client::{JournalistProfile, ProfileMatch, VerifiedKeysAndJournalistProfiles};
+
+/// Which kinds of profile to list
+pub struct ProfileFilter {
+    pub desks: bool,
+    pub individuals: bool,
+    pub include_hidden: bool,
+}
+
+impl ProfileFilter {
+    fn matches(&self, profile: &JournalistProfile) -> bool {
+        (!self.desks || profile.is_desk) && (!self.individuals || !profile.is_desk)
+    }
+}
+
+fn print_profile(profile: &JournalistProfile, default: bool) {
+    let kind = if profile.is_desk {
+        "desk"
+    } else {
+        "journalist"
+    };
+    let hidden = if profile.is_visible() {
+        ""
+    } else {
+        " (hidden)"
+    };
+    let default = if default { " (default)" } else { "" };
+
+    println!(
+        "{}\t{}{}{}\t{}\t{}",
+        profile.id, profile.display_name, hidden, default, kind, profile.description
+    );
+}
+
+pub fn print_journalist_profiles(
+    keys_and_profiles: &VerifiedKeysAndJournalistProfiles,
+    search: Option<&str>,
+    filter: ProfileFilter,
+) {
+    let profiles = match search {
+        Some(query) => keys_and_profiles
+            .search_profiles(query, filter.include_hidden)
+            .into_iter()
+            .map(|(profile, _)| profile)
+            .collect(),
+        None => keys_and_profiles.listed_profiles(filter.include_hidden),
+    };
+
+    let is_default = |profile: &JournalistProfile| {
+        keys_and_profiles.default_journalist_id.as_ref() == Some(&profile.id)
+    };
+
+    println!("id\tname\tkind\tdescription");
+    for profile in profiles.iter().filter(|profile| filter.matches(profile)) {
+        print_profile(profile, is_default(profile));
+    }
+
+    if let Some(query) = search {
+        let exact_match = keys_and_profiles
+            .search_profiles(query, filter.include_hidden)
+            .iter()
+            .any(|(_, m)| *m == ProfileMatch::Exact);
+
+        if !exact_match {
+            match keys_and_profiles.recommended_desk(query) {
+                Some(desk) => {
+                    println!(
+                        "No exact match for \"{query}\", try the {} desk",
+                        desk.display_name
+                    );
+                    print_profile(desk, is_default(desk));
+                }
+                None => println!("No exact match for \"{query}\""),
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use common::api::models::journalist_id::JournalistIdentity;
 use common::client::mailbox::user_mailbox::UserMailbox;
+use common::protocol;
 use common::protocol::keys::CoverDropPublicKeyHierarchy;
 use common::u2j_appender::messaging_client::MessagingClient;
-use common::{protocol, FixedSizeMessageText};
 
+/// Place a message in the mailbox's private sending queue and then send the next queued
+/// message, which will be this one unless older messages are still pending. Long messages are
+/// split into fragments, the remaining fragments are sent in later sending rounds.
 pub async fn send_user_to_journalist_real_message(
     messaging_client: &MessagingClient,
     mailbox: &mut UserMailbox,
@@ -11,22 +14,24 @@ pub async fn send_user_to_journalist_real_message(
     journalist_id: &JournalistIdentity,
     message: &str,
 ) -> anyhow::Result<()> {
-    let message = FixedSizeMessageText::new(message)?;
-    mailbox.add_message_to_journalist_from_user(journalist_id, &message);
-
-    let user_pk = mailbox.user_key_pair().public_key();
-
-    let encrypted_outer_msg =
-        protocol::user::encrypt_real_message_from_user_to_journalist_via_covernode(
-            keys,
-            user_pk,
-            journalist_id,
-            message,
-        )?;
-
-    messaging_client
-        .post_user_message(encrypted_outer_msg)
-        .await?;
+    mailbox.enqueue_text_to_journalist(keys, journalist_id, message)?;
+
+    send_user_queued_message(messaging_client, mailbox, keys).await
+}
+
+/// Send the next message from the mailbox's private sending queue. This is a cover message
+/// unless there are real messages pending. The message stays in the queue if it can't be sent,
+/// so it is retried in the next sending round.
+pub async fn send_user_queued_message(
+    messaging_client: &MessagingClient,
+    mailbox: &mut UserMailbox,
+    keys: &CoverDropPublicKeyHierarchy,
+) -> anyhow::Result<()> {
+    let msg = mailbox.peek_message(keys)?;
+
+    messaging_client.post_user_message(msg).await?;
+
+    mailbox.dequeue_message(keys)?;
 
     Ok(())
 }
@@ -1,19 +1,29 @@
 pub mod dead_drops;
+pub mod journalists;
 pub mod messages;
+pub mod simulate;
 
 use std::path::PathBuf;
 
+use chrono::Duration;
 use common::api::api_client::ApiClient;
+use common::clap::validate_password_from_args;
+use common::protocol::safety_number::SafetyNumber;
 use common::time;
 use common::u2j_appender::messaging_client::MessagingClient;
 use hex::encode;
 
 use crate::cli::UserCommand;
-use crate::commands::{load_user_mailbox_from_args, print_mailbox_messages};
+use crate::commands::{
+    load_user_mailbox_from_args, print_evicted_messages, print_mailbox_messages,
+};
+use crate::error::Error;
 
 use self::{
     dead_drops::load_user_dead_drop_messages,
-    messages::{send_user_to_journalist_cover_message, send_user_to_journalist_real_message},
+    journalists::{print_journalist_profiles, ProfileFilter},
+    messages::{send_user_queued_message, send_user_to_journalist_real_message},
+    simulate::{simulate_users, SimulationConfig},
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -24,12 +34,49 @@ pub async fn handle_user_commands(
     command: UserCommand,
     api_client: ApiClient,
 ) -> anyhow::Result<()> {
+    // Simulated users each have their own mailbox rather than the one given on the command line
+    let command = match command {
+        UserCommand::Simulate {
+            messaging_url,
+            users,
+            mean_foreground_seconds,
+            mean_background_seconds,
+            speed_up,
+            run_for_seconds,
+            seed,
+        } => {
+            let config = SimulationConfig {
+                users,
+                mean_foreground: Duration::seconds(mean_foreground_seconds),
+                mean_background: Duration::seconds(mean_background_seconds),
+                speed_up,
+                run_for: run_for_seconds.map(std::time::Duration::from_secs),
+                seed: seed.unwrap_or_else(rand::random),
+            };
+
+            return simulate_users(
+                api_client,
+                MessagingClient::new(messaging_url),
+                mailbox_path,
+                config,
+            )
+            .await;
+        }
+        command => command,
+    };
+
     let mut mailbox = load_user_mailbox_from_args(mailbox_path, password, password_path)?;
 
     let keys_and_profiles = api_client
         .get_public_keys()
         .await?
-        .into_trusted(mailbox.org_pks(), time::now());
+        .into_trusted(mailbox.org_pks(), time::now())?;
+
+    let mut known_tree_heads = mailbox.known_tree_heads();
+    keys_and_profiles
+        .check_tree_head_with_api(&api_client, &mut known_tree_heads)
+        .await?;
+    mailbox.set_known_tree_heads(&known_tree_heads);
 
     match command {
         UserCommand::ReadMailbox => {
@@ -45,7 +92,19 @@ pub async fn handle_user_commands(
             let messages = &mailbox.secret.messages;
             println!("{} Messages", messages.count());
 
-            print_mailbox_messages(mailbox.secret.messages.iter())?;
+            print_mailbox_messages(mailbox.reassembled_messages()?, |message| {
+                mailbox.is_read_by_journalist(message)
+            })?;
+
+            let dead_drop_cache = mailbox.dead_drop_cache();
+            println!(
+                "{} cached dead drops (latest ID {})",
+                dead_drop_cache.len(),
+                dead_drop_cache
+                    .max_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or("none".to_string())
+            );
 
             Ok(())
         }
@@ -56,9 +115,12 @@ pub async fn handle_user_commands(
             let messaging_client = MessagingClient::new(messaging_url);
 
             for _ in 0..number {
-                send_user_to_journalist_cover_message(&messaging_client, &keys_and_profiles.keys)
+                send_user_queued_message(&messaging_client, &mut mailbox, &keys_and_profiles.keys)
                     .await?;
             }
+
+            print_evicted_messages(&mut mailbox);
+
             Ok(())
         }
         UserCommand::SendMessage {
@@ -68,6 +130,21 @@ pub async fn handle_user_commands(
         } => {
             let messaging_client = MessagingClient::new(messaging_url);
 
+            let journalist_id = match journalist_id {
+                Some(journalist_id) => journalist_id,
+                None => mailbox
+                    .default_recipient()
+                    .cloned()
+                    .ok_or(Error::NoDefaultRecipient)?,
+            };
+
+            if let Some(next_eviction) = mailbox.next_eviction(&journalist_id) {
+                println!(
+                    "Warning: mailbox is full, sending this message will remove the message from {}",
+                    next_eviction.received_at
+                );
+            }
+
             send_user_to_journalist_real_message(
                 &messaging_client,
                 &mut mailbox,
@@ -75,7 +152,27 @@ pub async fn handle_user_commands(
                 &journalist_id,
                 &message,
             )
-            .await
+            .await?;
+
+            print_evicted_messages(&mut mailbox);
+
+            Ok(())
+        }
+        UserCommand::Journalists {
+            search,
+            desks,
+            individuals,
+            include_hidden,
+        } => {
+            let filter = ProfileFilter {
+                desks,
+                individuals,
+                include_hidden,
+            };
+
+            print_journalist_profiles(&keys_and_profiles, search.as_deref(), filter);
+
+            Ok(())
         }
         UserCommand::PullDeadDrops => {
             let dead_drop_list = api_client
@@ -89,7 +186,55 @@ pub async fn handle_user_commands(
                 time::now(),
             )?;
 
+            print_evicted_messages(&mut mailbox);
+
+            Ok(())
+        }
+        UserCommand::SendReadReceipt { journalist_id } => {
+            let journalist_id = match journalist_id {
+                Some(journalist_id) => journalist_id,
+                None => mailbox
+                    .default_recipient()
+                    .cloned()
+                    .ok_or(Error::NoDefaultRecipient)?,
+            };
+
+            let acknowledged =
+                mailbox.enqueue_read_receipt(&keys_and_profiles.keys, &journalist_id)?;
+
+            println!("Queued read receipt for {acknowledged} messages from {journalist_id}");
+
+            Ok(())
+        }
+        UserCommand::ChangePassword {
+            new_password,
+            new_password_path,
+        } => {
+            let new_password = validate_password_from_args(new_password, new_password_path)?;
+            mailbox.change_password(&new_password)
+        }
+        UserCommand::RotateKey => {
+            let journalist_ids = mailbox.rotate_user_key(&keys_and_profiles.keys)?;
+
+            for journalist_id in journalist_ids {
+                println!("Queued key rotation for {journalist_id}");
+            }
+
+            Ok(())
+        }
+        UserCommand::SafetyNumber { journalist_id } => {
+            let safety_number = SafetyNumber::for_latest_published(
+                mailbox.secret.user_key_pair.public_key(),
+                &journalist_id,
+                &keys_and_profiles.keys,
+            )
+            .ok_or(Error::PublicKeyNotFound)?;
+
+            println!("{}", safety_number.digits());
+            println!("{}", safety_number.words()?);
+
             Ok(())
         }
+        UserCommand::Simulate { .. } => unreachable!("Simulations don't use a single mailbox"),
     }
 }
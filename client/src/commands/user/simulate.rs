@@ -0,0 +1,284 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use chrono::{DateTime, Duration, Utc};
+use common::{
+    api::api_client::ApiClient,
+    client::{mailbox::user_mailbox::UserMailbox, VerifiedKeysAndJournalistProfiles},
+    protocol::constants::{
+        CLIENT_DEFAULT_DOWNLOAD_RATE, CLIENT_STATUS_DOWNLOAD_RATE, MAX_BACKGROUND_DURATION,
+    },
+    time,
+    u2j_appender::messaging_client::MessagingClient,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::task::JoinSet;
+
+use super::{dead_drops::load_user_dead_drop_messages, messages::send_user_queued_message};
+
+/// How the simulated users behave.
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    /// The number of simulated users
+    pub users: usize,
+    /// The average time the app is kept open for
+    pub mean_foreground: Duration,
+    /// The average time between the app being closed and opened again
+    pub mean_background: Duration,
+    /// How many times faster than real time the users' schedules run
+    pub speed_up: u32,
+    /// Stop after this much real time, otherwise run until interrupted
+    pub run_for: Option<std::time::Duration>,
+    /// Seed for the users' schedules
+    pub seed: u64,
+}
+
+#[derive(Default)]
+struct SimulationStats {
+    sessions: AtomicUsize,
+    messages_sent: AtomicUsize,
+    key_downloads: AtomicUsize,
+    dead_drop_downloads: AtomicUsize,
+    evicted_messages: AtomicUsize,
+    status_downloads: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+impl SimulationStats {
+    fn increment(counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn print(&self) {
+        println!("sessions\tsent\tkeys\tdead_drops\tevicted\tstatus\terrors");
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.sessions.load(Ordering::Relaxed),
+            self.messages_sent.load(Ordering::Relaxed),
+            self.key_downloads.load(Ordering::Relaxed),
+            self.dead_drop_downloads.load(Ordering::Relaxed),
+            self.evicted_messages.load(Ordering::Relaxed),
+            self.status_downloads.load(Ordering::Relaxed),
+            self.errors.load(Ordering::Relaxed),
+        );
+    }
+}
+
+/// A user with their own mailbox who opens and closes the app on a random schedule.
+///
+/// Like the mobile apps, a cover message (or a queued real message) is sent each time a new
+/// session starts. Returning to the app within [`MAX_BACKGROUND_DURATION`] resumes the previous
+/// session instead. Keys and dead drops are downloaded at most every
+/// [`CLIENT_DEFAULT_DOWNLOAD_RATE`] and the status at most every [`CLIENT_STATUS_DOWNLOAD_RATE`].
+struct SimulatedUser {
+    mailbox: UserMailbox,
+    rng: StdRng,
+    api_client: ApiClient,
+    messaging_client: MessagingClient,
+    config: SimulationConfig,
+    stats: Arc<SimulationStats>,
+    keys_and_profiles: Option<VerifiedKeysAndJournalistProfiles>,
+    // Time as seen by this user, which runs `speed_up` times faster than real time
+    simulated_now: DateTime<Utc>,
+    last_keys_download: Option<DateTime<Utc>>,
+    last_status_download: Option<DateTime<Utc>>,
+}
+
+impl SimulatedUser {
+    async fn run(mut self) {
+        // Start at a random point in the schedule so the users don't all open the app at once
+        let initial_delay = self.random_duration(self.config.mean_background);
+        self.sleep(initial_delay).await;
+
+        let mut backgrounded_for = None;
+
+        loop {
+            let is_new_session =
+                backgrounded_for.is_none_or(|duration| duration > MAX_BACKGROUND_DURATION);
+
+            if let Err(e) = self.foreground(is_new_session).await {
+                SimulationStats::increment(&self.stats.errors);
+                eprintln!("Simulated user failed: {e}");
+            }
+
+            let foreground_for = self.random_duration(self.config.mean_foreground);
+            self.sleep(foreground_for).await;
+
+            let background_for = self.random_duration(self.config.mean_background);
+            self.sleep(background_for).await;
+            backgrounded_for = Some(background_for);
+        }
+    }
+
+    async fn foreground(&mut self, is_new_session: bool) -> anyhow::Result<()> {
+        if is_new_session {
+            SimulationStats::increment(&self.stats.sessions);
+        }
+
+        if is_due(
+            self.last_keys_download,
+            self.simulated_now,
+            CLIENT_DEFAULT_DOWNLOAD_RATE,
+        ) {
+            let keys_and_profiles = self
+                .api_client
+                .get_public_keys()
+                .await?
+                .into_trusted(self.mailbox.org_pks(), time::now())?;
+            SimulationStats::increment(&self.stats.key_downloads);
+
+            let mut known_tree_heads = self.mailbox.known_tree_heads();
+            keys_and_profiles
+                .check_tree_head_with_api(&self.api_client, &mut known_tree_heads)
+                .await?;
+            self.mailbox.set_known_tree_heads(&known_tree_heads);
+
+            let dead_drop_list = self
+                .api_client
+                .pull_user_dead_drops(self.mailbox.max_dead_drop_id())
+                .await?;
+            SimulationStats::increment(&self.stats.dead_drop_downloads);
+
+            load_user_dead_drop_messages(
+                &dead_drop_list,
+                &keys_and_profiles.keys,
+                &mut self.mailbox,
+                time::now(),
+            )?;
+            self.count_evicted_messages();
+
+            self.keys_and_profiles = Some(keys_and_profiles);
+            self.last_keys_download = Some(self.simulated_now);
+        }
+
+        if is_due(
+            self.last_status_download,
+            self.simulated_now,
+            CLIENT_STATUS_DOWNLOAD_RATE,
+        ) {
+            self.api_client.get_latest_status().await?;
+            SimulationStats::increment(&self.stats.status_downloads);
+
+            self.last_status_download = Some(self.simulated_now);
+        }
+
+        if is_new_session {
+            let Some(keys_and_profiles) = &self.keys_and_profiles else {
+                anyhow::bail!("No keys downloaded yet");
+            };
+
+            send_user_queued_message(
+                &self.messaging_client,
+                &mut self.mailbox,
+                &keys_and_profiles.keys,
+            )
+            .await?;
+            SimulationStats::increment(&self.stats.messages_sent);
+            self.count_evicted_messages();
+        }
+
+        Ok(())
+    }
+
+    /// Messages are evicted from a full mailbox both when sending and when receiving messages
+    fn count_evicted_messages(&mut self) {
+        let evicted = self.mailbox.take_evicted_messages().len();
+        self.stats
+            .evicted_messages
+            .fetch_add(evicted, Ordering::Relaxed);
+    }
+
+    /// A random duration from an exponential distribution, which is how the time between
+    /// independent events such as opening an app is usually modelled.
+    fn random_duration(&mut self, mean: Duration) -> Duration {
+        let uniform: f64 = self.rng.gen();
+        let scale = -(1.0 - uniform).ln();
+
+        Duration::milliseconds((mean.num_milliseconds() as f64 * scale) as i64)
+    }
+
+    async fn sleep(&mut self, duration: Duration) {
+        let real_duration = duration / self.config.speed_up as i32;
+        tokio::time::sleep(real_duration.to_std().unwrap_or_default()).await;
+
+        self.simulated_now += duration;
+    }
+}
+
+fn is_due(last: Option<DateTime<Utc>>, now: DateTime<Utc>, rate: Duration) -> bool {
+    last.is_none_or(|last| now - last >= rate)
+}
+
+/// Run many simulated users against the API and U2J appender. Each user has a mailbox in
+/// `mailbox_dir` and follows its own schedule of opening and closing the app.
+pub async fn simulate_users(
+    api_client: ApiClient,
+    messaging_client: MessagingClient,
+    mailbox_dir: PathBuf,
+    config: SimulationConfig,
+) -> anyhow::Result<()> {
+    if config.speed_up == 0 {
+        anyhow::bail!("Speed up must be at least 1");
+    }
+
+    std::fs::create_dir_all(&mailbox_dir)?;
+
+    let keys = api_client.get_public_keys().await?;
+    let org_pks = keys.untrusted_org_pk_iter().cloned().collect::<Vec<_>>();
+
+    println!(
+        "Simulating {} users with seed {}",
+        config.users, config.seed
+    );
+
+    let stats = Arc::new(SimulationStats::default());
+    let mut users = JoinSet::new();
+
+    for i in 0..config.users {
+        let mailbox_path = mailbox_dir.join(format!("simulated-user-{i}.mailbox"));
+        let org_pks = org_pks.clone();
+
+        // Deriving the mailbox key is deliberately slow
+        let mailbox = tokio::task::spawn_blocking(move || {
+            UserMailbox::new(
+                &format!("simulated user {i}"),
+                org_pks.iter(),
+                mailbox_path,
+                time::now(),
+            )
+        })
+        .await??;
+
+        let user = SimulatedUser {
+            mailbox,
+            rng: StdRng::seed_from_u64(config.seed.wrapping_add(i as u64)),
+            api_client: api_client.clone(),
+            messaging_client: messaging_client.clone(),
+            config: config.clone(),
+            stats: stats.clone(),
+            keys_and_profiles: None,
+            simulated_now: time::now(),
+            last_keys_download: None,
+            last_status_download: None,
+        };
+
+        users.spawn(user.run());
+    }
+
+    match config.run_for {
+        Some(run_for) => tokio::time::sleep(run_for).await,
+        None => tokio::signal::ctrl_c().await?,
+    }
+
+    // Dropping the users saves their mailboxes
+    users.shutdown().await;
+
+    stats.print();
+
+    Ok(())
+}
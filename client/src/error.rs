@@ -6,4 +6,6 @@ pub enum Error {
     PublicKeyNotFound,
     #[error("Multiple keys found for search")]
     MultiplePublicKeys,
+    #[error("No journalist given and the mailbox has no default recipient")]
+    NoDefaultRecipient,
 }
@@ -1,13 +1,20 @@
 use clap::Parser;
 use client::{
     cli::{Cli, Command},
-    commands::{journalist::handle_journalist_command, user::handle_user_commands},
+    commands::{
+        evidence::{
+            generate_evidence_key_pair, import_evidence_bundle, print_exported_conversation,
+        },
+        journalist::handle_journalist_command,
+        user::handle_user_commands,
+    },
 };
 use common::{
     api::api_client::ApiClient, client::mailbox::user_mailbox::UserMailbox,
     generators::PasswordGenerator, time, FixedSizeMessageText,
 };
 use common::{aws::kinesis::client::KinesisClient, crypto::pbkdf::DEFAULT_PASSPHRASE_WORDS};
+use trust_anchors::get_trust_anchors;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -56,6 +63,25 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Command::GenerateEvidenceKeyPair { output_directory } => {
+            generate_evidence_key_pair(output_directory)
+        }
+        Command::ImportEvidenceBundle {
+            bundle_path,
+            key_pair_path,
+            stage,
+        } => {
+            let now = time::now();
+            let org_pks = get_trust_anchors(&stage, now)?;
+
+            let conversation =
+                import_evidence_bundle(bundle_path, key_pair_path, &api_client, &org_pks, now)
+                    .await?;
+
+            print_exported_conversation(&conversation);
+
+            Ok(())
+        }
         Command::User {
             command,
             mailbox_path,
@@ -8,11 +8,13 @@ use crate::api::models::dead_drops::{
 };
 
 use crate::backup::forms::retrieve_upload_url::RetrieveUploadUrlWithMetadataForm;
+use crate::backup::secret_sharing_settings::SignedSecretSharingSettings;
 use crate::client::JournalistStatus;
 use crate::crypto::keys::public_key::PublicKey;
 use crate::epoch::Epoch;
 use crate::healthcheck::HealthCheck;
 use crate::identity_api::models::UntrustedJournalistIdPublicKeyWithEpoch;
+use crate::key_transparency::merkle::MerkleHash;
 use crate::protocol::keys::{
     CoverNodeIdKeyPair, CoverNodeIdPublicKey, CoverNodeMessagingPublicKey,
     CoverNodeProvisioningKeyPair, JournalistIdKeyPair, JournalistMessagingPublicKey,
@@ -27,6 +29,7 @@ use crate::clients::{
     handle_response, handle_response_json, handle_response_text, new_reqwest_client,
 };
 use crate::system::keys::AdminKeyPair;
+use crate::system::message_retention::SignedMessageRetentionLimit;
 
 use super::forms::{
     DeleteJournalistForm, PatchJournalistForm, PostAdminPublicKeyForm,
@@ -85,6 +88,32 @@ impl ApiClient {
         Ok(keys)
     }
 
+    /// Fetch the proof that the key transparency log of size `first_size` is a prefix of the
+    /// log of size `second_size`, see [`KnownTreeHeads::consistency_proof_sizes`].
+    ///
+    /// [`KnownTreeHeads::consistency_proof_sizes`]: crate::key_transparency::KnownTreeHeads::consistency_proof_sizes
+    pub async fn get_key_transparency_consistency_proof(
+        &self,
+        first_size: u64,
+        second_size: u64,
+    ) -> anyhow::Result<Vec<MerkleHash>> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("v1")
+            .push("public-keys")
+            .push("key-transparency")
+            .push("consistency-proof")
+            .push(&first_size.to_string())
+            .push(&second_size.to_string());
+
+        let resp = self.client.get(url).send().await?;
+
+        let proof = handle_response_json(resp).await?;
+
+        Ok(proof)
+    }
+
     pub async fn backup_retrieve_upload_url(
         &self,
         form: RetrieveUploadUrlWithMetadataForm,
@@ -130,6 +159,22 @@ impl ApiClient {
         handle_response(resp).await
     }
 
+    pub async fn post_backup_secret_sharing_settings(
+        &self,
+        settings: &SignedSecretSharingSettings,
+    ) -> anyhow::Result<()> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("v1")
+            .push("backups")
+            .push("secret-sharing-settings");
+
+        let resp = self.client.post(url).json(settings).send().await?;
+
+        handle_response(resp).await
+    }
+
     pub async fn post_covernode_provisioning_pk(
         &self,
         form: PostCoverNodeProvisioningPublicKeyForm,
@@ -293,6 +338,21 @@ impl ApiClient {
         handle_response(resp).await
     }
 
+    pub async fn post_message_retention_limit(
+        &self,
+        limit: &SignedMessageRetentionLimit,
+    ) -> anyhow::Result<()> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("v1")
+            .push("message-retention-limit");
+
+        let resp = self.client.post(url).json(limit).send().await?;
+
+        handle_response(resp).await
+    }
+
     pub async fn post_status_event_form(
         &self,
         body: PostSystemStatusEventForm,
@@ -7,7 +7,7 @@ use super::SerializedJournalistToUserDeadDropMessages;
 
 /// A representation of the data required to sign/verify a journalist to
 /// user dead drop.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct JournalistToUserDeadDropSignatureDataV2(pub [u8; 32]);
 
 impl JournalistToUserDeadDropSignatureDataV2 {
@@ -1,5 +1,9 @@
 use crate::api::models::journalist_id::{JournalistIdentity, MAX_JOURNALIST_IDENTITY_LEN};
-use crate::api::models::messages::{FLAG_J2U_MESSAGE_TYPE_HANDOVER, FLAG_J2U_MESSAGE_TYPE_MESSAGE};
+use crate::api::models::messages::read_receipt::ReadReceipt;
+use crate::api::models::messages::{
+    FLAG_J2U_MESSAGE_TYPE_HANDOVER, FLAG_J2U_MESSAGE_TYPE_MESSAGE,
+    FLAG_J2U_MESSAGE_TYPE_READ_RECEIPT,
+};
 use crate::crypto::keys::encryption::UnsignedEncryptionKeyPair;
 use crate::crypto::{Encryptable, TwoPartyBox};
 use crate::protocol::constants::JOURNALIST_TO_USER_MESSAGE_LEN;
@@ -43,6 +47,7 @@ pub fn new_random_encrypted_journalist_to_user_message(
 pub enum JournalistToUserMessage {
     Message(FixedSizeMessageText),
     HandOver(JournalistIdentity),
+    ReadReceipt(ReadReceipt),
 }
 
 impl fmt::Debug for JournalistToUserMessage {
@@ -63,10 +68,15 @@ impl JournalistToUserMessage {
         Self::HandOver(journalist_id)
     }
 
+    pub fn new_with_read_receipt(read_receipt: ReadReceipt) -> Self {
+        Self::ReadReceipt(read_receipt)
+    }
+
     pub fn get_type_flag(&self) -> u8 {
         match self {
             JournalistToUserMessage::Message(_) => FLAG_J2U_MESSAGE_TYPE_MESSAGE,
             JournalistToUserMessage::HandOver(_) => FLAG_J2U_MESSAGE_TYPE_HANDOVER,
+            JournalistToUserMessage::ReadReceipt(_) => FLAG_J2U_MESSAGE_TYPE_READ_RECEIPT,
         }
     }
 
@@ -84,6 +94,10 @@ impl JournalistToUserMessage {
                 bytes.extend(journalist_id.as_bytes());
                 bytes.resize(JOURNALIST_TO_USER_MESSAGE_LEN, b'\0');
             }
+            Self::ReadReceipt(read_receipt) => {
+                bytes.extend(read_receipt.to_bytes());
+                bytes.resize(JOURNALIST_TO_USER_MESSAGE_LEN, b'\0');
+            }
         }
 
         assert_eq!(bytes.len(), JOURNALIST_TO_USER_MESSAGE_LEN);
@@ -136,6 +150,9 @@ impl SerializedJournalistToUserMessage {
 
                 Ok(JournalistToUserMessage::new_with_hand_over(journalist_id))
             }
+            FLAG_J2U_MESSAGE_TYPE_READ_RECEIPT => Ok(
+                JournalistToUserMessage::new_with_read_receipt(ReadReceipt::from_bytes(content)?),
+            ),
             _ => anyhow::bail!(
                 "Serialized journalist to user message does not have a valid type flag"
             ),
@@ -163,6 +180,7 @@ mod tests_journalist_to_user {
         let messages = vec![
             JournalistToUserMessage::new_with_message(FixedSizeMessageText::new("test")?),
             JournalistToUserMessage::new_with_hand_over(JournalistIdentity::new("id")?),
+            JournalistToUserMessage::new_with_read_receipt(ReadReceipt::new([1, 2, 3])),
         ];
 
         for message in messages {
@@ -1,6 +1,7 @@
 pub mod covernode_to_journalist_message;
 pub mod journalist_to_covernode_message;
 pub mod journalist_to_user_message;
+pub mod read_receipt;
 pub mod user_to_covernode_message;
 pub mod user_to_journalist_message;
 pub mod user_to_journalist_message_with_dead_drop_id;
@@ -19,6 +20,14 @@ pub const FLAG_J2U_REAL: u8 = 0x01;
 //
 pub const FLAG_J2U_MESSAGE_TYPE_MESSAGE: u8 = 0x00;
 pub const FLAG_J2U_MESSAGE_TYPE_HANDOVER: u8 = 0x01;
+pub const FLAG_J2U_MESSAGE_TYPE_READ_RECEIPT: u8 = 0x02;
+
+//
+// Constants for [UserToJournalistMessage], stored in the reserved byte after the reply key
+//
+pub const FLAG_U2J_MESSAGE_TYPE_MESSAGE: u8 = 0x00;
+pub const FLAG_U2J_MESSAGE_TYPE_KEY_ROTATION: u8 = 0x01;
+pub const FLAG_U2J_MESSAGE_TYPE_READ_RECEIPT: u8 = 0x02;
 
 //
 // Constants for [UserToCoverNodeMessage]
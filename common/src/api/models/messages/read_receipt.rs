@@ -0,0 +1,152 @@
+use sha2::{Digest, Sha256};
+
+use crate::{
+    protocol::constants::{
+        MAX_READ_RECEIPT_MESSAGE_IDS, READ_RECEIPT_LEN, READ_RECEIPT_MESSAGE_ID_LEN,
+    },
+    Error, FixedSizeMessageText,
+};
+
+/// Identifies a message within a read receipt. Neither side of a conversation knows the IDs the
+/// other uses to store messages, so messages are identified by a hash of their padded text. The
+/// padding is random so two messages with the same text have different IDs.
+pub type ReadReceiptMessageId = u32;
+
+/// Tells the other side of a conversation which of their messages have been read. Receipts
+/// only ever add to what the recipient knows, so sending the same receipt twice is harmless.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReadReceipt {
+    message_ids: Vec<ReadReceiptMessageId>,
+}
+
+impl ReadReceipt {
+    /// Create a receipt for the given message IDs, oldest first. Duplicates are removed and
+    /// only the most recent [`MAX_READ_RECEIPT_MESSAGE_IDS`] are kept.
+    pub fn new(message_ids: impl IntoIterator<Item = ReadReceiptMessageId>) -> Self {
+        let mut deduplicated: Vec<ReadReceiptMessageId> = vec![];
+
+        for id in message_ids {
+            deduplicated.retain(|existing| *existing != id);
+            deduplicated.push(id);
+        }
+
+        let skip = deduplicated
+            .len()
+            .saturating_sub(MAX_READ_RECEIPT_MESSAGE_IDS);
+
+        Self {
+            message_ids: deduplicated.split_off(skip),
+        }
+    }
+
+    /// Create a receipt acknowledging the given messages, oldest first.
+    pub fn for_messages<'a>(messages: impl IntoIterator<Item = &'a FixedSizeMessageText>) -> Self {
+        Self::new(messages.into_iter().map(Self::message_id))
+    }
+
+    /// The ID used to refer to a message in a read receipt
+    pub fn message_id(message: &FixedSizeMessageText) -> ReadReceiptMessageId {
+        let hash = Sha256::digest(message.as_bytes());
+
+        let mut id = [0; READ_RECEIPT_MESSAGE_ID_LEN];
+        id.copy_from_slice(&hash[..READ_RECEIPT_MESSAGE_ID_LEN]);
+
+        ReadReceiptMessageId::from_be_bytes(id)
+    }
+
+    pub fn message_ids(&self) -> &[ReadReceiptMessageId] {
+        &self.message_ids
+    }
+
+    pub fn acknowledges(&self, message: &FixedSizeMessageText) -> bool {
+        self.message_ids.contains(&Self::message_id(message))
+    }
+
+    /// Serialize the receipt into exactly [`READ_RECEIPT_LEN`] bytes
+    pub fn to_bytes(&self) -> [u8; READ_RECEIPT_LEN] {
+        let mut bytes = [0; READ_RECEIPT_LEN];
+        bytes[0] = self.message_ids.len() as u8;
+
+        for (id, chunk) in self
+            .message_ids
+            .iter()
+            .zip(bytes[1..].chunks_exact_mut(READ_RECEIPT_MESSAGE_ID_LEN))
+        {
+            chunk.copy_from_slice(&id.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    /// Parse a receipt from the start of a message payload. Any bytes after the first
+    /// [`READ_RECEIPT_LEN`] are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = bytes
+            .get(..READ_RECEIPT_LEN)
+            .ok_or(Error::InvalidReadReceipt)?;
+
+        let count = bytes[0] as usize;
+        if count > MAX_READ_RECEIPT_MESSAGE_IDS {
+            return Err(Error::InvalidReadReceipt);
+        }
+
+        let message_ids = bytes[1..]
+            .chunks_exact(READ_RECEIPT_MESSAGE_ID_LEN)
+            .take(count)
+            .map(|chunk| {
+                let mut id = [0; READ_RECEIPT_MESSAGE_ID_LEN];
+                id.copy_from_slice(chunk);
+                ReadReceiptMessageId::from_be_bytes(id)
+            })
+            .collect();
+
+        Ok(Self { message_ids })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_at_fixed_size() -> anyhow::Result<()> {
+        let messages = [
+            FixedSizeMessageText::new("hello")?,
+            FixedSizeMessageText::new("hello")?,
+        ];
+
+        let receipt = ReadReceipt::for_messages(&messages);
+
+        // Random padding means identical texts are still distinct messages
+        assert_eq!(receipt.message_ids().len(), 2);
+        assert!(receipt.acknowledges(&messages[0]));
+        assert!(!receipt.acknowledges(&FixedSizeMessageText::new("hello")?));
+
+        let bytes = receipt.to_bytes();
+        assert_eq!(bytes.len(), READ_RECEIPT_LEN);
+        assert_eq!(ReadReceipt::from_bytes(&bytes)?, receipt);
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_most_recent_unique_ids() {
+        let receipt = ReadReceipt::new((0..100).chain([99, 50]));
+
+        assert_eq!(receipt.message_ids().len(), MAX_READ_RECEIPT_MESSAGE_IDS);
+        assert_eq!(receipt.message_ids().last(), Some(&50));
+        assert_eq!(
+            receipt.message_ids().iter().filter(|id| **id == 99).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_count() {
+        let mut bytes = [0; READ_RECEIPT_LEN];
+        bytes[0] = MAX_READ_RECEIPT_MESSAGE_IDS as u8 + 1;
+
+        assert!(ReadReceipt::from_bytes(&bytes).is_err());
+        assert!(ReadReceipt::from_bytes(&bytes[..10]).is_err());
+    }
+}
@@ -1,9 +1,15 @@
+use crate::api::models::messages::{
+    read_receipt::ReadReceipt, FLAG_U2J_MESSAGE_TYPE_KEY_ROTATION, FLAG_U2J_MESSAGE_TYPE_MESSAGE,
+    FLAG_U2J_MESSAGE_TYPE_READ_RECEIPT,
+};
 use crate::crypto::keys::encryption::traits::PublicEncryptionKey;
 use crate::crypto::keys::encryption::UnsignedEncryptionKeyPair;
-use crate::crypto::{AnonymousBox, Encryptable};
+use crate::crypto::{AnonymousBox, Encryptable, TwoPartyBox};
 use crate::error::Error;
 use crate::protocol::constants::*;
-use crate::protocol::keys::UserPublicKey;
+use crate::protocol::keys::{
+    JournalistMessagingKeyPair, JournalistMessagingPublicKey, UserKeyPair, UserPublicKey,
+};
 use crate::protocol::roles::{JournalistMessaging, User};
 use crate::FixedSizeMessageText;
 use core::fmt;
@@ -36,12 +42,61 @@ pub fn new_random_encrypted_user_to_journalist_message() -> EncryptedUserToJourn
         .expect("AnonymousBox::encrypt should not fail for known valid inputs")
 }
 
-const PLACEHOLDER_RESERVED_BYTE_VALUE: u8 = 0;
+/// Proof that a key rotation was sent by the holder of the user's previous key. This is the new
+/// reply key encrypted in a [TwoPartyBox] from the previous key to the journalist's messaging key,
+/// so only someone holding the previous secret key could have produced it.
+#[derive(Clone, Eq, PartialEq)]
+pub struct UserKeyRotation {
+    pub previous_reply_key: UserPublicKey,
+    proof: TwoPartyBox<Vec<u8>>,
+}
+
+impl UserKeyRotation {
+    pub fn new(
+        previous_key_pair: &UserKeyPair,
+        new_reply_key: &UserPublicKey,
+        journalist_msg_pk: &JournalistMessagingPublicKey,
+    ) -> Result<Self, Error> {
+        let proof = TwoPartyBox::encrypt(
+            journalist_msg_pk,
+            previous_key_pair.secret_key(),
+            new_reply_key.as_bytes().to_vec(),
+        )?;
+
+        assert_eq!(proof.len(), USER_KEY_ROTATION_PROOF_LEN);
+
+        Ok(Self {
+            previous_reply_key: previous_key_pair.public_key().clone(),
+            proof,
+        })
+    }
+
+    /// Check that the rotation to `new_reply_key` was sent by the holder of the previous key,
+    /// using the journalist messaging key pair that decrypted the message.
+    pub fn verify(
+        &self,
+        new_reply_key: &UserPublicKey,
+        journalist_msg_key_pair: &JournalistMessagingKeyPair,
+    ) -> bool {
+        TwoPartyBox::decrypt(
+            &self.previous_reply_key,
+            journalist_msg_key_pair.secret_key(),
+            &self.proof,
+        )
+        .is_ok_and(|decrypted| decrypted == new_reply_key.as_bytes())
+    }
+}
 
 #[derive(Clone, Eq, PartialEq)]
 pub struct UserToJournalistMessage {
     pub reply_key: UserPublicKey,
     pub message: FixedSizeMessageText,
+    /// Set when this message tells the journalist that the user has replaced `previous_reply_key`
+    /// with `reply_key`. Key rotation messages have an empty `message`.
+    pub key_rotation: Option<UserKeyRotation>,
+    /// Set when this message tells the journalist which of their messages the user has read.
+    /// Read receipts have an empty `message`.
+    pub read_receipt: Option<ReadReceipt>,
 }
 
 impl fmt::Debug for UserToJournalistMessage {
@@ -55,14 +110,60 @@ impl UserToJournalistMessage {
         Self {
             reply_key: reply_key.into(),
             message,
+            key_rotation: None,
+            read_receipt: None,
+        }
+    }
+
+    pub fn new_with_key_rotation(
+        reply_key: impl Into<UserPublicKey>,
+        key_rotation: UserKeyRotation,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            reply_key: reply_key.into(),
+            message: FixedSizeMessageText::new("")?,
+            key_rotation: Some(key_rotation),
+            read_receipt: None,
+        })
+    }
+
+    pub fn new_with_read_receipt(
+        reply_key: impl Into<UserPublicKey>,
+        read_receipt: ReadReceipt,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            reply_key: reply_key.into(),
+            message: FixedSizeMessageText::new("")?,
+            key_rotation: None,
+            read_receipt: Some(read_receipt),
+        })
+    }
+
+    pub fn get_type_flag(&self) -> u8 {
+        match (&self.key_rotation, &self.read_receipt) {
+            (Some(_), _) => FLAG_U2J_MESSAGE_TYPE_KEY_ROTATION,
+            (None, Some(_)) => FLAG_U2J_MESSAGE_TYPE_READ_RECEIPT,
+            (None, None) => FLAG_U2J_MESSAGE_TYPE_MESSAGE,
         }
     }
 
     pub fn serialize(&self) -> SerializedUserToJournalistMessage {
         let mut bytes = Vec::with_capacity(USER_TO_JOURNALIST_MESSAGE_LEN);
         bytes.extend(self.reply_key.as_bytes());
-        bytes.extend([PLACEHOLDER_RESERVED_BYTE_VALUE]);
-        bytes.extend(self.message.as_unencrypted_bytes());
+        bytes.push(self.get_type_flag());
+
+        match (&self.key_rotation, &self.read_receipt) {
+            (Some(key_rotation), _) => {
+                bytes.extend(key_rotation.previous_reply_key.as_bytes());
+                bytes.extend(key_rotation.proof.as_bytes());
+                bytes.resize(USER_TO_JOURNALIST_MESSAGE_LEN, 0);
+            }
+            (None, Some(read_receipt)) => {
+                bytes.extend(read_receipt.to_bytes());
+                bytes.resize(USER_TO_JOURNALIST_MESSAGE_LEN, 0);
+            }
+            (None, None) => bytes.extend(self.message.as_unencrypted_bytes()),
+        }
 
         assert_eq!(bytes.len(), USER_TO_JOURNALIST_MESSAGE_LEN);
 
@@ -72,12 +173,15 @@ impl UserToJournalistMessage {
 
 /// The serialized representation of an [UserToJournalistMessage] using the following format:
 /// ```text
-/// ┌────────────┬──────────────────────────────────┐
-/// │ public_key │ padded_message                   │
-/// └────────────┴──────────────────────────────────┘
+/// ┌────────────┬───────────┬──────────────────────────────────┐
+/// │ public_key │ type_flag │ padded_message                   │
+/// └────────────┴───────────┴──────────────────────────────────┘
 /// ```
 /// The public key is [X25519_PUBLIC_KEY_LEN] bytes in length.
-/// The padded message is [USER_TO_JOURNALIST_PADDED_MESSAGE_LEN] in length.
+/// The type flag is [USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE] in length.
+/// The padded message is [MESSAGE_PADDING_LEN] in length. For key rotations it instead holds
+/// the previous public key and [USER_KEY_ROTATION_PROOF_LEN] bytes of proof, and for read
+/// receipts [READ_RECEIPT_LEN] bytes of receipt, both padded with zeros.
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -101,14 +205,49 @@ impl SerializedUserToJournalistMessage {
         let reply_key = UserPublicKey::from_bytes(key_bytes)
             .expect("User key should be X25519_PUBLIC_KEY_LEN bytes");
 
-        // Skip over reserved byte
-        // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~v
-        let message_bytes = &self.bytes[X25519_PUBLIC_KEY_LEN + 1..];
-        let message = Vec::from(message_bytes);
+        let type_flag = self.bytes[X25519_PUBLIC_KEY_LEN];
+        let payload =
+            &self.bytes[X25519_PUBLIC_KEY_LEN + USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE..];
+
+        if type_flag == FLAG_U2J_MESSAGE_TYPE_KEY_ROTATION {
+            let (previous_reply_key, rest) = payload.split_at(X25519_PUBLIC_KEY_LEN);
+            let previous_reply_key = UserPublicKey::from_bytes(previous_reply_key)
+                .expect("User key should be X25519_PUBLIC_KEY_LEN bytes");
+            let proof =
+                TwoPartyBox::from_vec_unchecked(rest[..USER_KEY_ROTATION_PROOF_LEN].to_vec());
+
+            return UserToJournalistMessage {
+                reply_key,
+                message: FixedSizeMessageText::new("")
+                    .expect("FixedSizeMessageText::new should not fail for an empty input string."),
+                key_rotation: Some(UserKeyRotation {
+                    previous_reply_key,
+                    proof,
+                }),
+                read_receipt: None,
+            };
+        }
+
+        if type_flag == FLAG_U2J_MESSAGE_TYPE_READ_RECEIPT {
+            if let Ok(read_receipt) = ReadReceipt::from_bytes(payload) {
+                return UserToJournalistMessage {
+                    reply_key,
+                    message: FixedSizeMessageText::new("").expect(
+                        "FixedSizeMessageText::new should not fail for an empty input string.",
+                    ),
+                    key_rotation: None,
+                    read_receipt: Some(read_receipt),
+                };
+            }
+        }
 
+        // Any other flag is treated as a regular message, which is what the reserved byte
+        // meant before key rotations were added
         UserToJournalistMessage {
             reply_key,
-            message: FixedSizeMessageText::from_vec_unchecked(message),
+            message: FixedSizeMessageText::from_vec_unchecked(payload.to_vec()),
+            key_rotation: None,
+            read_receipt: None,
         }
     }
 }
@@ -126,6 +265,8 @@ impl Encryptable for SerializedUserToJournalistMessage {
 #[cfg(test)]
 mod tests_user_to_journalist {
     use super::*;
+    use crate::protocol::keys::test::{generate_protocol_keys, ProtocolKeys};
+    use crate::time;
 
     #[test]
     fn when_creating_inner_message_with_key_then_serializes_and_deserializes() -> anyhow::Result<()>
@@ -150,6 +291,72 @@ mod tests_user_to_journalist {
         Ok(())
     }
 
+    #[test]
+    fn when_creating_key_rotation_message_then_serializes_and_verifies() -> anyhow::Result<()> {
+        let ProtocolKeys {
+            journalist_msg_key_pair,
+            ..
+        } = generate_protocol_keys(time::now());
+
+        let previous_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let new_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+
+        let key_rotation = UserKeyRotation::new(
+            &previous_key_pair,
+            new_key_pair.public_key(),
+            journalist_msg_key_pair.public_key(),
+        )?;
+        let inner_message = UserToJournalistMessage::new_with_key_rotation(
+            new_key_pair.public_key().clone(),
+            key_rotation,
+        )?;
+
+        let serialized_message = inner_message.serialize();
+        assert_eq!(serialized_message.len(), USER_TO_JOURNALIST_MESSAGE_LEN);
+
+        let actual = serialized_message.clone().to_message();
+        assert_eq!(actual.serialize(), serialized_message);
+        assert!(actual.key_rotation == inner_message.key_rotation);
+
+        let actual_key_rotation = actual
+            .key_rotation
+            .expect("Message should be a key rotation");
+        assert_eq!(
+            actual_key_rotation.previous_reply_key,
+            *previous_key_pair.public_key()
+        );
+        assert!(actual_key_rotation.verify(&actual.reply_key, &journalist_msg_key_pair));
+
+        // Someone who only knows the previous public key can't redirect the conversation
+        let attacker_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        assert!(
+            !actual_key_rotation.verify(attacker_key_pair.public_key(), &journalist_msg_key_pair)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_creating_read_receipt_message_then_serializes_and_deserializes() -> anyhow::Result<()> {
+        let reply_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let read_receipt = ReadReceipt::for_messages(&[FixedSizeMessageText::new("read")?]);
+
+        let inner_message = UserToJournalistMessage::new_with_read_receipt(
+            reply_key_pair.public_key().clone(),
+            read_receipt.clone(),
+        )?;
+
+        let serialized_message = inner_message.serialize();
+        assert_eq!(serialized_message.len(), USER_TO_JOURNALIST_MESSAGE_LEN);
+
+        let actual = serialized_message.to_message();
+        assert_eq!(actual.reply_key, *reply_key_pair.public_key());
+        assert_eq!(actual.read_receipt, Some(read_receipt));
+        assert!(actual.key_rotation.is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn when_creating_random_encrypted_inner_message_then_matches_length() {
         let encrypted_message = new_random_encrypted_user_to_journalist_message();
@@ -3,12 +3,16 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
+    backup::secret_sharing_settings::SignedSecretSharingSettings,
     client::{JournalistProfile, VerifiedKeysAndJournalistProfiles},
     epoch::Epoch,
+    key_transparency::UntrustedKeyTransparencyProof,
     protocol::keys::{
         AnchorOrganizationPublicKey, UntrustedOrganizationPublicKey,
         UntrustedOrganizationPublicKeyFamilyList,
     },
+    system::message_retention::SignedMessageRetentionLimit,
+    Error,
 };
 
 use super::journalist_id::JournalistIdentity;
@@ -24,6 +28,18 @@ pub struct UntrustedKeysAndJournalistProfiles {
     pub default_journalist_id: Option<JournalistIdentity>,
     pub keys: UntrustedOrganizationPublicKeyFamilyList,
     pub max_epoch: Epoch,
+    /// Only present if the API has been configured with a key transparency log key
+    #[ts(type = "unknown")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_transparency: Option<UntrustedKeyTransparencyProof>,
+    /// Only present once the backup admins have published secret sharing settings
+    #[ts(type = "unknown")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_secret_sharing: Option<SignedSecretSharingSettings>,
+    /// Only present once the admins have published a message retention limit
+    #[ts(type = "unknown")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_retention_limit: Option<SignedMessageRetentionLimit>,
 }
 
 impl UntrustedKeysAndJournalistProfiles {
@@ -38,14 +54,41 @@ impl UntrustedKeysAndJournalistProfiles {
             default_journalist_id,
             keys,
             max_epoch,
+            key_transparency: None,
+            backup_secret_sharing: None,
+            message_retention_limit: None,
         }
     }
 
+    pub fn with_key_transparency(
+        mut self,
+        key_transparency: Option<UntrustedKeyTransparencyProof>,
+    ) -> Self {
+        self.key_transparency = key_transparency;
+        self
+    }
+
+    pub fn with_backup_secret_sharing(
+        mut self,
+        backup_secret_sharing: Option<SignedSecretSharingSettings>,
+    ) -> Self {
+        self.backup_secret_sharing = backup_secret_sharing;
+        self
+    }
+
+    pub fn with_message_retention_limit(
+        mut self,
+        message_retention_limit: Option<SignedMessageRetentionLimit>,
+    ) -> Self {
+        self.message_retention_limit = message_retention_limit;
+        self
+    }
+
     pub fn into_trusted(
         self,
         anchor_org_pks: &[AnchorOrganizationPublicKey],
         now: DateTime<Utc>,
-    ) -> VerifiedKeysAndJournalistProfiles {
+    ) -> Result<VerifiedKeysAndJournalistProfiles, Error> {
         VerifiedKeysAndJournalistProfiles::from_untrusted(self, anchor_org_pks, now)
     }
 
@@ -1,10 +1,11 @@
 use std::{path::Path, str::FromStr as _};
 
 use argon2::password_hash::SaltString;
+use rand::RngCore as _;
 use reqwest::Url;
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqliteSynchronous},
-    ConnectOptions as _, Row as _, SqliteConnection, SqlitePool,
+    ConnectOptions as _, SqliteConnection, SqlitePool,
 };
 use tokio::io::AsyncReadExt as _;
 
@@ -23,6 +24,8 @@ pub enum ConnectMode {
 enum KeyPragmaValue<'a> {
     Password(&'a str),
     Argon2Key(SecretBoxKey),
+    // Used when rekeying so SQLCipher writes a new salt to the start of the file
+    Argon2KeyWithSalt(SecretBoxKey, [u8; SQLCIPHER_SALT_LEN]),
     // TODO delete https://github.com/guardian/coverdrop-internal/issues/3100
     HexArgon2Key(SecretBoxKey),
 }
@@ -34,6 +37,13 @@ impl KeyPragmaValue<'_> {
             KeyPragmaValue::Argon2Key(key) => {
                 format!("\"x'{}'\"", hex::encode_upper(key))
             }
+            KeyPragmaValue::Argon2KeyWithSalt(key, salt_bytes) => {
+                format!(
+                    "\"x'{}{}'\"",
+                    hex::encode_upper(key),
+                    hex::encode_upper(salt_bytes)
+                )
+            }
             KeyPragmaValue::HexArgon2Key(key) => {
                 format!("\"x'{}'\"", hex::encode(hex::encode(key)))
             }
@@ -44,6 +54,34 @@ impl KeyPragmaValue<'_> {
 // https://www.zetetic.net/sqlcipher/sqlcipher-api/#cipher_salt
 const SQLCIPHER_SALT_LEN: usize = 16;
 
+// The salt is the only part of the file SQLCipher leaves unencrypted, so the Argon2
+// configuration is recorded at the start of it. Salts written before the configuration was
+// recorded are entirely random.
+const ARGON2_HEADER_MAGIC: [u8; 3] = *b"CDA";
+const ARGON2_HEADER_LEN: usize = ARGON2_HEADER_MAGIC.len() + 1;
+
+/// Build a new salt which records the Argon2 configuration used to derive the key
+fn salt_with_argon2_header(configuration: Argon2Configuration) -> [u8; SQLCIPHER_SALT_LEN] {
+    let mut salt_bytes = [0u8; SQLCIPHER_SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt_bytes[ARGON2_HEADER_LEN..]);
+
+    salt_bytes[..ARGON2_HEADER_MAGIC.len()].copy_from_slice(&ARGON2_HEADER_MAGIC);
+    salt_bytes[ARGON2_HEADER_MAGIC.len()] = configuration.as_byte();
+
+    salt_bytes
+}
+
+/// The Argon2 configuration recorded in a salt, `None` if the salt predates recording it
+fn argon2_configuration_from_salt(
+    salt_bytes: &[u8; SQLCIPHER_SALT_LEN],
+) -> Option<Argon2Configuration> {
+    if salt_bytes[..ARGON2_HEADER_MAGIC.len()] != ARGON2_HEADER_MAGIC {
+        return None;
+    }
+
+    Argon2Configuration::from_byte(salt_bytes[ARGON2_HEADER_MAGIC.len()]).ok()
+}
+
 pub struct Argon2SqlCipher {
     pool: SqlitePool,
 }
@@ -88,7 +126,7 @@ impl Argon2SqlCipher {
 
         // Now that we have the database and it's rekeyed with Argon2 we can
         // open it again using our `open_argon2` function.
-        let (pool, _) = Self::open_argon2(&path, password).await?;
+        let (pool, _, _) = Self::open_argon2(&path, password).await?;
 
         Ok(Self { pool })
     }
@@ -126,7 +164,8 @@ impl Argon2SqlCipher {
     }
 
     /// Open a database that already exists using the legacy keying mode (PBKDF2) and migrate it to
-    /// use Argon2. If the database is already using Argon2 it will open it as normal.
+    /// use Argon2. If the database is already using Argon2 it will open it as normal, re-keying it
+    /// first if the key was derived using an outdated Argon2 configuration.
     ///
     /// This function will be modified once all production databases have been migrated to work
     /// exclusively on argon2 based databases.
@@ -141,7 +180,32 @@ impl Argon2SqlCipher {
             path.display()
         );
 
-        if let Ok((argon2_pool, _)) = Self::open_argon2(&path, password).await {
+        if let Ok((argon2_pool, _, configuration)) = Self::open_argon2(&path, password).await {
+            let is_recorded = Self::salt_from_file(&path)
+                .await
+                .is_ok_and(|salt_bytes| argon2_configuration_from_salt(&salt_bytes).is_some());
+
+            if configuration.is_latest() && is_recorded {
+                return Ok(Self { pool: argon2_pool });
+            }
+
+            tracing::warn!(
+                "Database {} uses Argon2 configuration {:?} (recorded: {}), attempting to rekey with {:?}",
+                path.display(),
+                configuration,
+                is_recorded,
+                Argon2Configuration::LATEST
+            );
+
+            let mut conn = argon2_pool.acquire().await?;
+            Self::rekey_database(&mut conn, password).await?;
+
+            tracing::info!("Successfully rekeyed database {}", path.display());
+
+            drop(conn);
+            argon2_pool.close().await;
+
+            let (argon2_pool, _, _) = Self::open_argon2(&path, password).await?;
             return Ok(Self { pool: argon2_pool });
         }
 
@@ -163,7 +227,7 @@ impl Argon2SqlCipher {
                 path.display()
             );
 
-            if let Ok((argon2_pool, _)) = Self::open_argon2(&path, password).await {
+            if let Ok((argon2_pool, _, _)) = Self::open_argon2(&path, password).await {
                 return Ok(Self { pool: argon2_pool });
             }
         }
@@ -185,7 +249,7 @@ impl Argon2SqlCipher {
         path: impl AsRef<Path>,
         password: &str,
     ) -> anyhow::Result<String> {
-        let (_, key) = Self::open_argon2(path.as_ref(), password).await?;
+        let (_, key, _) = Self::open_argon2(path.as_ref(), password).await?;
 
         Ok(hex::encode(key))
     }
@@ -252,15 +316,39 @@ impl Argon2SqlCipher {
         Ok((pool, key))
     }
 
-    /// Attempt to open the database using a Argon2 derived key, returns a SQLite pool and
-    /// the key which successfully opened it
+    /// Attempt to open the database using a Argon2 derived key, returns a SQLite pool,
+    /// the key which successfully opened it and the Argon2 configuration used to derive it.
+    ///
+    /// The configuration is read from the start of the salt. Databases keyed before it was
+    /// recorded there are opened by trying each known configuration, most recent first.
     async fn open_argon2(
         path: impl AsRef<Path>,
         password: &str,
-    ) -> anyhow::Result<(SqlitePool, SecretBoxKey)> {
+    ) -> anyhow::Result<(SqlitePool, SecretBoxKey, Argon2Configuration)> {
         let salt_bytes = Self::salt_from_file(path.as_ref()).await?;
-        let key = derive_vault_key(password, salt_bytes)?;
 
+        if let Some(configuration) = argon2_configuration_from_salt(&salt_bytes) {
+            let key = derive_vault_key(password, salt_bytes, configuration)?;
+            let pool = Self::open_with_argon2_key(path.as_ref(), key).await?;
+
+            return Ok((pool, key, configuration));
+        }
+
+        for configuration in Argon2Configuration::ALL_NEWEST_FIRST {
+            let key = derive_vault_key(password, salt_bytes, configuration)?;
+
+            if let Ok(pool) = Self::open_with_argon2_key(path.as_ref(), key).await {
+                return Ok((pool, key, configuration));
+            }
+        }
+
+        anyhow::bail!("Failed to unlock database using any known Argon2 configuration")
+    }
+
+    async fn open_with_argon2_key(
+        path: impl AsRef<Path>,
+        key: SecretBoxKey,
+    ) -> anyhow::Result<SqlitePool> {
         let options = Self::sqlcipher_connection_options(
             path,
             KeyPragmaValue::Argon2Key(key),
@@ -272,21 +360,7 @@ impl Argon2SqlCipher {
         let mut conn = pool.acquire().await?;
         Self::check_is_unlocked(&mut conn).await?;
 
-        Ok((pool, key))
-    }
-
-    async fn salt_from_pragma(
-        conn: &mut SqliteConnection,
-    ) -> anyhow::Result<[u8; SQLCIPHER_SALT_LEN]> {
-        let row = sqlx::query("PRAGMA cipher_salt")
-            .fetch_one(&mut *conn)
-            .await?;
-
-        let salt_hex: String = row.get(0);
-
-        let mut salt_bytes = [0u8; SQLCIPHER_SALT_LEN];
-        hex::decode_to_slice(salt_hex, &mut salt_bytes)?;
-        Ok(salt_bytes)
+        Ok(pool)
     }
 
     async fn salt_from_file(path: impl AsRef<Path>) -> anyhow::Result<[u8; SQLCIPHER_SALT_LEN]> {
@@ -300,9 +374,19 @@ impl Argon2SqlCipher {
         conn: &mut SqliteConnection,
         new_password: &str,
     ) -> anyhow::Result<()> {
-        let salt_bytes = Self::salt_from_pragma(&mut *conn).await?;
-        let key = derive_vault_key(new_password, salt_bytes)?;
-        let key = KeyPragmaValue::Argon2Key(key);
+        Self::rekey_database_with_configuration(conn, new_password, Argon2Configuration::LATEST)
+            .await
+    }
+
+    async fn rekey_database_with_configuration(
+        conn: &mut SqliteConnection,
+        new_password: &str,
+        configuration: Argon2Configuration,
+    ) -> anyhow::Result<()> {
+        // Rekeying also replaces the salt so the new configuration is recorded in the file
+        let salt_bytes = salt_with_argon2_header(configuration);
+        let key = derive_vault_key(new_password, salt_bytes, configuration)?;
+        let key = KeyPragmaValue::Argon2KeyWithSalt(key, salt_bytes);
 
         sqlx::query(&format!("PRAGMA rekey = {};", key.to_pragma_string()))
             .execute(&mut *conn)
@@ -329,8 +413,23 @@ impl Argon2SqlCipher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::Row as _;
     use tempfile::{NamedTempFile, TempDir};
 
+    async fn salt_from_pragma(
+        conn: &mut SqliteConnection,
+    ) -> anyhow::Result<[u8; SQLCIPHER_SALT_LEN]> {
+        let row = sqlx::query("PRAGMA cipher_salt")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let salt_hex: String = row.get(0);
+
+        let mut salt_bytes = [0u8; SQLCIPHER_SALT_LEN];
+        hex::decode_to_slice(salt_hex, &mut salt_bytes)?;
+        Ok(salt_bytes)
+    }
+
     #[tokio::test]
     async fn test_create_and_open_argon2() {
         let temp_dir = TempDir::new().unwrap();
@@ -377,8 +476,8 @@ mod tests {
         let file_salt1 = Argon2SqlCipher::salt_from_file(&path1).await.unwrap();
         let file_salt2 = Argon2SqlCipher::salt_from_file(&path2).await.unwrap();
 
-        let pragma_salt1 = Argon2SqlCipher::salt_from_pragma(&mut conn1).await.unwrap();
-        let pragma_salt2 = Argon2SqlCipher::salt_from_pragma(&mut conn2).await.unwrap();
+        let pragma_salt1 = salt_from_pragma(&mut conn1).await.unwrap();
+        let pragma_salt2 = salt_from_pragma(&mut conn2).await.unwrap();
 
         assert_ne!(file_salt1, file_salt2);
         assert_ne!(pragma_salt1, pragma_salt2);
@@ -419,4 +518,138 @@ mod tests {
             assert!(argon2_open_result.is_ok());
         }
     }
+
+    #[tokio::test]
+    async fn records_argon2_configuration_in_salt() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.db");
+
+        Argon2SqlCipher::new(&path, "password").await.unwrap();
+
+        let salt_bytes = Argon2SqlCipher::salt_from_file(&path).await.unwrap();
+        assert_eq!(
+            argon2_configuration_from_salt(&salt_bytes),
+            Some(Argon2Configuration::LATEST)
+        );
+
+        // Key the database the way it was before the configuration was recorded, with the
+        // latest configuration but an entirely random salt
+        {
+            let (pool, _, _) = Argon2SqlCipher::open_argon2(&path, "password")
+                .await
+                .unwrap();
+            let mut conn = pool.acquire().await.unwrap();
+
+            let mut random_salt = [0u8; SQLCIPHER_SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut random_salt);
+            random_salt[0] = !ARGON2_HEADER_MAGIC[0];
+
+            let key =
+                derive_vault_key("password", random_salt, Argon2Configuration::LATEST).unwrap();
+            let key = KeyPragmaValue::Argon2KeyWithSalt(key, random_salt);
+            sqlx::query(&format!("PRAGMA rekey = {};", key.to_pragma_string()))
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+
+            drop(conn);
+            pool.close().await;
+        }
+
+        let salt_bytes = Argon2SqlCipher::salt_from_file(&path).await.unwrap();
+        assert_eq!(argon2_configuration_from_salt(&salt_bytes), None);
+
+        // Still opens by trying each configuration
+        let (pool, _, configuration) = Argon2SqlCipher::open_argon2(&path, "password")
+            .await
+            .unwrap();
+        assert_eq!(configuration, Argon2Configuration::LATEST);
+        pool.close().await;
+
+        // Opening normally records the configuration
+        let db = Argon2SqlCipher::open_and_maybe_migrate_from_legacy(&path, "password")
+            .await
+            .unwrap();
+        db.into_sqlite_pool().close().await;
+
+        let salt_bytes = Argon2SqlCipher::salt_from_file(&path).await.unwrap();
+        assert_eq!(
+            argon2_configuration_from_salt(&salt_bytes),
+            Some(Argon2Configuration::LATEST)
+        );
+
+        // Wrong passwords still fail when the configuration is recorded
+        assert!(Argon2SqlCipher::open_argon2(&path, "wrong password")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn rekey_outdated_argon2_configurations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.db");
+
+        {
+            let db = Argon2SqlCipher::new(&path, "password").await.unwrap();
+            let pool = db.into_sqlite_pool();
+
+            sqlx::query("CREATE TABLE t (v TEXT NOT NULL)")
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO t (v) VALUES ('hello')")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let (_, _, configuration) = Argon2SqlCipher::open_argon2(&path, "password")
+            .await
+            .unwrap();
+        assert_eq!(configuration, Argon2Configuration::LATEST);
+
+        // V0 -> V2, then V1 -> V2
+        for outdated in [Argon2Configuration::V0, Argon2Configuration::V1] {
+            {
+                let (pool, _, _) = Argon2SqlCipher::open_argon2(&path, "password")
+                    .await
+                    .unwrap();
+                let mut conn = pool.acquire().await.unwrap();
+                Argon2SqlCipher::rekey_database_with_configuration(&mut conn, "password", outdated)
+                    .await
+                    .unwrap();
+                drop(conn);
+                pool.close().await;
+            }
+
+            {
+                let (pool, _, configuration) = Argon2SqlCipher::open_argon2(&path, "password")
+                    .await
+                    .unwrap();
+                assert_eq!(configuration, outdated);
+                pool.close().await;
+            }
+
+            {
+                let db = Argon2SqlCipher::open_and_maybe_migrate_from_legacy(&path, "password")
+                    .await
+                    .unwrap();
+                let pool = db.into_sqlite_pool();
+
+                let row = sqlx::query("SELECT v FROM t")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                let v: String = row.get(0);
+                assert_eq!(v, "hello");
+
+                pool.close().await;
+            }
+
+            let (_, _, configuration) = Argon2SqlCipher::open_argon2(&path, "password")
+                .await
+                .unwrap();
+            assert_eq!(configuration, Argon2Configuration::LATEST);
+        }
+    }
 }
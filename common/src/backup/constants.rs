@@ -16,3 +16,7 @@ pub const S3_META_BACKUP_DATA_SIGNATURE: &str = "backup-data-signature";
 /// S3 object metadata key for the JSON-encoded signing key
 /// (`UntrustedSignedPublicSigningKey`).
 pub const S3_META_SIGNED_WITH: &str = "signed-with";
+
+/// Domain separation label for the backup secret sharing settings signatures
+pub(crate) const SECRET_SHARING_SETTINGS_SIGNATURE_LABEL: &[u8] =
+    b"CoverDrop-Backup-SecretSharingSettings-v1";
@@ -3,3 +3,4 @@ pub mod forms;
 pub mod get_backup_data_s3;
 pub mod keys;
 pub mod roles;
+pub mod secret_sharing_settings;
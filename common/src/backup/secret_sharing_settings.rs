@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use hex_buffer_serde::Hex as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    backup::{
+        constants::SECRET_SHARING_SETTINGS_SIGNATURE_LABEL,
+        keys::{BackupIdKeyPair, BackupIdPublicKey},
+    },
+    crypto::{
+        keys::{serde::SignatureHex, signing::traits::PublicSigningKey as _},
+        ShareCount, Signable, Signature,
+    },
+    protocol::constants::{SECRET_SHARING_K_VALUE, SECRET_SHARING_N_VALUE},
+};
+
+pub const SECRET_SHARING_SETTINGS_FILENAME: &str = "backup_secret_sharing_settings.json";
+
+/// How a journalist's automated backups are split between their recovery contacts: the backup
+/// secret is split into `n` shares, one per contact, and any `k` of them restore the vault.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretSharingSettings {
+    pub k: ShareCount,
+    pub n: ShareCount,
+}
+
+impl SecretSharingSettings {
+    pub fn new(k: ShareCount, n: ShareCount) -> anyhow::Result<Self> {
+        if k < 1 {
+            anyhow::bail!("The backup protocol requires k >= 1, but got k={k}");
+        }
+
+        if n < k {
+            anyhow::bail!("The backup protocol requires n >= k, but got k={k} and n={n}");
+        }
+
+        Ok(Self { k, n })
+    }
+
+    fn signature_data(&self, created_at: DateTime<Utc>) -> SecretSharingSettingsSignatureData {
+        let mut hasher = Sha256::new();
+
+        hasher.update(SECRET_SHARING_SETTINGS_SIGNATURE_LABEL);
+        hasher.update([self.k, self.n]);
+        hasher.update(created_at.timestamp().to_be_bytes());
+
+        SecretSharingSettingsSignatureData(hasher.finalize().into())
+    }
+}
+
+/// Used until the backup admins have published settings for the organization
+impl Default for SecretSharingSettings {
+    fn default() -> Self {
+        Self {
+            k: SECRET_SHARING_K_VALUE as ShareCount,
+            n: SECRET_SHARING_N_VALUE as ShareCount,
+        }
+    }
+}
+
+/// A representation of the data required to sign/verify secret sharing settings
+#[derive(Clone, Debug)]
+pub struct SecretSharingSettingsSignatureData(pub [u8; 32]);
+
+impl Signable for SecretSharingSettingsSignatureData {
+    fn as_signable_bytes(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+/// Secret sharing settings signed by a backup admin's identity key. The API only stores and
+/// serves these, clients verify them against the backup identity keys in the key hierarchy so
+/// the API cannot weaken how backups are split.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedSecretSharingSettings {
+    pub settings: SecretSharingSettings,
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "SignatureHex")]
+    pub signature: Signature<SecretSharingSettingsSignatureData>,
+}
+
+impl SignedSecretSharingSettings {
+    pub fn new(
+        settings: SecretSharingSettings,
+        backup_id_key_pair: &BackupIdKeyPair,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let signature = backup_id_key_pair.sign(&settings.signature_data(now));
+
+        Self {
+            settings,
+            created_at: now,
+            signature,
+        }
+    }
+
+    /// Verify the settings were signed by one of the given backup identity keys. Settings which
+    /// would not produce a valid backup, or which claim to be created in the future, are
+    /// rejected even if the signature is valid.
+    pub fn verify<'a>(
+        &self,
+        backup_id_pks: impl IntoIterator<Item = &'a BackupIdPublicKey>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<SecretSharingSettings> {
+        if self.created_at > now {
+            anyhow::bail!(
+                "Secret sharing settings were created in the future at {}",
+                self.created_at
+            );
+        }
+
+        let signature_data = self.settings.signature_data(self.created_at);
+
+        let is_signed = backup_id_pks
+            .into_iter()
+            .any(|pk| pk.verify(&signature_data, &self.signature, now).is_ok());
+
+        if !is_signed {
+            anyhow::bail!("Secret sharing settings are not signed by a backup identity key");
+        }
+
+        SecretSharingSettings::new(self.settings.k, self.settings.n)
+    }
+
+    /// Whether these settings should replace `previous`. Settings are only ever replaced by newer
+    /// ones so the API can't roll clients back to settings the backup admins have replaced.
+    pub fn supersedes(&self, previous: Option<&SignedSecretSharingSettings>) -> bool {
+        previous.is_none_or(|previous| self.created_at > previous.created_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        backup::keys::generate_backup_id_key_pair, protocol::keys::generate_organization_key_pair,
+        time,
+    };
+
+    use super::*;
+
+    #[test]
+    fn signed_settings_are_verified() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let backup_id_key_pair = generate_backup_id_key_pair(&org_key_pair, now);
+        let other_backup_id_key_pair = generate_backup_id_key_pair(&org_key_pair, now);
+
+        let settings = SecretSharingSettings::new(2, 3)?;
+        let signed = SignedSecretSharingSettings::new(settings, &backup_id_key_pair, now);
+
+        assert_eq!(
+            signed.verify([backup_id_key_pair.public_key()], now)?,
+            settings
+        );
+        assert!(signed
+            .verify([other_backup_id_key_pair.public_key()], now)
+            .is_err());
+
+        // Tampering with the settings invalidates the signature
+        let mut weakened = signed.clone();
+        weakened.settings.k = 1;
+        assert!(weakened
+            .verify([backup_id_key_pair.public_key()], now)
+            .is_err());
+
+        let mut backdated = signed;
+        backdated.created_at = now - Duration::days(1);
+        assert!(backdated
+            .verify([backup_id_key_pair.public_key()], now)
+            .is_err());
+
+        // Settings from the future are rejected even when correctly signed
+        let future = SignedSecretSharingSettings::new(
+            settings,
+            &backup_id_key_pair,
+            now + Duration::hours(1),
+        );
+        assert!(future
+            .verify([backup_id_key_pair.public_key()], now)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_newer_settings_supersede() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let backup_id_key_pair = generate_backup_id_key_pair(&org_key_pair, now);
+
+        let older = SignedSecretSharingSettings::new(
+            SecretSharingSettings::new(2, 3)?,
+            &backup_id_key_pair,
+            now - Duration::days(1),
+        );
+        let newer = SignedSecretSharingSettings::new(
+            SecretSharingSettings::new(1, 1)?,
+            &backup_id_key_pair,
+            now,
+        );
+
+        assert!(older.supersedes(None));
+        assert!(newer.supersedes(Some(&older)));
+        assert!(!older.supersedes(Some(&newer)));
+        assert!(!newer.supersedes(Some(&newer)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(SecretSharingSettings::new(0, 1).is_err());
+        assert!(SecretSharingSettings::new(3, 2).is_err());
+        assert!(SecretSharingSettings::new(2, 2).is_ok());
+    }
+}
@@ -63,3 +63,108 @@ impl JournalistProfile {
         }
     }
 }
+
+/// How closely a journalist profile matches a search query, ordered from worst to best.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProfileMatch {
+    /// The letters of the query appear in order in the display name
+    Fuzzy,
+    /// The query appears somewhere in the description
+    Description,
+    /// The query appears somewhere in the display or sort name
+    Name,
+    /// A word in the display or sort name starts with the query
+    NamePrefix,
+    /// The query is the journalist's ID or display name
+    Exact,
+}
+
+impl JournalistProfile {
+    /// Whether the profile should be shown in the recipient picker. Profiles hidden from the UI
+    /// can still be messaged if the user already knows their ID.
+    pub fn is_visible(&self) -> bool {
+        self.status == JournalistStatus::Visible
+    }
+
+    /// Match the profile against a search query, ignoring case. Returns `None` if the
+    /// profile doesn't match at all.
+    pub fn search(&self, query: &str) -> Option<ProfileMatch> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+
+        let display_name = self.display_name.to_lowercase();
+        let sort_name = self.sort_name.to_lowercase();
+
+        if *self.id == query || display_name == query {
+            return Some(ProfileMatch::Exact);
+        }
+
+        let names = [&display_name, &sort_name];
+
+        if names.iter().any(|name| {
+            name.split(|c: char| !c.is_alphanumeric())
+                .any(|word| word.starts_with(&query))
+        }) {
+            return Some(ProfileMatch::NamePrefix);
+        }
+
+        if names.iter().any(|name| name.contains(&query)) {
+            return Some(ProfileMatch::Name);
+        }
+
+        if self.description.to_lowercase().contains(&query) {
+            return Some(ProfileMatch::Description);
+        }
+
+        let mut display_name_chars = display_name.chars();
+        if query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|c| display_name_chars.any(|name_c| name_c == c))
+        {
+            return Some(ProfileMatch::Fuzzy);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(
+        id: &str,
+        display_name: &str,
+        sort_name: &str,
+        description: &str,
+    ) -> JournalistProfile {
+        JournalistProfile::new(
+            JournalistIdentity::new(id).unwrap(),
+            display_name.to_string(),
+            sort_name.to_string(),
+            description.to_string(),
+            false,
+            JournalistStatus::Visible,
+        )
+    }
+
+    #[test]
+    fn search_ranks_matches() {
+        let profile = profile("jane_doe", "Jane Doe", "Doe Jane", "Investigations editor");
+
+        assert_eq!(profile.search("jane_doe"), Some(ProfileMatch::Exact));
+        assert_eq!(profile.search(" JANE DOE "), Some(ProfileMatch::Exact));
+        assert_eq!(profile.search("do"), Some(ProfileMatch::NamePrefix));
+        assert_eq!(profile.search("ne d"), Some(ProfileMatch::Name));
+        assert_eq!(profile.search("editor"), Some(ProfileMatch::Description));
+        assert_eq!(profile.search("jdoe"), Some(ProfileMatch::Fuzzy));
+        assert_eq!(profile.search("dj"), None);
+        assert_eq!(profile.search(""), None);
+
+        assert!(ProfileMatch::Exact > ProfileMatch::NamePrefix);
+        assert!(ProfileMatch::Description > ProfileMatch::Fuzzy);
+    }
+}
@@ -6,6 +6,7 @@ use sqlx::{Decode, Encode};
 
 use crate::api::models::journalist_id::JournalistIdentity;
 use crate::protocol::keys::UserPublicKey;
+use crate::protocol::recipient_tag::RecipientTag;
 use crate::read_ext::ReadExt;
 use crate::{cover_serializable::CoverSerializable, crypto::Encryptable, FixedSizeMessageText};
 
@@ -101,6 +102,14 @@ impl MailboxMessage {
     pub fn is_journalist(&self) -> bool {
         matches!(self.from, MessageSender::Journalist(_))
     }
+
+    /// The tag of the journalist this message was sent to or received from, which identifies
+    /// the conversation it belongs to.
+    pub fn journalist_tag(&self) -> Option<RecipientTag> {
+        self.from
+            .to_journalist_tag()
+            .or_else(|| self.to.to_journalist_tag())
+    }
 }
 
 const IS_COVER_MESSAGE: u8 = 0x0;
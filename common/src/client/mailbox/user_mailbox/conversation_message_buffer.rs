@@ -0,0 +1,260 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, Write},
+};
+
+use crate::{
+    client::mailbox::mailbox_message::MailboxMessage, protocol::recipient_tag::RecipientTag,
+    FixedBuffer,
+};
+
+use super::{MAX_MAILBOX_MESSAGES, MIN_MAILBOX_MESSAGES_PER_CONVERSATION};
+
+/// The on-disk layout, which is shared with the ring buffer used by earlier versions
+/// of the mailbox.
+type SerializedMessageBuffer = FixedBuffer<MailboxMessage, MAX_MAILBOX_MESSAGES>;
+
+/// Fixed size storage for the messages in a user's mailbox.
+///
+/// Messages are kept oldest first. Once the buffer is full, adding a message evicts the oldest
+/// message from a conversation which has more than [`MIN_MAILBOX_MESSAGES_PER_CONVERSATION`]
+/// messages, so a busy conversation can't push a quieter one out of the mailbox entirely.
+/// Only when every conversation is at its minimum is a message evicted from the conversation
+/// being added to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConversationMessageBuffer {
+    messages: Vec<MailboxMessage>,
+}
+
+impl ConversationMessageBuffer {
+    pub const SERIALIZED_LEN: usize = SerializedMessageBuffer::SERIALIZED_LEN;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a message, returning the message which was evicted to make space for it, if any.
+    pub fn push(&mut self, message: MailboxMessage) -> Option<MailboxMessage> {
+        let evicted = if self.messages.len() == MAX_MAILBOX_MESSAGES {
+            let index = self.eviction_index(message.journalist_tag().as_ref());
+            Some(self.messages.remove(index))
+        } else {
+            None
+        };
+
+        self.messages.push(message);
+
+        evicted
+    }
+
+    /// The message which would be evicted if a message in the conversation with the given
+    /// journalist was added now. This lets clients warn a user before their history is lost.
+    pub fn next_eviction(&self, journalist_tag: &RecipientTag) -> Option<&MailboxMessage> {
+        if self.messages.len() < MAX_MAILBOX_MESSAGES {
+            return None;
+        }
+
+        self.messages.get(self.eviction_index(Some(journalist_tag)))
+    }
+
+    fn eviction_index(&self, conversation: Option<&RecipientTag>) -> usize {
+        let mut counts = HashMap::new();
+        for message in &self.messages {
+            *counts.entry(message.journalist_tag()).or_insert(0) += 1;
+        }
+
+        let over_minimum = |message: &MailboxMessage| {
+            counts[&message.journalist_tag()] > MIN_MAILBOX_MESSAGES_PER_CONVERSATION
+        };
+        let in_conversation =
+            |message: &MailboxMessage| message.journalist_tag().as_ref() == conversation;
+
+        // Messages are stored oldest first so the first match is the oldest
+        self.messages
+            .iter()
+            .position(over_minimum)
+            .or_else(|| self.messages.iter().position(in_conversation))
+            .unwrap_or(0)
+    }
+
+    /// Count the number of messages in the buffer.
+    pub fn count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Iterate over the messages, oldest first.
+    pub fn iter(&self) -> std::slice::Iter<'_, MailboxMessage> {
+        self.messages.iter()
+    }
+
+    pub fn read<R>(reader: &mut R) -> anyhow::Result<Self>
+    where
+        R: Read + Seek,
+    {
+        let buffer = SerializedMessageBuffer::read(reader)?;
+
+        let slots = buffer.underlying_array();
+        let mut messages = slots.iter().flatten().cloned().collect::<Vec<_>>();
+
+        // Mailboxes written as a ring buffer have their oldest message in the slot after the
+        // most recently written one. We always write messages oldest first and record the
+        // number of messages, so this is a no-op for them.
+        if messages.len() == MAX_MAILBOX_MESSAGES {
+            messages.rotate_left(buffer.current_index() % MAX_MAILBOX_MESSAGES);
+        }
+
+        Ok(Self { messages })
+    }
+
+    pub fn write<W>(&self, writer: &mut W) -> anyhow::Result<()>
+    where
+        W: Write + Seek,
+    {
+        let slots = std::array::from_fn(|i| self.messages.get(i).cloned());
+        let buffer = SerializedMessageBuffer::from_parts(self.messages.len(), slots);
+
+        buffer.write(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        api::models::journalist_id::JournalistIdentity,
+        crypto::keys::encryption::UnsignedEncryptionKeyPair, protocol::roles::User, time,
+        FixedSizeMessageText,
+    };
+
+    use super::*;
+
+    fn message(journalist_id: &JournalistIdentity, text: &str) -> MailboxMessage {
+        let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+
+        MailboxMessage::from_user_to_journalist(
+            0,
+            journalist_id,
+            user_key_pair.public_key(),
+            &FixedSizeMessageText::new(text).unwrap(),
+            time::now(),
+            false,
+            None,
+        )
+    }
+
+    fn texts(
+        buffer: &ConversationMessageBuffer,
+        journalist_id: &JournalistIdentity,
+    ) -> Vec<String> {
+        let tag = RecipientTag::from_journalist_id(journalist_id);
+
+        buffer
+            .iter()
+            .filter(|m| m.journalist_tag() == Some(tag.clone()))
+            .map(|m| m.message.to_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn busy_conversation_does_not_evict_quiet_conversation() -> anyhow::Result<()> {
+        let busy = JournalistIdentity::new("busy")?;
+        let quiet = JournalistIdentity::new("quiet")?;
+
+        let mut buffer = ConversationMessageBuffer::new();
+
+        for i in 0..MIN_MAILBOX_MESSAGES_PER_CONVERSATION {
+            assert!(buffer.push(message(&quiet, &i.to_string())).is_none());
+        }
+
+        let mut evicted = vec![];
+        for i in 0..(2 * MAX_MAILBOX_MESSAGES) {
+            if let Some(m) = buffer.push(message(&busy, &i.to_string())) {
+                evicted.push(m.message.to_string()?);
+            }
+        }
+
+        assert_eq!(buffer.count(), MAX_MAILBOX_MESSAGES);
+
+        // The quiet conversation keeps its minimum number of messages
+        let expected_quiet = (0..MIN_MAILBOX_MESSAGES_PER_CONVERSATION)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(texts(&buffer, &quiet), expected_quiet);
+
+        // The busy conversation evicts its own oldest messages
+        let kept = MAX_MAILBOX_MESSAGES - MIN_MAILBOX_MESSAGES_PER_CONVERSATION;
+        let expected_busy = ((2 * MAX_MAILBOX_MESSAGES - kept)..(2 * MAX_MAILBOX_MESSAGES))
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(texts(&buffer, &busy), expected_busy);
+
+        let expected_evicted = (0..(2 * MAX_MAILBOX_MESSAGES - kept))
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(evicted, expected_evicted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn next_eviction_predicts_evicted_message() -> anyhow::Result<()> {
+        let busy = JournalistIdentity::new("busy")?;
+        let quiet = JournalistIdentity::new("quiet")?;
+        let quiet_tag = RecipientTag::from_journalist_id(&quiet);
+
+        let mut buffer = ConversationMessageBuffer::new();
+
+        buffer.push(message(&quiet, "quiet"));
+        for i in 0..(MAX_MAILBOX_MESSAGES - 2) {
+            buffer.push(message(&busy, &i.to_string()));
+        }
+
+        // Not full yet so nothing will be evicted
+        assert!(buffer.next_eviction(&quiet_tag).is_none());
+        buffer.push(message(&busy, "last"));
+
+        let predicted = buffer.next_eviction(&quiet_tag).cloned().unwrap();
+        assert_eq!(predicted.message.to_string()?, "0");
+
+        let evicted = buffer.push(message(&quiet, "new")).unwrap();
+        assert_eq!(evicted, predicted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reading_ring_buffer_restores_oldest_first_order() -> anyhow::Result<()> {
+        let journalist_id = JournalistIdentity::new("journalist")?;
+
+        // Write a mailbox the way the ring buffer used to, after it had wrapped around
+        let mut ring_buffer = SerializedMessageBuffer::new();
+        for i in 0..(MAX_MAILBOX_MESSAGES + 10) {
+            ring_buffer.push(message(&journalist_id, &i.to_string()));
+        }
+
+        let mut buf = Cursor::new(vec![]);
+        ring_buffer.write(&mut buf)?;
+        buf.set_position(0);
+
+        let buffer = ConversationMessageBuffer::read(&mut buf)?;
+
+        let expected = (10..(MAX_MAILBOX_MESSAGES + 10))
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(texts(&buffer, &journalist_id), expected);
+
+        // Round trips without changing the order
+        let mut buf = Cursor::new(vec![]);
+        buffer.write(&mut buf)?;
+        assert_eq!(
+            buf.get_ref().len(),
+            ConversationMessageBuffer::SERIALIZED_LEN
+        );
+        buf.set_position(0);
+
+        assert_eq!(ConversationMessageBuffer::read(&mut buf)?, buffer);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    io::{Cursor, Read, Write},
+    mem::size_of,
+};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::SIGNATURE_LENGTH;
+
+use crate::{
+    api::models::dead_drops::{
+        DeadDropId, JournalistToUserDeadDrop, JournalistToUserDeadDropSignatureDataV2,
+        SerializedJournalistToUserDeadDropMessages, UnverifiedJournalistToUserDeadDrop,
+    },
+    client::mailbox::message_timestamp::MessageTimestamp,
+    crypto::{SecretBox, SecretBoxKey, Signable, Signature, SECRET_BOX_FOOTER_LEN},
+    protocol::constants::CLIENT_DEAD_DROP_CACHE_TTL,
+};
+
+/// The size of the decrypted cache. A dead drop takes up its messages plus a small header, so
+/// this holds a couple of weeks of dead drops at the rate they are currently published.
+pub const DEAD_DROP_CACHE_LEN: usize = 2 * 1024 * 1024;
+
+/// A journalist to user dead drop which passed signature verification when it was downloaded.
+/// The signature is kept so the entry can be checked again later.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedDeadDrop {
+    pub id: DeadDropId,
+    pub created_at: DateTime<Utc>,
+    pub data: SerializedJournalistToUserDeadDropMessages,
+    pub signature: Signature<JournalistToUserDeadDropSignatureDataV2>,
+    /// When the signature was verified against the public key hierarchy
+    pub verified_at: DateTime<Utc>,
+}
+
+impl CachedDeadDrop {
+    const HEADER_LEN: usize = size_of::<DeadDropId>() // ID
+        + MessageTimestamp::SERIALIZED_LEN // Created at
+        + MessageTimestamp::SERIALIZED_LEN // Verified at
+        + SIGNATURE_LENGTH // Signature
+        + size_of::<u32>(); // Data length
+
+    /// Cache a dead drop which has been verified using [`verify_journalist_to_user_dead_drop`].
+    ///
+    /// [`verify_journalist_to_user_dead_drop`]: crate::protocol::covernode::verify_journalist_to_user_dead_drop
+    pub fn new(dead_drop: &UnverifiedJournalistToUserDeadDrop, verified_at: DateTime<Utc>) -> Self {
+        Self {
+            id: dead_drop.id,
+            created_at: dead_drop.created_at,
+            data: dead_drop.data.clone(),
+            signature: dead_drop.signature.clone(),
+            verified_at,
+        }
+    }
+
+    pub fn to_verified(&self) -> JournalistToUserDeadDrop {
+        JournalistToUserDeadDrop::new(self.id, self.created_at, self.data.deserialize())
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::HEADER_LEN + self.data.len()
+    }
+
+    fn read(reader: &mut impl Read) -> anyhow::Result<Self> {
+        let mut id_buf = [0; size_of::<DeadDropId>()];
+        reader.read_exact(&mut id_buf)?;
+
+        let created_at = MessageTimestamp::read(reader)?;
+        let verified_at = MessageTimestamp::read(reader)?;
+
+        let mut signature_buf = vec![0; SIGNATURE_LENGTH];
+        reader.read_exact(&mut signature_buf)?;
+
+        let mut data_len_buf = [0; size_of::<u32>()];
+        reader.read_exact(&mut data_len_buf)?;
+
+        let mut data_buf = vec![0; u32::from_be_bytes(data_len_buf) as usize];
+        reader.read_exact(&mut data_buf)?;
+
+        Ok(Self {
+            id: DeadDropId::from_be_bytes(id_buf),
+            created_at: created_at.0,
+            data: SerializedJournalistToUserDeadDropMessages::from_vec_unchecked(data_buf),
+            signature: Signature::from_vec_unchecked(signature_buf),
+            verified_at: verified_at.0,
+        })
+    }
+
+    fn write(&self, writer: &mut impl Write) -> anyhow::Result<()> {
+        writer.write_all(&self.id.to_be_bytes())?;
+        MessageTimestamp::new(self.created_at).write(writer)?;
+        MessageTimestamp::new(self.verified_at).write(writer)?;
+        writer.write_all(&self.signature.to_bytes())?;
+        writer.write_all(&(self.data.len() as u32).to_be_bytes())?;
+        writer.write_all(self.data.as_signable_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// A local cache of verified journalist to user dead drops, mirroring the one kept by the
+/// mobile apps. Dead drops are kept for [`CLIENT_DEAD_DROP_CACHE_TTL`] measured from the most
+/// recent dead drop, and the oldest are evicted early if the cache runs out of space.
+///
+/// The cache is always serialized to [`Self::SERIALIZED_LEN`] bytes so its size on disk does
+/// not reveal how many dead drops have been downloaded.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeadDropCache {
+    /// Ordered by ID, oldest first
+    dead_drops: Vec<CachedDeadDrop>,
+}
+
+impl DeadDropCache {
+    pub const SERIALIZED_LEN: usize = SECRET_BOX_FOOTER_LEN + DEAD_DROP_CACHE_LEN;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a verified dead drop, returning `false` if it was already cached or is too large
+    /// to ever fit in the cache.
+    pub fn insert(&mut self, dead_drop: CachedDeadDrop) -> bool {
+        if self.contains(dead_drop.id) {
+            return false;
+        }
+
+        if size_of::<u32>() + dead_drop.serialized_len() > DEAD_DROP_CACHE_LEN {
+            tracing::warn!(
+                "Dead drop {} is too large to be cached ({} bytes)",
+                dead_drop.id,
+                dead_drop.data.len()
+            );
+            return false;
+        }
+
+        let index = self.dead_drops.partition_point(|d| d.id < dead_drop.id);
+        self.dead_drops.insert(index, dead_drop);
+
+        while self.serialized_len() > DEAD_DROP_CACHE_LEN {
+            self.dead_drops.remove(0);
+        }
+
+        self.expire();
+
+        true
+    }
+
+    /// Remove dead drops older than [`CLIENT_DEAD_DROP_CACHE_TTL`]. The most recent dead drop
+    /// is used as the current time so a wrong local clock can't empty the cache.
+    fn expire(&mut self) {
+        let Some(newest) = self.dead_drops.iter().map(|d| d.created_at).max() else {
+            return;
+        };
+
+        self.dead_drops
+            .retain(|d| newest - d.created_at <= CLIENT_DEAD_DROP_CACHE_TTL);
+    }
+
+    pub fn contains(&self, id: DeadDropId) -> bool {
+        self.dead_drops.iter().any(|d| d.id == id)
+    }
+
+    pub fn max_id(&self) -> Option<DeadDropId> {
+        self.dead_drops.last().map(|d| d.id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dead_drops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dead_drops.is_empty()
+    }
+
+    /// Iterate over the cached dead drops, oldest first.
+    pub fn iter(&self) -> std::slice::Iter<'_, CachedDeadDrop> {
+        self.dead_drops.iter()
+    }
+
+    fn serialized_len(&self) -> usize {
+        size_of::<u32>()
+            + self
+                .dead_drops
+                .iter()
+                .map(CachedDeadDrop::serialized_len)
+                .sum::<usize>()
+    }
+
+    /// Deserialize the cache from a vector containing the encrypted cache data.
+    pub fn deserialize(encrypted_data: Vec<u8>, key: &SecretBoxKey) -> anyhow::Result<Self> {
+        let ciphertext = SecretBox::<Vec<u8>>::from_vec_unchecked(encrypted_data);
+        let plaintext = SecretBox::decrypt(key, ciphertext)?;
+
+        let mut cursor = Cursor::new(plaintext);
+
+        let mut count_buf = [0; size_of::<u32>()];
+        cursor.read_exact(&mut count_buf)?;
+
+        let dead_drops = (0..u32::from_be_bytes(count_buf))
+            .map(|_| CachedDeadDrop::read(&mut cursor))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { dead_drops })
+    }
+
+    pub fn write(&self, writer: &mut impl Write, key: &SecretBoxKey) -> anyhow::Result<()> {
+        let mut buf = Vec::with_capacity(DEAD_DROP_CACHE_LEN);
+
+        buf.write_all(&(self.dead_drops.len() as u32).to_be_bytes())?;
+        for dead_drop in &self.dead_drops {
+            dead_drop.write(&mut buf)?;
+        }
+
+        // Pad so the cache is always the same size
+        buf.resize(DEAD_DROP_CACHE_LEN, 0);
+
+        let ciphertext = SecretBox::encrypt(key, buf)?;
+        writer.write_all(ciphertext.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        crypto::{keys::signing::traits::PublicSigningKey, SECRET_BOX_KEY_LEN},
+        protocol::{
+            constants::JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN, keys::test::generate_protocol_keys,
+        },
+        time,
+    };
+
+    use super::*;
+
+    fn cached_dead_drop(
+        id: DeadDropId,
+        created_at: DateTime<Utc>,
+        message_count: usize,
+    ) -> CachedDeadDrop {
+        let data = SerializedJournalistToUserDeadDropMessages::from_vec_unchecked(vec![
+            id as u8;
+            message_count
+                * JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN
+        ]);
+
+        CachedDeadDrop {
+            id,
+            created_at,
+            data,
+            signature: Signature::from_vec_unchecked(vec![0; SIGNATURE_LENGTH]),
+            verified_at: created_at,
+        }
+    }
+
+    fn ids(cache: &DeadDropCache) -> Vec<DeadDropId> {
+        cache.iter().map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn roundtrips_at_fixed_size() -> anyhow::Result<()> {
+        let now = time::now();
+        let key = SecretBoxKey::from([1; SECRET_BOX_KEY_LEN]);
+        let keys = generate_protocol_keys(now);
+
+        let mut cache = DeadDropCache::new();
+
+        let mut empty = vec![];
+        cache.write(&mut empty, &key)?;
+        assert_eq!(empty.len(), DeadDropCache::SERIALIZED_LEN);
+
+        let data = SerializedJournalistToUserDeadDropMessages::from_vec_unchecked(vec![1; 1024]);
+        let signature = keys
+            .covernode_id_key_pair
+            .sign(&JournalistToUserDeadDropSignatureDataV2::new(&data, now));
+        let dead_drop = UnverifiedJournalistToUserDeadDrop {
+            id: 1,
+            created_at: now,
+            data,
+            signature,
+        };
+        assert!(cache.insert(CachedDeadDrop::new(&dead_drop, now)));
+        assert!(!cache.insert(CachedDeadDrop::new(&dead_drop, now)));
+
+        let mut buf = vec![];
+        cache.write(&mut buf, &key)?;
+        assert_eq!(buf.len(), DeadDropCache::SERIALIZED_LEN);
+
+        let read = DeadDropCache::deserialize(buf.clone(), &key)?;
+        assert_eq!(read, cache);
+
+        // The signature survives the round trip
+        let cached = read.iter().next().unwrap();
+        let signature_data =
+            JournalistToUserDeadDropSignatureDataV2::new(&cached.data, cached.created_at);
+        assert!(keys
+            .covernode_id_pk
+            .verify(&signature_data, &cached.signature, now)
+            .is_ok());
+
+        assert!(
+            DeadDropCache::deserialize(buf, &SecretBoxKey::from([2; SECRET_BOX_KEY_LEN])).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn expires_relative_to_newest_dead_drop() {
+        // Far in the past so expiry can't depend on the local clock
+        let start = time::now() - Duration::days(365);
+
+        let mut cache = DeadDropCache::new();
+        cache.insert(cached_dead_drop(1, start, 1));
+        cache.insert(cached_dead_drop(2, start + Duration::days(7), 1));
+        assert_eq!(ids(&cache), vec![1, 2]);
+
+        cache.insert(cached_dead_drop(
+            3,
+            start + CLIENT_DEAD_DROP_CACHE_TTL + Duration::hours(1),
+            1,
+        ));
+        assert_eq!(ids(&cache), vec![2, 3]);
+
+        // Out of order inserts are kept ordered by ID
+        cache.insert(cached_dead_drop(0, start + Duration::days(8), 1));
+        assert_eq!(ids(&cache), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn evicts_oldest_when_full() {
+        let now = time::now();
+
+        let messages_per_dead_drop = 1000;
+        let mut cache = DeadDropCache::new();
+
+        for id in 0..10 {
+            assert!(cache.insert(cached_dead_drop(id, now, messages_per_dead_drop)));
+        }
+
+        assert!(cache.serialized_len() <= DEAD_DROP_CACHE_LEN);
+        assert_eq!(cache.max_id(), Some(9));
+        assert!(!cache.contains(0));
+        assert!(cache.len() < 10);
+
+        // A single dead drop larger than the cache is rejected rather than emptying it
+        let len_before = cache.len();
+        assert!(!cache.insert(cached_dead_drop(10, now, 5000)));
+        assert_eq!(cache.len(), len_before);
+    }
+}
@@ -1,36 +1,64 @@
+mod conversation_message_buffer;
+mod dead_drop_cache;
 mod plain_mailbox_data;
 mod secret_mailbox_data;
 
 use std::{
-    fs::OpenOptions,
+    collections::HashSet,
+    fs::{File, OpenOptions},
     io::Read,
     path::{Path, PathBuf},
 };
 
 use crate::{
-    api::models::{dead_drops::DeadDropId, journalist_id::JournalistIdentity},
+    api::models::{
+        dead_drops::{DeadDropId, UnverifiedJournalistToUserDeadDropsList},
+        journalist_id::JournalistIdentity,
+        messages::{
+            read_receipt::ReadReceipt, user_to_covernode_message::EncryptedUserToCoverNodeMessage,
+        },
+    },
+    client::private_sending_queue::{
+        PrivateSendingQueue, PrivateSendingQueueSecret, PRIVATE_SENDING_QUEUE_N,
+    },
     crypto::{
         keys::encryption::UnsignedEncryptionKeyPair,
         pbkdf::{derive_secret_box_key_with_configuration, generate_salt, Argon2Configuration},
         SecretBoxKey,
     },
-    protocol::keys::{
-        anchor_org_pk, AnchorOrganizationPublicKey, UntrustedOrganizationPublicKey, UserKeyPair,
+    key_transparency::KnownTreeHeads,
+    message_fragments::{reassemble, split_into_fragments, ReassembledMessage},
+    protocol::{
+        covernode::verify_journalist_to_user_dead_drop,
+        keys::{
+            anchor_org_pk, AnchorOrganizationPublicKey, CoverDropPublicKeyHierarchy,
+            UntrustedOrganizationPublicKey, UserKeyPair,
+        },
+        recipient_tag::RecipientTag,
+        user::{
+            encrypt_key_rotation_from_user_to_journalist_via_covernode,
+            encrypt_read_receipt_from_user_to_journalist_via_covernode,
+            encrypt_real_message_from_user_to_journalist_via_covernode,
+            new_encrypted_cover_message_from_user_via_covernode,
+        },
     },
-    time, FixedBuffer, FixedSizeMessageText,
+    time, FixedSizeMessageText,
 };
 use chrono::{DateTime, Utc};
 use tracing::warn;
 
-use self::{
-    plain_mailbox_data::PlainMailboxData,
-    secret_mailbox_data::{FixedMessageBuffer, SecretMailboxData},
-};
+pub use self::conversation_message_buffer::ConversationMessageBuffer;
+pub use self::dead_drop_cache::{CachedDeadDrop, DeadDropCache, DEAD_DROP_CACHE_LEN};
+use self::{plain_mailbox_data::PlainMailboxData, secret_mailbox_data::SecretMailboxData};
 
-use super::mailbox_message::MailboxMessage;
+use super::{mailbox_message::MailboxMessage, message_sender::MessageSender};
 
 pub const MAX_MAILBOX_MESSAGES: usize = 128;
 
+/// The number of messages each conversation is guaranteed to keep when the mailbox is full
+/// and messages from other conversations are being added.
+pub const MIN_MAILBOX_MESSAGES_PER_CONVERSATION: usize = 16;
+
 /// The mailbox stores private data for a users CoverDrop session
 #[derive(Clone)]
 pub struct UserMailbox {
@@ -42,6 +70,12 @@ pub struct UserMailbox {
     // Both plain and secret data are serialized to disk
     pub plain: PlainMailboxData,
     pub secret: SecretMailboxData,
+
+    // Verified dead drops, stored in their own file next to the mailbox
+    dead_drop_cache: DeadDropCache,
+
+    // Messages evicted to make space for newer ones since the mailbox was loaded
+    evicted_messages: Vec<MailboxMessage>,
 }
 
 impl UserMailbox {
@@ -57,24 +91,12 @@ impl UserMailbox {
         now: DateTime<Utc>,
     ) -> anyhow::Result<Self> {
         let user_key_pair = UnsignedEncryptionKeyPair::generate();
-        let salt = generate_salt();
-        let key =
-            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V1)?;
 
         let org_pks = tofu_org_pk_iter
             .flat_map(|org_pk| anchor_org_pk(&org_pk.to_tofu_anchor(), now))
             .collect::<Vec<AnchorOrganizationPublicKey>>();
 
-        Ok(Self {
-            path: path.as_ref().to_path_buf(),
-            key,
-            secret: SecretMailboxData {
-                user_key_pair,
-                messages: FixedBuffer::default(),
-                max_dead_drop_id: 0,
-            },
-            plain: PlainMailboxData { salt, org_pks },
-        })
+        Self::new_with_keys(password, user_key_pair, org_pks, path)
     }
 
     pub fn new_with_keys(
@@ -83,22 +105,37 @@ impl UserMailbox {
         org_pks: Vec<AnchorOrganizationPublicKey>,
         path: impl AsRef<Path>,
     ) -> anyhow::Result<Self> {
+        let argon2_configuration = Argon2Configuration::LATEST;
         let salt = generate_salt();
-        let key =
-            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V1)?;
+        let key = derive_secret_box_key_with_configuration(password, &salt, argon2_configuration)?;
 
         Ok(Self {
             path: path.as_ref().to_path_buf(),
             key,
             secret: SecretMailboxData {
                 user_key_pair,
-                messages: FixedBuffer::default(),
+                messages: ConversationMessageBuffer::new(),
                 max_dead_drop_id: 0,
+                private_sending_queue_secret: PrivateSendingQueueSecret::generate(),
+                private_sending_queue: None,
+                default_recipient: None,
+                previous_user_key_pair: None,
+                read_by_journalists: vec![],
+                latest_tree_head: None,
             },
-            plain: PlainMailboxData { salt, org_pks },
+            plain: PlainMailboxData {
+                argon2_configuration: Some(argon2_configuration),
+                salt,
+                org_pks,
+            },
+            dead_drop_cache: DeadDropCache::new(),
+            evicted_messages: vec![],
         })
     }
 
+    /// Load a mailbox from disk. If the mailbox key was derived using an outdated Argon2
+    /// configuration the mailbox is transparently re-keyed using the latest configuration
+    /// and written back to disk.
     pub fn load(path: impl AsRef<Path>, password: &str) -> anyhow::Result<Self> {
         let mut file = OpenOptions::new().read(true).open(&path)?;
 
@@ -107,36 +144,121 @@ impl UserMailbox {
         let mut encrypted_data = vec![];
         file.read_to_end(&mut encrypted_data)?;
 
-        // first try to decrypt using the legacy Argon2 configuration
-        let key_v0 = derive_secret_box_key_with_configuration(
-            password,
-            &plain.salt,
-            Argon2Configuration::V0,
-        )?;
-        if let Ok(secret) = SecretMailboxData::deserialize(encrypted_data.clone(), &key_v0) {
-            warn!("Loaded mailbox with legacy Argon2 configuration, consider re-encrypting with new configuration");
-            return Ok(UserMailbox {
+        // Legacy mailboxes don't record their Argon2 configuration so we try the
+        // configurations that were in use before it was stored in the header.
+        let candidate_configurations = match plain.argon2_configuration {
+            Some(configuration) => vec![configuration],
+            None => vec![Argon2Configuration::V0, Argon2Configuration::V1],
+        };
+
+        for configuration in candidate_configurations {
+            let key =
+                derive_secret_box_key_with_configuration(password, &plain.salt, configuration)?;
+
+            let Ok(secret) = SecretMailboxData::deserialize(encrypted_data.clone(), &key) else {
+                continue;
+            };
+
+            let mut mailbox = UserMailbox {
                 path: path.as_ref().to_path_buf(),
-                key: key_v0,
+                key,
                 secret,
-                plain,
-            });
+                plain: PlainMailboxData {
+                    argon2_configuration: Some(configuration),
+                    ..plain
+                },
+                dead_drop_cache: DeadDropCache::new(),
+                evicted_messages: vec![],
+            };
+
+            mailbox.dead_drop_cache = mailbox.load_dead_drop_cache();
+
+            if !configuration.is_latest() {
+                warn!(
+                    "Loaded mailbox with outdated Argon2 configuration {:?}, re-keying with {:?}",
+                    configuration,
+                    Argon2Configuration::LATEST
+                );
+
+                mailbox.rekey(password, Argon2Configuration::LATEST)?;
+                mailbox.save()?;
+            }
+
+            return Ok(mailbox);
         }
 
-        // alternatively we try decrypting using the recent Argon2 configuration
-        let key_v1 = derive_secret_box_key_with_configuration(
-            password,
-            &plain.salt,
-            Argon2Configuration::V1,
-        )?;
-        let secret = SecretMailboxData::deserialize(encrypted_data, &key_v1)?;
+        anyhow::bail!("Failed to decrypt mailbox with the given password")
+    }
 
-        Ok(UserMailbox {
-            path: path.as_ref().to_path_buf(),
-            key: key_v1,
-            secret,
-            plain,
-        })
+    /// The cache is disposable, if it's missing or can't be read the dead drops will be
+    /// downloaded again.
+    fn load_dead_drop_cache(&self) -> DeadDropCache {
+        let path = self.dead_drop_cache_path();
+
+        if !path.exists() {
+            return DeadDropCache::new();
+        }
+
+        match std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|encrypted_data| DeadDropCache::deserialize(encrypted_data, &self.key))
+        {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("Failed to read dead drop cache {}: {}", path.display(), e);
+                DeadDropCache::new()
+            }
+        }
+    }
+
+    /// The dead drop cache is kept in a separate file so the mailbox itself stays small.
+    pub fn dead_drop_cache_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".dead-drops");
+        PathBuf::from(path)
+    }
+
+    /// Derive a new mailbox key from the password using a fresh salt and the given Argon2
+    /// configuration. The new key is used the next time the mailbox is saved.
+    fn rekey(&mut self, password: &str, configuration: Argon2Configuration) -> anyhow::Result<()> {
+        let salt = generate_salt();
+        let key = derive_secret_box_key_with_configuration(password, &salt, configuration)?;
+
+        self.key = key;
+        self.plain.argon2_configuration = Some(configuration);
+        self.plain.salt = salt;
+
+        Ok(())
+    }
+
+    /// Change the password used to unlock the mailbox. A new key is derived using a fresh salt
+    /// and the mailbox is written to a temporary file which then replaces the existing one, so a
+    /// crash part way through leaves the mailbox readable with one of the two passwords.
+    pub fn change_password(&mut self, new_password: &str) -> anyhow::Result<()> {
+        let configuration = Argon2Configuration::LATEST;
+        let salt = generate_salt();
+        let key = derive_secret_box_key_with_configuration(new_password, &salt, configuration)?;
+
+        let plain = PlainMailboxData {
+            argon2_configuration: Some(configuration),
+            salt,
+            ..self.plain.clone()
+        };
+
+        write_atomically(&self.path, |file| {
+            plain.write(file)?;
+            self.secret.write(file, &key)
+        })?;
+
+        // If this fails the cache can't be decrypted next time, so it's rebuilt
+        write_atomically(&self.dead_drop_cache_path(), |file| {
+            self.dead_drop_cache.write(file, &key)
+        })?;
+
+        self.key = key;
+        self.plain = plain;
+
+        Ok(())
     }
 
     pub fn save(&self) -> anyhow::Result<()> {
@@ -149,9 +271,21 @@ impl UserMailbox {
         self.plain.write(&mut file)?;
         self.secret.write(&mut file, &self.key)?;
 
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.dead_drop_cache_path())?;
+
+        self.dead_drop_cache.write(&mut file, &self.key)?;
+
         Ok(())
     }
 
+    pub fn argon2_configuration(&self) -> Option<Argon2Configuration> {
+        self.plain.argon2_configuration
+    }
+
     pub fn org_pks(&self) -> &[AnchorOrganizationPublicKey] {
         &self.plain.org_pks
     }
@@ -160,10 +294,36 @@ impl UserMailbox {
         &self.secret.user_key_pair
     }
 
-    pub fn messages(&self) -> &FixedMessageBuffer {
+    /// The key pair replaced by the most recent call to [`Self::rotate_user_key`], if any
+    pub fn previous_user_key_pair(&self) -> Option<&UserKeyPair> {
+        self.secret.previous_user_key_pair.as_ref()
+    }
+
+    pub fn messages(&self) -> &ConversationMessageBuffer {
         &self.secret.messages
     }
 
+    /// The message that would be evicted from the mailbox if another message in the
+    /// conversation with this journalist was added now. Clients should use this to warn the
+    /// user before sending a message causes them to lose some of their history.
+    pub fn next_eviction(&self, journalist_id: &JournalistIdentity) -> Option<&MailboxMessage> {
+        self.secret
+            .messages
+            .next_eviction(&RecipientTag::from_journalist_id(journalist_id))
+    }
+
+    /// Take the messages which have been evicted from the mailbox to make space for newer
+    /// messages since this was last called.
+    pub fn take_evicted_messages(&mut self) -> Vec<MailboxMessage> {
+        std::mem::take(&mut self.evicted_messages)
+    }
+
+    fn push_message(&mut self, message: MailboxMessage) {
+        if let Some(evicted) = self.secret.messages.push(message) {
+            self.evicted_messages.push(evicted);
+        }
+    }
+
     pub fn add_message_to_journalist_from_user(
         &mut self,
         to: &JournalistIdentity,
@@ -180,7 +340,7 @@ impl UserMailbox {
             None,
         );
 
-        self.secret.messages.push(message);
+        self.push_message(message);
     }
 
     pub fn add_message_to_user_from_journalist(
@@ -200,7 +360,7 @@ impl UserMailbox {
             None,
         );
 
-        self.secret.messages.push(message);
+        self.push_message(message);
     }
 
     pub fn max_dead_drop_id(&self) -> DeadDropId {
@@ -210,6 +370,368 @@ impl UserMailbox {
     pub fn set_max_dead_drop_id(&mut self, id: DeadDropId) {
         self.secret.max_dead_drop_id = id;
     }
+
+    /// The key transparency tree heads the next public keys response is checked against
+    pub fn known_tree_heads(&self) -> KnownTreeHeads {
+        KnownTreeHeads::from_latest(self.secret.latest_tree_head.clone())
+    }
+
+    pub fn set_known_tree_heads(&mut self, known_tree_heads: &KnownTreeHeads) {
+        self.secret.latest_tree_head = known_tree_heads.latest().cloned();
+    }
+
+    pub fn dead_drop_cache(&self) -> &DeadDropCache {
+        &self.dead_drop_cache
+    }
+
+    /// Verify the dead drops which aren't already cached and add them to the cache. Dead drops
+    /// which fail verification are dropped. Returns the number of dead drops added.
+    pub fn cache_dead_drops(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+        dead_drop_list: &UnverifiedJournalistToUserDeadDropsList,
+        now: DateTime<Utc>,
+    ) -> usize {
+        let mut cached = 0;
+
+        for dead_drop in &dead_drop_list.dead_drops {
+            if self.dead_drop_cache.contains(dead_drop.id) {
+                continue;
+            }
+
+            if verify_journalist_to_user_dead_drop(keys, dead_drop, now).is_none() {
+                warn!(
+                    "Failed to verify journalist to user dead drop {}",
+                    dead_drop.id
+                );
+                continue;
+            }
+
+            if self
+                .dead_drop_cache
+                .insert(CachedDeadDrop::new(dead_drop, now))
+            {
+                cached += 1;
+            }
+        }
+
+        cached
+    }
+
+    /// Record the user's messages which a journalist has read. Only messages the user sent to
+    /// that journalist are marked as read.
+    pub fn add_read_receipt(&mut self, from: &JournalistIdentity, read_receipt: &ReadReceipt) {
+        let tag = RecipientTag::from_journalist_id(from);
+
+        let read_ids = self
+            .secret
+            .messages
+            .iter()
+            .filter(|message| {
+                message.is_from_user() && message.journalist_tag().as_ref() == Some(&tag)
+            })
+            .filter(|message| read_receipt.acknowledges(&message.message))
+            .map(|message| ReadReceipt::message_id(&message.message))
+            .collect::<Vec<_>>();
+
+        for id in read_ids {
+            if !self.secret.read_by_journalists.contains(&id) {
+                self.secret.read_by_journalists.push(id);
+            }
+        }
+
+        // Forget about messages which have since been removed from the mailbox
+        let message_ids = self
+            .secret
+            .messages
+            .iter()
+            .map(|message| ReadReceipt::message_id(&message.message))
+            .collect::<HashSet<_>>();
+
+        self.secret
+            .read_by_journalists
+            .retain(|id| message_ids.contains(id));
+    }
+
+    /// Whether the journalist a message was sent to has said they've read it
+    pub fn is_read_by_journalist(&self, message: &MailboxMessage) -> bool {
+        message.is_from_user()
+            && self
+                .secret
+                .read_by_journalists
+                .contains(&ReadReceipt::message_id(&message.message))
+    }
+
+    /// Place a read receipt for the most recent messages received from a journalist in the
+    /// private sending queue. Returns the number of messages acknowledged.
+    pub fn enqueue_read_receipt(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+        to: &JournalistIdentity,
+    ) -> anyhow::Result<usize> {
+        let tag = RecipientTag::from_journalist_id(to);
+
+        let read_receipt = ReadReceipt::for_messages(
+            self.secret
+                .messages
+                .iter()
+                .filter(|message| {
+                    message.is_journalist() && message.journalist_tag().as_ref() == Some(&tag)
+                })
+                .map(|message| &message.message),
+        );
+
+        let acknowledged = read_receipt.message_ids().len();
+        if acknowledged == 0 {
+            return Ok(0);
+        }
+
+        let encrypted = encrypt_read_receipt_from_user_to_journalist_via_covernode(
+            keys,
+            self.secret.user_key_pair.public_key(),
+            to,
+            read_receipt,
+        )?;
+
+        let secret = self.secret.private_sending_queue_secret.clone();
+        self.private_sending_queue(keys)?
+            .enqueue(&secret, encrypted.into())?;
+
+        Ok(acknowledged)
+    }
+
+    /// The journalist messages should be sent to when the user doesn't pick one explicitly
+    pub fn default_recipient(&self) -> Option<&JournalistIdentity> {
+        self.secret.default_recipient.as_ref()
+    }
+
+    pub fn set_default_recipient(&mut self, journalist_id: JournalistIdentity) {
+        self.secret.default_recipient = Some(journalist_id);
+    }
+
+    /// Handle a journalist handing the conversation over to another journalist or desk.
+    /// The default recipient only changes if the handover came from the journalist the user is
+    /// currently talking to, so an old handover can't redirect an unrelated conversation.
+    pub fn hand_over(&mut self, from: &JournalistIdentity, to: &JournalistIdentity) {
+        let is_current_recipient = self
+            .secret
+            .default_recipient
+            .as_ref()
+            .is_none_or(|current| current == from);
+
+        if is_current_recipient {
+            self.secret.default_recipient = Some(to.clone());
+        }
+    }
+
+    /// Encrypt a message to a journalist and place it in the private sending queue. The message
+    /// is sent the next time a message is dequeued using [`Self::dequeue_message`].
+    pub fn enqueue_message_to_journalist(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+        to: &JournalistIdentity,
+        message: &FixedSizeMessageText,
+    ) -> anyhow::Result<()> {
+        let encrypted = encrypt_real_message_from_user_to_journalist_via_covernode(
+            keys,
+            self.secret.user_key_pair.public_key(),
+            to,
+            message.clone(),
+        )?;
+
+        let secret = self.secret.private_sending_queue_secret.clone();
+        self.private_sending_queue(keys)?
+            .enqueue(&secret, encrypted.into())?;
+
+        self.add_message_to_journalist_from_user(to, message);
+        self.secret.default_recipient = Some(to.clone());
+
+        Ok(())
+    }
+
+    /// Place a text for a journalist in the private sending queue, splitting it into fragments
+    /// if it is too long for a single message. Each fragment is sent in its own sending round.
+    /// Returns the number of messages that were queued.
+    pub fn enqueue_text_to_journalist(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+        to: &JournalistIdentity,
+        text: &str,
+    ) -> anyhow::Result<usize> {
+        let fragments = split_into_fragments(text)?;
+
+        if self.pending_message_count() + fragments.len() > PRIVATE_SENDING_QUEUE_N {
+            anyhow::bail!(
+                "Not enough space in the private sending queue for {} message fragments",
+                fragments.len()
+            );
+        }
+
+        for fragment in &fragments {
+            self.enqueue_message_to_journalist(keys, to, fragment)?;
+        }
+
+        Ok(fragments.len())
+    }
+
+    /// The messages in the mailbox with any fragmented messages put back together. Messages
+    /// with fragments which have not arrived yet are marked as partial.
+    pub fn reassembled_messages(&self) -> anyhow::Result<Vec<ReassembledMessage<MailboxMessage>>> {
+        let messages = self
+            .secret
+            .messages
+            .iter()
+            .map(|message| Ok((message.clone(), message.message.to_string()?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let reassembled = reassemble(
+            messages,
+            |(message, _)| (message.from.to_string(), message.to.to_string()),
+            |(_, text)| text,
+        );
+
+        Ok(reassembled
+            .into_iter()
+            .map(|r| ReassembledMessage {
+                message: r.message.0,
+                text: r.text,
+                missing_fragments: r.missing_fragments,
+            })
+            .collect())
+    }
+
+    /// Replace the user's key pair with a freshly generated one and tell every journalist the
+    /// user has a conversation with about the new public key. The key rotation messages are
+    /// placed in the private sending queue after any pending messages, which still carry the
+    /// old key. Returns the journalists who will be told about the new key.
+    pub fn rotate_user_key(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> anyhow::Result<Vec<JournalistIdentity>> {
+        let journalist_ids = self.conversation_journalist_ids(keys);
+
+        if self.pending_message_count() + journalist_ids.len() > PRIVATE_SENDING_QUEUE_N {
+            anyhow::bail!("Not enough space in the private sending queue to rotate the user key");
+        }
+
+        let new_key_pair = UnsignedEncryptionKeyPair::generate();
+
+        let secret = self.secret.private_sending_queue_secret.clone();
+        for journalist_id in &journalist_ids {
+            let encrypted = encrypt_key_rotation_from_user_to_journalist_via_covernode(
+                keys,
+                &self.secret.user_key_pair,
+                new_key_pair.public_key(),
+                journalist_id,
+            )?;
+
+            self.private_sending_queue(keys)?
+                .enqueue(&secret, encrypted.into())?;
+        }
+
+        let previous_key_pair = std::mem::replace(&mut self.secret.user_key_pair, new_key_pair);
+        self.secret.previous_user_key_pair = Some(previous_key_pair);
+
+        Ok(journalist_ids)
+    }
+
+    /// The journalists in the key hierarchy that the user has sent or received messages from.
+    /// The mailbox only stores recipient tags so we check each journalist's tag against them.
+    fn conversation_journalist_ids(
+        &self,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> Vec<JournalistIdentity> {
+        let tags = self
+            .secret
+            .messages
+            .iter()
+            .flat_map(|message| [&message.to, &message.from])
+            .filter_map(|sender| match sender {
+                MessageSender::Journalist(tag) => Some(tag.clone()),
+                MessageSender::User(_) => None,
+            })
+            .collect::<HashSet<_>>();
+
+        keys.latest_journalist_msg_pk_iter()
+            .map(|(journalist_id, _)| journalist_id)
+            .filter(|journalist_id| {
+                tags.contains(&RecipientTag::from_journalist_id(journalist_id))
+                    || self.secret.default_recipient.as_ref() == Some(*journalist_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The next message to send from the private sending queue, without removing it. Only
+    /// dequeue it with [`Self::dequeue_message`] once it has been sent, so that a real message
+    /// isn't lost if sending fails.
+    pub fn peek_message(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> anyhow::Result<EncryptedUserToCoverNodeMessage> {
+        let item = self.private_sending_queue(keys)?.peek().to_vec();
+
+        Ok(EncryptedUserToCoverNodeMessage::from_vec_unchecked(item))
+    }
+
+    /// Take the next message to send from the private sending queue. This is a real message if
+    /// any are pending, otherwise it is a cover message.
+    pub fn dequeue_message(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> anyhow::Result<EncryptedUserToCoverNodeMessage> {
+        let item = self
+            .private_sending_queue(keys)?
+            .dequeue(|| new_cover_item(keys))?;
+
+        Ok(EncryptedUserToCoverNodeMessage::from_vec_unchecked(item))
+    }
+
+    /// The number of real messages waiting in the private sending queue
+    pub fn pending_message_count(&self) -> usize {
+        self.secret
+            .private_sending_queue
+            .as_ref()
+            .map(|queue| queue.real_item_count(&self.secret.private_sending_queue_secret))
+            .unwrap_or(0)
+    }
+
+    /// Get the private sending queue, filling it with cover messages if this mailbox has not
+    /// sent a message before.
+    fn private_sending_queue(
+        &mut self,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> anyhow::Result<&mut PrivateSendingQueue> {
+        let queue = match self.secret.private_sending_queue.take() {
+            Some(queue) => queue,
+            None => PrivateSendingQueue::new_coverdrop(|| new_cover_item(keys))?,
+        };
+
+        Ok(self.secret.private_sending_queue.insert(queue))
+    }
+}
+
+/// Write to a file next to `path` and then move it into place, so that a crash part way through
+/// leaves the old file intact
+fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut file = File::create(&tmp_path)?;
+    write(&mut file)?;
+    file.sync_all()?;
+
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+fn new_cover_item(keys: &CoverDropPublicKeyHierarchy) -> anyhow::Result<Vec<u8>> {
+    Ok(new_encrypted_cover_message_from_user_via_covernode(keys)?.into())
 }
 
 impl Drop for UserMailbox {
@@ -222,15 +744,41 @@ impl Drop for UserMailbox {
 
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeZone as _, Utc};
+    use sodiumoxide::randombytes::randombytes;
     use tempfile::tempdir;
 
     use crate::{
-        api::models::journalist_id::JournalistIdentity,
-        protocol::keys::generate_organization_key_pair, time, FixedSizeMessageText,
+        api::models::{
+            dead_drops::{
+                DeadDropId, JournalistToUserDeadDropSignatureDataV2,
+                SerializedJournalistToUserDeadDropMessages, UnverifiedJournalistToUserDeadDrop,
+                UnverifiedJournalistToUserDeadDropsList,
+            },
+            journalist_id::JournalistIdentity,
+            messages::{
+                read_receipt::ReadReceipt, user_to_covernode_message::UserToCoverNodeMessage,
+            },
+        },
+        crypto::{pbkdf::Argon2Configuration, AnonymousBox},
+        key_transparency::{
+            merkle::{leaf_hash, root},
+            TreeHead,
+        },
+        protocol::{
+            constants::JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN,
+            covernode::decrypt_user_message,
+            keys::{
+                generate_organization_key_pair,
+                test::{generate_protocol_keys, ProtocolKeys},
+            },
+        },
+        time, FixedSizeMessageText,
     };
 
     use super::{
-        plain_mailbox_data::PlainMailboxData, secret_mailbox_data::SecretMailboxData, UserMailbox,
+        plain_mailbox_data::PlainMailboxData, secret_mailbox_data::SecretMailboxData,
+        DeadDropCache, UserMailbox, MAX_MAILBOX_MESSAGES,
     };
 
     const SERIALIZED_MAILBOX_SIZE: u64 =
@@ -326,7 +874,7 @@ mod tests {
     }
 
     #[test]
-    fn overflowing_fixed_buffer_results_in_wrap_around() -> anyhow::Result<()> {
+    fn overflowing_mailbox_evicts_oldest_messages() -> anyhow::Result<()> {
         let now = time::now();
 
         let test_files_dir = tempdir()?;
@@ -344,36 +892,554 @@ mod tests {
         // Insert messages
         let mut mailbox = UserMailbox::load(&test_file_path, "password")?;
 
-        assert_eq!(mailbox.secret.messages.current_index(), 0);
+        assert_eq!(mailbox.messages().count(), 0);
 
         let to = JournalistIdentity::new("journalist")?;
         for i in 0..138 {
+            if i == MAX_MAILBOX_MESSAGES {
+                let next_eviction = mailbox.next_eviction(&to).expect("Mailbox is full");
+                assert_eq!(next_eviction.message.to_string()?, "0");
+            }
+
             let message = FixedSizeMessageText::new(&i.to_string())?;
             mailbox.add_message_to_journalist_from_user(&to, &message);
         }
 
+        let evicted = mailbox
+            .take_evicted_messages()
+            .iter()
+            .map(|m| m.message.to_string())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(evicted, (0..10).map(|i| i.to_string()).collect::<Vec<_>>());
+        assert!(mailbox.take_evicted_messages().is_empty());
+
         mailbox.save()?;
         let file_size = test_file_path.metadata()?.len();
         assert_eq!(file_size, SERIALIZED_MAILBOX_SIZE);
 
-        // Assert we've only got messages 10..138
+        // Assert we've only got messages 10..138, oldest first
         let mailbox = UserMailbox::load(&test_file_path, "password")?;
 
-        assert_eq!(mailbox.secret.messages.current_index(), 138);
+        assert_eq!(mailbox.messages().count(), MAX_MAILBOX_MESSAGES);
 
-        let mut msg_iter = mailbox.secret.messages.iter();
-
-        // Strictly speaking we don't specify that the order is preserved during the wrap around.
-        // But this is convenient for checking all the values we expect to be there are there.
-        for i in 128..138 {
+        let mut msg_iter = mailbox.messages().iter();
+        for i in 10..138 {
             let m = msg_iter.next().unwrap();
             assert_eq!(m.message.to_string()?, i.to_string());
         }
-        for i in 10..128 {
-            let m = msg_iter.next().unwrap();
-            assert_eq!(m.message.to_string()?, i.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn loading_upgrades_outdated_argon2_configurations() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pk = generate_organization_key_pair(now).public_key().clone();
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        assert_eq!(
+            mailbox.argon2_configuration(),
+            Some(Argon2Configuration::LATEST)
+        );
+
+        let user_pk = mailbox.user_key_pair().public_key().clone();
+
+        // V0 -> V2, then V1 -> V2
+        for outdated in [Argon2Configuration::V0, Argon2Configuration::V1] {
+            mailbox.rekey("password", outdated)?;
+            mailbox.save()?;
+
+            let outdated_salt = mailbox.plain.salt.clone();
+            assert_eq!(
+                std::fs::read(&test_file_path)?[0],
+                outdated.as_byte(),
+                "Mailbox should be stored with the outdated configuration"
+            );
+
+            // Dropping also saves the mailbox, so make sure it happens before reloading
+            drop(mailbox);
+
+            let loaded = UserMailbox::load(&test_file_path, "password")?;
+            assert_eq!(
+                loaded.argon2_configuration(),
+                Some(Argon2Configuration::LATEST)
+            );
+            assert_ne!(loaded.plain.salt, outdated_salt);
+            assert_eq!(loaded.user_key_pair().public_key(), &user_pk);
+
+            // The re-keyed mailbox has been written back to disk with the same size
+            assert_eq!(
+                std::fs::read(&test_file_path)?[0],
+                Argon2Configuration::LATEST.as_byte()
+            );
+            assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+            mailbox = loaded;
         }
 
+        let reloaded = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(reloaded.user_key_pair().public_key(), &user_pk);
+        assert_eq!(reloaded.plain.salt, mailbox.plain.salt);
+
+        assert!(UserMailbox::load(&test_file_path, "wrong password").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn loading_upgrades_legacy_header_without_argon2_configuration() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pk = generate_organization_key_pair(now).public_key().clone();
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        let user_pk = mailbox.user_key_pair().public_key().clone();
+        mailbox.rekey("password", Argon2Configuration::V0)?;
+        mailbox.save()?;
+        drop(mailbox);
+
+        // Strip the configuration byte to recreate the legacy layout
+        let bytes = std::fs::read(&test_file_path)?;
+        assert_eq!(bytes[0], Argon2Configuration::V0.as_byte());
+        std::fs::write(&test_file_path, &bytes[1..])?;
+
+        let mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(
+            mailbox.argon2_configuration(),
+            Some(Argon2Configuration::LATEST)
+        );
+        assert_eq!(mailbox.user_key_pair().public_key(), &user_pk);
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn queued_messages_are_persisted_and_sent_before_cover() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk,
+            hierarchy,
+            covernode_msg_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        assert_eq!(mailbox.pending_message_count(), 0);
+
+        let to = JournalistIdentity::new("journalist_0")?;
+        let message = FixedSizeMessageText::new("hello")?;
+        mailbox.enqueue_message_to_journalist(&hierarchy, &to, &message)?;
+        mailbox.save()?;
+        drop(mailbox);
+
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+        let mut mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(mailbox.pending_message_count(), 1);
+
+        // Peeking, e.g. before a send which then fails, leaves the message queued
+        let peeked = mailbox.peek_message(&hierarchy)?;
+        assert_eq!(mailbox.pending_message_count(), 1);
+
+        let dequeued = mailbox.dequeue_message(&hierarchy)?;
+        assert_eq!(peeked.as_bytes(), dequeued.as_bytes());
+
+        let real = decrypt_user_message(&covernode_msg_key_pair, &dequeued)?;
+        assert!(matches!(real, UserToCoverNodeMessage::Real { .. }));
+        assert_eq!(mailbox.pending_message_count(), 0);
+
+        let cover = decrypt_user_message(
+            &covernode_msg_key_pair,
+            &mailbox.dequeue_message(&hierarchy)?,
+        )?;
+        assert!(matches!(cover, UserToCoverNodeMessage::Cover));
+
+        Ok(())
+    }
+
+    #[test]
+    fn long_text_is_queued_as_fragments_and_reassembled() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk, hierarchy, ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+
+        let to = JournalistIdentity::new("journalist_0")?;
+        let text = hex::encode(randombytes(1500));
+
+        let queued = mailbox.enqueue_text_to_journalist(&hierarchy, &to, &text)?;
+        assert!(queued > 1);
+        assert_eq!(mailbox.pending_message_count(), queued);
+        assert_eq!(mailbox.messages().count(), queued);
+
+        let reassembled = mailbox.reassembled_messages()?;
+        assert_eq!(reassembled.len(), 1);
+        assert_eq!(reassembled[0].text, text);
+        assert!(!reassembled[0].is_partial());
+
+        // A text too long to fit in the queue is rejected without queuing anything
+        let too_long = hex::encode(randombytes(3000));
+        assert!(mailbox
+            .enqueue_text_to_journalist(&hierarchy, &to, &too_long)
+            .is_err());
+        assert_eq!(mailbox.pending_message_count(), queued);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hand_over_switches_default_recipient_only_from_current_recipient() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk, hierarchy, ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let journalist_0 = JournalistIdentity::new("journalist_0")?;
+        let journalist_1 = JournalistIdentity::new("journalist_1")?;
+        let desk = JournalistIdentity::new("desk")?;
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        assert_eq!(mailbox.default_recipient(), None);
+
+        let message = FixedSizeMessageText::new("hello")?;
+        mailbox.enqueue_message_to_journalist(&hierarchy, &journalist_0, &message)?;
+        assert_eq!(mailbox.default_recipient(), Some(&journalist_0));
+
+        // A handover from someone the user isn't currently talking to is ignored
+        mailbox.hand_over(&desk, &journalist_1);
+        assert_eq!(mailbox.default_recipient(), Some(&journalist_0));
+
+        mailbox.hand_over(&journalist_0, &journalist_1);
+        assert_eq!(mailbox.default_recipient(), Some(&journalist_1));
+
+        mailbox.save()?;
+        drop(mailbox);
+
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+        let mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(mailbox.default_recipient(), Some(&journalist_1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn change_password_rekeys_mailbox_without_changing_size() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pk = generate_organization_key_pair(now).public_key().clone();
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        mailbox.add_message_to_journalist_from_user(
+            &JournalistIdentity::new("journalist_0")?,
+            &FixedSizeMessageText::new("hello")?,
+        );
+        mailbox.save()?;
+
+        let previous_salt = mailbox.plain.salt.clone();
+        mailbox.change_password("new password")?;
+        assert_ne!(mailbox.plain.salt, previous_salt);
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+        drop(mailbox);
+
+        assert!(UserMailbox::load(&test_file_path, "password").is_err());
+
+        let mailbox = UserMailbox::load(&test_file_path, "new password")?;
+        assert_eq!(mailbox.messages().count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn verified_dead_drops_are_cached_in_fixed_size_file() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk,
+            hierarchy,
+            covernode_id_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let dead_drop = |id: DeadDropId, byte: u8| {
+            let data = SerializedJournalistToUserDeadDropMessages::from_vec_unchecked(vec![
+                byte;
+                JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN
+            ]);
+            let signature = covernode_id_key_pair
+                .sign(&JournalistToUserDeadDropSignatureDataV2::new(&data, now));
+
+            UnverifiedJournalistToUserDeadDrop {
+                id,
+                created_at: now,
+                data,
+                signature,
+            }
+        };
+
+        let mut tampered = dead_drop(2, 2);
+        tampered.data = SerializedJournalistToUserDeadDropMessages::from_vec_unchecked(vec![
+            3;
+            JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN
+        ]);
+
+        let dead_drop_list =
+            UnverifiedJournalistToUserDeadDropsList::new(vec![dead_drop(1, 1), tampered]);
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+        mailbox.save()?;
+
+        let cache_path = mailbox.dead_drop_cache_path();
+        assert_eq!(
+            cache_path.metadata()?.len(),
+            DeadDropCache::SERIALIZED_LEN as u64
+        );
+
+        // Only the dead drop with a valid signature is cached, and only once
+        assert_eq!(
+            mailbox.cache_dead_drops(&hierarchy, &dead_drop_list, now),
+            1
+        );
+        assert_eq!(
+            mailbox.cache_dead_drops(&hierarchy, &dead_drop_list, now),
+            0
+        );
+        assert_eq!(mailbox.dead_drop_cache().max_id(), Some(1));
+
+        mailbox.save()?;
+        drop(mailbox);
+
+        assert_eq!(
+            cache_path.metadata()?.len(),
+            DeadDropCache::SERIALIZED_LEN as u64
+        );
+
+        let mut mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(mailbox.dead_drop_cache().len(), 1);
+
+        // The cache follows the mailbox to its new key
+        mailbox.change_password("new password")?;
+        drop(mailbox);
+
+        let mailbox = UserMailbox::load(&test_file_path, "new password")?;
+        let cached = mailbox.dead_drop_cache().iter().next().unwrap();
+        assert_eq!(cached.data, dead_drop(1, 1).data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotating_user_key_notifies_journalists_in_conversation() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk,
+            hierarchy,
+            covernode_msg_key_pair,
+            journalist_msg_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+
+        // No conversations yet so no one to tell
+        assert!(mailbox.rotate_user_key(&hierarchy)?.is_empty());
+
+        let journalist_id = hierarchy
+            .journalist_id_iter()
+            .next()
+            .expect("Hierarchy contains a journalist")
+            .clone();
+        mailbox.add_message_to_journalist_from_user(
+            &journalist_id,
+            &FixedSizeMessageText::new("hello")?,
+        );
+
+        let previous_pk = mailbox.user_key_pair().public_key().clone();
+        assert_eq!(mailbox.rotate_user_key(&hierarchy)?, vec![journalist_id]);
+        assert_ne!(mailbox.user_key_pair().public_key(), &previous_pk);
+        assert_eq!(mailbox.pending_message_count(), 1);
+
+        mailbox.save()?;
+        drop(mailbox);
+
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+        let mut mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(
+            mailbox
+                .previous_user_key_pair()
+                .map(|key_pair| key_pair.public_key()),
+            Some(&previous_pk)
+        );
+
+        let UserToCoverNodeMessage::Real { payload, .. } = decrypt_user_message(
+            &covernode_msg_key_pair,
+            &mailbox.dequeue_message(&hierarchy)?,
+        )?
+        else {
+            panic!("Expected a real message");
+        };
+
+        let u2j_message = AnonymousBox::decrypt(&journalist_msg_key_pair, &payload)?.to_message();
+        let key_rotation = u2j_message
+            .key_rotation
+            .expect("Message should be a key rotation");
+
+        assert_eq!(&u2j_message.reply_key, mailbox.user_key_pair().public_key());
+        assert_eq!(key_rotation.previous_reply_key, previous_pk);
+        assert!(key_rotation.verify(&u2j_message.reply_key, &journalist_msg_key_pair));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_receipts_are_sent_and_stored() -> anyhow::Result<()> {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk,
+            hierarchy,
+            covernode_msg_key_pair,
+            journalist_msg_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pks = [org_pk.to_untrusted()];
+
+        let journalist_id = hierarchy
+            .journalist_id_iter()
+            .next()
+            .expect("Hierarchy contains a journalist")
+            .clone();
+        let other_journalist_id = JournalistIdentity::new("other_journalist")?;
+
+        let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+
+        // Nothing to acknowledge yet
+        assert_eq!(mailbox.enqueue_read_receipt(&hierarchy, &journalist_id)?, 0);
+
+        let sent = FixedSizeMessageText::new("hello")?;
+        let sent_to_other = FixedSizeMessageText::new("hello")?;
+        let received = FixedSizeMessageText::new("hi")?;
+        mailbox.add_message_to_journalist_from_user(&journalist_id, &sent);
+        mailbox.add_message_to_journalist_from_user(&other_journalist_id, &sent_to_other);
+        mailbox.add_message_to_user_from_journalist(&journalist_id, &received);
+
+        // A journalist can only mark messages sent to them as read
+        let read_receipt = ReadReceipt::for_messages([&sent, &sent_to_other]);
+        mailbox.add_read_receipt(&journalist_id, &read_receipt);
+
+        assert_eq!(mailbox.enqueue_read_receipt(&hierarchy, &journalist_id)?, 1);
+
+        mailbox.save()?;
+        drop(mailbox);
+
+        assert_eq!(test_file_path.metadata()?.len(), SERIALIZED_MAILBOX_SIZE);
+
+        let mut mailbox = UserMailbox::load(&test_file_path, "password")?;
+
+        let read = mailbox
+            .secret
+            .messages
+            .iter()
+            .filter(|message| mailbox.is_read_by_journalist(message))
+            .map(|message| message.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(read, vec![sent]);
+
+        let UserToCoverNodeMessage::Real { payload, .. } = decrypt_user_message(
+            &covernode_msg_key_pair,
+            &mailbox.dequeue_message(&hierarchy)?,
+        )?
+        else {
+            panic!("Expected a real message");
+        };
+
+        let u2j_message = AnonymousBox::decrypt(&journalist_msg_key_pair, &payload)?.to_message();
+        let read_receipt = u2j_message
+            .read_receipt
+            .expect("Message should be a read receipt");
+
+        assert!(read_receipt.acknowledges(&received));
+        assert_eq!(read_receipt.message_ids().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn latest_tree_head_is_persisted() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let test_files_dir = tempdir()?;
+        let test_file_path = test_files_dir.path().join("test-mailbox");
+        let org_pk = generate_organization_key_pair(now).public_key().clone();
+        let org_pks = [org_pk.to_untrusted()];
+
+        let leaves: Vec<_> = (0..4_u8).map(|i| leaf_hash(&[i])).collect();
+        let tree_head = TreeHead::new(4, root(&leaves), Utc.timestamp_opt(1_000, 0).unwrap());
+
+        {
+            let mut mailbox = UserMailbox::new("password", org_pks.iter(), &test_file_path, now)?;
+            assert!(mailbox.known_tree_heads().latest().is_none());
+
+            let mut known_tree_heads = mailbox.known_tree_heads();
+            known_tree_heads.check_and_insert(&tree_head, &[])?;
+            mailbox.set_known_tree_heads(&known_tree_heads);
+
+            mailbox.save()?;
+        }
+
+        assert_eq!(
+            std::fs::metadata(&test_file_path)?.len(),
+            SERIALIZED_MAILBOX_SIZE
+        );
+
+        let mailbox = UserMailbox::load(&test_file_path, "password")?;
+        assert_eq!(mailbox.known_tree_heads().latest(), Some(&tree_head));
+
+        // A different tree of the same size is a fork of the log
+        let mut forked = leaves.clone();
+        forked[0] = leaf_hash(b"substituted key");
+        let forked_tree_head = TreeHead::new(4, root(&forked), now);
+
+        assert!(mailbox
+            .known_tree_heads()
+            .check_and_insert(&forked_tree_head, &[])
+            .is_err());
+
         Ok(())
     }
 }
@@ -4,7 +4,7 @@ use ed25519_dalek::SIGNATURE_LENGTH;
 
 use crate::{
     client::mailbox::message_timestamp::MessageTimestamp,
-    crypto::{keys::Ed25519PublicKey, Signature},
+    crypto::{keys::Ed25519PublicKey, pbkdf::Argon2Configuration, Signature},
     protocol::{constants::ED25519_PUBLIC_KEY_LEN, keys::AnchorOrganizationPublicKey},
     Argon2Salt, Error,
 };
@@ -13,23 +13,45 @@ const BASE_64_ENCODED_RECOMMENDED_SALT_LEN: usize = 22;
 /// Unencrypted data for the mailbox - at the moment this is the same for the user and the journalist
 #[derive(Clone)]
 pub struct PlainMailboxData {
+    /// The Argon2 parameters used to derive the mailbox key from the password and salt.
+    /// This is `None` for mailboxes written before the configuration was stored in the
+    /// header, in which case the configuration has to be found by trial decryption.
+    pub argon2_configuration: Option<Argon2Configuration>,
     pub salt: Argon2Salt,
     pub org_pks: Vec<AnchorOrganizationPublicKey>,
 }
 
 impl PlainMailboxData {
-    pub const SERIALIZED_LEN: usize = 1 // Length of argon2 salt
+    pub const SERIALIZED_LEN: usize = 1 // Argon2 configuration
+     + 1 // Length of argon2 salt
      + BASE_64_ENCODED_RECOMMENDED_SALT_LEN // Argon2 salt
      + ED25519_PUBLIC_KEY_LEN // Trusted organization public key
      + SIGNATURE_LENGTH // Self signed signature
      + MessageTimestamp::SERIALIZED_LEN; // not valid after
 
-    /// Deserialize the plain mailbox data, the first byte is the length of the salt
+    /// Deserialize the plain mailbox data, the first byte is the Argon2 configuration followed
+    /// by the length of the salt.
+    ///
+    /// Legacy mailboxes do not contain the configuration byte and start directly with the
+    /// length of the salt. Since the salt length is never a valid configuration byte we can
+    /// tell the two layouts apart.
     pub fn read(reader: &mut impl io::Read) -> anyhow::Result<Self> {
-        let mut size_buf = [0; 1];
-        reader.read_exact(&mut size_buf)?;
+        let mut first_byte_buf = [0; 1];
+        reader.read_exact(&mut first_byte_buf)?;
 
-        let mut salt_buf = vec![0; size_buf[0] as usize];
+        let (argon2_configuration, salt_len) =
+            if first_byte_buf[0] as usize == BASE_64_ENCODED_RECOMMENDED_SALT_LEN {
+                (None, first_byte_buf[0])
+            } else {
+                let configuration = Argon2Configuration::from_byte(first_byte_buf[0])?;
+
+                let mut size_buf = [0; 1];
+                reader.read_exact(&mut size_buf)?;
+
+                (Some(configuration), size_buf[0])
+            };
+
+        let mut salt_buf = vec![0; salt_len as usize];
         reader.read_exact(salt_buf.as_mut_slice())?;
 
         let salt = Argon2Salt::from_b64(std::str::from_utf8(&salt_buf)?)
@@ -51,6 +73,7 @@ impl PlainMailboxData {
         let org_pk = vec![org_pk];
 
         Ok(PlainMailboxData {
+            argon2_configuration,
             salt,
             org_pks: org_pk,
         })
@@ -62,6 +85,11 @@ impl PlainMailboxData {
     {
         let before = writer.stream_position()?;
 
+        let Some(argon2_configuration) = self.argon2_configuration else {
+            anyhow::bail!("Cannot write mailbox header without an Argon2 configuration");
+        };
+        writer.write_all(&[argon2_configuration.as_byte()])?;
+
         let salt_bytes = self.salt.as_str().as_bytes();
         assert_eq!(salt_bytes.len(), BASE_64_ENCODED_RECOMMENDED_SALT_LEN);
 
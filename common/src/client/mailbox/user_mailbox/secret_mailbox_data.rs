@@ -3,9 +3,18 @@ use std::{
     mem::size_of,
 };
 
+use chrono::DateTime;
+
 use crate::{
-    api::models::dead_drops::DeadDropId,
-    client::mailbox::mailbox_message::MailboxMessage,
+    api::models::{
+        dead_drops::DeadDropId,
+        journalist_id::{JournalistIdentity, MAX_JOURNALIST_IDENTITY_LEN},
+        messages::read_receipt::ReadReceiptMessageId,
+    },
+    client::private_sending_queue::{
+        PrivateSendingQueue, PrivateSendingQueueSecret, PRIVATE_SENDING_QUEUE_ITEM_LEN,
+        PRIVATE_SENDING_QUEUE_N, PRIVATE_SENDING_QUEUE_SECRET_LEN,
+    },
     crypto::{
         keys::{
             encryption::{traits::PublicEncryptionKey, UnsignedEncryptionKeyPair},
@@ -13,30 +22,61 @@ use crate::{
         },
         SecretBox, SecretBoxKey, SECRET_BOX_FOOTER_LEN,
     },
+    key_transparency::{merkle::MerkleHash, TreeHead},
     protocol::{
-        constants::{X25519_PUBLIC_KEY_LEN, X25519_SECRET_KEY_LEN},
+        constants::{READ_RECEIPT_MESSAGE_ID_LEN, X25519_PUBLIC_KEY_LEN, X25519_SECRET_KEY_LEN},
         keys::UserKeyPair,
     },
-    FixedBuffer,
 };
 
-use super::MAX_MAILBOX_MESSAGES;
+use super::{conversation_message_buffer::ConversationMessageBuffer, MAX_MAILBOX_MESSAGES};
 
-pub type FixedMessageBuffer = FixedBuffer<MailboxMessage, MAX_MAILBOX_MESSAGES>;
+const PRIVATE_SENDING_QUEUE_SERIALIZED_LEN: usize =
+    PrivateSendingQueue::serialized_len(PRIVATE_SENDING_QUEUE_N, PRIVATE_SENDING_QUEUE_ITEM_LEN);
 
 #[derive(Clone)]
 pub struct SecretMailboxData {
     pub user_key_pair: UserKeyPair,
     pub max_dead_drop_id: DeadDropId,
-    pub messages: FixedMessageBuffer,
+    pub messages: ConversationMessageBuffer,
+    pub private_sending_queue_secret: PrivateSendingQueueSecret,
+    /// The queue is filled with cover messages, which requires the public key hierarchy, so it
+    /// is `None` until the first time a message is sent from this mailbox.
+    pub private_sending_queue: Option<PrivateSendingQueue>,
+    /// The journalist messages are sent to when no recipient is given, this is the last journalist
+    /// the user wrote to unless the conversation has since been handed over to someone else.
+    pub default_recipient: Option<JournalistIdentity>,
+    /// The key pair replaced by the most recent key rotation. Kept so that replies which were
+    /// sent before the journalist learnt about the new key can still be decrypted.
+    pub previous_user_key_pair: Option<UserKeyPair>,
+    /// The read receipt IDs of messages sent by the user which a journalist has said they've
+    /// read. Only IDs of messages still in the mailbox are kept, oldest first.
+    pub read_by_journalists: Vec<ReadReceiptMessageId>,
+    /// The newest key transparency tree head this mailbox has accepted. Every tree head the API
+    /// returns afterwards must be consistent with it.
+    pub latest_tree_head: Option<TreeHead>,
 }
 
 impl SecretMailboxData {
     pub const SERIALIZED_LEN: usize = SECRET_BOX_FOOTER_LEN
         + X25519_PUBLIC_KEY_LEN // User public key
         + X25519_SECRET_KEY_LEN // User secret key
-        + FixedMessageBuffer::SERIALIZED_LEN  // Mailbox messages
-        + size_of::<DeadDropId>(); // Next dead drop ID
+        + ConversationMessageBuffer::SERIALIZED_LEN  // Mailbox messages
+        + size_of::<DeadDropId>() // Next dead drop ID
+        + PRIVATE_SENDING_QUEUE_SECRET_LEN // Private sending queue secret
+        + 1 // Private sending queue present flag
+        + PRIVATE_SENDING_QUEUE_SERIALIZED_LEN // Private sending queue
+        + 1 // Default recipient length
+        + MAX_JOURNALIST_IDENTITY_LEN // Default recipient
+        + 1 // Previous user key pair present flag
+        + X25519_PUBLIC_KEY_LEN // Previous user public key
+        + X25519_SECRET_KEY_LEN // Previous user secret key
+        + size_of::<u16>() // Read by journalists count
+        + MAX_MAILBOX_MESSAGES * READ_RECEIPT_MESSAGE_ID_LEN // Read by journalists
+        + 1 // Latest tree head present flag
+        + size_of::<u64>() // Latest tree head size
+        + size_of::<MerkleHash>() // Latest tree head root hash
+        + size_of::<i64>(); // Latest tree head timestamp
 
     /// Deserialize secret data from a vector containing *only* the encrypted secret mailbox data.
     pub fn deserialize(
@@ -58,16 +98,138 @@ impl SecretMailboxData {
 
         let user_key_pair = UnsignedEncryptionKeyPair::from_raw_keys(user_pk, user_sk);
 
-        let messages = FixedMessageBuffer::read(&mut cursor)?;
+        let messages = ConversationMessageBuffer::read(&mut cursor)?;
 
         let mut max_dead_drop_id_buf = [0; size_of::<DeadDropId>()];
         cursor.read_exact(&mut max_dead_drop_id_buf)?;
         let max_dead_drop_id = DeadDropId::from_be_bytes(max_dead_drop_id_buf);
 
+        // Mailboxes written before the private sending queue was added end here
+        let (private_sending_queue_secret, private_sending_queue) =
+            if cursor.position() as usize == cursor.get_ref().len() {
+                (PrivateSendingQueueSecret::generate(), None)
+            } else {
+                let mut secret_buf = [0; PRIVATE_SENDING_QUEUE_SECRET_LEN];
+                cursor.read_exact(&mut secret_buf)?;
+                let secret = PrivateSendingQueueSecret::from_bytes(secret_buf);
+
+                let mut present_buf = [0; 1];
+                cursor.read_exact(&mut present_buf)?;
+
+                let mut queue_buf = vec![0; PRIVATE_SENDING_QUEUE_SERIALIZED_LEN];
+                cursor.read_exact(&mut queue_buf)?;
+
+                let queue = match present_buf[0] {
+                    0 => None,
+                    _ => Some(PrivateSendingQueue::from_bytes(&queue_buf)?),
+                };
+
+                (secret, queue)
+            };
+
+        // Mailboxes written before the default recipient was added end here
+        let default_recipient = if cursor.position() as usize == cursor.get_ref().len() {
+            None
+        } else {
+            let mut len_buf = [0; 1];
+            cursor.read_exact(&mut len_buf)?;
+
+            let mut id_buf = [0; MAX_JOURNALIST_IDENTITY_LEN];
+            cursor.read_exact(&mut id_buf)?;
+
+            match len_buf[0] as usize {
+                0 => None,
+                len => {
+                    let id = std::str::from_utf8(&id_buf[..len])?;
+                    Some(JournalistIdentity::new(id)?)
+                }
+            }
+        };
+
+        // Mailboxes written before key rotation was added end here
+        let previous_user_key_pair = if cursor.position() as usize == cursor.get_ref().len() {
+            None
+        } else {
+            let mut present_buf = [0; 1];
+            cursor.read_exact(&mut present_buf)?;
+
+            let mut pk_buf = [0; X25519_PUBLIC_KEY_LEN];
+            cursor.read_exact(&mut pk_buf)?;
+
+            let mut sk_buf = [0; X25519_SECRET_KEY_LEN];
+            cursor.read_exact(&mut sk_buf)?;
+
+            match present_buf[0] {
+                0 => None,
+                _ => Some(UnsignedEncryptionKeyPair::from_raw_keys(
+                    X25519PublicKey::from(pk_buf),
+                    X25519SecretKey::from(sk_buf),
+                )),
+            }
+        };
+
+        // Mailboxes written before read receipts were added end here
+        let read_by_journalists = if cursor.position() as usize == cursor.get_ref().len() {
+            vec![]
+        } else {
+            let mut count_buf = [0; size_of::<u16>()];
+            cursor.read_exact(&mut count_buf)?;
+
+            let mut ids_buf = [0; MAX_MAILBOX_MESSAGES * READ_RECEIPT_MESSAGE_ID_LEN];
+            cursor.read_exact(&mut ids_buf)?;
+
+            ids_buf
+                .chunks_exact(READ_RECEIPT_MESSAGE_ID_LEN)
+                .take(u16::from_be_bytes(count_buf) as usize)
+                .map(|chunk| {
+                    let mut id = [0; READ_RECEIPT_MESSAGE_ID_LEN];
+                    id.copy_from_slice(chunk);
+                    ReadReceiptMessageId::from_be_bytes(id)
+                })
+                .collect()
+        };
+
+        // Mailboxes written before key transparency was added end here
+        let latest_tree_head = if cursor.position() as usize == cursor.get_ref().len() {
+            None
+        } else {
+            let mut present_buf = [0; 1];
+            cursor.read_exact(&mut present_buf)?;
+
+            let mut tree_size_buf = [0; size_of::<u64>()];
+            cursor.read_exact(&mut tree_size_buf)?;
+
+            let mut root_hash_buf = [0; size_of::<MerkleHash>()];
+            cursor.read_exact(&mut root_hash_buf)?;
+
+            let mut timestamp_buf = [0; size_of::<i64>()];
+            cursor.read_exact(&mut timestamp_buf)?;
+
+            match present_buf[0] {
+                0 => None,
+                _ => {
+                    let timestamp = DateTime::from_timestamp(i64::from_be_bytes(timestamp_buf), 0)
+                        .ok_or_else(|| anyhow::anyhow!("Invalid tree head timestamp"))?;
+
+                    Some(TreeHead::new(
+                        u64::from_be_bytes(tree_size_buf),
+                        MerkleHash::from_bytes(&root_hash_buf)?,
+                        timestamp,
+                    ))
+                }
+            }
+        };
+
         Ok(Self {
             user_key_pair,
             messages,
             max_dead_drop_id,
+            private_sending_queue_secret,
+            private_sending_queue,
+            default_recipient,
+            previous_user_key_pair,
+            read_by_journalists,
+            latest_tree_head,
         })
     }
 
@@ -75,7 +237,19 @@ impl SecretMailboxData {
     where
         W: Write + Seek,
     {
-        // Fill plaintext buffer
+        let ciphertext = SecretBox::encrypt(key, self.to_plaintext()?)?;
+
+        let before = writer.stream_position()?;
+        writer.write_all(ciphertext.as_bytes())?;
+        let after = writer.stream_position()?;
+        assert_eq!((after - before) as usize, Self::SERIALIZED_LEN);
+
+        Ok(())
+    }
+
+    /// Serialize the secret data without encrypting it. The ciphertext changes every time the
+    /// mailbox is saved so the test vectors use this to check the layout hasn't changed.
+    pub fn to_plaintext(&self) -> anyhow::Result<Vec<u8>> {
         let mut buf = Cursor::new(vec![]);
 
         buf.write_all(self.user_key_pair.public_key().as_bytes())?;
@@ -87,14 +261,73 @@ impl SecretMailboxData {
 
         buf.write_all(self.max_dead_drop_id.to_be_bytes().as_ref())?;
 
-        // Encrypt
-        let ciphertext = SecretBox::encrypt(key, buf.into_inner())?;
+        buf.write_all(self.private_sending_queue_secret.as_bytes())?;
 
-        let before = writer.stream_position()?;
-        writer.write_all(ciphertext.as_bytes())?;
-        let after = writer.stream_position()?;
-        assert_eq!((after - before) as usize, Self::SERIALIZED_LEN);
+        // Always write the full length of the queue so the size of the mailbox doesn't reveal
+        // whether the user has ever sent a message
+        match &self.private_sending_queue {
+            Some(queue) => {
+                buf.write_all(&[1])?;
+                queue.write(&mut buf)?;
+            }
+            None => {
+                buf.write_all(&[0])?;
+                buf.write_all(&[0; PRIVATE_SENDING_QUEUE_SERIALIZED_LEN])?;
+            }
+        }
 
-        Ok(())
+        // Padded to the maximum identity length for the same reason
+        let mut default_recipient_buf = [0; MAX_JOURNALIST_IDENTITY_LEN];
+        match &self.default_recipient {
+            Some(journalist_id) => {
+                default_recipient_buf[..journalist_id.len()]
+                    .copy_from_slice(journalist_id.as_bytes());
+                buf.write_all(&[journalist_id.len() as u8])?;
+            }
+            None => buf.write_all(&[0])?,
+        }
+        buf.write_all(&default_recipient_buf)?;
+
+        match &self.previous_user_key_pair {
+            Some(key_pair) => {
+                buf.write_all(&[1])?;
+                buf.write_all(key_pair.public_key().as_bytes())?;
+                buf.write_all(&key_pair.secret_key().to_bytes())?;
+            }
+            None => {
+                buf.write_all(&[0])?;
+                buf.write_all(&[0; X25519_PUBLIC_KEY_LEN + X25519_SECRET_KEY_LEN])?;
+            }
+        }
+
+        // Only the most recent IDs are written if there are more than there are messages
+        let skip = self
+            .read_by_journalists
+            .len()
+            .saturating_sub(MAX_MAILBOX_MESSAGES);
+        let mut read_by_journalists_buf = [0; MAX_MAILBOX_MESSAGES * READ_RECEIPT_MESSAGE_ID_LEN];
+        for (id, chunk) in self.read_by_journalists[skip..]
+            .iter()
+            .zip(read_by_journalists_buf.chunks_exact_mut(READ_RECEIPT_MESSAGE_ID_LEN))
+        {
+            chunk.copy_from_slice(&id.to_be_bytes());
+        }
+        buf.write_all(&((self.read_by_journalists.len() - skip) as u16).to_be_bytes())?;
+        buf.write_all(&read_by_journalists_buf)?;
+
+        match &self.latest_tree_head {
+            Some(tree_head) => {
+                buf.write_all(&[1])?;
+                buf.write_all(&tree_head.tree_size.to_be_bytes())?;
+                buf.write_all(tree_head.root_hash.as_bytes())?;
+                buf.write_all(&tree_head.timestamp.timestamp().to_be_bytes())?;
+            }
+            None => {
+                buf.write_all(&[0])?;
+                buf.write_all(&[0; size_of::<u64>() + size_of::<MerkleHash>() + size_of::<i64>()])?;
+            }
+        }
+
+        Ok(buf.into_inner())
     }
 }
@@ -1,6 +1,7 @@
 mod journalist_profile;
 pub mod mailbox;
+pub mod private_sending_queue;
 mod verified_keys_and_journalist_profiles;
 
-pub use journalist_profile::{JournalistProfile, JournalistStatus};
+pub use journalist_profile::{JournalistProfile, JournalistStatus, ProfileMatch};
 pub use verified_keys_and_journalist_profiles::VerifiedKeysAndJournalistProfiles;
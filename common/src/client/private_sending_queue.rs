@@ -0,0 +1,383 @@
+//! Rust reference implementation of the `PrivateSendingQueue` used by the mobile apps.
+//!
+//! The queue always holds exactly `n` items. Real items are placed in front of cover items, and
+//! only a caller that knows the [`PrivateSendingQueueSecret`] can tell how many of the items are
+//! real. Every time a message is due to be sent the front item is dequeued and the queue is
+//! topped up with a fresh cover item, so an observer with access to a single snapshot of the
+//! queue cannot tell whether the user has any pending messages.
+//!
+//! The serialized format matches `PrivateSendingQueue.kt` so that the test vectors created by
+//! `admin generate-test-vectors` can be checked by the Android and iOS implementations.
+
+use std::io::{Cursor, Read, Write};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::protocol::constants::USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN;
+
+/// The number of items in the CoverDrop user's sending queue
+pub const PRIVATE_SENDING_QUEUE_N: usize = 8;
+
+/// The size of the items in the CoverDrop user's sending queue
+pub const PRIVATE_SENDING_QUEUE_ITEM_LEN: usize = USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN;
+
+/// Set to match our security level of 128 bits
+pub const PRIVATE_SENDING_QUEUE_SECRET_LEN: usize = 16;
+
+/// Set to match our security level of 128 bits
+pub const PRIVATE_SENDING_QUEUE_HINT_LEN: usize = 16;
+
+/// Length of the `n` and `item_size` header fields
+const HEADER_LEN: usize = 2 * size_of::<u32>();
+
+/// Secret used to derive the [`PrivateSendingQueueHint`] for real items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateSendingQueueSecret([u8; PRIVATE_SENDING_QUEUE_SECRET_LEN]);
+
+impl PrivateSendingQueueSecret {
+    pub fn generate() -> Self {
+        let mut bytes = [0; PRIVATE_SENDING_QUEUE_SECRET_LEN];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_bytes(bytes: [u8; PRIVATE_SENDING_QUEUE_SECRET_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PRIVATE_SENDING_QUEUE_SECRET_LEN] {
+        &self.0
+    }
+
+    /// The truncated HMAC-SHA256 of `item`, real items are stored alongside this hint while
+    /// cover items are stored alongside a random hint.
+    fn hint(&self, item: &[u8]) -> PrivateSendingQueueHint {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts any key size");
+        mac.update(item);
+        let tag = mac.finalize().into_bytes();
+
+        let mut hint = [0; PRIVATE_SENDING_QUEUE_HINT_LEN];
+        hint.copy_from_slice(&tag[..PRIVATE_SENDING_QUEUE_HINT_LEN]);
+        PrivateSendingQueueHint(hint)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrivateSendingQueueHint([u8; PRIVATE_SENDING_QUEUE_HINT_LEN]);
+
+impl PrivateSendingQueueHint {
+    fn random() -> Self {
+        let mut bytes = [0; PRIVATE_SENDING_QUEUE_HINT_LEN];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PRIVATE_SENDING_QUEUE_HINT_LEN] {
+        &self.0
+    }
+}
+
+/// A fixed size queue of real and cover items, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateSendingQueue {
+    n: usize,
+    item_size: usize,
+    items: Vec<Vec<u8>>,
+    hints: Vec<PrivateSendingQueueHint>,
+}
+
+impl PrivateSendingQueue {
+    /// Create a queue of `n` items which is initially filled with cover items.
+    pub fn new(
+        n: usize,
+        item_size: usize,
+        mut create_cover_item: impl FnMut() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        let mut queue = Self {
+            n,
+            item_size,
+            items: Vec::with_capacity(n),
+            hints: Vec::with_capacity(n),
+        };
+
+        for _ in 0..n {
+            queue.push_cover_item(&mut create_cover_item)?;
+        }
+
+        queue.check_invariants()?;
+        Ok(queue)
+    }
+
+    /// Create a queue with the parameters used by CoverDrop users.
+    pub fn new_coverdrop(
+        create_cover_item: impl FnMut() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<Self> {
+        Self::new(
+            PRIVATE_SENDING_QUEUE_N,
+            PRIVATE_SENDING_QUEUE_ITEM_LEN,
+            create_cover_item,
+        )
+    }
+
+    /// The length of a serialized queue with the given parameters
+    pub const fn serialized_len(n: usize, item_size: usize) -> usize {
+        HEADER_LEN + n * (item_size + PRIVATE_SENDING_QUEUE_HINT_LEN)
+    }
+
+    /// The number of real items at the front of the queue. This requires the same `secret` to
+    /// have been used for every call to [`Self::enqueue`].
+    pub fn real_item_count(&self, secret: &PrivateSendingQueueSecret) -> usize {
+        self.items
+            .iter()
+            .zip(&self.hints)
+            .take_while(|(item, hint)| secret.hint(item) == **hint)
+            .count()
+    }
+
+    /// Place a real item after any other real items, replacing the front-most cover item.
+    ///
+    /// If a different `secret` was used for earlier calls then those items are not recognized
+    /// as real and may be overwritten.
+    pub fn enqueue(
+        &mut self,
+        secret: &PrivateSendingQueueSecret,
+        item: Vec<u8>,
+    ) -> anyhow::Result<PrivateSendingQueueHint> {
+        if item.len() != self.item_size {
+            anyhow::bail!(
+                "Private sending queue item has length {}, expected {}",
+                item.len(),
+                self.item_size
+            );
+        }
+
+        let real_item_count = self.real_item_count(secret);
+        if real_item_count >= self.n {
+            anyhow::bail!("The private sending queue is full");
+        }
+
+        let hint = secret.hint(&item);
+        self.items[real_item_count] = item;
+        self.hints[real_item_count] = hint;
+
+        self.check_invariants()?;
+        Ok(hint)
+    }
+
+    /// Remove the front-most item, which is the oldest real item if there is one, and top the
+    /// queue back up with a new cover item.
+    pub fn dequeue(
+        &mut self,
+        mut create_cover_item: impl FnMut() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<Vec<u8>> {
+        // Top up before removing the front item so the queue is left unchanged on failure
+        self.push_cover_item(&mut create_cover_item)?;
+
+        let item = self.items.remove(0);
+        self.hints.remove(0);
+
+        self.check_invariants()?;
+        Ok(item)
+    }
+
+    /// The front-most item without removing it
+    pub fn peek(&self) -> &[u8] {
+        &self.items[0]
+    }
+
+    /// Dequeue every real item so that the queue only contains cover items.
+    pub fn clear(
+        &mut self,
+        secret: &PrivateSendingQueueSecret,
+        mut create_cover_item: impl FnMut() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        for _ in 0..self.real_item_count(secret) {
+            self.dequeue(&mut create_cover_item)?;
+        }
+
+        Ok(())
+    }
+
+    /// All current hints, these can be compared against the hints returned from
+    /// [`Self::enqueue`] to check if a message is still pending.
+    pub fn hints(&self) -> &[PrivateSendingQueueHint] {
+        &self.hints
+    }
+
+    pub fn read(reader: &mut impl Read) -> anyhow::Result<Self> {
+        let mut u32_buf = [0; size_of::<u32>()];
+
+        reader.read_exact(&mut u32_buf)?;
+        let n = u32::from_be_bytes(u32_buf) as usize;
+
+        reader.read_exact(&mut u32_buf)?;
+        let item_size = u32::from_be_bytes(u32_buf) as usize;
+
+        let items = (0..n)
+            .map(|_| {
+                let mut item = vec![0; item_size];
+                reader.read_exact(&mut item)?;
+                Ok(item)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let hints = (0..n)
+            .map(|_| {
+                let mut hint = [0; PRIVATE_SENDING_QUEUE_HINT_LEN];
+                reader.read_exact(&mut hint)?;
+                Ok(PrivateSendingQueueHint(hint))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let queue = Self {
+            n,
+            item_size,
+            items,
+            hints,
+        };
+
+        queue.check_invariants()?;
+        Ok(queue)
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> anyhow::Result<()> {
+        writer.write_all(&(self.n as u32).to_be_bytes())?;
+        writer.write_all(&(self.item_size as u32).to_be_bytes())?;
+
+        for item in &self.items {
+            writer.write_all(item)?;
+        }
+
+        for hint in &self.hints {
+            writer.write_all(&hint.0)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut cursor = Cursor::new(bytes);
+        let queue = Self::read(&mut cursor)?;
+
+        if cursor.position() as usize != bytes.len() {
+            anyhow::bail!("Trailing bytes after private sending queue");
+        }
+
+        Ok(queue)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::serialized_len(self.n, self.item_size));
+        self.write(&mut buf)
+            .expect("Writing to a Vec should not fail");
+        buf
+    }
+
+    fn push_cover_item(
+        &mut self,
+        create_cover_item: &mut impl FnMut() -> anyhow::Result<Vec<u8>>,
+    ) -> anyhow::Result<()> {
+        let item = create_cover_item()?;
+        if item.len() != self.item_size {
+            anyhow::bail!(
+                "Private sending queue cover item has length {}, expected {}",
+                item.len(),
+                self.item_size
+            );
+        }
+
+        self.items.push(item);
+        self.hints.push(PrivateSendingQueueHint::random());
+
+        Ok(())
+    }
+
+    fn check_invariants(&self) -> anyhow::Result<()> {
+        if self.items.len() != self.n || self.hints.len() != self.n {
+            anyhow::bail!("Private sending queue does not contain {} items", self.n);
+        }
+
+        if self.items.iter().any(|item| item.len() != self.item_size) {
+            anyhow::bail!("Private sending queue contains an item of the wrong length");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 4;
+    const ITEM_SIZE: usize = 8;
+
+    fn cover_item() -> anyhow::Result<Vec<u8>> {
+        Ok(vec![0; ITEM_SIZE])
+    }
+
+    fn real_item(i: u8) -> Vec<u8> {
+        vec![i; ITEM_SIZE]
+    }
+
+    #[test]
+    fn real_items_are_dequeued_in_order_before_cover_items() -> anyhow::Result<()> {
+        let secret = PrivateSendingQueueSecret::generate();
+        let mut queue = PrivateSendingQueue::new(N, ITEM_SIZE, cover_item)?;
+
+        assert_eq!(queue.real_item_count(&secret), 0);
+
+        queue.enqueue(&secret, real_item(1))?;
+        queue.enqueue(&secret, real_item(2))?;
+        assert_eq!(queue.real_item_count(&secret), 2);
+
+        assert_eq!(queue.dequeue(cover_item)?, real_item(1));
+        assert_eq!(queue.dequeue(cover_item)?, real_item(2));
+        assert_eq!(queue.dequeue(cover_item)?, cover_item()?);
+        assert_eq!(queue.real_item_count(&secret), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn enqueue_fails_when_full() -> anyhow::Result<()> {
+        let secret = PrivateSendingQueueSecret::generate();
+        let mut queue = PrivateSendingQueue::new(N, ITEM_SIZE, cover_item)?;
+
+        for i in 0..N {
+            queue.enqueue(&secret, real_item(i as u8 + 1))?;
+        }
+
+        assert!(queue.enqueue(&secret, real_item(9)).is_err());
+
+        queue.clear(&secret, cover_item)?;
+        assert_eq!(queue.real_item_count(&secret), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialization_roundtrips_and_has_fixed_size() -> anyhow::Result<()> {
+        let secret = PrivateSendingQueueSecret::generate();
+        let mut queue = PrivateSendingQueue::new(N, ITEM_SIZE, cover_item)?;
+
+        let empty_bytes = queue.to_bytes();
+        assert_eq!(
+            empty_bytes.len(),
+            PrivateSendingQueue::serialized_len(N, ITEM_SIZE)
+        );
+
+        queue.enqueue(&secret, real_item(1))?;
+
+        let bytes = queue.to_bytes();
+        assert_eq!(bytes.len(), empty_bytes.len());
+
+        let deserialized = PrivateSendingQueue::from_bytes(&bytes)?;
+        assert_eq!(deserialized, queue);
+        assert_eq!(deserialized.real_item_count(&secret), 1);
+
+        Ok(())
+    }
+}
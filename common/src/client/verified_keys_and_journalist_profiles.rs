@@ -1,29 +1,47 @@
 use chrono::{DateTime, Utc};
 
 use crate::{
-    api::models::{
-        journalist_id::JournalistIdentity,
-        untrusted_keys_and_journalist_profiles::UntrustedKeysAndJournalistProfiles,
+    api::{
+        api_client::ApiClient,
+        models::{
+            journalist_id::JournalistIdentity,
+            untrusted_keys_and_journalist_profiles::UntrustedKeysAndJournalistProfiles,
+        },
     },
+    backup::secret_sharing_settings::{SecretSharingSettings, SignedSecretSharingSettings},
     epoch::Epoch,
+    key_transparency::{
+        merkle::MerkleHash, KnownTreeHeads, TreeHead, UntrustedKeyTransparencyProof,
+    },
     protocol::keys::{AnchorOrganizationPublicKey, OrganizationPublicKeyFamilyList},
+    system::message_retention::{MessageRetentionLimit, SignedMessageRetentionLimit},
+    Error,
 };
 
-use super::JournalistProfile;
+use super::{JournalistProfile, JournalistStatus, ProfileMatch};
 
 pub struct VerifiedKeysAndJournalistProfiles {
     pub journalist_profiles: Vec<JournalistProfile>,
     pub default_journalist_id: Option<JournalistIdentity>,
     pub keys: OrganizationPublicKeyFamilyList,
     pub max_epoch: Epoch,
+    /// The key transparency proof, `None` if the API isn't serving one yet
+    pub key_transparency: Option<UntrustedKeyTransparencyProof>,
+    /// The signed secret sharing settings, only kept if signed by a backup identity key in `keys`
+    pub backup_secret_sharing: Option<SignedSecretSharingSettings>,
+    /// The signed message retention limit, only kept if signed by an admin key whose parent is
+    /// an organization key in `keys`
+    pub message_retention_limit: Option<SignedMessageRetentionLimit>,
 }
 
 impl VerifiedKeysAndJournalistProfiles {
+    /// Verify the key hierarchy and the data signed by keys within it. Fails if the key
+    /// transparency proof doesn't show that every key in the hierarchy is in the log.
     pub fn from_untrusted(
         untrusted: UntrustedKeysAndJournalistProfiles,
         anchor_org_pks: &[AnchorOrganizationPublicKey],
         now: DateTime<Utc>,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let keys =
             OrganizationPublicKeyFamilyList::from_untrusted(untrusted.keys, anchor_org_pks, now);
 
@@ -33,20 +51,161 @@ impl VerifiedKeysAndJournalistProfiles {
                 .any(|journalist_with_key_id| journalist_with_key_id == id)
         });
 
-        Self {
+        if let Some(proof) = &untrusted.key_transparency {
+            proof.verify(&keys, now)?;
+        }
+
+        let backup_secret_sharing = untrusted.backup_secret_sharing.filter(|signed| {
+            signed
+                .verify(keys.backup_id_pk_iter(), now)
+                .inspect_err(|e| tracing::warn!("Failed to verify secret sharing settings: {e}"))
+                .is_ok()
+        });
+
+        let message_retention_limit = untrusted.message_retention_limit.filter(|signed| {
+            signed
+                .verify(keys.org_pk_iter(), now)
+                .inspect_err(|e| tracing::warn!("Failed to verify message retention limit: {e}"))
+                .is_ok()
+        });
+
+        Ok(Self {
             journalist_profiles: untrusted.journalist_profiles,
             default_journalist_id,
             keys,
             max_epoch: untrusted.max_epoch,
+            key_transparency: untrusted.key_transparency,
+            backup_secret_sharing,
+            message_retention_limit,
+        })
+    }
+
+    /// The verified key transparency tree head covering every key in `keys`
+    pub fn tree_head(&self) -> Option<&TreeHead> {
+        self.key_transparency
+            .as_ref()
+            .map(|proof| &proof.signed_tree_head.tree_head)
+    }
+
+    /// Check the tree head against the ones this client has seen before and remember it.
+    /// Use [`KnownTreeHeads::consistency_proof_sizes`] to find which consistency proof to fetch
+    /// from the API.
+    ///
+    /// A response without a proof is only accepted if this client has never seen one, so the API
+    /// can't stop serving proofs to hide a change to the keys.
+    pub fn check_tree_head(
+        &self,
+        known_tree_heads: &mut KnownTreeHeads,
+        consistency_proof: &[MerkleHash],
+    ) -> Result<(), Error> {
+        match self.tree_head() {
+            Some(tree_head) => known_tree_heads.check_and_insert(tree_head, consistency_proof),
+            None if known_tree_heads.latest().is_some() => Err(Error::KeyTransparencyProofMissing),
+            None => Ok(()),
         }
     }
 
+    /// Fetch whichever consistency proof is needed from the API and call [`Self::check_tree_head`]
+    pub async fn check_tree_head_with_api(
+        &self,
+        api_client: &ApiClient,
+        known_tree_heads: &mut KnownTreeHeads,
+    ) -> anyhow::Result<()> {
+        let sizes = self
+            .tree_head()
+            .and_then(|tree_head| known_tree_heads.consistency_proof_sizes(tree_head));
+
+        let consistency_proof = match sizes {
+            Some((first_size, second_size)) => {
+                api_client
+                    .get_key_transparency_consistency_proof(first_size, second_size)
+                    .await?
+            }
+            None => vec![],
+        };
+
+        self.check_tree_head(known_tree_heads, &consistency_proof)?;
+
+        Ok(())
+    }
+
+    /// How automated backups should be split between recovery contacts. Falls back to the
+    /// defaults if the backup admins haven't published any settings.
+    pub fn secret_sharing_settings(&self) -> SecretSharingSettings {
+        self.backup_secret_sharing
+            .as_ref()
+            .map(|signed| signed.settings)
+            .unwrap_or_default()
+    }
+
+    /// The longest journalists may keep messages for. Falls back to the default message lifetime
+    /// if the admins haven't published a limit.
+    pub fn message_retention_limit(&self) -> MessageRetentionLimit {
+        self.message_retention_limit
+            .as_ref()
+            .map(|signed| signed.limit)
+            .unwrap_or_default()
+    }
+
     pub fn find_profile(&self, id: &JournalistIdentity) -> Option<&JournalistProfile> {
         self.journalist_profiles
             .iter()
             .find(|profile| profile.id == *id)
     }
 
+    /// The profiles a user can pick a recipient from, sorted by their sort name. Profiles hidden
+    /// from the UI are only included when `include_hidden` is set, profiles hidden from the
+    /// response are never included.
+    pub fn listed_profiles(&self, include_hidden: bool) -> Vec<&JournalistProfile> {
+        let mut profiles = self
+            .journalist_profiles
+            .iter()
+            .filter(|profile| match profile.status {
+                JournalistStatus::Visible => true,
+                JournalistStatus::HiddenFromUi => include_hidden,
+                JournalistStatus::HiddenFromResponse => false,
+            })
+            .collect::<Vec<_>>();
+
+        profiles.sort_by(|a, b| a.sort_name.cmp(&b.sort_name));
+
+        profiles
+    }
+
+    /// Search the listed profiles, best match first
+    pub fn search_profiles(
+        &self,
+        query: &str,
+        include_hidden: bool,
+    ) -> Vec<(&JournalistProfile, ProfileMatch)> {
+        let mut matches = self
+            .listed_profiles(include_hidden)
+            .into_iter()
+            .filter_map(|profile| profile.search(query).map(|m| (profile, m)))
+            .collect::<Vec<_>>();
+
+        // Stable, so equally good matches stay sorted by name
+        matches.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        matches
+    }
+
+    /// The desk to suggest when a search doesn't find the journalist the user was looking for.
+    /// Prefers the best matching visible desk, falling back to the default journalist if it's a
+    /// desk.
+    pub fn recommended_desk(&self, query: &str) -> Option<&JournalistProfile> {
+        self.search_profiles(query, false)
+            .into_iter()
+            .map(|(profile, _)| profile)
+            .find(|profile| profile.is_desk)
+            .or_else(|| {
+                self.default_journalist_id
+                    .as_ref()
+                    .and_then(|id| self.find_profile(id))
+                    .filter(|profile| profile.is_desk && profile.is_visible())
+            })
+    }
+
     pub fn to_untrusted(&self) -> UntrustedKeysAndJournalistProfiles {
         let keys = self.keys.to_untrusted();
         UntrustedKeysAndJournalistProfiles::new(
@@ -55,5 +214,8 @@ impl VerifiedKeysAndJournalistProfiles {
             keys,
             self.max_epoch,
         )
+        .with_key_transparency(self.key_transparency.clone())
+        .with_backup_secret_sharing(self.backup_secret_sharing.clone())
+        .with_message_retention_limit(self.message_retention_limit.clone())
     }
 }
@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{
+    base64::{Base64, Standard},
+    formats::Unpadded,
+    serde_as,
+};
+
+use crate::protocol::constants::{ML_KEM_768_CIPHERTEXT_LEN, X25519_PUBLIC_KEY_LEN};
+use crate::Error;
+
+use super::hybrid_kem;
+use super::keys::hybrid_encryption::{HybridEncryptionKeyPair, HybridPublicEncryptionKey};
+use super::keys::role::Role;
+use super::Encryptable;
+
+/// The number of bytes a [`HybridAnonymousBox`] adds to its plaintext
+pub const OVERHEAD_LEN: usize =
+    X25519_PUBLIC_KEY_LEN + ML_KEM_768_CIPHERTEXT_LEN + hybrid_kem::CIPHERTEXT_OVERHEAD;
+
+/// The hybrid X25519 + ML-KEM-768 counterpart of the [`AnonymousBox`].
+///
+/// The byte array contains the ephemeral X25519 public key, the ML-KEM ciphertext, the
+/// Poly1305 tag and the XSalsa20 ciphertext, in that order.
+///
+/// [`AnonymousBox`]: super::AnonymousBox
+#[serde_as]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(transparent, deny_unknown_fields)]
+pub struct HybridAnonymousBox<T> {
+    #[serde_as(as = "Base64<Standard, Unpadded>")]
+    pk_kem_ciphertext_tag_and_ciphertext: Vec<u8>,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl<T> HybridAnonymousBox<T> {
+    /// Create a new `HybridAnonymousBox` from a byte vector without checking if it's valid.
+    pub fn from_vec_unchecked(bytes: Vec<u8>) -> HybridAnonymousBox<T> {
+        HybridAnonymousBox {
+            pk_kem_ciphertext_tag_and_ciphertext: bytes,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &Vec<u8> {
+        &self.pk_kem_ciphertext_tag_and_ciphertext
+    }
+
+    pub fn encrypt<R>(
+        recipient_pk: &HybridPublicEncryptionKey<R>,
+        data: T,
+    ) -> Result<HybridAnonymousBox<T>, Error>
+    where
+        R: Role,
+        T: Encryptable,
+    {
+        let (ephemeral_pk, ml_kem_ciphertext, key) =
+            hybrid_kem::encapsulate_anonymous(recipient_pk)?;
+
+        let tag_and_ciphertext = hybrid_kem::seal(data.as_unencrypted_bytes(), &key);
+
+        let mut bytes = Vec::with_capacity(OVERHEAD_LEN + data.as_unencrypted_bytes().len());
+        bytes.extend_from_slice(&ephemeral_pk);
+        bytes.extend_from_slice(&ml_kem_ciphertext);
+        bytes.extend_from_slice(&tag_and_ciphertext);
+
+        Ok(HybridAnonymousBox {
+            pk_kem_ciphertext_tag_and_ciphertext: bytes,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn decrypt<R>(
+        decryption_key_pair: &HybridEncryptionKeyPair<R>,
+        data: &HybridAnonymousBox<T>,
+    ) -> Result<T, Error>
+    where
+        T: Encryptable,
+        R: Role,
+    {
+        let bytes = &data.pk_kem_ciphertext_tag_and_ciphertext;
+        if bytes.len() < OVERHEAD_LEN {
+            return Err(Error::FailedToDecrypt);
+        }
+
+        let (ephemeral_pk, rest) = bytes.split_at(X25519_PUBLIC_KEY_LEN);
+        let (ml_kem_ciphertext, tag_and_ciphertext) = rest.split_at(ML_KEM_768_CIPHERTEXT_LEN);
+
+        let key = hybrid_kem::decapsulate_anonymous(
+            decryption_key_pair.secret_key(),
+            ephemeral_pk,
+            ml_kem_ciphertext,
+        )?;
+
+        let plaintext_bytes = hybrid_kem::open(tag_and_ciphertext, &key)?;
+
+        T::from_unencrypted_bytes(plaintext_bytes)
+    }
+
+    // Ignoring the clippy `len_without_is_empty` since this isn't a container
+    // it's a box, possibly `len` should be renamed.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.pk_kem_ciphertext_tag_and_ciphertext.len()
+    }
+}
+
+impl<T> AsRef<[u8]> for HybridAnonymousBox<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.pk_kem_ciphertext_tag_and_ciphertext
+    }
+}
+
+impl<T> From<HybridAnonymousBox<T>> for Vec<u8> {
+    fn from(value: HybridAnonymousBox<T>) -> Self {
+        value.pk_kem_ciphertext_tag_and_ciphertext
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            keys::{hybrid_encryption::HybridEncryptionKeyPair, role::Test},
+            HybridAnonymousBox,
+        },
+        Error,
+    };
+
+    use super::OVERHEAD_LEN;
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let input = "안녕하세요".to_owned();
+        let recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridAnonymousBox<String> =
+            HybridAnonymousBox::encrypt(recipient_key_pair.public_key(), input.clone())?;
+        let decrypted: String = HybridAnonymousBox::decrypt(&recipient_key_pair, &encrypted)?;
+
+        assert_eq!(input, decrypted);
+        assert_eq!(encrypted.len(), OVERHEAD_LEN + input.len());
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_using_different_key() -> Result<(), Error> {
+        let input = "안녕하세요".to_owned();
+
+        let intended_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let other_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridAnonymousBox<String> =
+            HybridAnonymousBox::encrypt(intended_recipient_key_pair.public_key(), input)?;
+
+        let decrypted: Result<String, Error> =
+            HybridAnonymousBox::decrypt(&other_recipient_key_pair, &encrypted);
+
+        assert!(
+            matches!(decrypted, Err(Error::FailedToDecrypt)),
+            "Making sure decryption failed, actual: {decrypted:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_truncated() -> Result<(), Error> {
+        let recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridAnonymousBox<String> =
+            HybridAnonymousBox::encrypt(recipient_key_pair.public_key(), "hello".to_owned())?;
+
+        let truncated = HybridAnonymousBox::<String>::from_vec_unchecked(
+            encrypted.as_bytes()[..OVERHEAD_LEN - 1].to_vec(),
+        );
+
+        let decrypted = HybridAnonymousBox::decrypt(&recipient_key_pair, &truncated);
+        assert!(matches!(decrypted, Err(Error::FailedToDecrypt)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_with_serialization() -> anyhow::Result<()> {
+        let input = "안녕하세요".to_owned();
+
+        let recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridAnonymousBox<String> =
+            HybridAnonymousBox::encrypt(recipient_key_pair.public_key(), input.clone())?;
+
+        let serialized = serde_json::to_string(&encrypted).unwrap();
+
+        let deserialized = serde_json::from_str::<HybridAnonymousBox<String>>(&serialized).unwrap();
+
+        let decrypted: String = HybridAnonymousBox::decrypt(&recipient_key_pair, &deserialized)?;
+
+        assert_eq!(decrypted, input);
+
+        Ok(())
+    }
+}
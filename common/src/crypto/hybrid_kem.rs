@@ -0,0 +1,192 @@
+//! The key encapsulation shared by the hybrid primitives. An X25519 key exchange and an
+//! ML-KEM-768 encapsulation are performed side by side and both shared secrets are combined,
+//! together with the public values of the exchange, into a single symmetric key using SHA-256.
+//!
+//! The combined key is only ever used to encrypt a single message so, like the
+//! [`MultiAnonymousBox`], the hybrid boxes use a constant nonce.
+//!
+//! [`MultiAnonymousBox`]: super::MultiAnonymousBox
+
+use ml_kem::{
+    kem::{Decapsulate as _, Encapsulate as _},
+    Ciphertext, MlKem768,
+};
+use rand::thread_rng;
+use sha2::{Digest as _, Sha256};
+use sodiumoxide::crypto::secretbox::xsalsa20poly1305;
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret as X25519SecretKey};
+
+use crate::{
+    protocol::constants::{ML_KEM_768_CIPHERTEXT_LEN, X25519_PUBLIC_KEY_LEN},
+    Error,
+};
+
+use super::keys::{
+    hybrid_encryption::{HybridPublicEncryptionKey, HybridSecretEncryptionKey},
+    role::Role,
+};
+
+pub type SymmetricKey = xsalsa20poly1305::Key;
+
+pub const CIPHERTEXT_OVERHEAD: usize = xsalsa20poly1305::MACBYTES;
+
+/// Domain separation label mixed into every combined key
+const HYBRID_KEM_LABEL: &[u8] = b"CoverDrop-X25519-MLKEM768-v1";
+
+fn combine(
+    x25519_shared_secret: &[u8],
+    ml_kem_shared_secret: &[u8],
+    sender_x25519_pk: &[u8; X25519_PUBLIC_KEY_LEN],
+    recipient_x25519_pk: &[u8; X25519_PUBLIC_KEY_LEN],
+    ml_kem_ciphertext: &[u8],
+) -> SymmetricKey {
+    let mut hasher = Sha256::new();
+    hasher.update(HYBRID_KEM_LABEL);
+    hasher.update(ml_kem_shared_secret);
+    hasher.update(x25519_shared_secret);
+    hasher.update(sender_x25519_pk);
+    hasher.update(recipient_x25519_pk);
+    hasher.update(ml_kem_ciphertext);
+
+    let digest: [u8; 32] = hasher.finalize().into();
+    xsalsa20poly1305::Key(digest)
+}
+
+fn ml_kem_ciphertext_from_slice(bytes: &[u8]) -> Result<Ciphertext<MlKem768>, Error> {
+    if bytes.len() != ML_KEM_768_CIPHERTEXT_LEN {
+        return Err(Error::FailedToDecrypt);
+    }
+
+    Ciphertext::<MlKem768>::try_from(bytes).map_err(|_| Error::FailedToDecrypt)
+}
+
+/// Encapsulate a fresh key to the recipient using an ephemeral X25519 key. Returns the
+/// ephemeral public key, the ML-KEM ciphertext and the combined key.
+pub(crate) fn encapsulate_anonymous<R: Role>(
+    recipient_pk: &HybridPublicEncryptionKey<R>,
+) -> Result<([u8; X25519_PUBLIC_KEY_LEN], Vec<u8>, SymmetricKey), Error> {
+    let mut csprng = thread_rng();
+
+    let ephemeral_sk = X25519SecretKey::random_from_rng(&mut csprng);
+    let ephemeral_pk = X25519PublicKey::from(&ephemeral_sk);
+
+    let x25519_shared_secret = ephemeral_sk.diffie_hellman(&recipient_pk.x25519);
+    if !x25519_shared_secret.was_contributory() {
+        return Err(Error::FailedToEncrypt);
+    }
+
+    let (ml_kem_ciphertext, ml_kem_shared_secret) = recipient_pk
+        .ml_kem
+        .encapsulate(&mut csprng)
+        .map_err(|_| Error::FailedToEncrypt)?;
+
+    let key = combine(
+        x25519_shared_secret.as_bytes(),
+        &ml_kem_shared_secret,
+        ephemeral_pk.as_bytes(),
+        recipient_pk.x25519.as_bytes(),
+        &ml_kem_ciphertext,
+    );
+
+    Ok((*ephemeral_pk.as_bytes(), ml_kem_ciphertext.to_vec(), key))
+}
+
+pub(crate) fn decapsulate_anonymous<R: Role>(
+    recipient_sk: &HybridSecretEncryptionKey<R>,
+    ephemeral_pk: &[u8],
+    ml_kem_ciphertext: &[u8],
+) -> Result<SymmetricKey, Error> {
+    let ephemeral_pk: [u8; X25519_PUBLIC_KEY_LEN] = ephemeral_pk
+        .try_into()
+        .map_err(|_| Error::FailedToDecrypt)?;
+    let ephemeral_pk = X25519PublicKey::from(ephemeral_pk);
+
+    let x25519_shared_secret = recipient_sk.x25519.diffie_hellman(&ephemeral_pk);
+    if !x25519_shared_secret.was_contributory() {
+        return Err(Error::FailedToDecrypt);
+    }
+
+    let ml_kem_ciphertext = ml_kem_ciphertext_from_slice(ml_kem_ciphertext)?;
+    let ml_kem_shared_secret = recipient_sk
+        .ml_kem
+        .decapsulate(&ml_kem_ciphertext)
+        .map_err(|_| Error::FailedToDecrypt)?;
+
+    let recipient_pk = X25519PublicKey::from(&recipient_sk.x25519);
+
+    Ok(combine(
+        x25519_shared_secret.as_bytes(),
+        &ml_kem_shared_secret,
+        ephemeral_pk.as_bytes(),
+        recipient_pk.as_bytes(),
+        &ml_kem_ciphertext,
+    ))
+}
+
+/// Encapsulate a fresh key to the recipient, authenticated by the sender's static X25519 key.
+/// Returns the ML-KEM ciphertext and the combined key.
+pub(crate) fn encapsulate_authenticated<RecipientRole: Role, SenderRole: Role>(
+    recipient_pk: &HybridPublicEncryptionKey<RecipientRole>,
+    sender_sk: &HybridSecretEncryptionKey<SenderRole>,
+) -> Result<(Vec<u8>, SymmetricKey), Error> {
+    let x25519_shared_secret = sender_sk.x25519.diffie_hellman(&recipient_pk.x25519);
+    if !x25519_shared_secret.was_contributory() {
+        return Err(Error::FailedToEncrypt);
+    }
+
+    let (ml_kem_ciphertext, ml_kem_shared_secret) = recipient_pk
+        .ml_kem
+        .encapsulate(&mut thread_rng())
+        .map_err(|_| Error::FailedToEncrypt)?;
+
+    let sender_pk = X25519PublicKey::from(&sender_sk.x25519);
+
+    let key = combine(
+        x25519_shared_secret.as_bytes(),
+        &ml_kem_shared_secret,
+        sender_pk.as_bytes(),
+        recipient_pk.x25519.as_bytes(),
+        &ml_kem_ciphertext,
+    );
+
+    Ok((ml_kem_ciphertext.to_vec(), key))
+}
+
+pub(crate) fn decapsulate_authenticated<RecipientRole: Role, SenderRole: Role>(
+    sender_pk: &HybridPublicEncryptionKey<SenderRole>,
+    recipient_sk: &HybridSecretEncryptionKey<RecipientRole>,
+    ml_kem_ciphertext: &[u8],
+) -> Result<SymmetricKey, Error> {
+    let x25519_shared_secret = recipient_sk.x25519.diffie_hellman(&sender_pk.x25519);
+    if !x25519_shared_secret.was_contributory() {
+        return Err(Error::FailedToDecrypt);
+    }
+
+    let ml_kem_ciphertext = ml_kem_ciphertext_from_slice(ml_kem_ciphertext)?;
+    let ml_kem_shared_secret = recipient_sk
+        .ml_kem
+        .decapsulate(&ml_kem_ciphertext)
+        .map_err(|_| Error::FailedToDecrypt)?;
+
+    let recipient_pk = X25519PublicKey::from(&recipient_sk.x25519);
+
+    Ok(combine(
+        x25519_shared_secret.as_bytes(),
+        &ml_kem_shared_secret,
+        sender_pk.x25519.as_bytes(),
+        recipient_pk.as_bytes(),
+        &ml_kem_ciphertext,
+    ))
+}
+
+/// Encrypt the plaintext with a single-use key, returning the tag and ciphertext
+pub(crate) fn seal(plaintext: &[u8], key: &SymmetricKey) -> Vec<u8> {
+    // since we always use fresh keys for each message, we can choose a constant nonce
+    let nonce = xsalsa20poly1305::Nonce([0u8; xsalsa20poly1305::NONCEBYTES]);
+    xsalsa20poly1305::seal(plaintext, &nonce, key)
+}
+
+pub(crate) fn open(tag_and_ciphertext: &[u8], key: &SymmetricKey) -> Result<Vec<u8>, Error> {
+    let nonce = xsalsa20poly1305::Nonce([0u8; xsalsa20poly1305::NONCEBYTES]);
+    xsalsa20poly1305::open(tag_and_ciphertext, &nonce, key).map_err(|_| Error::FailedToDecrypt)
+}
@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{
+    base64::{Base64, Standard},
+    formats::Unpadded,
+    serde_as,
+};
+use sodiumoxide::crypto::secretbox::xsalsa20poly1305;
+
+use crate::crypto::{hybrid_anonymous_box, HybridAnonymousBox};
+use crate::Error;
+
+use super::hybrid_kem;
+use super::keys::hybrid_encryption::{HybridEncryptionKeyPair, HybridPublicEncryptionKey};
+use super::keys::role::Role;
+use super::multi_anonymous_box::{SecretKey, KEY_LEN};
+use super::Encryptable;
+
+pub const WRAPPED_KEY_LEN: usize = hybrid_anonymous_box::OVERHEAD_LEN + KEY_LEN;
+pub const CIPHERTEXT_OVERHEAD: usize = hybrid_kem::CIPHERTEXT_OVERHEAD;
+
+/// The hybrid X25519 + ML-KEM-768 counterpart of the [`MultiAnonymousBox`]. A fresh secret
+/// key encrypts the payload and is then wrapped in an independent [`HybridAnonymousBox`]
+/// for each of the recipients.
+///
+/// [`MultiAnonymousBox`]: super::MultiAnonymousBox
+#[serde_as]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(transparent, deny_unknown_fields)]
+pub struct HybridMultiAnonymousBox<T, const NUM_RECIPIENTS: usize> {
+    #[serde_as(as = "Base64<Standard, Unpadded>")]
+    wrapped_keys_and_ciphertext_and_tag: Vec<u8>,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl<T, const NUM_RECIPIENTS: usize> HybridMultiAnonymousBox<T, NUM_RECIPIENTS> {
+    /// Create a new `HybridMultiAnonymousBox` from a byte vector without checking whether it's valid.
+    pub fn from_vec_unchecked(bytes: Vec<u8>) -> HybridMultiAnonymousBox<T, NUM_RECIPIENTS> {
+        HybridMultiAnonymousBox {
+            wrapped_keys_and_ciphertext_and_tag: bytes,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &Vec<u8> {
+        &self.wrapped_keys_and_ciphertext_and_tag
+    }
+
+    pub fn encrypt<R>(
+        recipients: [&HybridPublicEncryptionKey<R>; NUM_RECIPIENTS],
+        data: T,
+    ) -> Result<HybridMultiAnonymousBox<T, NUM_RECIPIENTS>, Error>
+    where
+        R: Role,
+        T: Encryptable,
+    {
+        let key = xsalsa20poly1305::gen_key();
+        let bytes = data.as_unencrypted_bytes();
+
+        let output_len = recipients.len() * WRAPPED_KEY_LEN + bytes.len() + CIPHERTEXT_OVERHEAD;
+        let mut output: Vec<u8> = Vec::with_capacity(output_len);
+
+        for recipient_pk in recipients {
+            let wrapped_key = HybridAnonymousBox::encrypt(recipient_pk, key.clone())?;
+            output.extend_from_slice(wrapped_key.as_ref());
+        }
+
+        output.extend(hybrid_kem::seal(bytes, &key));
+
+        assert_eq!(output.len(), output_len);
+        Ok(HybridMultiAnonymousBox {
+            wrapped_keys_and_ciphertext_and_tag: output,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn decrypt<R>(
+        decryption_key_pair: &HybridEncryptionKeyPair<R>,
+        data: &HybridMultiAnonymousBox<T, NUM_RECIPIENTS>,
+    ) -> Result<T, Error>
+    where
+        T: Encryptable,
+        R: Role,
+    {
+        let wrapped_keys_len = NUM_RECIPIENTS * WRAPPED_KEY_LEN;
+        if data.wrapped_keys_and_ciphertext_and_tag.len() < wrapped_keys_len + CIPHERTEXT_OVERHEAD {
+            return Err(Error::FailedToDecrypt);
+        }
+
+        let (wrapped_keys, ciphertext) = data
+            .wrapped_keys_and_ciphertext_and_tag
+            .split_at(wrapped_keys_len);
+
+        let Some(matching_key) = Self::find_key(decryption_key_pair, wrapped_keys) else {
+            return Err(Error::FailedToDecrypt);
+        };
+
+        let plaintext_bytes = hybrid_kem::open(ciphertext, &matching_key)?;
+        T::from_unencrypted_bytes(plaintext_bytes)
+    }
+
+    fn find_key<R>(
+        decryption_key_pair: &HybridEncryptionKeyPair<R>,
+        wrapped_keys: &[u8],
+    ) -> Option<SecretKey>
+    where
+        R: Role,
+    {
+        wrapped_keys
+            .chunks_exact(WRAPPED_KEY_LEN)
+            .find_map(|candidate| {
+                let hybrid_anonymous_box =
+                    HybridAnonymousBox::<SecretKey>::from_vec_unchecked(candidate.to_vec());
+                HybridAnonymousBox::decrypt(decryption_key_pair, &hybrid_anonymous_box).ok()
+            })
+    }
+
+    // Ignoring the clippy `len_without_is_empty` since this isn't a container
+    // it's a box, possibly `len` should be renamed.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.wrapped_keys_and_ciphertext_and_tag.len()
+    }
+}
+
+impl<T, const NUM_RECIPIENTS: usize> AsRef<[u8]> for HybridMultiAnonymousBox<T, NUM_RECIPIENTS> {
+    fn as_ref(&self) -> &[u8] {
+        &self.wrapped_keys_and_ciphertext_and_tag
+    }
+}
+
+impl<T, const NUM_RECIPIENTS: usize> From<HybridMultiAnonymousBox<T, NUM_RECIPIENTS>> for Vec<u8> {
+    fn from(value: HybridMultiAnonymousBox<T, NUM_RECIPIENTS>) -> Self {
+        value.wrapped_keys_and_ciphertext_and_tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            keys::{hybrid_encryption::HybridEncryptionKeyPair, role::Test},
+            HybridMultiAnonymousBox,
+        },
+        Error,
+    };
+
+    #[test]
+    fn single_recipient_fails_when_using_different_key() -> Result<(), Error> {
+        let input = "안녕하세요".to_owned();
+
+        let intended_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let other_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let recipients = [intended_recipient_key_pair.public_key()];
+        let encrypted: HybridMultiAnonymousBox<String, 1> =
+            HybridMultiAnonymousBox::encrypt(recipients, input)?;
+
+        let decrypted: Result<String, Error> =
+            HybridMultiAnonymousBox::decrypt(&other_recipient_key_pair, &encrypted);
+
+        assert!(
+            matches!(decrypted, Err(Error::FailedToDecrypt)),
+            "Making sure decryption failed, actual: {decrypted:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn multi_recipients_round_trip_with_serialization() -> anyhow::Result<()> {
+        let input = "안녕하세요".to_owned();
+        const NUM_RECIPIENTS: usize = 3;
+
+        let recipients_key_pairs: Vec<_> = (0..NUM_RECIPIENTS)
+            .map(|_| HybridEncryptionKeyPair::<Test>::generate())
+            .collect();
+        let recipients_pks: Vec<&_> = recipients_key_pairs
+            .iter()
+            .map(|x| x.public_key())
+            .collect();
+
+        let encrypted = HybridMultiAnonymousBox::<String, NUM_RECIPIENTS>::encrypt(
+            recipients_pks.as_slice().try_into().unwrap(),
+            input.clone(),
+        )?;
+
+        let serialized = serde_json::to_string(&encrypted).unwrap();
+        let deserialized =
+            serde_json::from_str::<HybridMultiAnonymousBox<String, NUM_RECIPIENTS>>(&serialized)
+                .unwrap();
+
+        for recipient_key_pair in recipients_key_pairs {
+            let decrypted = HybridMultiAnonymousBox::decrypt(&recipient_key_pair, &deserialized)?;
+
+            assert_eq!(input, decrypted);
+        }
+
+        Ok(())
+    }
+}
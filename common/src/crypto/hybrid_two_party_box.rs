@@ -0,0 +1,206 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use serde_with::{
+    base64::{Base64, Standard},
+    formats::Unpadded,
+    serde_as,
+};
+
+use crate::protocol::constants::ML_KEM_768_CIPHERTEXT_LEN;
+use crate::Error;
+
+use super::hybrid_kem;
+use super::keys::{
+    hybrid_encryption::{HybridPublicEncryptionKey, HybridSecretEncryptionKey},
+    role::Role,
+};
+use super::Encryptable;
+
+/// The number of bytes a [`HybridTwoPartyBox`] adds to its plaintext
+pub const OVERHEAD_LEN: usize = ML_KEM_768_CIPHERTEXT_LEN + hybrid_kem::CIPHERTEXT_OVERHEAD;
+
+/// The hybrid X25519 + ML-KEM-768 counterpart of the [`TwoPartyBox`].
+///
+/// The static X25519 key exchange between sender and recipient authenticates the message, the
+/// ML-KEM encapsulation to the recipient protects its confidentiality against an adversary who
+/// records traffic now and breaks X25519 later. Since every message is encrypted with a fresh
+/// key no nonce is stored.
+///
+/// The byte array contains the ML-KEM ciphertext, the Poly1305 tag and the XSalsa20 ciphertext.
+///
+/// [`TwoPartyBox`]: super::TwoPartyBox
+#[serde_as]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(transparent, deny_unknown_fields)]
+pub struct HybridTwoPartyBox<T> {
+    #[serde_as(as = "Base64<Standard, Unpadded>")]
+    kem_ciphertext_tag_and_ciphertext: Vec<u8>,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl<T> HybridTwoPartyBox<T> {
+    pub fn from_vec_unchecked(bytes: Vec<u8>) -> HybridTwoPartyBox<T> {
+        HybridTwoPartyBox {
+            kem_ciphertext_tag_and_ciphertext: bytes,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &Vec<u8> {
+        &self.kem_ciphertext_tag_and_ciphertext
+    }
+
+    pub fn encrypt<RecipientRole, SenderRole>(
+        recipient_pk: &HybridPublicEncryptionKey<RecipientRole>,
+        sender_sk: &HybridSecretEncryptionKey<SenderRole>,
+        data: T,
+    ) -> Result<HybridTwoPartyBox<T>, Error>
+    where
+        T: Encryptable,
+        RecipientRole: Role,
+        SenderRole: Role,
+    {
+        let (ml_kem_ciphertext, key) =
+            hybrid_kem::encapsulate_authenticated(recipient_pk, sender_sk)?;
+
+        let mut bytes = ml_kem_ciphertext;
+        bytes.extend(hybrid_kem::seal(data.as_unencrypted_bytes(), &key));
+
+        Ok(HybridTwoPartyBox {
+            kem_ciphertext_tag_and_ciphertext: bytes,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn decrypt<SenderRole, RecipientRole>(
+        sender_pk: &HybridPublicEncryptionKey<SenderRole>,
+        recipient_sk: &HybridSecretEncryptionKey<RecipientRole>,
+        data: &HybridTwoPartyBox<T>,
+    ) -> Result<T, Error>
+    where
+        SenderRole: Role,
+        RecipientRole: Role,
+        T: Encryptable,
+    {
+        let bytes = &data.kem_ciphertext_tag_and_ciphertext;
+        if bytes.len() < OVERHEAD_LEN {
+            return Err(Error::FailedToDecrypt);
+        }
+
+        let (ml_kem_ciphertext, tag_and_ciphertext) = bytes.split_at(ML_KEM_768_CIPHERTEXT_LEN);
+
+        let key =
+            hybrid_kem::decapsulate_authenticated(sender_pk, recipient_sk, ml_kem_ciphertext)?;
+
+        let plaintext_bytes = hybrid_kem::open(tag_and_ciphertext, &key)?;
+
+        T::from_unencrypted_bytes(plaintext_bytes)
+    }
+
+    // Ignoring the clippy `len_without_is_empty` since this isn't a container
+    // it's a box, possibly `len` should be renamed.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.kem_ciphertext_tag_and_ciphertext.len()
+    }
+}
+
+impl<T> AsRef<[u8]> for HybridTwoPartyBox<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.kem_ciphertext_tag_and_ciphertext
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            keys::{hybrid_encryption::HybridEncryptionKeyPair, role::Test},
+            HybridTwoPartyBox,
+        },
+        Error,
+    };
+
+    use super::OVERHEAD_LEN;
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let input = "こんにちは".to_owned();
+        let my_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridTwoPartyBox<String> = HybridTwoPartyBox::encrypt(
+            recipient_key_pair.public_key(),
+            my_key_pair.secret_key(),
+            input.clone(),
+        )?;
+        let decrypted: String = HybridTwoPartyBox::decrypt(
+            my_key_pair.public_key(),
+            recipient_key_pair.secret_key(),
+            &encrypted,
+        )?;
+
+        assert_eq!(input, decrypted);
+        assert_eq!(encrypted.len(), OVERHEAD_LEN + input.len());
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_using_different_recipient_key() -> Result<(), Error> {
+        let input = "こんにちは".to_owned();
+
+        let my_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let intended_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let other_recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridTwoPartyBox<String> = HybridTwoPartyBox::encrypt(
+            intended_recipient_key_pair.public_key(),
+            my_key_pair.secret_key(),
+            input,
+        )?;
+
+        let decrypted = HybridTwoPartyBox::decrypt(
+            my_key_pair.public_key(),
+            other_recipient_key_pair.secret_key(),
+            &encrypted,
+        );
+
+        assert!(
+            matches!(decrypted, Err(Error::FailedToDecrypt)),
+            "Making sure decryption failed, actual: {decrypted:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fails_when_using_different_sender_key() -> Result<(), Error> {
+        let input = "こんにちは".to_owned();
+
+        let my_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let other_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+        let recipient_key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let encrypted: HybridTwoPartyBox<String> = HybridTwoPartyBox::encrypt(
+            recipient_key_pair.public_key(),
+            my_key_pair.secret_key(),
+            input,
+        )?;
+
+        let decrypted = HybridTwoPartyBox::decrypt(
+            other_key_pair.public_key(),
+            recipient_key_pair.secret_key(),
+            &encrypted,
+        );
+
+        assert!(
+            matches!(decrypted, Err(Error::FailedToDecrypt)),
+            "Making sure decryption failed, actual: {decrypted:?}"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use std::marker::PhantomData;
+
+use hex_buffer_serde::Hex as _;
+use ml_kem::{EncodedSizeUser as _, KemCore as _, MlKem768};
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret as X25519SecretKey};
+
+use super::{
+    encryption::{PublicEncryptionKey, SecretEncryptionKey},
+    public_key::PublicKey,
+    role::Role,
+    serde::{
+        MlKemDecapsulationKeyHex, MlKemEncapsulationKeyHex, PublicEncryptionKeyHex,
+        SecretEncryptionKeyHex,
+    },
+    MlKemDecapsulationKey, MlKemEncapsulationKey,
+};
+
+/// A public key for the hybrid X25519 + ML-KEM-768 primitives. Messages encrypted to this key
+/// remain confidential as long as at least one of the two key exchanges is unbroken.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HybridPublicEncryptionKey<T: Role> {
+    #[serde(with = "PublicEncryptionKeyHex")]
+    pub x25519: X25519PublicKey,
+    #[serde(with = "MlKemEncapsulationKeyHex")]
+    pub ml_kem: MlKemEncapsulationKey,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl<T: Role> HybridPublicEncryptionKey<T> {
+    pub fn new(x25519: X25519PublicKey, ml_kem: MlKemEncapsulationKey) -> Self {
+        HybridPublicEncryptionKey {
+            x25519,
+            ml_kem,
+            marker: PhantomData,
+        }
+    }
+
+    /// The classical half of this key, useful when talking to peers that only support X25519
+    pub fn to_x25519_public_key(&self) -> PublicEncryptionKey<T> {
+        PublicEncryptionKey::new(self.x25519)
+    }
+
+    /// The concatenation of the X25519 public key and the encoded ML-KEM encapsulation key
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.x25519.as_bytes().to_vec();
+        bytes.extend_from_slice(&self.ml_kem.as_bytes());
+        bytes
+    }
+}
+
+impl<R: Role> PublicKey for HybridPublicEncryptionKey<R> {
+    // Key IDs are derived from the X25519 half so that a hybrid key and its classical
+    // counterpart are easy to match up in logs and on disk.
+    fn public_key_hex(&self) -> String {
+        hex::encode(self.x25519.as_bytes())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HybridSecretEncryptionKey<T: Role> {
+    #[serde(with = "SecretEncryptionKeyHex")]
+    pub x25519: X25519SecretKey,
+    #[serde(with = "MlKemDecapsulationKeyHex")]
+    pub ml_kem: MlKemDecapsulationKey,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl<T: Role> HybridSecretEncryptionKey<T> {
+    pub fn new(x25519: X25519SecretKey, ml_kem: MlKemDecapsulationKey) -> Self {
+        HybridSecretEncryptionKey {
+            x25519,
+            ml_kem,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn to_x25519_secret_key(&self) -> SecretEncryptionKey<T> {
+        SecretEncryptionKey::new(self.x25519.clone())
+    }
+
+    pub fn to_public_key(&self) -> HybridPublicEncryptionKey<T> {
+        HybridPublicEncryptionKey::new(
+            X25519PublicKey::from(&self.x25519),
+            self.ml_kem.encapsulation_key().clone(),
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HybridEncryptionKeyPair<R: Role> {
+    public_key: HybridPublicEncryptionKey<R>,
+    secret_key: HybridSecretEncryptionKey<R>,
+}
+
+impl<R: Role> HybridEncryptionKeyPair<R> {
+    pub fn new(
+        public_key: HybridPublicEncryptionKey<R>,
+        secret_key: HybridSecretEncryptionKey<R>,
+    ) -> Self {
+        HybridEncryptionKeyPair {
+            public_key,
+            secret_key,
+        }
+    }
+
+    /// Generate a random hybrid key pair to be used for encryption.
+    pub fn generate() -> HybridEncryptionKeyPair<R> {
+        let mut csprng = thread_rng();
+
+        let x25519_secret_key = X25519SecretKey::random_from_rng(&mut csprng);
+        let (ml_kem_decapsulation_key, _) = MlKem768::generate(&mut csprng);
+
+        let secret_key =
+            HybridSecretEncryptionKey::new(x25519_secret_key, ml_kem_decapsulation_key);
+        let public_key = secret_key.to_public_key();
+
+        HybridEncryptionKeyPair::new(public_key, secret_key)
+    }
+
+    pub fn public_key(&self) -> &HybridPublicEncryptionKey<R> {
+        &self.public_key
+    }
+
+    pub fn secret_key(&self) -> &HybridSecretEncryptionKey<R> {
+        &self.secret_key
+    }
+}
+
+impl<R: Role> PublicKey for HybridEncryptionKeyPair<R> {
+    fn public_key_hex(&self) -> String {
+        self.public_key.public_key_hex()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::keys::role::Test,
+        protocol::constants::{ML_KEM_768_ENCAPSULATION_KEY_LEN, X25519_PUBLIC_KEY_LEN},
+    };
+
+    use super::HybridEncryptionKeyPair;
+
+    #[test]
+    fn public_key_serde_round_trip() -> anyhow::Result<()> {
+        let key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let json = serde_json::to_string(key_pair.public_key())?;
+        let deserialized = serde_json::from_str(&json)?;
+
+        assert_eq!(key_pair.public_key(), &deserialized);
+        assert_eq!(
+            key_pair.public_key().to_bytes().len(),
+            X25519_PUBLIC_KEY_LEN + ML_KEM_768_ENCAPSULATION_KEY_LEN
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn key_pair_serde_round_trip() -> anyhow::Result<()> {
+        let key_pair = HybridEncryptionKeyPair::<Test>::generate();
+
+        let json = serde_json::to_string(&key_pair)?;
+        let deserialized = serde_json::from_str::<HybridEncryptionKeyPair<Test>>(&json)?;
+
+        assert_eq!(key_pair.public_key(), deserialized.public_key());
+        assert_eq!(
+            key_pair.secret_key().to_public_key(),
+            deserialized.secret_key().to_public_key()
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 pub mod encryption;
+#[cfg(feature = "post-quantum")]
+pub mod hybrid_encryption;
 pub mod key_certificate_data;
 pub mod public_key;
 pub mod role;
@@ -13,3 +15,8 @@ pub type Ed25519Signature = ed25519_dalek::Signature;
 
 pub type X25519PublicKey = x25519_dalek::PublicKey;
 pub type X25519SecretKey = x25519_dalek::StaticSecret;
+
+#[cfg(feature = "post-quantum")]
+pub type MlKemEncapsulationKey = ml_kem::kem::EncapsulationKey<ml_kem::MlKem768Params>;
+#[cfg(feature = "post-quantum")]
+pub type MlKemDecapsulationKey = ml_kem::kem::DecapsulationKey<ml_kem::MlKem768Params>;
@@ -94,6 +94,54 @@ impl Hex<X25519SecretKey> for SecretEncryptionKeyHex {
     }
 }
 
+#[cfg(feature = "post-quantum")]
+pub(crate) struct MlKemEncapsulationKeyHex;
+
+#[cfg(feature = "post-quantum")]
+impl Hex<super::MlKemEncapsulationKey> for MlKemEncapsulationKeyHex {
+    type Error = &'static str;
+
+    fn create_bytes(value: &super::MlKemEncapsulationKey) -> Cow<'_, [u8]> {
+        use ml_kem::EncodedSizeUser as _;
+
+        Cow::from(value.as_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<super::MlKemEncapsulationKey, Self::Error> {
+        use ml_kem::EncodedSizeUser as _;
+
+        let encoded = bytes
+            .try_into()
+            .map_err(|_| "Provided ML-KEM encapsulation key byte array was the wrong length")?;
+
+        Ok(super::MlKemEncapsulationKey::from_bytes(encoded))
+    }
+}
+
+#[cfg(feature = "post-quantum")]
+pub(crate) struct MlKemDecapsulationKeyHex;
+
+#[cfg(feature = "post-quantum")]
+impl Hex<super::MlKemDecapsulationKey> for MlKemDecapsulationKeyHex {
+    type Error = &'static str;
+
+    fn create_bytes(value: &super::MlKemDecapsulationKey) -> Cow<'_, [u8]> {
+        use ml_kem::EncodedSizeUser as _;
+
+        Cow::from(value.as_bytes().to_vec())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<super::MlKemDecapsulationKey, Self::Error> {
+        use ml_kem::EncodedSizeUser as _;
+
+        let encoded = bytes
+            .try_into()
+            .map_err(|_| "Provided ML-KEM decapsulation key byte array was the wrong length")?;
+
+        Ok(super::MlKemDecapsulationKey::from_bytes(encoded))
+    }
+}
+
 pub(crate) struct SignatureHex;
 
 impl<T> Hex<Signature<T>> for SignatureHex {
@@ -1,6 +1,14 @@
 mod anonymous_box;
 mod encryptable;
 mod human_key_digest;
+#[cfg(feature = "post-quantum")]
+mod hybrid_anonymous_box;
+#[cfg(feature = "post-quantum")]
+mod hybrid_kem;
+#[cfg(feature = "post-quantum")]
+mod hybrid_multi_anonymous_box;
+#[cfg(feature = "post-quantum")]
+mod hybrid_two_party_box;
 pub mod keys;
 mod multi_anonymous_box;
 pub mod pbkdf;
@@ -16,6 +24,12 @@ mod verified;
 pub use anonymous_box::AnonymousBox;
 pub use encryptable::Encryptable;
 pub use human_key_digest::human_readable_digest;
+#[cfg(feature = "post-quantum")]
+pub use hybrid_anonymous_box::HybridAnonymousBox;
+#[cfg(feature = "post-quantum")]
+pub use hybrid_multi_anonymous_box::HybridMultiAnonymousBox;
+#[cfg(feature = "post-quantum")]
+pub use hybrid_two_party_box::HybridTwoPartyBox;
 pub use multi_anonymous_box::MultiAnonymousBox;
 pub use secret_box::{SecretBox, SecretBoxKey, SECRET_BOX_FOOTER_LEN, SECRET_BOX_KEY_LEN};
 pub use secret_sharing::GeneralSecretSharing;
@@ -14,16 +14,53 @@ use super::secret_box::{SecretBoxKey, SECRET_BOX_KEY_LEN};
 /// See: `docs/client_passphrase_configurations.md`
 pub const DEFAULT_PASSPHRASE_WORDS: usize = 5;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Argon2Configuration {
     /// The original variant which we now consider insecure
     V0,
 
     /// The more secure variant
     V1,
+
+    /// Parameters matching the results in `docs/client_passphrase_configurations.md`,
+    /// benchmarked using `measure_time_for_hashing_for_variants` on the target devices
+    V2,
 }
 
 impl Argon2Configuration {
+    /// The configuration used for all newly derived keys. Data protected with an older
+    /// configuration should be re-keyed using this one when it is next opened.
+    pub const LATEST: Argon2Configuration = Argon2Configuration::V2;
+
+    /// All known configurations, ordered from the most to the least recent
+    pub const ALL_NEWEST_FIRST: [Argon2Configuration; 3] = [
+        Argon2Configuration::V2,
+        Argon2Configuration::V1,
+        Argon2Configuration::V0,
+    ];
+
+    pub fn is_latest(&self) -> bool {
+        *self == Self::LATEST
+    }
+
+    /// The single byte representation used when storing the configuration alongside a salt
+    pub fn as_byte(&self) -> u8 {
+        match self {
+            Argon2Configuration::V0 => 0,
+            Argon2Configuration::V1 => 1,
+            Argon2Configuration::V2 => 2,
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Argon2Configuration::V0),
+            1 => Ok(Argon2Configuration::V1),
+            2 => Ok(Argon2Configuration::V2),
+            _ => Err(Error::Argon2UnknownConfiguration(byte)),
+        }
+    }
+
     pub fn params(&self) -> Result<argon2::Params, argon2::Error> {
         match self {
             // default parameters from the argon2 crate as per version 0.4.1
@@ -39,6 +76,13 @@ impl Argon2Configuration {
                 .t_cost(6)
                 .p_cost(4)
                 .build(),
+
+            // 256 MiB and 3 iterations as per `docs/client_passphrase_configurations.md`
+            Argon2Configuration::V2 => argon2::ParamsBuilder::new()
+                .m_cost(256 * 1024)
+                .t_cost(3)
+                .p_cost(4)
+                .build(),
         }
     }
 }
@@ -47,10 +91,6 @@ pub fn generate_salt() -> SaltString {
     SaltString::generate(&mut OsRng)
 }
 
-pub fn derive_secret_box_key(password: &str, salt: &SaltString) -> anyhow::Result<SecretBoxKey> {
-    derive_secret_box_key_with_configuration(password, salt, Argon2Configuration::V0)
-}
-
 pub fn derive_secret_box_key_with_configuration(
     password: &str,
     salt: &SaltString,
@@ -78,9 +118,13 @@ pub fn derive_secret_box_key_with_configuration(
     Ok(SecretBoxKey::from(key))
 }
 
-pub fn derive_vault_key(password: &str, salt_bytes: [u8; 16]) -> anyhow::Result<SecretBoxKey> {
+pub fn derive_vault_key(
+    password: &str,
+    salt_bytes: [u8; 16],
+    configuration: Argon2Configuration,
+) -> anyhow::Result<SecretBoxKey> {
     let salt = SaltString::encode_b64(&salt_bytes).unwrap();
-    derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V1)
+    derive_secret_box_key_with_configuration(password, &salt, configuration)
 }
 
 #[cfg(test)]
@@ -93,7 +137,8 @@ mod tests {
         let password = "password";
         let salt = SaltString::from_b64("Y2hpcCBzcGljZQ").unwrap();
 
-        let key = derive_secret_box_key(password, &salt)?;
+        let key =
+            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V0)?;
 
         assert_eq!(
             key,
@@ -112,10 +157,12 @@ mod tests {
         let salt = SaltString::from_b64("Y2hpcCBzcGljZQ").unwrap();
 
         let password_1 = "password";
-        let key_1 = derive_secret_box_key(password_1, &salt)?;
+        let key_1 =
+            derive_secret_box_key_with_configuration(password_1, &salt, Argon2Configuration::V0)?;
 
         let password_2 = "a different password";
-        let key_2 = derive_secret_box_key(password_2, &salt)?;
+        let key_2 =
+            derive_secret_box_key_with_configuration(password_2, &salt, Argon2Configuration::V0)?;
 
         assert_ne!(key_1, key_2);
 
@@ -129,8 +176,10 @@ mod tests {
         let salt_1 = SaltString::from_b64("Y2hpcCBzcGljZQ").unwrap();
         let salt_2 = SaltString::from_b64("ZGlmZmVyZW50").unwrap();
 
-        let key_1 = derive_secret_box_key(password, &salt_1)?;
-        let key_2 = derive_secret_box_key(password, &salt_2)?;
+        let key_1 =
+            derive_secret_box_key_with_configuration(password, &salt_1, Argon2Configuration::V0)?;
+        let key_2 =
+            derive_secret_box_key_with_configuration(password, &salt_2, Argon2Configuration::V0)?;
 
         assert_ne!(key_1, key_2);
 
@@ -142,7 +191,7 @@ mod tests {
         let password = "password";
         let salt = SaltString::generate(&mut rand::thread_rng());
 
-        for configuration in [Argon2Configuration::V0, Argon2Configuration::V1] {
+        for configuration in Argon2Configuration::ALL_NEWEST_FIRST.into_iter().rev() {
             let start = std::time::Instant::now();
             let _key =
                 derive_secret_box_key_with_configuration(password, &salt, configuration).unwrap();
@@ -153,4 +202,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn configurations_roundtrip_through_bytes() -> anyhow::Result<()> {
+        for configuration in Argon2Configuration::ALL_NEWEST_FIRST {
+            let byte = configuration.as_byte();
+            assert_eq!(Argon2Configuration::from_byte(byte)?, configuration);
+        }
+
+        assert!(Argon2Configuration::from_byte(0xFF).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn different_configurations_derive_different_keys() -> anyhow::Result<()> {
+        let password = "password";
+        let salt = SaltString::from_b64("Y2hpcCBzcGljZQ").unwrap();
+
+        let key_v0 =
+            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V0)?;
+        let key_v1 =
+            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V1)?;
+        let key_v2 =
+            derive_secret_box_key_with_configuration(password, &salt, Argon2Configuration::V2)?;
+
+        assert_ne!(key_v0, key_v1);
+        assert_ne!(key_v1, key_v2);
+        assert_ne!(key_v0, key_v2);
+
+        Ok(())
+    }
 }
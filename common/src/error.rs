@@ -17,6 +17,10 @@ pub enum Error {
     Aead(#[from] aead::Error),
     #[error("Compressed string too long")]
     CompressedStringTooLong(f32),
+    #[error("Message too long to split into fragments")]
+    TooManyMessageFragments(f32),
+    #[error("Invalid read receipt")]
+    InvalidReadReceipt,
     #[error("Decompression ratio is too high")]
     DecompressionRatioTooHigh,
     #[error("Invalid padded compressed string")]
@@ -43,6 +47,8 @@ pub enum Error {
     Argon2Missing,
     #[error("Argon2 bad parameters")]
     Argon2BadParameters,
+    #[error("Unknown Argon2 configuration {0}")]
+    Argon2UnknownConfiguration(u8),
     #[error("Journalist ID is invalid")]
     InvalidJournalistId,
     #[error("Journalist '{0}' not found")]
@@ -79,4 +85,12 @@ pub enum Error {
     SecretSharingTooFewShares(u64, u64),
     #[error("Share size mismatch; expected {0} bytes, but got {1}")]
     SecretSharingShareSizeMismatch(usize, usize),
+    #[error("Key has not been added to the key transparency log")]
+    KeyTransparencyKeyNotLogged,
+    #[error("Invalid key transparency inclusion proof")]
+    KeyTransparencyInvalidProof,
+    #[error("Key transparency proof missing")]
+    KeyTransparencyProofMissing,
+    #[error("Key transparency log tree head of size {0} is inconsistent with a known tree head")]
+    KeyTransparencyFork(u64),
 }
@@ -1,7 +1,7 @@
 pub mod error;
 pub mod name_generator;
 pub mod password_generator;
-mod word_list;
+pub(crate) mod word_list;
 
 pub use error::GeneratorError;
 pub use name_generator::NameGenerator;
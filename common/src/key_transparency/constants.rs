@@ -0,0 +1,7 @@
+use chrono::Duration;
+
+/// Lives as long as the organization key - we don't want to be rotating this super often
+pub const KEY_TRANSPARENCY_LOG_KEY_VALID_DURATION: Duration = Duration::weeks(52);
+
+/// Domain separation label for the tree head signatures
+pub(crate) const TREE_HEAD_SIGNATURE_LABEL: &[u8] = b"CoverDrop-KeyTransparency-TreeHead-v1";
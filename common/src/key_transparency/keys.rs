@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    crypto::keys::{
+        serde::StorableKeyMaterial,
+        signing::{traits, SignedPublicSigningKey, SignedSigningKeyPair, UnsignedSigningKeyPair},
+        untrusted::signing::{UntrustedSignedPublicSigningKey, UntrustedSignedSigningKeyPair},
+    },
+    protocol::{
+        keys::{OrganizationKeyPair, OrganizationPublicKey},
+        roles::AnchorOrganization,
+    },
+};
+
+use super::{constants::KEY_TRANSPARENCY_LOG_KEY_VALID_DURATION, roles::KeyTransparencyLog};
+
+pub type UntrustedKeyTransparencyLogPublicKey = UntrustedSignedPublicSigningKey<KeyTransparencyLog>;
+pub type UntrustedKeyTransparencyLogKeyPair = UntrustedSignedSigningKeyPair<KeyTransparencyLog>;
+pub type KeyTransparencyLogPublicKey = SignedPublicSigningKey<KeyTransparencyLog>;
+pub type KeyTransparencyLogKeyPair = SignedSigningKeyPair<KeyTransparencyLog>;
+
+pub fn generate_key_transparency_log_key_pair(
+    org_key_pair: &OrganizationKeyPair,
+    now: DateTime<Utc>,
+) -> KeyTransparencyLogKeyPair {
+    let not_valid_after = now + KEY_TRANSPARENCY_LOG_KEY_VALID_DURATION;
+
+    UnsignedSigningKeyPair::generate().to_signed_key_pair(org_key_pair, not_valid_after)
+}
+
+pub fn verify_key_transparency_log_pk(
+    untrusted: &UntrustedKeyTransparencyLogPublicKey,
+    org_pk: &OrganizationPublicKey,
+    now: DateTime<Utc>,
+) -> anyhow::Result<KeyTransparencyLogPublicKey> {
+    untrusted.to_trusted(org_pk, now)
+}
+
+pub fn load_key_transparency_log_key_pairs(
+    keys_path: impl AsRef<Path>,
+    org_pks: &[impl traits::PublicSigningKey<AnchorOrganization>],
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<KeyTransparencyLogKeyPair>> {
+    let key_pairs = UntrustedKeyTransparencyLogKeyPair::load_from_directory(&keys_path)?
+        .iter()
+        .flat_map(|key_pair| {
+            org_pks
+                .iter()
+                .flat_map(|org_pk| key_pair.to_trusted(org_pk, now))
+        })
+        .collect::<Vec<_>>();
+
+    Ok(key_pairs)
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+use super::{
+    merkle::{self, MerkleHash},
+    tree_head::TreeHead,
+};
+
+/// The tree heads a client has previously accepted, ordered by tree size.
+///
+/// A client that is shown a different view of the log to everyone else will eventually be
+/// handed a tree head which is not consistent with one of these.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KnownTreeHeads(Vec<TreeHead>);
+
+impl KnownTreeHeads {
+    /// Clients only need to keep the newest tree head they've accepted, since every later tree
+    /// head must be consistent with it
+    pub fn from_latest(latest: Option<TreeHead>) -> Self {
+        Self(latest.into_iter().collect())
+    }
+
+    pub fn latest(&self) -> Option<&TreeHead> {
+        self.0.last()
+    }
+
+    /// The tree sizes a consistency proof must be requested for before `tree_head` can be passed
+    /// to [`Self::check_and_insert`]. `None` if no proof is required.
+    pub fn consistency_proof_sizes(&self, tree_head: &TreeHead) -> Option<(u64, u64)> {
+        if self.find_same_size(tree_head).is_some() {
+            return None;
+        }
+
+        if let Some(older) = self.closest_older(tree_head) {
+            Some((older.tree_size, tree_head.tree_size))
+        } else {
+            self.closest_newer(tree_head)
+                .map(|newer| (tree_head.tree_size, newer.tree_size))
+        }
+    }
+
+    /// Check that `tree_head` is consistent with the tree heads seen before and remember it.
+    /// `consistency_proof` must be the proof for the sizes returned by
+    /// [`Self::consistency_proof_sizes`].
+    pub fn check_and_insert(
+        &mut self,
+        tree_head: &TreeHead,
+        consistency_proof: &[MerkleHash],
+    ) -> Result<(), Error> {
+        if let Some(known) = self.find_same_size(tree_head) {
+            if known.root_hash != tree_head.root_hash {
+                return Err(Error::KeyTransparencyFork(tree_head.tree_size));
+            }
+
+            return Ok(());
+        }
+
+        let is_consistent = if let Some(older) = self.closest_older(tree_head) {
+            merkle::verify_consistency(
+                older.tree_size,
+                tree_head.tree_size,
+                &older.root_hash,
+                &tree_head.root_hash,
+                consistency_proof,
+            )
+        } else if let Some(newer) = self.closest_newer(tree_head) {
+            merkle::verify_consistency(
+                tree_head.tree_size,
+                newer.tree_size,
+                &tree_head.root_hash,
+                &newer.root_hash,
+                consistency_proof,
+            )
+        } else {
+            true
+        };
+
+        if !is_consistent {
+            return Err(Error::KeyTransparencyFork(tree_head.tree_size));
+        }
+
+        let index = self
+            .0
+            .partition_point(|known| known.tree_size < tree_head.tree_size);
+        self.0.insert(index, tree_head.clone());
+
+        Ok(())
+    }
+
+    fn find_same_size(&self, tree_head: &TreeHead) -> Option<&TreeHead> {
+        self.0
+            .iter()
+            .find(|known| known.tree_size == tree_head.tree_size)
+    }
+
+    fn closest_older(&self, tree_head: &TreeHead) -> Option<&TreeHead> {
+        self.0
+            .iter()
+            .rev()
+            .find(|known| known.tree_size < tree_head.tree_size)
+    }
+
+    fn closest_newer(&self, tree_head: &TreeHead) -> Option<&TreeHead> {
+        self.0
+            .iter()
+            .find(|known| known.tree_size > tree_head.tree_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::{
+        key_transparency::merkle::{consistency_proof, leaf_hash, root},
+        Error,
+    };
+
+    use super::*;
+
+    fn tree_head(leaves: &[MerkleHash]) -> TreeHead {
+        TreeHead::new(
+            leaves.len() as u64,
+            root(leaves),
+            Utc.timestamp_opt(0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn accepts_growing_log_and_rejects_fork() -> anyhow::Result<()> {
+        let leaves: Vec<_> = (0..10_u8).map(|i| leaf_hash(&[i])).collect();
+        let mut known = KnownTreeHeads::default();
+
+        known.check_and_insert(&tree_head(&leaves[..4]), &[])?;
+
+        let grown = tree_head(&leaves[..10]);
+        assert_eq!(known.consistency_proof_sizes(&grown), Some((4, 10)));
+        known.check_and_insert(&grown, &consistency_proof(&leaves[..10], 4))?;
+        assert_eq!(known.latest(), Some(&grown));
+
+        // Same size, different contents
+        let mut forked = leaves.clone();
+        forked[2] = leaf_hash(b"substituted key");
+        let result = known.check_and_insert(&tree_head(&forked[..10]), &[]);
+        assert!(matches!(result, Err(Error::KeyTransparencyFork(10))));
+
+        // Larger tree which doesn't extend the known one
+        forked.push(leaf_hash(b"new key"));
+        let result = known.check_and_insert(&tree_head(&forked), &consistency_proof(&forked, 10));
+        assert!(matches!(result, Err(Error::KeyTransparencyFork(11))));
+
+        Ok(())
+    }
+}
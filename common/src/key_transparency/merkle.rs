@@ -0,0 +1,314 @@
+//! Merkle tree hashing, inclusion proofs and consistency proofs as described in RFC 9162
+//! (Certificate Transparency version 2.0), sections 2.1.1 to 2.1.4.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const LEAF_HASH_PREFIX: u8 = 0x00;
+const NODE_HASH_PREFIX: u8 = 0x01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MerkleHash(#[serde(with = "hex")] [u8; 32]);
+
+impl MerkleHash {
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("invalid merkle hash length: {}", bytes.len()))?;
+
+        Ok(MerkleHash(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for MerkleHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The hash of the empty tree
+fn empty_root() -> MerkleHash {
+    MerkleHash(Sha256::digest([]).into())
+}
+
+pub fn leaf_hash(data: &[u8]) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_HASH_PREFIX]);
+    hasher.update(data);
+
+    MerkleHash(hasher.finalize().into())
+}
+
+fn node_hash(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_HASH_PREFIX]);
+    hasher.update(left.0);
+    hasher.update(right.0);
+
+    MerkleHash(hasher.finalize().into())
+}
+
+/// The largest power of two strictly smaller than `n`, `n` must be greater than one
+fn split_point(n: usize) -> usize {
+    debug_assert!(n > 1);
+
+    1 << (usize::BITS - (n - 1).leading_zeros() - 1)
+}
+
+/// Compute the root hash of a tree containing the given leaf hashes
+pub fn root(leaves: &[MerkleHash]) -> MerkleHash {
+    match leaves.len() {
+        0 => empty_root(),
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            node_hash(&root(&leaves[..k]), &root(&leaves[k..]))
+        }
+    }
+}
+
+/// Build the audit path proving that the leaf at `index` is part of the tree made of `leaves`.
+pub fn inclusion_proof(leaves: &[MerkleHash], index: usize) -> Vec<MerkleHash> {
+    assert!(index < leaves.len(), "leaf index out of bounds");
+
+    let mut proof = Vec::new();
+    build_inclusion_proof(leaves, index, &mut proof);
+    proof
+}
+
+fn build_inclusion_proof(leaves: &[MerkleHash], index: usize, proof: &mut Vec<MerkleHash>) {
+    let n = leaves.len();
+    if n <= 1 {
+        return;
+    }
+
+    let k = split_point(n);
+    if index < k {
+        build_inclusion_proof(&leaves[..k], index, proof);
+        proof.push(root(&leaves[k..]));
+    } else {
+        build_inclusion_proof(&leaves[k..], index - k, proof);
+        proof.push(root(&leaves[..k]));
+    }
+}
+
+/// Build the proof that the tree made of the first `first_size` leaves is a prefix of the tree
+/// made of all of `leaves`.
+pub fn consistency_proof(leaves: &[MerkleHash], first_size: usize) -> Vec<MerkleHash> {
+    assert!(
+        first_size <= leaves.len(),
+        "first tree is larger than second"
+    );
+
+    let mut proof = Vec::new();
+    if first_size > 0 && first_size < leaves.len() {
+        build_consistency_proof(leaves, first_size, true, &mut proof);
+    }
+    proof
+}
+
+fn build_consistency_proof(
+    leaves: &[MerkleHash],
+    m: usize,
+    is_complete_subtree: bool,
+    proof: &mut Vec<MerkleHash>,
+) {
+    let n = leaves.len();
+    if m == n {
+        if !is_complete_subtree {
+            proof.push(root(leaves));
+        }
+        return;
+    }
+
+    let k = split_point(n);
+    if m <= k {
+        build_consistency_proof(&leaves[..k], m, is_complete_subtree, proof);
+        proof.push(root(&leaves[k..]));
+    } else {
+        build_consistency_proof(&leaves[k..], m - k, false, proof);
+        proof.push(root(&leaves[..k]));
+    }
+}
+
+/// Verify an audit path produced by [`inclusion_proof`] against a known root hash.
+pub fn verify_inclusion(
+    leaf_hash: &MerkleHash,
+    leaf_index: u64,
+    tree_size: u64,
+    audit_path: &[MerkleHash],
+    root_hash: &MerkleHash,
+) -> bool {
+    if leaf_index >= tree_size {
+        return false;
+    }
+
+    let mut f_n = leaf_index;
+    let mut s_n = tree_size - 1;
+    let mut r = *leaf_hash;
+
+    for p in audit_path {
+        if s_n == 0 {
+            return false;
+        }
+
+        if f_n & 1 == 1 || f_n == s_n {
+            r = node_hash(p, &r);
+            while f_n & 1 == 0 && f_n != 0 {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    s_n == 0 && r == *root_hash
+}
+
+/// Verify a proof produced by [`consistency_proof`] that the tree with `first_root` is a
+/// prefix of the tree with `second_root`.
+pub fn verify_consistency(
+    first_size: u64,
+    second_size: u64,
+    first_root: &MerkleHash,
+    second_root: &MerkleHash,
+    proof: &[MerkleHash],
+) -> bool {
+    if first_size > second_size {
+        return false;
+    }
+
+    if first_size == second_size {
+        return proof.is_empty() && first_root == second_root;
+    }
+
+    if first_size == 0 {
+        return proof.is_empty();
+    }
+
+    let mut path = Vec::with_capacity(proof.len() + 1);
+    if first_size.is_power_of_two() {
+        path.push(*first_root);
+    }
+    path.extend_from_slice(proof);
+
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+
+    let mut f_n = first_size - 1;
+    let mut s_n = second_size - 1;
+
+    while f_n & 1 == 1 {
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    let mut f_r = *first;
+    let mut s_r = *first;
+
+    for c in rest {
+        if s_n == 0 {
+            return false;
+        }
+
+        if f_n & 1 == 1 || f_n == s_n {
+            f_r = node_hash(c, &f_r);
+            s_r = node_hash(c, &s_r);
+            while f_n & 1 == 0 && f_n != 0 {
+                f_n >>= 1;
+                s_n >>= 1;
+            }
+        } else {
+            s_r = node_hash(&s_r, c);
+        }
+
+        f_n >>= 1;
+        s_n >>= 1;
+    }
+
+    f_r == *first_root && s_r == *second_root && s_n == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<MerkleHash> {
+        (0..n)
+            .map(|i| leaf_hash(&(i as u64).to_be_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn inclusion_proofs_verify_for_every_leaf() {
+        for n in 1..=33 {
+            let leaves = leaves(n);
+            let root_hash = root(&leaves);
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = inclusion_proof(&leaves, index);
+                assert!(
+                    verify_inclusion(leaf, index as u64, n as u64, &proof, &root_hash),
+                    "leaf {index} of {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inclusion_proof_fails_for_wrong_leaf_or_index() {
+        let leaves = leaves(11);
+        let root_hash = root(&leaves);
+        let proof = inclusion_proof(&leaves, 4);
+
+        assert!(!verify_inclusion(&leaves[5], 4, 11, &proof, &root_hash));
+        assert!(!verify_inclusion(&leaves[4], 5, 11, &proof, &root_hash));
+        assert!(!verify_inclusion(&leaves[4], 4, 17, &proof, &root_hash));
+        assert!(!verify_inclusion(&leaves[4], 11, 11, &proof, &root_hash));
+    }
+
+    #[test]
+    fn consistency_proofs_verify_for_every_prefix() {
+        for n in 1..=33 {
+            let leaves = leaves(n);
+            let second_root = root(&leaves);
+
+            for m in 0..=n {
+                let first_root = root(&leaves[..m]);
+                let proof = consistency_proof(&leaves, m);
+
+                assert!(
+                    verify_consistency(m as u64, n as u64, &first_root, &second_root, &proof),
+                    "prefix {m} of {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn consistency_proof_fails_for_forked_tree() {
+        let leaves = leaves(13);
+        let mut forked = leaves.clone();
+        forked[3] = leaf_hash(b"substituted key");
+
+        let proof = consistency_proof(&forked, 7);
+
+        assert!(!verify_consistency(
+            7,
+            13,
+            &root(&leaves[..7]),
+            &root(&forked),
+            &proof
+        ));
+    }
+}
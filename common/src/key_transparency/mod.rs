@@ -0,0 +1,17 @@
+//! An append-only Merkle log of every public key the API has published.
+//!
+//! Alongside the key hierarchy, the API returns a signed tree head and an inclusion proof for
+//! each key. Clients remember the tree heads they have seen in [`KnownTreeHeads`] so that they
+//! can tell if they are being shown a different set of keys to everyone else.
+
+pub mod constants;
+pub mod keys;
+mod known_tree_heads;
+pub mod merkle;
+mod proof;
+pub mod roles;
+mod tree_head;
+
+pub use known_tree_heads::KnownTreeHeads;
+pub use proof::{key_transparency_leaves, InclusionProof, UntrustedKeyTransparencyProof};
+pub use tree_head::{SignedTreeHead, TreeHead, TreeHeadSignatureData};
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crypto::keys::{
+        encryption::SignedPublicEncryptionKey, role::Role, signing::SignedPublicSigningKey,
+    },
+    protocol::keys::OrganizationPublicKeyFamilyList,
+    Error,
+};
+
+use super::{
+    keys::{
+        verify_key_transparency_log_pk, KeyTransparencyLogKeyPair,
+        UntrustedKeyTransparencyLogPublicKey,
+    },
+    merkle::{self, MerkleHash},
+    tree_head::{SignedTreeHead, TreeHead},
+};
+
+/// The audit path for a single published key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InclusionProof {
+    pub leaf_hash: MerkleHash,
+    pub leaf_index: u64,
+    pub audit_path: Vec<MerkleHash>,
+}
+
+/// Sent alongside the public key hierarchy. Proves that every key in the hierarchy has been
+/// appended to the key transparency log described by the signed tree head.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UntrustedKeyTransparencyProof {
+    pub log_pk: UntrustedKeyTransparencyLogPublicKey,
+    pub signed_tree_head: SignedTreeHead,
+    pub inclusion_proofs: Vec<InclusionProof>,
+}
+
+impl UntrustedKeyTransparencyProof {
+    /// Build the proof for the `published` leaves. `log` must contain every leaf in the log, in
+    /// the order they were appended.
+    pub fn new(
+        log_key_pair: &KeyTransparencyLogKeyPair,
+        log: &[MerkleHash],
+        published: &[MerkleHash],
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Self> {
+        let leaf_indexes = log
+            .iter()
+            .enumerate()
+            .map(|(index, leaf_hash)| (leaf_hash, index))
+            .collect::<HashMap<_, _>>();
+
+        let inclusion_proofs = published
+            .iter()
+            .map(|leaf_hash| {
+                let Some(&leaf_index) = leaf_indexes.get(leaf_hash) else {
+                    anyhow::bail!("Published key is missing from the key transparency log");
+                };
+
+                Ok(InclusionProof {
+                    leaf_hash: *leaf_hash,
+                    leaf_index: leaf_index as u64,
+                    audit_path: merkle::inclusion_proof(log, leaf_index),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let tree_head = TreeHead::new(log.len() as u64, merkle::root(log), now);
+
+        Ok(Self {
+            log_pk: log_key_pair.public_key().to_untrusted(),
+            signed_tree_head: SignedTreeHead::new(tree_head, log_key_pair),
+            inclusion_proofs,
+        })
+    }
+
+    /// Reuse a stored proof for the `published` leaves, dropping the inclusion proofs of keys
+    /// which are no longer published. `None` if the proof was signed with a different log key or
+    /// is missing one of the leaves, in which case a new proof has to be built.
+    pub fn for_published(
+        &self,
+        log_key_pair: &KeyTransparencyLogKeyPair,
+        published: &[MerkleHash],
+    ) -> Option<Self> {
+        if self.log_pk != log_key_pair.public_key().to_untrusted() {
+            return None;
+        }
+
+        let inclusion_proofs = published
+            .iter()
+            .map(|leaf_hash| {
+                self.inclusion_proofs
+                    .iter()
+                    .find(|proof| proof.leaf_hash == *leaf_hash)
+                    .cloned()
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            log_pk: self.log_pk.clone(),
+            signed_tree_head: self.signed_tree_head.clone(),
+            inclusion_proofs,
+        })
+    }
+
+    /// Check that the tree head was signed by a log key certified by one of the organization keys
+    /// in `keys`, and that every key in `keys` is included in the tree.
+    pub fn verify(
+        &self,
+        keys: &OrganizationPublicKeyFamilyList,
+        now: DateTime<Utc>,
+    ) -> Result<&TreeHead, Error> {
+        let log_pk = keys
+            .org_pk_iter()
+            .find_map(|org_pk| verify_key_transparency_log_pk(&self.log_pk, org_pk, now).ok())
+            .ok_or(Error::UnableToVerifyKey)?;
+
+        let tree_head = self.signed_tree_head.verify(&log_pk, now)?;
+
+        for leaf_hash in key_transparency_leaves(keys) {
+            let proof = self
+                .inclusion_proofs
+                .iter()
+                .find(|proof| proof.leaf_hash == leaf_hash)
+                .ok_or(Error::KeyTransparencyKeyNotLogged)?;
+
+            if !merkle::verify_inclusion(
+                &proof.leaf_hash,
+                proof.leaf_index,
+                tree_head.tree_size,
+                &proof.audit_path,
+                &tree_head.root_hash,
+            ) {
+                return Err(Error::KeyTransparencyInvalidProof);
+            }
+        }
+
+        Ok(tree_head)
+    }
+}
+
+fn leaf<R: Role>(owner: &str, key: &[u8], not_valid_after: DateTime<Utc>) -> MerkleHash {
+    let mut data = Vec::new();
+    data.extend(R::entity_name().as_bytes());
+    data.push(0);
+    data.extend(owner.as_bytes());
+    data.push(0);
+    data.extend(key);
+    data.extend(not_valid_after.timestamp().to_be_bytes());
+
+    merkle::leaf_hash(&data)
+}
+
+fn signing_pk_leaf<R: Role>(owner: &str, pk: &SignedPublicSigningKey<R>) -> MerkleHash {
+    leaf::<R>(owner, pk.key.as_bytes(), pk.not_valid_after)
+}
+
+fn encryption_pk_leaf<R: Role>(owner: &str, pk: &SignedPublicEncryptionKey<R>) -> MerkleHash {
+    leaf::<R>(owner, pk.key.as_bytes(), pk.not_valid_after)
+}
+
+/// The log leaves for every organization, provisioning, identity and messaging key in the
+/// hierarchy. Keys belonging to a journalist or CoverNode are bound to its identity so that
+/// a key cannot be moved from one owner to another without changing its leaf.
+pub fn key_transparency_leaves(keys: &OrganizationPublicKeyFamilyList) -> Vec<MerkleHash> {
+    let org_pks = keys.org_pk_iter().map(|pk| signing_pk_leaf("", pk));
+
+    let covernode_provisioning_pks = keys
+        .covernode_provisioning_pk_iter()
+        .map(|pk| signing_pk_leaf("", pk));
+
+    let covernode_id_pks = keys
+        .covernode_id_pk_iter()
+        .map(|(covernode_id, pk)| signing_pk_leaf(covernode_id, pk));
+
+    let covernode_msg_pks = keys
+        .covernode_msg_pk_iter()
+        .map(|(covernode_id, pk)| encryption_pk_leaf(covernode_id, pk));
+
+    let journalist_provisioning_pks = keys
+        .journalist_provisioning_pk_iter()
+        .map(|pk| signing_pk_leaf("", pk));
+
+    let journalist_id_pks = keys
+        .journalist_id_pk_iter()
+        .map(|(journalist_id, pk)| signing_pk_leaf(journalist_id, pk));
+
+    let journalist_msg_pks = keys
+        .journalist_msg_pk_iter()
+        .map(|(journalist_id, pk)| encryption_pk_leaf(journalist_id, pk));
+
+    org_pks
+        .chain(covernode_provisioning_pks)
+        .chain(covernode_id_pks)
+        .chain(covernode_msg_pks)
+        .chain(journalist_provisioning_pks)
+        .chain(journalist_id_pks)
+        .chain(journalist_msg_pks)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        key_transparency::keys::generate_key_transparency_log_key_pair,
+        protocol::keys::test::{generate_protocol_keys, ProtocolKeys},
+        time,
+    };
+
+    use super::*;
+
+    #[test]
+    fn proof_verifies_when_every_key_is_logged() {
+        let now = time::now();
+        let ProtocolKeys {
+            hierarchy,
+            org_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let log_key_pair = generate_key_transparency_log_key_pair(&org_key_pair, now);
+
+        let published = key_transparency_leaves(&hierarchy);
+        let mut log = vec![merkle::leaf_hash(b"some older key")];
+        log.extend(published.iter().copied());
+
+        let proof = UntrustedKeyTransparencyProof::new(&log_key_pair, &log, &published, now)
+            .expect("Build proof");
+
+        let tree_head = proof.verify(&hierarchy, now).expect("Verify proof");
+        assert_eq!(tree_head.tree_size, log.len() as u64);
+        assert_eq!(tree_head.root_hash, merkle::root(&log));
+
+        // A proof that leaves out one of the published keys is rejected
+        let partial = UntrustedKeyTransparencyProof::new(&log_key_pair, &log, &published[1..], now)
+            .expect("Build proof");
+
+        assert!(matches!(
+            partial.verify(&hierarchy, now),
+            Err(Error::KeyTransparencyKeyNotLogged)
+        ));
+    }
+
+    #[test]
+    fn stored_proof_is_reused_only_when_it_covers_every_published_key() {
+        let now = time::now();
+        let ProtocolKeys {
+            hierarchy,
+            org_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let log_key_pair = generate_key_transparency_log_key_pair(&org_key_pair, now);
+
+        let published = key_transparency_leaves(&hierarchy);
+        let mut log = published.clone();
+        log.push(merkle::leaf_hash(b"since expired key"));
+
+        let stored = UntrustedKeyTransparencyProof::new(&log_key_pair, &log, &log, now)
+            .expect("Build proof");
+
+        let reused = stored
+            .for_published(&log_key_pair, &published)
+            .expect("Stored proof covers published keys");
+        assert_eq!(reused.inclusion_proofs.len(), published.len());
+        reused.verify(&hierarchy, now).expect("Verify proof");
+
+        // A newly uploaded key isn't in the stored proof
+        let mut published_with_new_key = published.clone();
+        published_with_new_key.push(merkle::leaf_hash(b"new key"));
+        assert!(stored
+            .for_published(&log_key_pair, &published_with_new_key)
+            .is_none());
+
+        // Neither is a proof signed by a log key which has since been replaced
+        let new_log_key_pair = generate_key_transparency_log_key_pair(&org_key_pair, now);
+        assert!(stored
+            .for_published(&new_log_key_pair, &published)
+            .is_none());
+    }
+}
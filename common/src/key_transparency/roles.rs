@@ -0,0 +1,9 @@
+use crate::define_role;
+
+// The key transparency log key is held by the API and signs the tree heads of the log of
+// published public keys.
+define_role!(
+    KeyTransparencyLog,
+    "key transparency log",
+    "key_transparency_log"
+);
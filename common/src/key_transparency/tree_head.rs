@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use hex_buffer_serde::Hex as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    crypto::{
+        keys::{serde::SignatureHex, signing::traits::PublicSigningKey as _},
+        Signable, Signature,
+    },
+    Error,
+};
+
+use super::{
+    constants::TREE_HEAD_SIGNATURE_LABEL,
+    keys::{KeyTransparencyLogKeyPair, KeyTransparencyLogPublicKey},
+    merkle::MerkleHash,
+};
+
+/// A snapshot of the key transparency log. Every tree head with the same `tree_size` must have
+/// the same `root_hash`, otherwise the log has been forked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TreeHead {
+    pub tree_size: u64,
+    pub root_hash: MerkleHash,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TreeHead {
+    pub fn new(tree_size: u64, root_hash: MerkleHash, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            tree_size,
+            root_hash,
+            timestamp,
+        }
+    }
+
+    fn signature_data(&self) -> TreeHeadSignatureData {
+        let mut hasher = Sha256::new();
+
+        hasher.update(TREE_HEAD_SIGNATURE_LABEL);
+        hasher.update(self.tree_size.to_be_bytes());
+        hasher.update(self.root_hash.as_bytes());
+        hasher.update(self.timestamp.timestamp().to_be_bytes());
+
+        TreeHeadSignatureData(hasher.finalize().into())
+    }
+}
+
+/// A representation of the data required to sign/verify a tree head
+#[derive(Clone, Debug)]
+pub struct TreeHeadSignatureData(pub [u8; 32]);
+
+impl Signable for TreeHeadSignatureData {
+    fn as_signable_bytes(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedTreeHead {
+    pub tree_head: TreeHead,
+    #[serde(with = "SignatureHex")]
+    pub signature: Signature<TreeHeadSignatureData>,
+}
+
+impl SignedTreeHead {
+    pub fn new(tree_head: TreeHead, log_key_pair: &KeyTransparencyLogKeyPair) -> Self {
+        let signature = log_key_pair.sign(&tree_head.signature_data());
+
+        Self {
+            tree_head,
+            signature,
+        }
+    }
+
+    pub fn verify(
+        &self,
+        log_pk: &KeyTransparencyLogPublicKey,
+        now: DateTime<Utc>,
+    ) -> Result<&TreeHead, Error> {
+        log_pk.verify(&self.tree_head.signature_data(), &self.signature, now)?;
+
+        Ok(&self.tree_head)
+    }
+}
@@ -20,6 +20,8 @@ pub mod form;
 pub mod generators;
 pub mod healthcheck;
 pub mod identity_api;
+pub mod key_transparency;
+pub mod message_fragments;
 pub mod metrics;
 pub mod monitoring;
 #[allow(dead_code)]
@@ -0,0 +1,365 @@
+//! Splitting texts which are too long for a single [`FixedSizeMessageText`] into numbered
+//! fragments, and putting them back together on the receiving side.
+//!
+//! Each fragment is a regular message whose text starts with a short header containing a random
+//! message ID, the index of the fragment and the total number of fragments. Texts which fit in a
+//! single message are sent without a header so they look the same as they always have.
+
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    hash::Hash,
+    iter,
+};
+
+use crate::{protocol::constants::MAX_MESSAGE_FRAGMENTS, Error, FixedSizeMessageText};
+
+/// Marks the start of a fragment header. The ASCII record separator is not something a person
+/// would type into a message.
+const FRAGMENT_MARKER: char = '\u{1e}';
+
+/// The message ID, index and count encoded as fixed width lowercase hex.
+const FRAGMENT_HEADER_HEX_LEN: usize = 8 + 2 + 2;
+
+/// A single fragment of a longer text, parsed from the text of a received message.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MessageFragment<'a> {
+    /// Random ID shared by all the fragments of one text
+    pub message_id: u32,
+    pub index: u8,
+    pub count: u8,
+    pub text: &'a str,
+}
+
+impl<'a> MessageFragment<'a> {
+    /// Parse a fragment from the text of a message. Returns `None` if the text is a regular,
+    /// unfragmented message.
+    pub fn parse(text: &'a str) -> Option<Self> {
+        let rest = text.strip_prefix(FRAGMENT_MARKER)?;
+        let header = rest.get(..FRAGMENT_HEADER_HEX_LEN)?;
+
+        if !header.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let message_id = u32::from_str_radix(&header[..8], 16).ok()?;
+        let index = u8::from_str_radix(&header[8..10], 16).ok()?;
+        let count = u8::from_str_radix(&header[10..12], 16).ok()?;
+
+        if index >= count {
+            return None;
+        }
+
+        Some(Self {
+            message_id,
+            index,
+            count,
+            text: &rest[FRAGMENT_HEADER_HEX_LEN..],
+        })
+    }
+}
+
+fn fragment_text(message_id: u32, index: u8, count: u8, text: &str) -> String {
+    format!("{FRAGMENT_MARKER}{message_id:08x}{index:02x}{count:02x}{text}")
+}
+
+/// Split a text into messages. Texts which fit into a single message are returned as is,
+/// longer texts are split into at most [`MAX_MESSAGE_FRAGMENTS`] fragments which should be sent
+/// in order over successive sending rounds.
+pub fn split_into_fragments(text: &str) -> Result<Vec<FixedSizeMessageText>, Error> {
+    match FixedSizeMessageText::new(text) {
+        Ok(message) => return Ok(vec![message]),
+        Err(Error::CompressedStringTooLong(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let message_id = rand::random::<u32>();
+
+    // The headers contain the total number of fragments, which we only know after splitting.
+    // The header is fixed width so a second pass with the correct count almost always produces
+    // the same split.
+    let mut count = 2;
+    for _ in 0..MAX_MESSAGE_FRAGMENTS {
+        let fragments = split_with_count(text, message_id, count)?;
+
+        if fragments.len() == count {
+            return Ok(fragments);
+        }
+
+        count = fragments.len();
+    }
+
+    Err(Error::General(
+        "Failed to find a stable split of message into fragments".into(),
+    ))
+}
+
+fn split_with_count(
+    text: &str,
+    message_id: u32,
+    count: usize,
+) -> Result<Vec<FixedSizeMessageText>, Error> {
+    let header_count = u8::try_from(count).expect("Fragment count fits in u8");
+
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(iter::once(text.len()))
+        .collect();
+    let last = boundaries.len() - 1;
+
+    let mut fragments = Vec::with_capacity(count);
+    let mut start = 0;
+
+    while start < last {
+        if fragments.len() == MAX_MESSAGE_FRAGMENTS {
+            let ratio = text.len() as f32 / boundaries[start] as f32;
+            return Err(Error::TooManyMessageFragments(ratio));
+        }
+
+        let index = fragments.len() as u8;
+
+        // Find the longest run of characters which still fits in a message
+        let mut best = None;
+        let mut low = start + 1;
+        let mut high = last;
+
+        while low <= high {
+            let mid = low + (high - low) / 2;
+            let chunk = &text[boundaries[start]..boundaries[mid]];
+
+            match encode_fragment(message_id, index, header_count, chunk)? {
+                Some(fragment) => {
+                    best = Some((mid, fragment));
+                    low = mid + 1;
+                }
+                None => high = mid - 1,
+            }
+        }
+
+        let (end, fragment) = best.ok_or(Error::InvalidPaddedCompressedString)?;
+
+        fragments.push(fragment);
+        start = end;
+    }
+
+    Ok(fragments)
+}
+
+fn encode_fragment(
+    message_id: u32,
+    index: u8,
+    count: u8,
+    chunk: &str,
+) -> Result<Option<FixedSizeMessageText>, Error> {
+    let text = fragment_text(message_id, index, count, chunk);
+
+    match FixedSizeMessageText::new(&text) {
+        // Very repetitive text can compress so well that the receiver would reject it
+        Ok(fragment) if fragment.to_string().is_ok() => Ok(Some(fragment)),
+        Ok(_) | Err(Error::CompressedStringTooLong(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// A message after its fragments have been put back together.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReassembledMessage<T> {
+    /// The message which carried the first fragment to be received
+    pub message: T,
+    pub text: String,
+    /// The indices of fragments which have not been received yet
+    pub missing_fragments: Vec<u8>,
+}
+
+impl<T> ReassembledMessage<T> {
+    pub fn is_partial(&self) -> bool {
+        !self.missing_fragments.is_empty()
+    }
+}
+
+/// Put the fragments of messages back together, keeping the order of the messages.
+///
+/// Fragments are matched using their message ID within a conversation, and the reassembled
+/// text takes the position of the first fragment received. If some fragments are missing the
+/// text is made of the fragments that did arrive and the message is marked as partial.
+pub fn reassemble<T, K: Eq + Hash>(
+    messages: impl IntoIterator<Item = T>,
+    conversation: impl Fn(&T) -> K,
+    text: impl Fn(&T) -> &str,
+) -> Vec<ReassembledMessage<T>> {
+    enum Assembling<T> {
+        Whole(T, String),
+        Fragments {
+            first: T,
+            count: u8,
+            parts: BTreeMap<u8, String>,
+        },
+    }
+
+    let mut assembling = vec![];
+    let mut fragmented_messages = HashMap::new();
+
+    for message in messages {
+        let fragment = MessageFragment::parse(text(&message))
+            .map(|f| (f.message_id, f.index, f.count, f.text.to_owned()));
+
+        let Some((message_id, index, count, part)) = fragment else {
+            let text = text(&message).to_owned();
+            assembling.push(Assembling::Whole(message, text));
+            continue;
+        };
+
+        match fragmented_messages.entry((conversation(&message), message_id)) {
+            Entry::Occupied(entry) => {
+                if let Assembling::Fragments { parts, .. } = &mut assembling[*entry.get()] {
+                    parts.entry(index).or_insert(part);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(assembling.len());
+                assembling.push(Assembling::Fragments {
+                    first: message,
+                    count,
+                    parts: BTreeMap::from([(index, part)]),
+                });
+            }
+        }
+    }
+
+    assembling
+        .into_iter()
+        .map(|a| match a {
+            Assembling::Whole(message, text) => ReassembledMessage {
+                message,
+                text,
+                missing_fragments: vec![],
+            },
+            Assembling::Fragments {
+                first,
+                count,
+                parts,
+            } => ReassembledMessage {
+                message: first,
+                missing_fragments: (0..count).filter(|i| !parts.contains_key(i)).collect(),
+                text: parts.into_values().collect(),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_text(len: usize) -> String {
+        let mut rng = StdRng::seed_from_u64(0);
+        (0..len)
+            .map(|_| match rng.gen_range(0..30) {
+                0..=3 => ' ',
+                4 => 'é',
+                n => (b'a' + n as u8 - 5) as char,
+            })
+            .collect()
+    }
+
+    fn texts(fragments: &[FixedSizeMessageText]) -> Vec<String> {
+        fragments.iter().map(|f| f.to_string().unwrap()).collect()
+    }
+
+    #[test]
+    fn short_text_is_not_fragmented() -> Result<(), Error> {
+        let fragments = split_into_fragments("hello")?;
+
+        assert_eq!(texts(&fragments), vec!["hello".to_string()]);
+        assert!(MessageFragment::parse("hello").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn long_text_round_trips_through_fragments() -> Result<(), Error> {
+        let text = random_text(3000);
+
+        let fragments = texts(&split_into_fragments(&text)?);
+        assert!(fragments.len() > 1);
+
+        let parsed = fragments
+            .iter()
+            .map(|f| MessageFragment::parse(f).expect("Parse fragment"))
+            .collect::<Vec<_>>();
+
+        for (i, fragment) in parsed.iter().enumerate() {
+            assert_eq!(fragment.index as usize, i);
+            assert_eq!(fragment.count as usize, fragments.len());
+            assert_eq!(fragment.message_id, parsed[0].message_id);
+        }
+
+        let reassembled = reassemble(fragments, |_| (), |t| t);
+        assert_eq!(reassembled.len(), 1);
+        assert_eq!(reassembled[0].text, text);
+        assert!(!reassembled[0].is_partial());
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_fragment_is_reported_as_partial() -> Result<(), Error> {
+        let text = random_text(3000);
+
+        let mut fragments = texts(&split_into_fragments(&text)?);
+        let missing = fragments.remove(1);
+
+        let reassembled = reassemble(fragments, |_| (), |t| t);
+        assert_eq!(reassembled.len(), 1);
+        assert!(reassembled[0].is_partial());
+        assert_eq!(reassembled[0].missing_fragments, vec![1]);
+
+        let missing_text = MessageFragment::parse(&missing).unwrap().text;
+        assert_eq!(reassembled[0].text, text.replacen(missing_text, "", 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fragments_are_reassembled_per_conversation_in_order() -> Result<(), Error> {
+        let text = random_text(3000);
+        let fragments = texts(&split_into_fragments(&text)?);
+
+        // The same fragments turning up in two conversations, interleaved with other messages
+        let mut messages = vec![("alice", "before".to_string())];
+        for fragment in &fragments {
+            messages.push(("alice", fragment.clone()));
+            messages.push(("bob", fragment.clone()));
+        }
+        messages.push(("alice", "after".to_string()));
+
+        let reassembled = reassemble(messages, |(c, _)| *c, |(_, t)| t);
+
+        let summary = reassembled
+            .iter()
+            .map(|r| (r.message.0, r.text.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("alice", "before"),
+                ("alice", text.as_str()),
+                ("bob", text.as_str()),
+                ("alice", "after"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_needing_too_many_fragments_is_rejected() {
+        let text = random_text(20_000);
+
+        let result = split_into_fragments(&text);
+        assert!(matches!(result, Err(Error::TooManyMessageFragments(ratio)) if ratio > 1.0));
+    }
+}
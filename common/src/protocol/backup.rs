@@ -135,6 +135,7 @@ pub fn sentinel_create_backup(
         wrapped_encrypted_shares,
         created_at: now,
         recovery_contacts: recovery_contacts.into_iter().map(|c| c.identity).collect(),
+        k,
     };
     let backup_data_with_signature = backup_data
         .to_backup_data_with_signature(&journalist_identity_key)
@@ -210,6 +211,14 @@ pub struct BackupRestorationInProgress {
     #[serde(with = "BackupEncryptedPaddedVault")]
     backup_encrypted_padded_vault: BackupEncryptedPaddedVault,
     pub encrypted_shares: Vec<EncryptedSecretShareWithRecipient>,
+    /// The number of shares needed to restore the backup
+    #[serde(default = "default_k")]
+    pub k: ShareCount,
+}
+
+/// Restorations started before `k` was recorded were always for backups with a single share
+fn default_k() -> ShareCount {
+    1
 }
 
 /// Runs on the backup admin's device to initiate the restoration of a Sentinel backup. It
@@ -259,10 +268,19 @@ pub fn coverup_initiate_restore_step(
         ));
     }
 
+    if unwrapped_encrypted_shares.len() < backup_data.k as usize {
+        return Err(anyhow::anyhow!(
+            "Only {} encrypted shares could be unwrapped, but {} are needed to restore the backup",
+            unwrapped_encrypted_shares.len(),
+            backup_data.k
+        ));
+    }
+
     let backup_state = BackupRestorationInProgress {
         journalist_identity: backup_data.journalist_identity,
         backup_encrypted_padded_vault: backup_data.backup_encrypted_padded_vault,
         encrypted_shares: unwrapped_encrypted_shares,
+        k: backup_data.k,
     };
     Ok(backup_state)
 }
@@ -317,12 +335,12 @@ pub fn coverup_finish_restore_step(
     backup_state: BackupRestorationInProgress,
     wrapped_shares: Vec<WrappedSecretShare>,
     backup_admin_encryption_key_pairs: &[SignedEncryptionKeyPair<BackupMsg>],
-    k: ShareCount,
 ) -> anyhow::Result<Vec<u8>> {
     if wrapped_shares.is_empty() {
         return Err(anyhow::anyhow!("No wrapped shares provided"));
     }
 
+    let k = backup_state.k;
     if k < 1 {
         return Err(anyhow::anyhow!(
             "The backup protocol requires k >= 1, but got k={k}"
@@ -498,7 +516,6 @@ mod tests {
             backup_state,
             vec![wrapped_share],
             &vec![backup_admin_encryption_pair],
-            1, // k=1
         )
         .expect("Failed to finish restore");
 
@@ -739,7 +756,6 @@ mod tests {
             backup_state,
             vec![wrapped_share],
             &vec![backup_admin_encryption_pair],
-            1, // k=1
         );
 
         assert!(
@@ -806,7 +822,6 @@ mod tests {
             backup_state,
             vec![wrapped_share],
             &vec![backup_admin_encryption_pair],
-            1, // k=1
         );
 
         assert!(
@@ -944,7 +959,6 @@ mod tests {
             backup_state,
             vec![wrapped_share1, wrapped_share2],
             &vec![backup_admin_encryption_pair],
-            2, // k=2
         )
         .expect("Failed to finish restore");
 
@@ -1022,7 +1036,6 @@ mod tests {
             backup_state,
             vec![wrapped_share1],
             &vec![backup_admin_encryption_pair],
-            2, // k=2
         );
 
         assert!(
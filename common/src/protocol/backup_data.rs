@@ -3,7 +3,7 @@ use crate::crypto::keys::signing::traits::PublicSigningKey;
 use crate::crypto::keys::signing::{SignedPublicSigningKey, SignedSigningKeyPair};
 use crate::crypto::keys::untrusted::signing::UntrustedSignedPublicSigningKey;
 use crate::crypto::{
-    AnonymousBox, Encryptable, SecretBox, SecretSharingShare, Signable, Signature,
+    AnonymousBox, Encryptable, SecretBox, SecretSharingShare, ShareCount, Signable, Signature,
 };
 use crate::padded_byte_vector::SteppingPaddedByteVector;
 use crate::protocol::roles::JournalistId;
@@ -116,6 +116,14 @@ pub struct BackupData {
     pub wrapped_encrypted_shares: Vec<BackupEncryptedSecretShareWithRecipient>,
     pub created_at: DateTime<Utc>,
     pub recovery_contacts: Vec<JournalistIdentity>,
+    /// The number of shares needed to restore the backup
+    #[serde(default = "default_k")]
+    pub k: ShareCount,
+}
+
+/// Backups created before `k` was recorded could always be restored from a single share
+fn default_k() -> ShareCount {
+    1
 }
 
 impl BackupData {
@@ -350,6 +358,7 @@ mod tests {
             ],
             created_at: now(),
             recovery_contacts: vec![recovery_journalist_identity],
+            k: 1,
         };
         Ok(backup_data)
     }
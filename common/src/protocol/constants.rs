@@ -68,6 +68,10 @@ pub const CLIENT_STATUS_DOWNLOAD_RATE: Duration = Duration::minutes(5);
 /// The size a message is padded to after compression.
 pub const MESSAGE_PADDING_LEN: u16 = 512;
 
+/// The maximum number of fragments a long message can be split into. Each fragment occupies its
+/// own message slot, so this matches the number of messages a user can have queued at once.
+pub const MAX_MESSAGE_FRAGMENTS: usize = 8;
+
 /// The number of messaging keys from different CoverNodes used when encrypting a *_TO_COVERNODE message.
 pub const COVERNODE_WRAPPING_KEY_COUNT: usize = 2;
 
@@ -154,6 +158,33 @@ pub const USER_TO_JOURNALIST_MESSAGE_LEN: usize =
     X25519_PUBLIC_KEY_LEN + USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE + MESSAGE_PADDING_LEN as usize;
 pub const USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE: usize = 1;
 
+/// The length of the proof that a user key rotation came from the holder of the previous key.
+/// The proof is the new public key in a `TwoPartyBox` from the previous key to the journalist.
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(USER_KEY_ROTATION_PROOF_LEN, 72);
+/// assert_eq!(USER_KEY_ROTATION_PROOF_LEN,
+///     X25519_PUBLIC_KEY_LEN + POLY1305_AUTH_TAG_LEN + TWO_PARTY_BOX_NONCE_LEN);
+/// ```
+pub const USER_KEY_ROTATION_PROOF_LEN: usize =
+    X25519_PUBLIC_KEY_LEN + POLY1305_AUTH_TAG_LEN + TWO_PARTY_BOX_NONCE_LEN;
+
+/// The maximum number of messages acknowledged by a single read receipt.
+pub const MAX_READ_RECEIPT_MESSAGE_IDS: usize = 32;
+
+/// The length of the ID a read receipt uses to refer to a message, derived from the
+/// message's padded text.
+pub const READ_RECEIPT_MESSAGE_ID_LEN: usize = 4;
+
+/// The length of a read receipt carried in a J2U or U2J message, one byte for the number of
+/// acknowledged messages followed by their IDs. Unused IDs are zero.
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(READ_RECEIPT_LEN, 129);
+/// assert_eq!(READ_RECEIPT_LEN, 1 + MAX_READ_RECEIPT_MESSAGE_IDS * READ_RECEIPT_MESSAGE_ID_LEN);
+/// ```
+pub const READ_RECEIPT_LEN: usize = 1 + MAX_READ_RECEIPT_MESSAGE_IDS * READ_RECEIPT_MESSAGE_ID_LEN;
+
 //
 // COVERNODE_TO_...
 //
@@ -265,6 +296,114 @@ pub const JOURNALIST_TO_USER_MESSAGE_LEN: usize =
     JOURNALIST_TO_USER_MESSAGE_TYPE_FLAG_LEN + MESSAGE_PADDING_LEN as usize;
 pub const JOURNALIST_TO_USER_MESSAGE_TYPE_FLAG_LEN: usize = 1;
 
+//
+// HYBRID_...
+//
+// Message lengths when the `post-quantum` feature replaces the X25519-only boxes with their
+// hybrid X25519 + ML-KEM-768 counterparts. As with the constants above, real and cover
+// messages must be exactly these lengths so they remain indistinguishable.
+//
+
+/// The length of the message data which is sent to the CoverNode API when using the hybrid
+/// primitives.
+///
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN, 5221);
+/// assert_eq!(HYBRID_USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN,
+///    COVERNODE_WRAPPING_KEY_COUNT
+///    * (X25519_PUBLIC_KEY_LEN + ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN + MULTI_ANONYMOUS_BOX_SECRET_KEY_LEN)
+///    + HYBRID_USER_TO_COVERNODE_MESSAGE_LEN
+///    + POLY1305_AUTH_TAG_LEN);
+/// ```
+pub const HYBRID_USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN: usize = COVERNODE_WRAPPING_KEY_COUNT
+    * (X25519_PUBLIC_KEY_LEN
+        + ML_KEM_768_CIPHERTEXT_LEN
+        + POLY1305_AUTH_TAG_LEN
+        + MULTI_ANONYMOUS_BOX_SECRET_KEY_LEN)
+    + HYBRID_USER_TO_COVERNODE_MESSAGE_LEN
+    + POLY1305_AUTH_TAG_LEN;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_USER_TO_COVERNODE_MESSAGE_LEN, 2869);
+/// assert_eq!(HYBRID_USER_TO_COVERNODE_MESSAGE_LEN,
+///     RECIPIENT_TAG_LEN + HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN);
+/// ```
+pub const HYBRID_USER_TO_COVERNODE_MESSAGE_LEN: usize =
+    RECIPIENT_TAG_LEN + HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN, 2865);
+/// assert_eq!(HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN,
+///     X25519_PUBLIC_KEY_LEN + ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN
+///     + HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN);
+/// ```
+pub const HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN: usize = X25519_PUBLIC_KEY_LEN
+    + ML_KEM_768_CIPHERTEXT_LEN
+    + POLY1305_AUTH_TAG_LEN
+    + HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN;
+
+/// The inner message has to carry the user's full hybrid reply key so that the journalist can
+/// answer using the hybrid primitives.
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN, 1729);
+/// assert_eq!(HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN,
+///     X25519_PUBLIC_KEY_LEN + ML_KEM_768_ENCAPSULATION_KEY_LEN
+///     + USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE + MESSAGE_PADDING_LEN as usize);
+/// ```
+pub const HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN: usize = X25519_PUBLIC_KEY_LEN
+    + ML_KEM_768_ENCAPSULATION_KEY_LEN
+    + USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE
+    + MESSAGE_PADDING_LEN as usize;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_COVERNODE_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN, 3969);
+/// assert_eq!(HYBRID_COVERNODE_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN,
+///     ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN + HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN);
+/// ```
+pub const HYBRID_COVERNODE_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN: usize = ML_KEM_768_CIPHERTEXT_LEN
+    + POLY1305_AUTH_TAG_LEN
+    + HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN, 3970);
+/// assert_eq!(HYBRID_JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN,
+///    COVERNODE_WRAPPING_KEY_COUNT
+///    * (X25519_PUBLIC_KEY_LEN + ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN + MULTI_ANONYMOUS_BOX_SECRET_KEY_LEN)
+///    + HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN
+///    + POLY1305_AUTH_TAG_LEN);
+/// ```
+pub const HYBRID_JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN: usize = COVERNODE_WRAPPING_KEY_COUNT
+    * (X25519_PUBLIC_KEY_LEN
+        + ML_KEM_768_CIPHERTEXT_LEN
+        + POLY1305_AUTH_TAG_LEN
+        + MULTI_ANONYMOUS_BOX_SECRET_KEY_LEN)
+    + HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN
+    + POLY1305_AUTH_TAG_LEN;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN, 1618);
+/// assert_eq!(HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN,
+///     REAL_OR_COVER_BYTE_LEN + HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN);
+/// ```
+pub const HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN: usize =
+    REAL_OR_COVER_BYTE_LEN + HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN;
+
+/// ```
+/// use common::protocol::constants::*;
+/// assert_eq!(HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN, 1617);
+/// assert_eq!(HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN,
+///     ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN + JOURNALIST_TO_USER_MESSAGE_LEN);
+/// ```
+pub const HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN: usize =
+    ML_KEM_768_CIPHERTEXT_LEN + POLY1305_AUTH_TAG_LEN + JOURNALIST_TO_USER_MESSAGE_LEN;
+
 //
 // Constants just to make the above more readable, will actually be verified by the doc test.
 //
@@ -284,6 +423,8 @@ pub const X25519_SECRET_KEY_LEN: usize = 32;
 pub const POLY1305_AUTH_TAG_LEN: usize = 16;
 pub const TWO_PARTY_BOX_NONCE_LEN: usize = 24;
 pub const MULTI_ANONYMOUS_BOX_SECRET_KEY_LEN: usize = 32;
+pub const ML_KEM_768_ENCAPSULATION_KEY_LEN: usize = 1184;
+pub const ML_KEM_768_CIPHERTEXT_LEN: usize = 1088;
 
 //
 // App related durations
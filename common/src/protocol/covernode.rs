@@ -3,8 +3,9 @@ use chrono::{DateTime, Utc};
 use crate::api::models::dead_drops::{
     JournalistToUserDeadDrop, JournalistToUserDeadDropSignatureDataV2,
     UnpublishedJournalistToUserDeadDrop, UnpublishedUserToJournalistDeadDrop,
-    UnverifiedJournalistToUserDeadDropsList, UnverifiedUserToJournalistDeadDropsList,
-    UserToJournalistDeadDrop, UserToJournalistDeadDropSignatureDataV2,
+    UnverifiedJournalistToUserDeadDrop, UnverifiedJournalistToUserDeadDropsList,
+    UnverifiedUserToJournalistDeadDropsList, UserToJournalistDeadDrop,
+    UserToJournalistDeadDropSignatureDataV2,
 };
 use crate::api::models::messages::covernode_to_journalist_message::{
     CoverNodeToJournalistMessage, EncryptedCoverNodeToJournalistMessage,
@@ -63,6 +64,21 @@ pub fn covernode_msg_pks_from_hierarchy(
     Ok(keys)
 }
 
+/// When the first of the CoverNode messaging keys returned by [covernode_msg_pks_from_hierarchy]
+/// expires. A message encrypted to these keys can't be decrypted by the CoverNodes once it has
+/// passed, so it must be encrypted again if it hasn't been sent by then.
+pub fn covernode_msg_pks_not_valid_after(
+    keys: &CoverDropPublicKeyHierarchy,
+) -> anyhow::Result<DateTime<Utc>> {
+    let not_valid_after = covernode_msg_pks_from_hierarchy(keys)?
+        .iter()
+        .map(|covernode_msg_pk| covernode_msg_pk.not_valid_after)
+        .min()
+        .expect("At least one CoverNode messaging key");
+
+    Ok(not_valid_after)
+}
+
 //
 // Decryption
 //
@@ -164,30 +180,41 @@ pub fn verify_journalist_to_user_dead_drop_list(
         .dead_drops
         .iter()
         .filter_map(|dead_drop| {
-            // For each ID PK, check the dead drop
-            for (_, id_pk) in keys.covernode_id_pk_iter() {
-                let signature_data = dead_drop.signature_data();
+            let verified_dead_drop = verify_journalist_to_user_dead_drop(keys, dead_drop, now);
 
-                if id_pk
-                    .verify(&signature_data, &dead_drop.signature, now)
-                    .is_ok()
-                {
-                    let verified_dead_drop = JournalistToUserDeadDrop::new(
-                        dead_drop.id,
-                        dead_drop.created_at,
-                        dead_drop.data.deserialize(),
-                    );
-
-                    return Some(verified_dead_drop);
-                }
+            if verified_dead_drop.is_none() {
+                tracing::warn!("Failed to verify journalist to user dead drop in dead drop list");
             }
 
-            tracing::warn!("Failed to verify journalist to user dead drop in dead drop list");
-            None
+            verified_dead_drop
         })
         .collect::<Vec<JournalistToUserDeadDrop>>()
 }
 
+/// Iterates through available verified covernode identity keys, trying to find one that can
+/// verify the provided journalist to user dead drop
+pub fn verify_journalist_to_user_dead_drop(
+    keys: &CoverDropPublicKeyHierarchy,
+    dead_drop: &UnverifiedJournalistToUserDeadDrop,
+    now: DateTime<Utc>,
+) -> Option<JournalistToUserDeadDrop> {
+    let signature_data = dead_drop.signature_data();
+
+    keys.covernode_id_pk_iter()
+        .any(|(_, id_pk)| {
+            id_pk
+                .verify(&signature_data, &dead_drop.signature, now)
+                .is_ok()
+        })
+        .then(|| {
+            JournalistToUserDeadDrop::new(
+                dead_drop.id,
+                dead_drop.created_at,
+                dead_drop.data.deserialize(),
+            )
+        })
+}
+
 /// Iterates through available verified covernode identity keys, trying to find one that can
 /// verify the provided user to journalist dead drop
 pub fn verify_unpublished_user_to_journalist_dead_drop(
@@ -0,0 +1,285 @@
+use crate::api::models::journalist_id::JournalistIdentity;
+use crate::crypto::keys::encryption::{PublicEncryptionKey, UnsignedEncryptionKeyPair};
+use crate::crypto::keys::signing::traits::PublicSigningKey;
+use crate::crypto::keys::signing::SignedSigningKeyPair;
+use crate::crypto::keys::untrusted::signing::UntrustedSignedPublicSigningKey;
+use crate::crypto::{AnonymousBox, Signable, Signature};
+use crate::protocol::keys::{
+    verify_journalist_id_pk, verify_journalist_provisioning_pk, AnchorOrganizationPublicKey,
+    JournalistProvisioningPublicKey, UntrustedJournalistProvisioningPublicKey, UserPublicKey,
+};
+use crate::protocol::roles::{EvidenceRecipient, JournalistId};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A single message from an exported conversation
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedMessage {
+    pub from_user: bool,
+    pub text: String,
+    /// When the message was received from, or sent to, the user
+    pub timestamp: DateTime<Utc>,
+    /// Set when this message handed the conversation over to another journalist or desk
+    pub hand_over_to: Option<JournalistIdentity>,
+}
+
+/// All the messages a journalist has exchanged with a single user, along with the alias and
+/// description the journalist gave them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedConversation {
+    pub journalist_identity: JournalistIdentity,
+    pub user_pk: UserPublicKey,
+    pub alias: Option<String>,
+    pub description: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+impl ExportedConversation {
+    fn to_bytes(&self) -> anyhow::Result<ExportedConversationBytes> {
+        serde_json::to_vec(self)
+            .context("Failed to serialize ExportedConversation to bytes")
+            .map(ExportedConversationBytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        serde_json::from_slice(bytes).context("Failed to deserialize ExportedConversation")
+    }
+
+    /// Sign the conversation with the journalist's identity key and encrypt it to the recipient.
+    /// The provisioning key which signed the identity key is included so the bundle can be
+    /// verified against the organization key alone, even once the keys have expired.
+    pub fn to_evidence_bundle(
+        &self,
+        journalist_identity_key_pair: &SignedSigningKeyPair<JournalistId>,
+        journalist_provisioning_pk: &JournalistProvisioningPublicKey,
+        recipient_pk: &PublicEncryptionKey<EvidenceRecipient>,
+    ) -> anyhow::Result<EvidenceBundle> {
+        let bytes = self.to_bytes()?;
+        let signature = journalist_identity_key_pair.sign(&bytes);
+
+        let signed = SignedExportedConversation {
+            conversation_bytes: bytes,
+            conversation_signature: signature,
+            signed_with: journalist_identity_key_pair
+                .public_key()
+                .clone()
+                .to_untrusted(),
+            signed_with_parent: journalist_provisioning_pk.to_untrusted(),
+        };
+
+        let signed_bytes = serde_json::to_vec(&signed)?;
+
+        let bundle = AnonymousBox::encrypt(recipient_pk, signed_bytes)?;
+
+        Ok(EvidenceBundle(bundle))
+    }
+}
+
+/// Helper for (de)serializing the `ExportedConversation` as a byte array that can be signed/verified.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+struct ExportedConversationBytes(Vec<u8>);
+
+impl Signable for ExportedConversationBytes {
+    fn as_signable_bytes(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+/// An `ExportedConversation` along with a signature by the journalist's identity key, and the
+/// chain of keys from that key up to the organization key. The fields are non-public to ensure
+/// that callsites need to verify.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct SignedExportedConversation {
+    conversation_bytes: ExportedConversationBytes,
+    conversation_signature: Signature<ExportedConversationBytes>,
+    signed_with: UntrustedSignedPublicSigningKey<JournalistId>,
+    signed_with_parent: UntrustedJournalistProvisioningPublicKey,
+}
+
+impl SignedExportedConversation {
+    /// The key the conversation claims to be signed with
+    pub fn signed_with(&self) -> &UntrustedSignedPublicSigningKey<JournalistId> {
+        &self.signed_with
+    }
+
+    /// Verify the conversation was signed by a journalist identity key belonging to one of the
+    /// given organizations.
+    ///
+    /// The keys are checked at the time the conversation was exported, using the key chain in
+    /// the bundle rather than the keys currently published by the API, so bundles can still be
+    /// verified after the keys which signed them have expired or been rotated out.
+    pub fn to_verified(
+        self,
+        anchor_org_pks: &[AnchorOrganizationPublicKey],
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<ExportedConversation> {
+        // Only trusted once the signature over it has been checked below
+        let exported_at = ExportedConversation::from_bytes(&self.conversation_bytes.0)?.exported_at;
+
+        if exported_at > now {
+            anyhow::bail!("Evidence bundle claims to be exported in the future at {exported_at}");
+        }
+
+        let journalist_provisioning_pk = anchor_org_pks
+            .iter()
+            .find_map(|anchor_org_pk| {
+                verify_journalist_provisioning_pk(
+                    &self.signed_with_parent,
+                    &anchor_org_pk.to_non_anchor(),
+                    exported_at,
+                )
+                .ok()
+            })
+            .context("Evidence bundle was not signed by a key from a trusted organization")?;
+
+        let journalist_identity_public_key =
+            verify_journalist_id_pk(&self.signed_with, &journalist_provisioning_pk, exported_at)
+                .context("Evidence bundle was not signed by a valid journalist identity key")?;
+
+        journalist_identity_public_key.verify(
+            &self.conversation_bytes,
+            &self.conversation_signature,
+            exported_at,
+        )?;
+
+        ExportedConversation::from_bytes(&self.conversation_bytes.0)
+    }
+}
+
+/// A `SignedExportedConversation` encrypted to the public key of whoever the conversation is being
+/// handed to, so it can be stored or transferred without exposing the messages.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EvidenceBundle(AnonymousBox<Vec<u8>>);
+
+impl EvidenceBundle {
+    pub fn from_vec_unchecked(bytes: Vec<u8>) -> Self {
+        Self(AnonymousBox::from_vec_unchecked(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    pub fn decrypt(
+        &self,
+        recipient_key_pair: &UnsignedEncryptionKeyPair<EvidenceRecipient>,
+    ) -> anyhow::Result<SignedExportedConversation> {
+        let signed_bytes = AnonymousBox::decrypt(recipient_key_pair, &self.0)
+            .context("Failed to decrypt evidence bundle")?;
+
+        serde_json::from_slice(&signed_bytes)
+            .context("Failed to deserialize SignedExportedConversation")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keys::{
+        generate_journalist_id_key_pair, generate_journalist_provisioning_key_pair,
+        generate_organization_key_pair,
+    };
+    use crate::time::now;
+
+    fn sample_conversation(
+        journalist_identity: JournalistIdentity,
+        now: DateTime<Utc>,
+    ) -> ExportedConversation {
+        ExportedConversation {
+            journalist_identity,
+            user_pk: UnsignedEncryptionKeyPair::generate().public_key().clone(),
+            alias: Some("Whistleblower".to_string()),
+            description: Some("Works in the mayor's office".to_string()),
+            exported_at: now,
+            messages: vec![
+                ExportedMessage {
+                    from_user: true,
+                    text: "I have documents".to_string(),
+                    timestamp: now - chrono::Duration::days(2),
+                    hand_over_to: None,
+                },
+                ExportedMessage {
+                    from_user: false,
+                    text: "Please send them".to_string(),
+                    timestamp: now - chrono::Duration::days(1),
+                    hand_over_to: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn evidence_bundle_round_trip() -> anyhow::Result<()> {
+        let now = now();
+        let conversation = sample_conversation(JournalistIdentity::new("journalist")?, now);
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let anchor_org_pks = [org_key_pair.public_key().clone().into_anchor()];
+        let journalist_provisioning_key_pair =
+            generate_journalist_provisioning_key_pair(&org_key_pair, now);
+        let journalist_identity_key_pair =
+            generate_journalist_id_key_pair(&journalist_provisioning_key_pair, now);
+        let recipient_key_pair = UnsignedEncryptionKeyPair::<EvidenceRecipient>::generate();
+
+        let bundle = conversation.to_evidence_bundle(
+            &journalist_identity_key_pair,
+            journalist_provisioning_key_pair.public_key(),
+            recipient_key_pair.public_key(),
+        )?;
+
+        // Happy path
+        let bundle = EvidenceBundle::from_vec_unchecked(bundle.as_bytes().to_vec());
+        let signed = bundle.decrypt(&recipient_key_pair)?;
+        let verified = signed.clone().to_verified(&anchor_org_pks, now)?;
+        assert_eq!(conversation, verified);
+
+        // The keys are checked when the conversation was exported, so the bundle can still be
+        // verified once they have expired
+        let verified = signed
+            .clone()
+            .to_verified(&anchor_org_pks, now + chrono::Duration::days(3650))?;
+        assert_eq!(conversation, verified);
+
+        // Failure path: wrong recipient
+        let other_key_pair = UnsignedEncryptionKeyPair::<EvidenceRecipient>::generate();
+        assert!(
+            bundle.decrypt(&other_key_pair).is_err(),
+            "Only the recipient should be able to decrypt the bundle"
+        );
+
+        // Failure path: signed by a journalist from a different organization
+        let other_org_key_pair = generate_organization_key_pair(now);
+        let result = signed.clone().to_verified(
+            &[other_org_key_pair.public_key().clone().into_anchor()],
+            now,
+        );
+        assert!(
+            result.is_err(),
+            "Untrusted organization should fail verification"
+        );
+
+        // Failure path: key chain from a different journalist
+        let other_journalist_key_pair =
+            generate_journalist_id_key_pair(&journalist_provisioning_key_pair, now);
+        let mut swapped_key = signed.clone();
+        swapped_key.signed_with = other_journalist_key_pair.public_key().to_untrusted();
+        let result = swapped_key.to_verified(&anchor_org_pks, now);
+        assert!(result.is_err(), "Key mismatch should fail verification");
+
+        // Failure path: tampered conversation
+        let mut tampered = signed.clone();
+        tampered.conversation_bytes.0[16] ^= 0x01;
+        let result = tampered.to_verified(&anchor_org_pks, now);
+        assert!(result.is_err(), "Tampered data should fail verification");
+
+        // Failure path: exported in the future
+        let result = signed.to_verified(&anchor_org_pks, now - chrono::Duration::days(1));
+        assert!(result.is_err(), "Future export should fail verification");
+
+        Ok(())
+    }
+}
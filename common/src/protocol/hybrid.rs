@@ -0,0 +1,383 @@
+//! Hybrid X25519 + ML-KEM-768 variants of the message flows in [`super::user`],
+//! [`super::journalist`] and [`super::covernode`].
+//!
+//! The plaintext layouts match the X25519-only protocol, except that the user's reply key in
+//! the inner user to journalist message also carries the ML-KEM encapsulation key. The message
+//! lengths are given by the `HYBRID_*` constants in [`super::constants`].
+
+use crate::api::models::journalist_id::JournalistIdentity;
+use crate::api::models::messages::journalist_to_covernode_message::SerializedJournalistToCoverNodeMessage;
+use crate::api::models::messages::journalist_to_user_message::{
+    JournalistToUserMessage, SerializedJournalistToUserMessage,
+};
+use crate::api::models::messages::user_to_covernode_message::SerializedUserToCoverNodeMessage;
+use crate::api::models::messages::user_to_journalist_message::SerializedUserToJournalistMessage;
+use crate::api::models::messages::{FLAG_J2U_COVER, FLAG_J2U_REAL, FLAG_U2J_MESSAGE_TYPE_MESSAGE};
+use crate::crypto::keys::hybrid_encryption::{HybridEncryptionKeyPair, HybridPublicEncryptionKey};
+use crate::crypto::{Encryptable, HybridAnonymousBox, HybridMultiAnonymousBox, HybridTwoPartyBox};
+use crate::protocol::constants::*;
+use crate::protocol::recipient_tag::{RecipientTag, RECIPIENT_TAG_FOR_COVER};
+use crate::FixedSizeMessageText;
+
+use super::roles::{CoverNodeHybridMessaging, JournalistHybridMessaging, User};
+
+pub type CoverNodeHybridMessagingPublicKey = HybridPublicEncryptionKey<CoverNodeHybridMessaging>;
+pub type CoverNodeHybridMessagingKeyPair = HybridEncryptionKeyPair<CoverNodeHybridMessaging>;
+
+pub type JournalistHybridMessagingPublicKey = HybridPublicEncryptionKey<JournalistHybridMessaging>;
+pub type JournalistHybridMessagingKeyPair = HybridEncryptionKeyPair<JournalistHybridMessaging>;
+
+pub type UserHybridPublicKey = HybridPublicEncryptionKey<User>;
+pub type UserHybridKeyPair = HybridEncryptionKeyPair<User>;
+
+pub type HybridEncryptedUserToJournalistMessage =
+    HybridAnonymousBox<SerializedUserToJournalistMessage>;
+pub type HybridEncryptedUserToCoverNodeMessage =
+    HybridMultiAnonymousBox<SerializedUserToCoverNodeMessage, COVERNODE_WRAPPING_KEY_COUNT>;
+pub type HybridEncryptedCoverNodeToJournalistMessage =
+    HybridTwoPartyBox<HybridEncryptedUserToJournalistMessage>;
+
+pub type HybridEncryptedJournalistToUserMessage =
+    HybridTwoPartyBox<SerializedJournalistToUserMessage>;
+pub type HybridEncryptedJournalistToCoverNodeMessage =
+    HybridMultiAnonymousBox<SerializedJournalistToCoverNodeMessage, COVERNODE_WRAPPING_KEY_COUNT>;
+
+impl Encryptable for HybridEncryptedUserToJournalistMessage {
+    fn as_unencrypted_bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+
+    fn from_unencrypted_bytes(bytes: Vec<u8>) -> Result<Self, crate::Error> {
+        Ok(HybridAnonymousBox::from_vec_unchecked(bytes))
+    }
+}
+
+/// Same layout as [`UserToJournalistMessage::serialize`] but with the user's hybrid reply key.
+///
+/// [`UserToJournalistMessage::serialize`]: crate::api::models::messages::user_to_journalist_message::UserToJournalistMessage::serialize
+fn serialize_user_to_journalist_message(
+    message: &FixedSizeMessageText,
+    reply_key: &UserHybridPublicKey,
+) -> SerializedUserToJournalistMessage {
+    let mut bytes = Vec::with_capacity(HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN);
+    bytes.extend(reply_key.to_bytes());
+    bytes.push(FLAG_U2J_MESSAGE_TYPE_MESSAGE);
+    bytes.extend(message.as_unencrypted_bytes());
+
+    assert_eq!(bytes.len(), HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN);
+
+    SerializedUserToJournalistMessage::from_vec_unchecked(bytes)
+}
+
+pub fn encrypt_real_message_from_user_to_journalist_via_covernode_hybrid(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+    journalist_msg_pk: &JournalistHybridMessagingPublicKey,
+    journalist_id: &JournalistIdentity,
+    user_pk: &UserHybridPublicKey,
+    message: &FixedSizeMessageText,
+) -> anyhow::Result<HybridEncryptedUserToCoverNodeMessage> {
+    let encrypted_user_to_journalist_message = HybridAnonymousBox::encrypt(
+        journalist_msg_pk,
+        serialize_user_to_journalist_message(message, user_pk),
+    )?;
+
+    assert_eq!(
+        encrypted_user_to_journalist_message.len(),
+        HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN
+    );
+
+    let recipient_tag = RecipientTag::from_journalist_id(journalist_id);
+
+    let mut bytes = Vec::with_capacity(HYBRID_USER_TO_COVERNODE_MESSAGE_LEN);
+    bytes.extend(recipient_tag.as_ref());
+    bytes.extend(encrypted_user_to_journalist_message.as_ref());
+
+    encrypt_from_user_for_covernode(covernode_msg_pks, bytes)
+}
+
+pub fn new_encrypted_cover_message_from_user_via_covernode_hybrid(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+) -> anyhow::Result<HybridEncryptedUserToCoverNodeMessage> {
+    let mut bytes = Vec::with_capacity(HYBRID_USER_TO_COVERNODE_MESSAGE_LEN);
+    bytes.extend(RECIPIENT_TAG_FOR_COVER.as_ref());
+    bytes.extend([0_u8; HYBRID_USER_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN]);
+
+    encrypt_from_user_for_covernode(covernode_msg_pks, bytes)
+}
+
+fn encrypt_from_user_for_covernode(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+    user_to_covernode_message: Vec<u8>,
+) -> anyhow::Result<HybridEncryptedUserToCoverNodeMessage> {
+    assert_eq!(
+        user_to_covernode_message.len(),
+        HYBRID_USER_TO_COVERNODE_MESSAGE_LEN
+    );
+
+    Ok(HybridMultiAnonymousBox::encrypt(
+        covernode_msg_pks,
+        SerializedUserToCoverNodeMessage::from_slice_unchecked(&user_to_covernode_message),
+    )?)
+}
+
+/// Wrap an inner user to journalist message for the journalists' dead drop
+pub fn encrypt_message_from_covernode_to_journalist_hybrid(
+    covernode_msg_key_pair: &CoverNodeHybridMessagingKeyPair,
+    journalist_msg_pk: &JournalistHybridMessagingPublicKey,
+    payload: HybridEncryptedUserToJournalistMessage,
+) -> anyhow::Result<HybridEncryptedCoverNodeToJournalistMessage> {
+    let encrypted = HybridTwoPartyBox::encrypt(
+        journalist_msg_pk,
+        covernode_msg_key_pair.secret_key(),
+        payload,
+    )?;
+
+    assert_eq!(
+        encrypted.len(),
+        HYBRID_COVERNODE_TO_JOURNALIST_ENCRYPTED_MESSAGE_LEN
+    );
+
+    Ok(encrypted)
+}
+
+/// Generate a new fake inner message to be used as cover using freshly generated key pairs,
+/// see [`new_random_encrypted_user_to_journalist_message`].
+///
+/// [`new_random_encrypted_user_to_journalist_message`]: crate::api::models::messages::user_to_journalist_message::new_random_encrypted_user_to_journalist_message
+pub fn new_random_encrypted_user_to_journalist_message_hybrid(
+) -> anyhow::Result<HybridEncryptedUserToJournalistMessage> {
+    let journalist_msg_key_pair = JournalistHybridMessagingKeyPair::generate();
+    let user_key_pair = UserHybridKeyPair::generate();
+
+    let plaintext = FixedSizeMessageText::new("")?;
+    let message = serialize_user_to_journalist_message(&plaintext, user_key_pair.public_key());
+
+    Ok(HybridAnonymousBox::encrypt(
+        journalist_msg_key_pair.public_key(),
+        message,
+    )?)
+}
+
+pub fn encrypt_real_message_from_journalist_to_user_via_covernode_hybrid(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+    user_pk: &UserHybridPublicKey,
+    journalist_key_pair: &JournalistHybridMessagingKeyPair,
+    message: &FixedSizeMessageText,
+) -> anyhow::Result<HybridEncryptedJournalistToCoverNodeMessage> {
+    let journalist_to_user_message = JournalistToUserMessage::new_with_message(message.clone());
+
+    let encrypted_journalist_to_user_message: HybridEncryptedJournalistToUserMessage =
+        HybridTwoPartyBox::encrypt(
+            user_pk,
+            journalist_key_pair.secret_key(),
+            journalist_to_user_message.serialize(),
+        )?;
+
+    assert_eq!(
+        encrypted_journalist_to_user_message.len(),
+        HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN
+    );
+
+    let mut bytes = Vec::with_capacity(HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN);
+    bytes.push(FLAG_J2U_REAL);
+    bytes.extend(encrypted_journalist_to_user_message.as_ref());
+
+    encrypt_from_journalist_for_covernode(covernode_msg_pks, bytes)
+}
+
+pub fn new_encrypted_cover_message_from_journalist_via_covernode_hybrid(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+) -> anyhow::Result<HybridEncryptedJournalistToCoverNodeMessage> {
+    let mut bytes = Vec::with_capacity(HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN);
+    bytes.push(FLAG_J2U_COVER);
+    bytes.extend([0_u8; HYBRID_JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN]);
+
+    encrypt_from_journalist_for_covernode(covernode_msg_pks, bytes)
+}
+
+fn encrypt_from_journalist_for_covernode(
+    covernode_msg_pks: [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT],
+    journalist_to_covernode_message: Vec<u8>,
+) -> anyhow::Result<HybridEncryptedJournalistToCoverNodeMessage> {
+    assert_eq!(
+        journalist_to_covernode_message.len(),
+        HYBRID_JOURNALIST_TO_COVERNODE_MESSAGE_LEN
+    );
+
+    Ok(HybridMultiAnonymousBox::encrypt(
+        covernode_msg_pks,
+        SerializedJournalistToCoverNodeMessage::from_slice_unchecked(
+            &journalist_to_covernode_message,
+        ),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::models::{
+            journalist_id::JournalistIdentity,
+            messages::journalist_to_user_message::JournalistToUserMessage,
+        },
+        crypto::{HybridAnonymousBox, HybridMultiAnonymousBox, HybridTwoPartyBox},
+        protocol::{
+            constants::*,
+            recipient_tag::{RecipientTag, RECIPIENT_TAG_FOR_COVER},
+        },
+        FixedSizeMessageText,
+    };
+
+    use super::*;
+
+    struct HybridProtocolKeys {
+        covernode_msg_key_pairs: [CoverNodeHybridMessagingKeyPair; COVERNODE_WRAPPING_KEY_COUNT],
+        journalist_msg_key_pair: JournalistHybridMessagingKeyPair,
+        user_key_pair: UserHybridKeyPair,
+    }
+
+    impl HybridProtocolKeys {
+        fn generate() -> Self {
+            Self {
+                covernode_msg_key_pairs: std::array::from_fn(|_| {
+                    CoverNodeHybridMessagingKeyPair::generate()
+                }),
+                journalist_msg_key_pair: JournalistHybridMessagingKeyPair::generate(),
+                user_key_pair: UserHybridKeyPair::generate(),
+            }
+        }
+
+        fn covernode_msg_pks(
+            &self,
+        ) -> [&CoverNodeHybridMessagingPublicKey; COVERNODE_WRAPPING_KEY_COUNT] {
+            std::array::from_fn(|i| self.covernode_msg_key_pairs[i].public_key())
+        }
+    }
+
+    #[test]
+    fn user_to_covernode_real_and_cover_messages_have_the_same_length() -> anyhow::Result<()> {
+        let keys = HybridProtocolKeys::generate();
+        let journalist_id = JournalistIdentity::new("journalist_0")?;
+        let message = FixedSizeMessageText::new("test message")?;
+
+        let real = encrypt_real_message_from_user_to_journalist_via_covernode_hybrid(
+            keys.covernode_msg_pks(),
+            keys.journalist_msg_key_pair.public_key(),
+            &journalist_id,
+            keys.user_key_pair.public_key(),
+            &message,
+        )?;
+        let cover =
+            new_encrypted_cover_message_from_user_via_covernode_hybrid(keys.covernode_msg_pks())?;
+
+        assert_eq!(real.len(), HYBRID_USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN);
+        assert_eq!(cover.len(), HYBRID_USER_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN);
+
+        // Each CoverNode can unwrap the outer layer
+        for covernode_msg_key_pair in &keys.covernode_msg_key_pairs {
+            let decrypted_cover = HybridMultiAnonymousBox::decrypt(covernode_msg_key_pair, &cover)?;
+            assert_eq!(
+                &decrypted_cover.bytes[..RECIPIENT_TAG_LEN],
+                RECIPIENT_TAG_FOR_COVER.as_ref()
+            );
+
+            let decrypted_real = HybridMultiAnonymousBox::decrypt(covernode_msg_key_pair, &real)?;
+            assert_eq!(decrypted_real.len(), HYBRID_USER_TO_COVERNODE_MESSAGE_LEN);
+            assert_eq!(
+                &decrypted_real.bytes[..RECIPIENT_TAG_LEN],
+                RecipientTag::from_journalist_id(&journalist_id).as_ref()
+            );
+        }
+
+        // The CoverNode forwards the inner message to the journalist's dead drop
+        let decrypted_real =
+            HybridMultiAnonymousBox::decrypt(&keys.covernode_msg_key_pairs[0], &real)?;
+        let payload = HybridEncryptedUserToJournalistMessage::from_vec_unchecked(
+            decrypted_real.bytes[RECIPIENT_TAG_LEN..].to_vec(),
+        );
+
+        let real_c2j = encrypt_message_from_covernode_to_journalist_hybrid(
+            &keys.covernode_msg_key_pairs[0],
+            keys.journalist_msg_key_pair.public_key(),
+            payload,
+        )?;
+        let cover_c2j = encrypt_message_from_covernode_to_journalist_hybrid(
+            &keys.covernode_msg_key_pairs[0],
+            keys.journalist_msg_key_pair.public_key(),
+            new_random_encrypted_user_to_journalist_message_hybrid()?,
+        )?;
+
+        assert_eq!(real_c2j.len(), cover_c2j.len());
+
+        let inner = HybridTwoPartyBox::decrypt(
+            keys.covernode_msg_key_pairs[0].public_key(),
+            keys.journalist_msg_key_pair.secret_key(),
+            &real_c2j,
+        )?;
+        let inner = HybridAnonymousBox::decrypt(&keys.journalist_msg_key_pair, &inner)?;
+
+        assert_eq!(inner.bytes.len(), HYBRID_USER_TO_JOURNALIST_MESSAGE_LEN);
+
+        let reply_key_len = X25519_PUBLIC_KEY_LEN + ML_KEM_768_ENCAPSULATION_KEY_LEN;
+        assert_eq!(
+            &inner.bytes[..reply_key_len],
+            keys.user_key_pair.public_key().to_bytes().as_slice()
+        );
+        assert_eq!(
+            &inner.bytes[reply_key_len + USER_TO_JOURNALIST_MESSAGE_RESERVED_BYTE..],
+            message.as_unencrypted_bytes()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn journalist_to_covernode_real_and_cover_messages_have_the_same_length() -> anyhow::Result<()>
+    {
+        let keys = HybridProtocolKeys::generate();
+        let message = FixedSizeMessageText::new("test reply")?;
+
+        let real = encrypt_real_message_from_journalist_to_user_via_covernode_hybrid(
+            keys.covernode_msg_pks(),
+            keys.user_key_pair.public_key(),
+            &keys.journalist_msg_key_pair,
+            &message,
+        )?;
+        let cover = new_encrypted_cover_message_from_journalist_via_covernode_hybrid(
+            keys.covernode_msg_pks(),
+        )?;
+
+        assert_eq!(
+            real.len(),
+            HYBRID_JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN
+        );
+        assert_eq!(
+            cover.len(),
+            HYBRID_JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN
+        );
+
+        let decrypted_real =
+            HybridMultiAnonymousBox::decrypt(&keys.covernode_msg_key_pairs[1], &real)?;
+        assert_eq!(decrypted_real.bytes[0], FLAG_J2U_REAL);
+
+        let decrypted_cover =
+            HybridMultiAnonymousBox::decrypt(&keys.covernode_msg_key_pairs[1], &cover)?;
+        assert_eq!(decrypted_cover.bytes[0], FLAG_J2U_COVER);
+        assert_eq!(decrypted_real.len(), decrypted_cover.len());
+
+        let j2u = HybridEncryptedJournalistToUserMessage::from_vec_unchecked(
+            decrypted_real.bytes[REAL_OR_COVER_BYTE_LEN..].to_vec(),
+        );
+        let j2u = HybridTwoPartyBox::decrypt(
+            keys.journalist_msg_key_pair.public_key(),
+            keys.user_key_pair.secret_key(),
+            &j2u,
+        )?;
+
+        assert_eq!(
+            j2u.to_message()?,
+            JournalistToUserMessage::new_with_message(message)
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::api::models::dead_drops::DeadDropId;
+use crate::api::models::journalist_id::JournalistIdentity;
 use crate::api::models::messages::covernode_to_journalist_message::EncryptedCoverNodeToJournalistMessage;
 use crate::api::models::messages::journalist_to_covernode_message::{
     EncryptedJournalistToCoverNodeMessage, JournalistToCoverNodeMessage,
@@ -6,6 +7,7 @@ use crate::api::models::messages::journalist_to_covernode_message::{
 use crate::api::models::messages::journalist_to_user_message::{
     EncryptedJournalistToUserMessage, JournalistToUserMessage,
 };
+use crate::api::models::messages::read_receipt::ReadReceipt;
 use crate::api::models::messages::user_to_journalist_message::EncryptedUserToJournalistMessage;
 use crate::api::models::messages::user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId;
 use crate::protocol::constants::JOURNALIST_TO_USER_ENCRYPTED_MESSAGE_LEN;
@@ -25,6 +27,55 @@ pub fn encrypt_real_message_from_journalist_to_user_via_covernode(
 ) -> anyhow::Result<EncryptedJournalistToCoverNodeMessage> {
     let journalist_to_user_message = JournalistToUserMessage::new_with_message(message.clone());
 
+    encrypt_journalist_to_user_message_via_covernode(
+        keys,
+        user_pk,
+        journalist_key_pair,
+        journalist_to_user_message,
+    )
+}
+
+/// Encrypt a message telling the user that their conversation has been handed over to
+/// another journalist or desk. The user's client should switch its default recipient to `to`.
+pub fn encrypt_hand_over_from_journalist_to_user_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    user_pk: &UserPublicKey,
+    journalist_key_pair: &JournalistMessagingKeyPair,
+    to: &JournalistIdentity,
+) -> anyhow::Result<EncryptedJournalistToCoverNodeMessage> {
+    let journalist_to_user_message = JournalistToUserMessage::new_with_hand_over(to.clone());
+
+    encrypt_journalist_to_user_message_via_covernode(
+        keys,
+        user_pk,
+        journalist_key_pair,
+        journalist_to_user_message,
+    )
+}
+
+/// Encrypt a message telling the user which of their messages the journalist has read
+pub fn encrypt_read_receipt_from_journalist_to_user_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    user_pk: &UserPublicKey,
+    journalist_key_pair: &JournalistMessagingKeyPair,
+    read_receipt: ReadReceipt,
+) -> anyhow::Result<EncryptedJournalistToCoverNodeMessage> {
+    let journalist_to_user_message = JournalistToUserMessage::new_with_read_receipt(read_receipt);
+
+    encrypt_journalist_to_user_message_via_covernode(
+        keys,
+        user_pk,
+        journalist_key_pair,
+        journalist_to_user_message,
+    )
+}
+
+fn encrypt_journalist_to_user_message_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    user_pk: &UserPublicKey,
+    journalist_key_pair: &JournalistMessagingKeyPair,
+    journalist_to_user_message: JournalistToUserMessage,
+) -> anyhow::Result<EncryptedJournalistToCoverNodeMessage> {
     let encrypted_journalist_to_user_message = EncryptedJournalistToUserMessage::encrypt(
         user_pk,
         journalist_key_pair.secret_key(),
@@ -98,8 +149,18 @@ pub fn get_decrypted_journalist_dead_drop_message(
             );
 
             if let Ok(inner_decrypted_serialized) = inner_maybe_decrypted {
+                let u2j_message = inner_decrypted_serialized.to_message();
+
+                // Anyone who knows a user's public key could claim to have rotated it, so only
+                // accept rotations which prove they were sent by the holder of the previous key
+                if let Some(key_rotation) = &u2j_message.key_rotation {
+                    if !key_rotation.verify(&u2j_message.reply_key, journalist_msg_key_pair) {
+                        return None;
+                    }
+                }
+
                 return Some(UserToJournalistMessageWithDeadDropId {
-                    u2j_message: inner_decrypted_serialized.to_message(),
+                    u2j_message,
                     dead_drop_id,
                 });
             }
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 
 use crate::{
     api::models::{covernode_id::CoverNodeIdentity, journalist_id::JournalistIdentity},
-    backup::keys::BackupMsgPublicKey,
+    backup::keys::{BackupIdPublicKey, BackupMsgPublicKey},
     crypto::keys::Ed25519PublicKey,
     protocol::keys::{
         AnchorOrganizationPublicKey, CoverNodeIdKeyPair, CoverNodeIdPublicKey,
@@ -459,6 +459,15 @@ impl OrganizationPublicKeyFamilyList {
         })
     }
 
+    pub fn backup_id_pk_iter(&self) -> impl Iterator<Item = &BackupIdPublicKey> {
+        self.0.iter().flat_map(|org_pk_family| {
+            org_pk_family
+                .backups
+                .iter()
+                .flat_map(|backup| backup.id_pk_iter())
+        })
+    }
+
     pub fn backup_msg_pk_iter(&self) -> impl Iterator<Item = &BackupMsgPublicKey> {
         self.0.iter().flat_map(|org_pk_family| {
             org_pk_family
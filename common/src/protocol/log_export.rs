@@ -0,0 +1,231 @@
+use crate::api::models::journalist_id::JournalistIdentity;
+use crate::crypto::keys::encryption::{PublicEncryptionKey, UnsignedEncryptionKeyPair};
+use crate::crypto::AnonymousBox;
+use crate::protocol::keys::UserPublicKey;
+use crate::protocol::roles::SupportRecipient;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single log line from a journalist's vault
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLogEntry {
+    pub session_id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// The logs written by Sentinel between two points in time, exported so that support can
+/// diagnose problems without access to the journalist's machine.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLogs {
+    pub journalist_identity: JournalistIdentity,
+    pub exported_at: DateTime<Utc>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// True if user public keys and message contents were removed before exporting
+    pub scrubbed: bool,
+    pub entries: Vec<ExportedLogEntry>,
+}
+
+impl ExportedLogs {
+    /// Encrypt the logs to the support team's public key.
+    ///
+    /// Unlike evidence bundles the logs aren't signed since support most often needs them when
+    /// the journalist's keys are broken.
+    pub fn to_log_export(
+        &self,
+        recipient_pk: &PublicEncryptionKey<SupportRecipient>,
+    ) -> anyhow::Result<EncryptedLogExport> {
+        let bytes = serde_json::to_vec(self).context("Failed to serialize ExportedLogs")?;
+
+        let export = AnonymousBox::encrypt(recipient_pk, bytes)?;
+
+        Ok(EncryptedLogExport(export))
+    }
+}
+
+/// `ExportedLogs` encrypted to the support team's public key so it can be attached to a
+/// support ticket.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EncryptedLogExport(AnonymousBox<Vec<u8>>);
+
+impl EncryptedLogExport {
+    pub fn from_vec_unchecked(bytes: Vec<u8>) -> Self {
+        Self(AnonymousBox::from_vec_unchecked(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    pub fn decrypt(
+        &self,
+        recipient_key_pair: &UnsignedEncryptionKeyPair<SupportRecipient>,
+    ) -> anyhow::Result<ExportedLogs> {
+        let bytes = AnonymousBox::decrypt(recipient_key_pair, &self.0)
+            .context("Failed to decrypt log export")?;
+
+        serde_json::from_slice(&bytes).context("Failed to deserialize ExportedLogs")
+    }
+}
+
+/// Messages shorter than this aren't scrubbed since replies like "ok" or "thanks" would also
+/// match unrelated parts of log lines.
+pub const MIN_SCRUBBED_MESSAGE_LEN: usize = 12;
+
+lazy_static! {
+    /// 32 byte keys formatted as hex
+    static ref HEX_KEY_REGEX: Regex = Regex::new(r"\b[0-9a-fA-F]{64}\b").unwrap();
+    /// 32 byte keys formatted with their debug representation, e.g. `[76, 200, ...]`
+    static ref DEBUG_KEY_REGEX: Regex = Regex::new(r"\[(?:\s*\d{1,3}\s*,){31}\s*\d{1,3}\s*\]").unwrap();
+}
+
+/// Removes public keys and message contents from log lines.
+///
+/// Every 32 byte key is removed whether or not it belongs to a user in the vault, so keys
+/// which have since been rotated are scrubbed too. Keys belonging to known users are replaced
+/// with a stable placeholder so support can still follow a single conversation through the logs.
+#[derive(Default)]
+pub struct LogScrubber {
+    user_placeholders: HashMap<String, String>,
+    messages: Vec<String>,
+}
+
+impl LogScrubber {
+    pub fn add_user(&mut self, user_pk: &UserPublicKey) {
+        let placeholder = format!("[user {}]", self.user_placeholders.len() + 1);
+
+        self.user_placeholders
+            .entry(hex::encode(user_pk.key.as_bytes()))
+            .or_insert(placeholder);
+    }
+
+    pub fn add_message(&mut self, text: &str) {
+        let text = text.trim();
+
+        if text.chars().count() >= MIN_SCRUBBED_MESSAGE_LEN {
+            self.messages.push(text.to_string());
+        }
+    }
+
+    fn key_placeholder(&self, key_hex: &str) -> String {
+        self.user_placeholders
+            .get(&key_hex.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| "[key]".to_string())
+    }
+
+    pub fn scrub(&self, line: &str) -> String {
+        let mut line = line.to_string();
+
+        // Replace longer messages first so a message which contains another is removed whole
+        let mut messages = self.messages.iter().collect::<Vec<_>>();
+        messages.sort_by_key(|message| std::cmp::Reverse(message.len()));
+
+        for message in messages {
+            if line.contains(message.as_str()) {
+                line = line.replace(message.as_str(), "[message]");
+            }
+        }
+
+        let line = HEX_KEY_REGEX.replace_all(&line, |captures: &Captures| {
+            self.key_placeholder(&captures[0])
+        });
+
+        DEBUG_KEY_REGEX
+            .replace_all(&line, |captures: &Captures| {
+                let bytes = captures[0]
+                    .trim_matches(['[', ']'])
+                    .split(',')
+                    .map(|byte| byte.trim().parse::<u8>())
+                    .collect::<Result<Vec<_>, _>>();
+
+                match bytes {
+                    Ok(bytes) => self.key_placeholder(&hex::encode(bytes)),
+                    Err(_) => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::roles::User, time::now};
+
+    #[test]
+    fn log_export_round_trip() -> anyhow::Result<()> {
+        let now = now();
+        let user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+
+        let mut scrubber = LogScrubber::default();
+        scrubber.add_user(&user_pk);
+        scrubber.add_message("I have documents");
+
+        let message = format!(
+            "Marking chat {} as read after receiving \"I have documents\"",
+            hex::encode(user_pk.key.as_bytes())
+        );
+        let scrubbed = scrubber.scrub(&message);
+        assert_eq!(
+            scrubbed,
+            "Marking chat [user 1] as read after receiving \"[message]\""
+        );
+
+        // Keys are scrubbed in their debug representation too, and keys which aren't in the
+        // vault any more, such as a user's key before it was rotated, are still removed
+        let rotated_user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        assert_eq!(
+            scrubber.scrub(&format!(
+                "Rotated {} to {:?}",
+                hex::encode(rotated_user_pk.key.as_bytes()),
+                user_pk.key.as_bytes()
+            )),
+            "Rotated [key] to [user 1]"
+        );
+
+        // Short messages would match unrelated text so are left alone
+        scrubber.add_message("ok");
+        assert_eq!(scrubber.scrub("Token ok"), "Token ok");
+
+        let logs = ExportedLogs {
+            journalist_identity: JournalistIdentity::new("journalist")?,
+            exported_at: now,
+            from: now - chrono::Duration::days(1),
+            to: now,
+            scrubbed: true,
+            entries: vec![ExportedLogEntry {
+                session_id: 1,
+                timestamp: now,
+                level: "INFO".to_string(),
+                target: "journalist_client".to_string(),
+                message: scrubbed,
+            }],
+        };
+
+        let recipient_key_pair = UnsignedEncryptionKeyPair::<SupportRecipient>::generate();
+        let export = logs.to_log_export(recipient_key_pair.public_key())?;
+
+        let export = EncryptedLogExport::from_vec_unchecked(export.as_bytes().to_vec());
+        assert_eq!(export.decrypt(&recipient_key_pair)?, logs);
+
+        // Wrong key
+        let other_key_pair = UnsignedEncryptionKeyPair::<SupportRecipient>::generate();
+        assert!(export.decrypt(&other_key_pair).is_err());
+
+        Ok(())
+    }
+}
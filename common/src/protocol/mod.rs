@@ -3,8 +3,13 @@ pub mod backup;
 pub mod backup_data;
 pub mod constants;
 pub mod covernode;
+pub mod evidence_bundle;
+#[cfg(feature = "post-quantum")]
+pub mod hybrid;
 pub mod journalist;
 pub mod keys;
+pub mod log_export;
 pub mod recipient_tag;
 pub mod roles;
+pub mod safety_number;
 pub mod user;
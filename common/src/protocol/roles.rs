@@ -80,6 +80,17 @@ define_role!(
     Some(COVERNODE_MSG_KEY_ROTATE_AFTER)
 );
 
+// The hybrid CoverNode messaging key pairs an X25519 key with an ML-KEM key and is used
+// by the post-quantum variants of the client to CoverNode messages.
+#[cfg(feature = "post-quantum")]
+define_role!(
+    CoverNodeHybridMessaging,
+    "CoverNode hybrid messaging",
+    "covernode_hybrid_msg",
+    Some(COVERNODE_MSG_KEY_VALID_DURATION),
+    Some(COVERNODE_MSG_KEY_ROTATE_AFTER)
+);
+
 ////////////////
 // Journalist //
 ////////////////
@@ -124,6 +135,18 @@ define_role!(
     Some(JOURNALIST_MSG_KEY_ROTATE_AFTER)
 );
 
+// The hybrid messaging key pairs an X25519 key with an ML-KEM key so that messages from
+// sources cannot be decrypted by an adversary who records them today and later gains
+// access to a quantum computer.
+#[cfg(feature = "post-quantum")]
+define_role!(
+    JournalistHybridMessaging,
+    "journalist hybrid messaging",
+    "journalist_hybrid_msg",
+    Some(JOURNALIST_MSG_KEY_VALID_DURATION),
+    Some(JOURNALIST_MSG_KEY_ROTATE_AFTER)
+);
+
 ///////////
 // Users //
 ///////////
@@ -134,3 +157,21 @@ define_role!(User, "user", "user");
 // The mailbox role is used when storing public keys in the mailbox since we're not
 // differentiating between a users send key and a journalists reply key.
 define_role!(Mailbox, "mailbox", "mailbox");
+
+//////////////
+// Evidence //
+//////////////
+
+// The evidence recipient role is used by whoever receives conversations exported from a
+// journalist's vault, e.g. the legal team. These keys are not part of the key hierarchy and are
+// exchanged out of band.
+define_role!(
+    EvidenceRecipient,
+    "evidence recipient",
+    "evidence_recipient"
+);
+
+// The support recipient role is used by the team which receives logs exported from Sentinel
+// when a journalist raises a support ticket. Like evidence recipient keys these are exchanged
+// out of band.
+define_role!(SupportRecipient, "support recipient", "support_recipient");
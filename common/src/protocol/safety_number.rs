@@ -0,0 +1,217 @@
+use itertools::Itertools;
+use sha2::{Digest, Sha512};
+
+use crate::{
+    api::models::journalist_id::JournalistIdentity,
+    generators::{word_list::WordList, GeneratorError},
+};
+
+use super::keys::{CoverDropPublicKeyHierarchy, JournalistIdPublicKey, UserPublicKey};
+
+const SAFETY_NUMBER_LABEL: &[u8] = b"COVERDROP_SAFETY_NUMBER_V1";
+
+const DIGIT_GROUPS: usize = 6;
+const DIGIT_GROUP_BYTES: usize = 5;
+const DIGIT_GROUP_MODULUS: u64 = 100_000;
+
+const WORD_COUNT: usize = 6;
+const WORD_BYTES: usize = 4;
+
+/// A number which both the source and the journalist can compute for their conversation.
+///
+/// The number is derived from the source's public key and the journalist's identity key, so if
+/// both parties see the same number when they compare them in person then neither key has been
+/// substituted. Since journalist identity keys rotate the safety number will change whenever the
+/// journalist publishes a new identity key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafetyNumber([u8; 64]);
+
+impl SafetyNumber {
+    pub fn new(
+        user_pk: &UserPublicKey,
+        journalist_id: &JournalistIdentity,
+        journalist_id_pk: &JournalistIdPublicKey,
+    ) -> Self {
+        let mut hasher = Sha512::new();
+        hasher.update(SAFETY_NUMBER_LABEL);
+        hasher.update(user_pk.key.as_bytes());
+        hasher.update(journalist_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(journalist_id_pk.key.as_bytes());
+
+        SafetyNumber(hasher.finalize().into())
+    }
+
+    /// The safety number for the journalist's latest *published* identity key, which is the key
+    /// the source will use. A journalist must not use the latest key in their vault since it may
+    /// not have been published yet. `None` if the journalist has no published identity key.
+    pub fn for_latest_published(
+        user_pk: &UserPublicKey,
+        journalist_id: &JournalistIdentity,
+        keys: &CoverDropPublicKeyHierarchy,
+    ) -> Option<Self> {
+        keys.latest_journalist_id_pk(journalist_id)
+            .map(|journalist_id_pk| Self::new(user_pk, journalist_id, journalist_id_pk))
+    }
+
+    /// Render the safety number as groups of five digits, e.g. `01234 56789 ...`
+    pub fn digits(&self) -> String {
+        self.0
+            .chunks_exact(DIGIT_GROUP_BYTES)
+            .take(DIGIT_GROUPS)
+            .map(|chunk| {
+                let value = chunk
+                    .iter()
+                    .fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+
+                format!("{:05}", value % DIGIT_GROUP_MODULUS)
+            })
+            .join(" ")
+    }
+
+    /// Render the safety number as words from the EFF large wordlist
+    pub fn words(&self) -> Result<String, GeneratorError> {
+        let word_list = WordList::from_eff_large_wordlist()?;
+
+        // The digits use the start of the digest so take the words from the end
+        let words = self
+            .0
+            .rchunks_exact(WORD_BYTES)
+            .take(WORD_COUNT)
+            .map(|chunk| {
+                let value = u32::from_be_bytes(chunk.try_into().expect("Chunk is 4 bytes"));
+                word_list.words[value as usize % word_list.words.len()]
+            })
+            .join(" ");
+
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Duration;
+
+    use crate::{
+        protocol::keys::{
+            generate_journalist_id_key_pair,
+            test::{generate_protocol_keys, ProtocolKeys},
+            CoverNodeProvisioningPublicKeyFamilyList, JournalistIdPublicKeyFamily,
+            JournalistIdPublicKeyFamilyList, JournalistProvisioningPublicKeyFamily,
+            JournalistProvisioningPublicKeyFamilyList, OrganizationPublicKeyFamily, UserKeyPair,
+        },
+        time,
+    };
+
+    use super::*;
+
+    #[test]
+    fn safety_number_depends_on_both_keys() {
+        let now = time::now();
+        let ProtocolKeys {
+            user_pk,
+            journalist_id_pk,
+            journalist_provisioning_key_pair,
+            ..
+        } = generate_protocol_keys(now);
+
+        let journalist_id = JournalistIdentity::new("journalist").unwrap();
+
+        let safety_number = SafetyNumber::new(&user_pk, &journalist_id, &journalist_id_pk);
+
+        assert_eq!(
+            safety_number,
+            SafetyNumber::new(&user_pk, &journalist_id, &journalist_id_pk)
+        );
+
+        let digits = safety_number.digits();
+        assert_eq!(digits.len(), DIGIT_GROUPS * 6 - 1);
+        assert!(digits.chars().all(|c| c.is_ascii_digit() || c == ' '));
+
+        let words = safety_number.words().unwrap();
+        assert_eq!(words.split(' ').count(), WORD_COUNT);
+
+        let other_user_pk = UserKeyPair::generate().public_key().clone();
+        assert_ne!(
+            safety_number,
+            SafetyNumber::new(&other_user_pk, &journalist_id, &journalist_id_pk)
+        );
+
+        let other_journalist_id_pk =
+            generate_journalist_id_key_pair(&journalist_provisioning_key_pair, now)
+                .public_key()
+                .clone();
+        assert_ne!(
+            safety_number,
+            SafetyNumber::new(&user_pk, &journalist_id, &other_journalist_id_pk)
+        );
+    }
+
+    #[test]
+    fn safety_number_uses_latest_published_id_pk() {
+        let now = time::now();
+        let ProtocolKeys {
+            org_pk,
+            user_pk,
+            journalist_provisioning_pk,
+            journalist_provisioning_key_pair,
+            journalist_id_pk,
+            mut hierarchy,
+            ..
+        } = generate_protocol_keys(now);
+
+        let journalist_id = JournalistIdentity::new("journalist_0").unwrap();
+
+        // The journalist has rotated their identity key but the new key isn't published yet
+        let rotated_id_key_pair = generate_journalist_id_key_pair(
+            &journalist_provisioning_key_pair,
+            now + Duration::days(1),
+        );
+        let rotated_id_pk = rotated_id_key_pair.public_key().clone();
+
+        let before_publishing =
+            SafetyNumber::for_latest_published(&user_pk, &journalist_id, &hierarchy).unwrap();
+
+        assert_eq!(
+            before_publishing,
+            SafetyNumber::new(&user_pk, &journalist_id, &journalist_id_pk)
+        );
+        assert_ne!(
+            before_publishing,
+            SafetyNumber::new(&user_pk, &journalist_id, &rotated_id_pk)
+        );
+
+        hierarchy.insert(OrganizationPublicKeyFamily::new(
+            org_pk,
+            CoverNodeProvisioningPublicKeyFamilyList::new(vec![]),
+            JournalistProvisioningPublicKeyFamilyList::new(vec![
+                JournalistProvisioningPublicKeyFamily::new(
+                    journalist_provisioning_pk,
+                    HashMap::from([(
+                        journalist_id.clone(),
+                        JournalistIdPublicKeyFamilyList::new(vec![
+                            JournalistIdPublicKeyFamily::new(rotated_id_pk.clone(), vec![]),
+                        ]),
+                    )]),
+                ),
+            ]),
+            None,
+        ));
+
+        let after_publishing =
+            SafetyNumber::for_latest_published(&user_pk, &journalist_id, &hierarchy).unwrap();
+
+        assert_eq!(
+            after_publishing,
+            SafetyNumber::new(&user_pk, &journalist_id, &rotated_id_pk)
+        );
+
+        let unknown_journalist_id = JournalistIdentity::new("unknown").unwrap();
+        assert!(
+            SafetyNumber::for_latest_published(&user_pk, &unknown_journalist_id, &hierarchy)
+                .is_none()
+        );
+    }
+}
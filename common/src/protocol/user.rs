@@ -1,8 +1,11 @@
 use crate::api::models::journalist_id::JournalistIdentity;
+use crate::api::models::messages::read_receipt::ReadReceipt;
 use crate::api::models::messages::user_to_covernode_message::{
     EncryptedUserToCoverNodeMessage, UserToCoverNodeMessage,
 };
-use crate::api::models::messages::user_to_journalist_message::UserToJournalistMessage;
+use crate::api::models::messages::user_to_journalist_message::{
+    UserKeyRotation, UserToJournalistMessage,
+};
 use crate::Error;
 
 use crate::api::models::messages::journalist_to_user_message::{
@@ -14,7 +17,9 @@ use crate::protocol::recipient_tag::RecipientTag;
 use crate::{crypto::AnonymousBox, protocol::constants::*, FixedSizeMessageText};
 
 use super::covernode::covernode_msg_pks_from_hierarchy;
-use super::keys::{CoverDropPublicKeyHierarchy, UserKeyPair, UserPublicKey};
+use super::keys::{
+    CoverDropPublicKeyHierarchy, JournalistMessagingPublicKey, UserKeyPair, UserPublicKey,
+};
 
 pub fn encrypt_real_message_from_user_to_journalist_via_covernode(
     keys: &CoverDropPublicKeyHierarchy,
@@ -28,6 +33,68 @@ pub fn encrypt_real_message_from_user_to_journalist_via_covernode(
 
     let user_to_journalist_message = UserToJournalistMessage::new(message, user_pk);
 
+    encrypt_user_to_journalist_message_via_covernode(
+        keys,
+        journalist_id,
+        journalist_msg_pk,
+        user_to_journalist_message,
+    )
+}
+
+/// Tell a journalist that the user has replaced the key pair `previous_key_pair` with a new key
+/// pair with the public key `new_user_pk`. Replies from the journalist will be sent to the new key.
+pub fn encrypt_key_rotation_from_user_to_journalist_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    previous_key_pair: &UserKeyPair,
+    new_user_pk: &UserPublicKey,
+    journalist_id: &JournalistIdentity,
+) -> anyhow::Result<EncryptedUserToCoverNodeMessage> {
+    let Some(journalist_msg_pk) = keys.latest_journalist_msg_pk(journalist_id) else {
+        Err(Error::JournalistMessagingKeyNotFound(journalist_id.clone()))?
+    };
+
+    // The proof must be encrypted to the same messaging key as the message itself since that's
+    // the key the journalist will have used to decrypt it
+    let key_rotation = UserKeyRotation::new(previous_key_pair, new_user_pk, journalist_msg_pk)?;
+    let user_to_journalist_message =
+        UserToJournalistMessage::new_with_key_rotation(new_user_pk.clone(), key_rotation)?;
+
+    encrypt_user_to_journalist_message_via_covernode(
+        keys,
+        journalist_id,
+        journalist_msg_pk,
+        user_to_journalist_message,
+    )
+}
+
+/// Tell a journalist which of their messages the user has read
+pub fn encrypt_read_receipt_from_user_to_journalist_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    user_pk: &UserPublicKey,
+    journalist_id: &JournalistIdentity,
+    read_receipt: ReadReceipt,
+) -> anyhow::Result<EncryptedUserToCoverNodeMessage> {
+    let Some(journalist_msg_pk) = keys.latest_journalist_msg_pk(journalist_id) else {
+        Err(Error::JournalistMessagingKeyNotFound(journalist_id.clone()))?
+    };
+
+    let user_to_journalist_message =
+        UserToJournalistMessage::new_with_read_receipt(user_pk, read_receipt)?;
+
+    encrypt_user_to_journalist_message_via_covernode(
+        keys,
+        journalist_id,
+        journalist_msg_pk,
+        user_to_journalist_message,
+    )
+}
+
+fn encrypt_user_to_journalist_message_via_covernode(
+    keys: &CoverDropPublicKeyHierarchy,
+    journalist_id: &JournalistIdentity,
+    journalist_msg_pk: &JournalistMessagingPublicKey,
+    user_to_journalist_message: UserToJournalistMessage,
+) -> anyhow::Result<EncryptedUserToCoverNodeMessage> {
     let encrypted_user_to_journalist_message =
         AnonymousBox::encrypt(journalist_msg_pk, user_to_journalist_message.serialize())?;
 
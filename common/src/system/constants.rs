@@ -2,3 +2,7 @@ use chrono::Duration;
 
 /// Lives as long as the organization key - we don't want to be rotating this super often
 pub const ADMIN_KEY_VALID_DURATION: Duration = Duration::weeks(52);
+
+/// Domain separation label for the message retention limit signatures
+pub(crate) const MESSAGE_RETENTION_LIMIT_SIGNATURE_LABEL: &[u8] =
+    b"CoverDrop-System-MessageRetentionLimit-v1";
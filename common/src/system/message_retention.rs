@@ -0,0 +1,213 @@
+use chrono::{DateTime, Duration, Utc};
+use hex_buffer_serde::Hex as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    crypto::{
+        keys::{serde::SignatureHex, signing::traits::PublicSigningKey as _},
+        Signable, Signature,
+    },
+    protocol::{constants::MESSAGE_VALID_FOR_DURATION, keys::OrganizationPublicKey},
+    system::{
+        constants::MESSAGE_RETENTION_LIMIT_SIGNATURE_LABEL,
+        keys::{verify_admin_pk, AdminKeyPair, UntrustedAdminPublicKey},
+    },
+};
+
+/// The longest a journalist may keep messages in their vault, regardless of the retention
+/// policy they have chosen for a conversation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MessageRetentionLimit {
+    pub max_retention_days: u16,
+}
+
+impl MessageRetentionLimit {
+    pub fn new(max_retention_days: u16) -> anyhow::Result<Self> {
+        let min_days = MESSAGE_VALID_FOR_DURATION.num_days();
+
+        if i64::from(max_retention_days) < min_days {
+            anyhow::bail!(
+                "The message retention limit must be at least {min_days} days, but got {max_retention_days}"
+            );
+        }
+
+        Ok(Self { max_retention_days })
+    }
+
+    pub fn max_retention(&self) -> Duration {
+        Duration::days(self.max_retention_days.into())
+    }
+
+    fn signature_data(&self, created_at: DateTime<Utc>) -> MessageRetentionLimitSignatureData {
+        let mut hasher = Sha256::new();
+
+        hasher.update(MESSAGE_RETENTION_LIMIT_SIGNATURE_LABEL);
+        hasher.update(self.max_retention_days.to_be_bytes());
+        hasher.update(created_at.timestamp().to_be_bytes());
+
+        MessageRetentionLimitSignatureData(hasher.finalize().into())
+    }
+}
+
+/// Used until the admins have published a limit for the organization, meaning no conversation
+/// can be kept for longer than the default message lifetime.
+impl Default for MessageRetentionLimit {
+    fn default() -> Self {
+        Self {
+            max_retention_days: MESSAGE_VALID_FOR_DURATION.num_days() as u16,
+        }
+    }
+}
+
+/// A representation of the data required to sign/verify a message retention limit
+#[derive(Clone, Debug)]
+pub struct MessageRetentionLimitSignatureData(pub [u8; 32]);
+
+impl Signable for MessageRetentionLimitSignatureData {
+    fn as_signable_bytes(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+/// A message retention limit signed by an admin key. Admin keys are not part of the published
+/// key hierarchy so the signing key is included, clients check it was signed by one of the
+/// organization keys they trust.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedMessageRetentionLimit {
+    pub limit: MessageRetentionLimit,
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "SignatureHex")]
+    pub signature: Signature<MessageRetentionLimitSignatureData>,
+    pub signed_with: UntrustedAdminPublicKey,
+}
+
+impl SignedMessageRetentionLimit {
+    pub fn new(
+        limit: MessageRetentionLimit,
+        admin_key_pair: &AdminKeyPair,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let signature = admin_key_pair.sign(&limit.signature_data(now));
+
+        Self {
+            limit,
+            created_at: now,
+            signature,
+            signed_with: admin_key_pair.public_key().to_untrusted(),
+        }
+    }
+
+    /// Verify the limit was signed by an admin key which was itself signed by one of the given
+    /// organization keys. Limits which claim to be created in the future are rejected.
+    pub fn verify<'a>(
+        &self,
+        org_pks: impl IntoIterator<Item = &'a OrganizationPublicKey>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<MessageRetentionLimit> {
+        if self.created_at > now {
+            anyhow::bail!(
+                "Message retention limit was created in the future at {}",
+                self.created_at
+            );
+        }
+
+        let Some(admin_pk) = org_pks
+            .into_iter()
+            .find_map(|org_pk| verify_admin_pk(&self.signed_with, org_pk, now).ok())
+        else {
+            anyhow::bail!("Message retention limit is not signed by a known admin key");
+        };
+
+        admin_pk.verify(
+            &self.limit.signature_data(self.created_at),
+            &self.signature,
+            now,
+        )?;
+
+        MessageRetentionLimit::new(self.limit.max_retention_days)
+    }
+
+    /// Whether this limit should replace `previous`. Limits are only ever replaced by newer ones
+    /// so the API can't roll clients back to a limit the admins have replaced.
+    pub fn supersedes(&self, previous: Option<&SignedMessageRetentionLimit>) -> bool {
+        previous.is_none_or(|previous| self.created_at > previous.created_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        protocol::keys::generate_organization_key_pair, system::keys::generate_admin_key_pair, time,
+    };
+
+    use super::*;
+
+    #[test]
+    fn signed_limit_is_verified() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let other_org_key_pair = generate_organization_key_pair(now);
+        let admin_key_pair = generate_admin_key_pair(&org_key_pair, now);
+
+        let limit = MessageRetentionLimit::new(90)?;
+        let signed = SignedMessageRetentionLimit::new(limit, &admin_key_pair, now);
+
+        assert_eq!(signed.verify([org_key_pair.public_key()], now)?, limit);
+        assert!(signed
+            .verify([other_org_key_pair.public_key()], now)
+            .is_err());
+
+        // Tampering with the limit invalidates the signature
+        let mut extended = signed.clone();
+        extended.limit.max_retention_days = 365;
+        assert!(extended.verify([org_key_pair.public_key()], now).is_err());
+
+        let mut backdated = signed;
+        backdated.created_at = now - Duration::days(1);
+        assert!(backdated.verify([org_key_pair.public_key()], now).is_err());
+
+        // Limits from the future are rejected even when correctly signed
+        let future =
+            SignedMessageRetentionLimit::new(limit, &admin_key_pair, now + Duration::hours(1));
+        assert!(future.verify([org_key_pair.public_key()], now).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_newer_limits_supersede() -> anyhow::Result<()> {
+        let now = time::now();
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let admin_key_pair = generate_admin_key_pair(&org_key_pair, now);
+
+        let older = SignedMessageRetentionLimit::new(
+            MessageRetentionLimit::new(90)?,
+            &admin_key_pair,
+            now - Duration::days(1),
+        );
+        let newer =
+            SignedMessageRetentionLimit::new(MessageRetentionLimit::new(30)?, &admin_key_pair, now);
+
+        assert!(older.supersedes(None));
+        assert!(newer.supersedes(Some(&older)));
+        assert!(!older.supersedes(Some(&newer)));
+        assert!(!newer.supersedes(Some(&newer)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn limit_shorter_than_message_lifetime_is_rejected() {
+        assert!(MessageRetentionLimit::new(1).is_err());
+        assert!(
+            MessageRetentionLimit::new(MessageRetentionLimit::default().max_retention_days).is_ok()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod constants;
 pub mod forms;
 pub mod keys;
+pub mod message_retention;
 pub mod roles;
@@ -0,0 +1,345 @@
+//! Statistical timing tests for the trial decryption paths, based on the approach taken by
+//! dudect (<https://eprint.iacr.org/2016/1123.pdf>).
+//!
+//! Each test prepares two classes of inputs which an observer must not be able to tell apart, for
+//! example a real message for somebody else and a cover message. The operation under test is
+//! timed for randomly interleaved inputs from both classes and Welch's t-test is applied to the
+//! two timing distributions. A large t statistic means the classes are distinguishable by timing.
+//!
+//! These tests are slow and sensitive to the machine they run on so they are opt-in:
+//!
+//! ```sh
+//! cargo test --release -p common --features timing-tests --test constant_time -- --test-threads=1
+//! ```
+//!
+//! The number of measurements per test and the failure threshold can be overridden with the
+//! `COVERDROP_TIMING_SAMPLES` and `COVERDROP_TIMING_T_THRESHOLD` environment variables.
+
+use std::{hint::black_box, time::Instant};
+
+use common::{
+    api::models::{
+        journalist_id::JournalistIdentity,
+        messages::{
+            covernode_to_journalist_message::{
+                new_random_encrypted_covernode_to_journalist_message, CoverNodeToJournalistMessage,
+                EncryptedCoverNodeToJournalistMessage,
+            },
+            journalist_to_user_message::{
+                new_random_encrypted_journalist_to_user_message, EncryptedJournalistToUserMessage,
+                JournalistToUserMessage,
+            },
+            user_to_journalist_message::{
+                new_random_encrypted_user_to_journalist_message, UserToJournalistMessage,
+            },
+        },
+    },
+    crypto::{keys::encryption::UnsignedEncryptionKeyPair, AnonymousBox},
+    protocol::{
+        covernode::{decrypt_journalist_message, decrypt_user_message},
+        journalist::{
+            encrypt_real_message_from_journalist_to_user_via_covernode,
+            get_decrypted_journalist_dead_drop_message,
+            new_encrypted_cover_message_from_journalist_via_covernode,
+        },
+        keys::{
+            generate_journalist_messaging_key_pair,
+            test::{generate_protocol_keys, ProtocolKeys},
+        },
+        roles::User,
+        user::{
+            encrypt_real_message_from_user_to_journalist_via_covernode,
+            get_decrypted_user_dead_drop_message,
+            new_encrypted_cover_message_from_user_via_covernode,
+        },
+    },
+    time, FixedSizeMessageText,
+};
+use rand::Rng;
+
+const DEFAULT_SAMPLES: usize = 100_000;
+
+/// dudect considers a t statistic above 10 to mean the operation is definitely not constant time
+const DEFAULT_T_THRESHOLD: f64 = 10.0;
+
+/// Number of distinct inputs prepared for each class, inputs are reused across measurements
+const INPUTS_PER_CLASS: usize = 64;
+
+/// Measurements are discarded during warm up so that caches and branch predictors settle
+const WARM_UP_SAMPLES: usize = 1_000;
+
+/// Measurements above these percentiles are cropped before running the t-test, large
+/// measurements are mostly caused by interrupts and context switches.
+const CROP_PERCENTILES: [f64; 5] = [0.5, 0.75, 0.9, 0.95, 0.99];
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Online mean and variance for Welch's t-test
+#[derive(Default)]
+struct WelchTTest {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl WelchTTest {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+
+        let var_0 = self.m2[0] / (self.n[0] - 1.0);
+        let var_1 = self.m2[1] / (self.n[1] - 1.0);
+        let denominator = (var_0 / self.n[0] + var_1 / self.n[1]).sqrt();
+
+        if denominator == 0.0 {
+            return 0.0;
+        }
+
+        (self.mean[0] - self.mean[1]) / denominator
+    }
+}
+
+/// Time `op` for randomly interleaved inputs from both classes and return the largest absolute
+/// t statistic across the uncropped and cropped measurements.
+fn max_t_statistic<I>(classes: [Vec<I>; 2], mut op: impl FnMut(&I)) -> f64 {
+    let samples = env_or("COVERDROP_TIMING_SAMPLES", DEFAULT_SAMPLES);
+    let mut rng = rand::thread_rng();
+
+    let mut measurements = Vec::with_capacity(samples);
+
+    for i in 0..(WARM_UP_SAMPLES + samples) {
+        let class = rng.gen_range(0..2);
+        let input = &classes[class][rng.gen_range(0..classes[class].len())];
+
+        let start = Instant::now();
+        op(black_box(input));
+        let elapsed = start.elapsed().as_nanos() as f64;
+
+        if i >= WARM_UP_SAMPLES {
+            measurements.push((class, elapsed));
+        }
+    }
+
+    let mut sorted = measurements.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+    sorted.sort_by(f64::total_cmp);
+
+    let crop_thresholds = CROP_PERCENTILES
+        .iter()
+        .map(|p| sorted[((sorted.len() - 1) as f64 * p) as usize])
+        .chain(std::iter::once(f64::INFINITY));
+
+    crop_thresholds
+        .map(|threshold| {
+            let mut test = WelchTTest::default();
+            for (class, elapsed) in &measurements {
+                if *elapsed <= threshold {
+                    test.push(*class, *elapsed);
+                }
+            }
+            test.t().abs()
+        })
+        .fold(0.0, f64::max)
+}
+
+fn assert_constant_time<I>(name: &str, classes: [Vec<I>; 2], op: impl FnMut(&I)) {
+    let threshold = env_or("COVERDROP_TIMING_T_THRESHOLD", DEFAULT_T_THRESHOLD);
+
+    let t = max_t_statistic(classes, op);
+    println!("{name}: max |t| = {t:.2}");
+
+    assert!(
+        t < threshold,
+        "{name} timing depends on the input class: |t| = {t:.2}, threshold = {threshold}"
+    );
+}
+
+#[test]
+fn user_dead_drop_trial_decryption_is_constant_time() {
+    let now = time::now();
+    let ProtocolKeys {
+        hierarchy,
+        user_key_pair,
+        journalist_msg_key_pair,
+        ..
+    } = generate_protocol_keys(now);
+
+    let cover_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| new_random_encrypted_journalist_to_user_message().unwrap())
+        .collect();
+
+    // Real messages from a known journalist which were sent to a different source
+    let real_messages_for_others = (0..INPUTS_PER_CLASS)
+        .map(|_| {
+            let other_user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+            let message =
+                JournalistToUserMessage::new_with_message(FixedSizeMessageText::new("hi").unwrap());
+
+            EncryptedJournalistToUserMessage::encrypt(
+                other_user_key_pair.public_key(),
+                journalist_msg_key_pair.secret_key(),
+                message.serialize(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    assert_constant_time(
+        "user dead drop trial decryption",
+        [cover_messages, real_messages_for_others],
+        |message| {
+            let decrypted =
+                get_decrypted_user_dead_drop_message(&user_key_pair, &hierarchy, message);
+            assert!(matches!(decrypted, Ok(None)));
+        },
+    );
+}
+
+#[test]
+fn journalist_dead_drop_trial_decryption_is_constant_time() {
+    let now = time::now();
+    let ProtocolKeys {
+        covernode_msg_key_pair,
+        journalist_id_key_pair,
+        journalist_msg_key_pair,
+        ..
+    } = generate_protocol_keys(now);
+
+    let cover_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| {
+            new_random_encrypted_covernode_to_journalist_message(
+                &covernode_msg_key_pair,
+                new_random_encrypted_user_to_journalist_message(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    // Real messages forwarded by the CoverNode to a different journalist
+    let real_messages_for_others = (0..INPUTS_PER_CLASS)
+        .map(|_| {
+            let other_journalist_msg_key_pair =
+                generate_journalist_messaging_key_pair(&journalist_id_key_pair, now);
+            let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+
+            let user_to_journalist_message = UserToJournalistMessage::new(
+                FixedSizeMessageText::new("hi").unwrap(),
+                user_key_pair.public_key(),
+            );
+            let user_to_journalist_message = AnonymousBox::encrypt(
+                other_journalist_msg_key_pair.public_key(),
+                user_to_journalist_message.serialize(),
+            )
+            .unwrap();
+
+            EncryptedCoverNodeToJournalistMessage::encrypt(
+                other_journalist_msg_key_pair.public_key(),
+                covernode_msg_key_pair.secret_key(),
+                CoverNodeToJournalistMessage::new(user_to_journalist_message).serialize(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let covernode_msg_pks = [covernode_msg_key_pair.public_key()];
+    let journalist_msg_key_pairs = [journalist_msg_key_pair];
+
+    assert_constant_time(
+        "journalist dead drop trial decryption",
+        [cover_messages, real_messages_for_others],
+        |message| {
+            let decrypted = get_decrypted_journalist_dead_drop_message(
+                &covernode_msg_pks,
+                &journalist_msg_key_pairs,
+                message,
+                0,
+            );
+            assert!(decrypted.is_none());
+        },
+    );
+}
+
+#[test]
+fn covernode_user_message_decryption_is_constant_time() {
+    let now = time::now();
+    let ProtocolKeys {
+        hierarchy,
+        user_pk,
+        covernode_msg_key_pair,
+        ..
+    } = generate_protocol_keys(now);
+
+    let journalist_id = JournalistIdentity::new("journalist_0").unwrap();
+
+    let cover_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| new_encrypted_cover_message_from_user_via_covernode(&hierarchy).unwrap())
+        .collect();
+
+    let real_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| {
+            encrypt_real_message_from_user_to_journalist_via_covernode(
+                &hierarchy,
+                &user_pk,
+                &journalist_id,
+                FixedSizeMessageText::new("hi").unwrap(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    assert_constant_time(
+        "CoverNode user message decryption",
+        [cover_messages, real_messages],
+        |message| {
+            decrypt_user_message(&covernode_msg_key_pair, message).unwrap();
+        },
+    );
+}
+
+#[test]
+fn covernode_journalist_message_decryption_is_constant_time() {
+    let now = time::now();
+    let ProtocolKeys {
+        hierarchy,
+        user_pk,
+        covernode_msg_key_pair,
+        journalist_msg_key_pair,
+        ..
+    } = generate_protocol_keys(now);
+
+    let cover_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| new_encrypted_cover_message_from_journalist_via_covernode(&hierarchy).unwrap())
+        .collect();
+
+    let real_messages = (0..INPUTS_PER_CLASS)
+        .map(|_| {
+            encrypt_real_message_from_journalist_to_user_via_covernode(
+                &hierarchy,
+                &user_pk,
+                &journalist_msg_key_pair,
+                &FixedSizeMessageText::new("hi").unwrap(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    assert_constant_time(
+        "CoverNode journalist message decryption",
+        [cover_messages, real_messages],
+        |message| {
+            decrypt_journalist_message(&covernode_msg_key_pair, message).unwrap();
+        },
+    );
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use common::api::models::dead_drops::SerializedUserToJournalistDeadDropMessages;
+use common::client::private_sending_queue::{PrivateSendingQueue, PrivateSendingQueueSecret};
 use common::crypto::keys::encryption::{
     PublicEncryptionKey, SecretEncryptionKey, SignedEncryptionKeyPair, UnsignedEncryptionKeyPair,
 };
@@ -192,8 +193,7 @@ fn test_journalist_dead_drop() -> anyhow::Result<()> {
 #[test]
 fn test_signature() -> anyhow::Result<()> {
     let key_pair = ed25519_dalek::SigningKey::from_bytes(
-        include_bytes!("vectors/signature/02_sk")
-            .as_ref()
+        include_bytes!("vectors/signature/02_sk")[..]
             .try_into()
             .unwrap(),
     );
@@ -241,3 +241,48 @@ fn test_certificate_data() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_private_sending_queue() -> anyhow::Result<()> {
+    let secret = PrivateSendingQueueSecret::from_bytes(
+        include_bytes!("vectors/private_sending_queue/01_secret").to_owned(),
+    );
+
+    let mut queue = PrivateSendingQueue::from_bytes(include_bytes!(
+        "vectors/private_sending_queue/02_initial_queue"
+    ))?;
+    assert_eq!(queue.real_item_count(&secret), 0);
+
+    // Enqueuing is deterministic given the secret, so the serialized queues must match exactly
+    let item_1 = include_bytes!("vectors/private_sending_queue/03_item_1").to_vec();
+    let hint_1 = queue.enqueue(&secret, item_1.clone())?;
+    assert_eq!(
+        hint_1.as_bytes(),
+        include_bytes!("vectors/private_sending_queue/04_hint_1")
+    );
+    assert_eq!(
+        queue.to_bytes(),
+        include_bytes!("vectors/private_sending_queue/05_queue_after_enqueue_1")
+    );
+
+    let item_2 = include_bytes!("vectors/private_sending_queue/06_item_2").to_vec();
+    let hint_2 = queue.enqueue(&secret, item_2.clone())?;
+    assert_eq!(
+        hint_2.as_bytes(),
+        include_bytes!("vectors/private_sending_queue/07_hint_2")
+    );
+    assert_eq!(
+        queue.to_bytes(),
+        include_bytes!("vectors/private_sending_queue/08_queue_after_enqueue_2")
+    );
+    assert_eq!(queue.real_item_count(&secret), 2);
+
+    // Real items are dequeued first and in order
+    let item_len = queue.peek().len();
+    let create_cover_item = || Ok(vec![0; item_len]);
+    assert_eq!(queue.dequeue(create_cover_item)?, item_1);
+    assert_eq!(queue.dequeue(create_cover_item)?, item_2);
+    assert_eq!(queue.real_item_count(&secret), 0);
+
+    Ok(())
+}
@@ -0,0 +1,459 @@
+//! Verifies the end-to-end protocol test vectors, see `vectors/README.md`.
+//!
+//! Unlike the primitive test vectors these cover a complete flow, e.g. a message from the
+//! plaintext typed by the user to the ciphertext submitted to the CoverNode.
+
+use common::api::models::journalist_id::JournalistIdentity;
+use common::api::models::messages::journalist_to_covernode_message::{
+    EncryptedJournalistToCoverNodeMessage, JournalistToCoverNodeMessage,
+};
+use common::api::models::messages::journalist_to_user_message::JournalistToUserMessage;
+use common::api::models::messages::user_to_covernode_message::{
+    EncryptedUserToCoverNodeMessage, UserToCoverNodeMessage,
+};
+use common::backup::roles::{BackupId, BackupMsg};
+use common::client::mailbox::user_mailbox::UserMailbox;
+use common::crypto::keys::encryption::UnsignedEncryptionKeyPair;
+use common::crypto::keys::role::Role;
+use common::crypto::keys::signing::PublicSigningKey;
+use common::crypto::keys::untrusted::encryption::UntrustedSignedEncryptionKeyPair;
+use common::crypto::keys::untrusted::signing::UntrustedSignedSigningKeyPair;
+use common::crypto::keys::{Ed25519PublicKey, X25519PublicKey, X25519SecretKey};
+use common::crypto::pbkdf::derive_secret_box_key_with_configuration;
+use common::crypto::{
+    AnonymousBox, MultiAnonymousBox, SecretBox, TwoPartyBox, SECRET_BOX_FOOTER_LEN,
+};
+use common::protocol::backup::{
+    coverup_finish_restore_step, coverup_initiate_restore_step,
+    sentinel_restore_try_unwrap_and_wrap_share_step,
+};
+use common::protocol::backup_data::{BackupDataBytes, BackupDataWithSignature};
+use common::protocol::keys::UserPublicKey;
+use common::protocol::recipient_tag::RecipientTag;
+use common::protocol::roles::{
+    CoverNodeMessaging, JournalistId, JournalistMessaging, JournalistProvisioning, User,
+};
+use common::{time, FixedSizeMessageText};
+use std::io::Cursor;
+use tempfile::tempdir;
+
+fn encryption_key_pair<R: Role>(pk: &[u8; 32], sk: &[u8; 32]) -> UnsignedEncryptionKeyPair<R> {
+    UnsignedEncryptionKeyPair::from_raw_keys(X25519PublicKey::from(*pk), X25519SecretKey::from(*sk))
+}
+
+#[test]
+fn test_padded_compressed_string() -> anyhow::Result<()> {
+    let cases: [(&[u8], &[u8]); 5] = [
+        (
+            include_bytes!("vectors/padded_compressed_string/01_empty_text"),
+            include_bytes!("vectors/padded_compressed_string/01_empty_padded"),
+        ),
+        (
+            include_bytes!("vectors/padded_compressed_string/02_ascii_text"),
+            include_bytes!("vectors/padded_compressed_string/02_ascii_padded"),
+        ),
+        (
+            include_bytes!("vectors/padded_compressed_string/03_unicode_text"),
+            include_bytes!("vectors/padded_compressed_string/03_unicode_padded"),
+        ),
+        (
+            include_bytes!("vectors/padded_compressed_string/04_compressible_text"),
+            include_bytes!("vectors/padded_compressed_string/04_compressible_padded"),
+        ),
+        (
+            include_bytes!("vectors/padded_compressed_string/05_longest_text"),
+            include_bytes!("vectors/padded_compressed_string/05_longest_padded"),
+        ),
+    ];
+
+    // The padding is random so we can only check that the padded strings decode to the text
+    for (text, padded) in cases {
+        let text = std::str::from_utf8(text)?;
+        let padded = FixedSizeMessageText::from_vec_unchecked(padded.to_vec());
+
+        assert_eq!(padded.total_len(), FixedSizeMessageText::TOTAL_LEN);
+        assert_eq!(padded.to_string()?, text);
+        assert!(FixedSizeMessageText::new(text).is_ok());
+    }
+
+    let too_long = std::str::from_utf8(include_bytes!(
+        "vectors/padded_compressed_string/06_too_long_text"
+    ))?;
+    assert!(FixedSizeMessageText::new(too_long).is_err());
+
+    let too_compressible = FixedSizeMessageText::from_vec_unchecked(
+        include_bytes!("vectors/padded_compressed_string/07_too_compressible_padded").to_vec(),
+    );
+    assert!(too_compressible.to_string().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_user_to_journalist_flow() -> anyhow::Result<()> {
+    let user_pk = UserPublicKey::new(X25519PublicKey::from(
+        include_bytes!("vectors/user_to_journalist_flow/01_user_pk").to_owned(),
+    ));
+    let journalist_key_pair = encryption_key_pair::<JournalistMessaging>(
+        include_bytes!("vectors/user_to_journalist_flow/02_journalist_pk"),
+        include_bytes!("vectors/user_to_journalist_flow/02_journalist_sk"),
+    );
+    let covernode_key_pairs = [
+        encryption_key_pair::<CoverNodeMessaging>(
+            include_bytes!("vectors/user_to_journalist_flow/03_covernode_1_pk"),
+            include_bytes!("vectors/user_to_journalist_flow/03_covernode_1_sk"),
+        ),
+        encryption_key_pair::<CoverNodeMessaging>(
+            include_bytes!("vectors/user_to_journalist_flow/04_covernode_2_pk"),
+            include_bytes!("vectors/user_to_journalist_flow/04_covernode_2_sk"),
+        ),
+    ];
+
+    let journalist_id = JournalistIdentity::new(std::str::from_utf8(include_bytes!(
+        "vectors/user_to_journalist_flow/05_journalist_id"
+    ))?)?;
+    let text = std::str::from_utf8(include_bytes!(
+        "vectors/user_to_journalist_flow/06_message_text"
+    ))?;
+
+    let encrypted_user_to_covernode_message: EncryptedUserToCoverNodeMessage =
+        MultiAnonymousBox::from_vec_unchecked(
+            include_bytes!(
+                "vectors/user_to_journalist_flow/11_encrypted_user_to_covernode_message"
+            )
+            .to_vec(),
+        );
+
+    // Either CoverNode can unwrap the message
+    for covernode_key_pair in &covernode_key_pairs {
+        let user_to_covernode_message =
+            MultiAnonymousBox::decrypt(covernode_key_pair, &encrypted_user_to_covernode_message)?;
+        assert_eq!(
+            user_to_covernode_message.bytes,
+            include_bytes!("vectors/user_to_journalist_flow/10_user_to_covernode_message")
+        );
+    }
+
+    let user_to_covernode_message = MultiAnonymousBox::decrypt(
+        &covernode_key_pairs[0],
+        &encrypted_user_to_covernode_message,
+    )?
+    .to_message();
+
+    let UserToCoverNodeMessage::Real {
+        recipient_tag,
+        payload,
+    } = user_to_covernode_message
+    else {
+        panic!("Expected a real message");
+    };
+
+    assert_eq!(
+        recipient_tag.as_ref(),
+        include_bytes!("vectors/user_to_journalist_flow/09_recipient_tag")
+    );
+    assert_eq!(
+        recipient_tag,
+        RecipientTag::from_journalist_id(&journalist_id)
+    );
+    assert_eq!(
+        payload.as_bytes(),
+        include_bytes!("vectors/user_to_journalist_flow/08_encrypted_user_to_journalist_message")
+    );
+
+    let user_to_journalist_message = AnonymousBox::decrypt(&journalist_key_pair, &payload)?;
+    assert_eq!(
+        user_to_journalist_message.bytes,
+        include_bytes!("vectors/user_to_journalist_flow/07_user_to_journalist_message")
+    );
+
+    let user_to_journalist_message = user_to_journalist_message.to_message();
+    assert_eq!(user_to_journalist_message.reply_key, user_pk);
+    assert_eq!(user_to_journalist_message.message.to_string()?, text);
+
+    Ok(())
+}
+
+#[test]
+fn test_journalist_to_user_flow() -> anyhow::Result<()> {
+    let user_key_pair = encryption_key_pair::<User>(
+        include_bytes!("vectors/journalist_to_user_flow/01_user_pk"),
+        include_bytes!("vectors/journalist_to_user_flow/01_user_sk"),
+    );
+    let journalist_key_pair = encryption_key_pair::<JournalistMessaging>(
+        include_bytes!("vectors/journalist_to_user_flow/02_journalist_pk"),
+        include_bytes!("vectors/journalist_to_user_flow/02_journalist_sk"),
+    );
+    let covernode_key_pairs = [
+        encryption_key_pair::<CoverNodeMessaging>(
+            include_bytes!("vectors/journalist_to_user_flow/03_covernode_1_pk"),
+            include_bytes!("vectors/journalist_to_user_flow/03_covernode_1_sk"),
+        ),
+        encryption_key_pair::<CoverNodeMessaging>(
+            include_bytes!("vectors/journalist_to_user_flow/04_covernode_2_pk"),
+            include_bytes!("vectors/journalist_to_user_flow/04_covernode_2_sk"),
+        ),
+    ];
+
+    let text = std::str::from_utf8(include_bytes!(
+        "vectors/journalist_to_user_flow/05_message_text"
+    ))?;
+    let hand_over_journalist_id = JournalistIdentity::new(std::str::from_utf8(include_bytes!(
+        "vectors/journalist_to_user_flow/06_hand_over_journalist_id"
+    ))?)?;
+
+    let cases: [[&[u8]; 4]; 2] = [
+        [
+            include_bytes!("vectors/journalist_to_user_flow/07_message_journalist_to_user_message"),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/07_message_encrypted_journalist_to_user_message"
+            ),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/07_message_journalist_to_covernode_message"
+            ),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/07_message_encrypted_journalist_to_covernode_message"
+            ),
+        ],
+        [
+            include_bytes!(
+                "vectors/journalist_to_user_flow/08_hand_over_journalist_to_user_message"
+            ),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/08_hand_over_encrypted_journalist_to_user_message"
+            ),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/08_hand_over_journalist_to_covernode_message"
+            ),
+            include_bytes!(
+                "vectors/journalist_to_user_flow/08_hand_over_encrypted_journalist_to_covernode_message"
+            ),
+        ],
+    ];
+
+    let mut decrypted_messages = vec![];
+
+    for [journalist_to_user_message, encrypted_journalist_to_user_message, journalist_to_covernode_message, encrypted_journalist_to_covernode_message] in
+        cases
+    {
+        let encrypted: EncryptedJournalistToCoverNodeMessage =
+            MultiAnonymousBox::from_vec_unchecked(
+                encrypted_journalist_to_covernode_message.to_vec(),
+            );
+
+        // Either CoverNode can unwrap the message
+        for covernode_key_pair in &covernode_key_pairs {
+            let decrypted = MultiAnonymousBox::decrypt(covernode_key_pair, &encrypted)?;
+            assert_eq!(decrypted.bytes, journalist_to_covernode_message);
+        }
+
+        let decrypted = MultiAnonymousBox::decrypt(&covernode_key_pairs[0], &encrypted)?;
+        let JournalistToCoverNodeMessage::Real { payload } = decrypted.to_message() else {
+            panic!("Expected a real message");
+        };
+        assert_eq!(payload.as_bytes(), encrypted_journalist_to_user_message);
+
+        // The CoverNode publishes the payload in the user dead drop
+        let decrypted = TwoPartyBox::decrypt(
+            journalist_key_pair.public_key(),
+            user_key_pair.secret_key(),
+            &payload,
+        )?;
+        assert_eq!(decrypted.bytes, journalist_to_user_message);
+
+        decrypted_messages.push(decrypted.to_message()?);
+    }
+
+    let [JournalistToUserMessage::Message(message), JournalistToUserMessage::HandOver(journalist_id)] =
+        decrypted_messages.as_slice()
+    else {
+        panic!("Expected a message followed by a hand over");
+    };
+
+    assert_eq!(message.to_string()?, text);
+    assert_eq!(journalist_id, &hand_over_journalist_id);
+
+    Ok(())
+}
+
+#[test]
+fn test_user_mailbox() -> anyhow::Result<()> {
+    let mailbox_bytes = include_bytes!("vectors/user_mailbox/08_mailbox");
+    let org_pk = include_bytes!("vectors/user_mailbox/03_org_pk");
+
+    // The mailbox starts with the plain header: the Argon2 configuration, the length of the
+    // salt, the salt and then the organization public key
+    let salt_len = mailbox_bytes[1] as usize;
+    let org_pk_offset = 2 + salt_len;
+    assert_eq!(
+        &mailbox_bytes[org_pk_offset..org_pk_offset + org_pk.len()],
+        org_pk
+    );
+
+    // Loading may re-key the mailbox so work on a copy
+    let test_files_dir = tempdir()?;
+    let mailbox_path = test_files_dir.path().join("mailbox");
+    std::fs::write(&mailbox_path, mailbox_bytes)?;
+
+    let password = std::str::from_utf8(include_bytes!("vectors/user_mailbox/01_password"))?;
+    let mailbox = UserMailbox::load(&mailbox_path, password)?;
+
+    assert_eq!(mailbox.org_pks()[0].key.as_bytes(), org_pk);
+    assert_eq!(
+        &mailbox.user_key_pair().raw_public_key().to_bytes(),
+        include_bytes!("vectors/user_mailbox/02_user_pk")
+    );
+    assert_eq!(
+        &mailbox.user_key_pair().secret_key().to_bytes(),
+        include_bytes!("vectors/user_mailbox/02_user_sk")
+    );
+    assert_eq!(
+        mailbox.max_dead_drop_id(),
+        i32::from_be_bytes(include_bytes!("vectors/user_mailbox/07_max_dead_drop_id").to_owned())
+    );
+
+    let journalist_id = JournalistIdentity::new(std::str::from_utf8(include_bytes!(
+        "vectors/user_mailbox/04_journalist_id"
+    ))?)?;
+    let journalist_tag = RecipientTag::from_journalist_id(&journalist_id);
+
+    let mut messages = mailbox.messages().iter();
+
+    let message = messages.next().expect("Message to journalist");
+    assert!(message.is_from_user());
+    assert_eq!(message.to.to_journalist_tag(), Some(journalist_tag.clone()));
+    assert_eq!(
+        message.message.to_string()?,
+        std::str::from_utf8(include_bytes!(
+            "vectors/user_mailbox/05_message_to_journalist_text"
+        ))?
+    );
+
+    let message = messages.next().expect("Message from journalist");
+    assert!(message.is_journalist());
+    assert_eq!(message.from.to_journalist_tag(), Some(journalist_tag));
+    assert_eq!(
+        message.message.to_string()?,
+        std::str::from_utf8(include_bytes!(
+            "vectors/user_mailbox/06_message_from_journalist_text"
+        ))?
+    );
+
+    assert!(messages.next().is_none());
+
+    // Re-serializing the mailbox must reproduce the vector exactly, otherwise the layout has
+    // changed and the vector needs regenerating. The secret data is encrypted with a fresh
+    // nonce every time it's written so it's compared before encryption.
+    let mut header = Cursor::new(vec![]);
+    mailbox.plain.write(&mut header)?;
+    let header = header.into_inner();
+    assert_eq!(&mailbox_bytes[..header.len()], header.as_slice());
+
+    let configuration = mailbox
+        .argon2_configuration()
+        .expect("Mailbox has an Argon2 configuration");
+    let key =
+        derive_secret_box_key_with_configuration(password, &mailbox.plain.salt, configuration)?;
+    let secret = SecretBox::<Vec<u8>>::from_vec_unchecked(mailbox_bytes[header.len()..].to_vec());
+    let plaintext = SecretBox::decrypt(&key, secret)?;
+
+    assert_eq!(mailbox.secret.to_plaintext()?, plaintext);
+    assert_eq!(
+        mailbox_bytes.len(),
+        header.len() + plaintext.len() + SECRET_BOX_FOOTER_LEN
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_backup() -> anyhow::Result<()> {
+    let now = time::now();
+
+    let provisioning_pk =
+        PublicSigningKey::<JournalistProvisioning>::new(Ed25519PublicKey::from_bytes(
+            include_bytes!("vectors/backup/01_journalist_provisioning_pk"),
+        )?);
+    let journalist_id = JournalistIdentity::new(std::str::from_utf8(include_bytes!(
+        "vectors/backup/02_journalist_id"
+    ))?)?;
+    let id_key_pair: UntrustedSignedSigningKeyPair<JournalistId> = serde_json::from_slice(
+        include_bytes!("vectors/backup/03_journalist_id_key_pair.json"),
+    )?;
+    let id_key_pair = id_key_pair.to_trusted(&provisioning_pk, now)?;
+
+    let backup_id_pk = PublicSigningKey::<BackupId>::new(Ed25519PublicKey::from_bytes(
+        include_bytes!("vectors/backup/04_backup_id_pk"),
+    )?);
+    let backup_msg_key_pair: UntrustedSignedEncryptionKeyPair<BackupMsg> =
+        serde_json::from_slice(include_bytes!("vectors/backup/05_backup_msg_key_pair.json"))?;
+    let backup_msg_key_pairs = [backup_msg_key_pair.to_trusted(&backup_id_pk, now)?];
+
+    let recovery_contacts: [(&[u8], &[u8]); 3] = [
+        (
+            include_bytes!("vectors/backup/06_recovery_contact_1_id"),
+            include_bytes!("vectors/backup/06_recovery_contact_1_msg_key_pair.json"),
+        ),
+        (
+            include_bytes!("vectors/backup/06_recovery_contact_2_id"),
+            include_bytes!("vectors/backup/06_recovery_contact_2_msg_key_pair.json"),
+        ),
+        (
+            include_bytes!("vectors/backup/06_recovery_contact_3_id"),
+            include_bytes!("vectors/backup/06_recovery_contact_3_msg_key_pair.json"),
+        ),
+    ];
+
+    let k = include_bytes!("vectors/backup/07_k")[0];
+
+    let backup = BackupDataWithSignature::new(
+        BackupDataBytes(include_bytes!("vectors/backup/09_backup_data_bytes").to_vec()),
+        serde_json::from_slice(include_bytes!(
+            "vectors/backup/10_backup_data_signature.json"
+        ))?,
+        serde_json::from_slice(include_bytes!("vectors/backup/11_signed_with.json"))?,
+    )?;
+
+    let state = coverup_initiate_restore_step(
+        journalist_id,
+        backup,
+        id_key_pair.public_key(),
+        &backup_msg_key_pairs,
+        now,
+    )?;
+    assert_eq!(state.encrypted_shares.len(), recovery_contacts.len());
+    assert_eq!(state.k, k);
+
+    // Only k of the recovery contacts need to take part in the restore
+    let mut wrapped_shares = vec![];
+    for ((identity, encrypted_share), (expected_identity, msg_key_pair)) in state
+        .encrypted_shares
+        .iter()
+        .zip(recovery_contacts)
+        .take(k as usize)
+    {
+        assert_eq!(identity.as_bytes(), expected_identity);
+
+        let msg_key_pair: UntrustedSignedEncryptionKeyPair<JournalistMessaging> =
+            serde_json::from_slice(msg_key_pair)?;
+        let msg_key_pair = msg_key_pair.to_trusted(&provisioning_pk, now)?;
+
+        let wrapped_share = sentinel_restore_try_unwrap_and_wrap_share_step(
+            encrypted_share.clone(),
+            vec![msg_key_pair],
+            backup_msg_key_pairs[0].public_key().clone(),
+        )?
+        .expect("Recovery contact can decrypt their share");
+
+        wrapped_shares.push(wrapped_share);
+    }
+
+    let encrypted_vault =
+        coverup_finish_restore_step(state, wrapped_shares, &backup_msg_key_pairs)?;
+    assert_eq!(
+        encrypted_vault,
+        include_bytes!("vectors/backup/08_encrypted_vault")
+    );
+
+    Ok(())
+}
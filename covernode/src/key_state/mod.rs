@@ -36,7 +36,7 @@ impl KeyState {
         let keys_and_profiles = api_client
             .get_public_keys()
             .await?
-            .into_trusted(&anchor_org_pks, now);
+            .into_trusted(&anchor_org_pks, now)?;
 
         let keys = keys_and_profiles.keys;
 
@@ -19,7 +19,7 @@ impl RecipientTagKeyLookupTable {
         let keys_and_profiles = api_client
             .get_public_keys()
             .await?
-            .into_trusted(anchor_org_pks, now);
+            .into_trusted(anchor_org_pks, now)?;
 
         let keys = keys_and_profiles.keys;
         let mut recipient_tag_to_public_key = HashMap::new();
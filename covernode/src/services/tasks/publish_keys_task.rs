@@ -50,7 +50,7 @@ impl Task for PublishedKeysTask {
                 .api_client
                 .get_public_keys()
                 .await?
-                .into_trusted(key_state.anchor_org_pks(), now)
+                .into_trusted(key_state.anchor_org_pks(), now)?
                 .keys;
 
             let published_covernode_id_key_pairs = key_state.published_covernode_id_key_pairs();
@@ -71,7 +71,7 @@ pub async fn fetch_and_verify_journalist_key(
         .map_err(|e| {
             DeliveryServiceError::Anyhow(anyhow::anyhow!("Failed to fetch public keys: {}", e))
         })?
-        .into_trusted(trust_anchors, time::now());
+        .into_trusted(trust_anchors, time::now())?;
 
     let (client_id, verifying_id_pk) = verified_public_keys_and_profiles
         .keys
@@ -22,11 +22,14 @@ pub async fn post_rotate_covernode_id_key(
         .await
         .map_err(AppError::DatabaseError)?;
 
-    let keys = api_client.get_public_keys().await.map(|keys_and_profiles| {
-        keys_and_profiles
-            .into_trusted(&anchor_org_pks, time::now())
-            .keys
-    });
+    let keys = api_client
+        .get_public_keys()
+        .await
+        .and_then(|keys_and_profiles| {
+            Ok(keys_and_profiles
+                .into_trusted(&anchor_org_pks, time::now())?
+                .keys)
+        });
 
     let keys = match keys {
         Ok(keys) => keys,
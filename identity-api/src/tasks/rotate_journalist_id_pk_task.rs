@@ -41,10 +41,10 @@ impl Task for RotateJournalistIdPublicKeysTask {
             .api_client
             .get_public_keys()
             .await
-            .map(|keys_and_profiles| {
-                keys_and_profiles
-                    .into_trusted(&anchor_org_pks, time::now())
-                    .keys
+            .and_then(|keys_and_profiles| {
+                Ok(keys_and_profiles
+                    .into_trusted(&anchor_org_pks, time::now())?
+                    .keys)
             })?;
 
         let to_rotate = self.api_client.get_journalist_id_pk_forms().await?;
@@ -52,6 +52,7 @@ pub async fn get_and_verify_public_keys(
     get_public_keys(api_client)
         .await
         .into_trusted(anchor_org_pks, now)
+        .expect("Verify public keys")
 }
 
 pub async fn generate_test_journalist(
@@ -620,8 +620,9 @@ impl CoverDropStackBuilder {
                     .await
                     .expect("Get keys from API");
 
-                let keys_and_profiles =
-                    keys_and_profiles.into_trusted(&stack_keys.anchor_org_pks(), base_time);
+                let keys_and_profiles = keys_and_profiles
+                    .into_trusted(&stack_keys.anchor_org_pks(), base_time)
+                    .expect("Verify keys from API");
 
                 async move {
                     send_user_to_journalist_cover_message(
@@ -1,5 +1,6 @@
 use common::api::models::journalist_id::JournalistIdentity;
 use common::backup::get_backup_data_s3::get_latest_journalist_backup_from_s3;
+use common::backup::secret_sharing_settings::SecretSharingSettings;
 use common::clap::Stage::Development;
 use common::crypto::keys::serde::StorableKeyMaterial;
 use common::protocol::backup::{coverup_finish_restore_step, coverup_initiate_restore_step};
@@ -26,6 +27,7 @@ use integration_tests::{
     stack::{CoverDropStack, StackProfile},
 };
 use journalist_vault::JournalistVault;
+use std::path::PathBuf;
 use std::time::Duration;
 use std::{fs, slice};
 
@@ -230,12 +232,10 @@ async fn backup_scenario() {
     .expect("No share could be unwrapped");
 
     // Complete restore
-    let k = 1;
     let restored_vault = coverup_finish_restore_step(
         backup_state,
         vec![wrapped_share.clone()],
         std::slice::from_ref(&backup_encryption_key_2b),
-        k,
     )
     .expect("Failed to finish restore");
 
@@ -279,27 +279,7 @@ async fn backup_scenario() {
     .await
     .expect("Admin CLI initiate restore finalize");
 
-    // Load the wrapped shares from disk and extract recipient identity from filename
-    let encrypted_shares = wrapped_shares_paths
-        .iter()
-        .filter_map(|path| {
-            let share_base64 = fs::read_to_string(path).expect("Read wrapped share file from disk");
-            let encrypted_share = EncryptedSecretShare::from_base64_string(&share_base64).ok()?;
-
-            // Extract journalist ID from filename (format: restore-{timestamp}-share-{num}-{recipient-id}.recovery-share.txt)
-            let filename = path.file_name()?.to_str()?;
-
-            // Split from right to extract recipient-id before .recovery-share.txt extension
-            let journalist_id_str = filename
-                .strip_suffix(".recovery-share.txt")?
-                .rsplit('-')
-                .next()?;
-
-            let journalist_id = JournalistIdentity::new(journalist_id_str).ok()?;
-
-            Some((journalist_id, encrypted_share))
-        })
-        .collect::<Vec<_>>();
+    let encrypted_shares = load_encrypted_shares(&wrapped_shares_paths);
 
     // Hand over to recovery contact to unwrap and rewrap the share
     let encrypted_share_for_contact = &encrypted_shares[0].1;
@@ -386,6 +366,369 @@ async fn backup_scenario() {
     );
 }
 
+#[tokio::test]
+async fn k_of_n_backup_scenario() {
+    pretty_env_logger::try_init().unwrap();
+
+    let stack = CoverDropStack::builder(StackProfile::CoverDropOnly)
+        .with_default_journalist_id("generated_test_desk")
+        .build()
+        .await;
+
+    let backup = create_k_of_n_backup(&stack).await;
+
+    let (in_progress_bundle_path, encrypted_shares) =
+        admin_initiate_restore(&stack, &backup.journalist_identity).await;
+    assert_eq!(encrypted_shares.len(), 3);
+
+    let wrapped_shares = backup
+        .recovery_contacts
+        .iter()
+        .take(2)
+        .map(|(identity, msg_key_pair)| {
+            let (_, encrypted_share) = encrypted_shares
+                .iter()
+                .find(|(recipient, _)| recipient == identity)
+                .expect("Share for recovery contact");
+
+            sentinel_restore_try_unwrap_and_wrap_share_step(
+                encrypted_share.clone(),
+                vec![msg_key_pair.clone()],
+                backup.backup_encryption_key.public_key().clone(),
+            )
+            .expect("Failed to unwrap share")
+            .expect("No share could be unwrapped")
+        })
+        .collect::<Vec<_>>();
+
+    // A single recovery contact is not enough to restore a 2-of-3 backup
+    let restore_with_one_share = admin::backup_complete_restore(
+        &in_progress_bundle_path,
+        &backup.recovery_dir,
+        stack.keys_path(),
+        wrapped_shares[..1].to_vec(),
+        stack.now(),
+    )
+    .await;
+    assert!(
+        restore_with_one_share.is_err(),
+        "Restore should fail with fewer than k shares"
+    );
+
+    let restored_vault_path = admin::backup_complete_restore(
+        &in_progress_bundle_path,
+        &backup.recovery_dir,
+        stack.keys_path(),
+        wrapped_shares,
+        stack.now(),
+    )
+    .await
+    .expect("Admin CLI complete restore");
+
+    assert_restored_vault_opens(&stack, restored_vault_path, &backup.journalist_identity).await;
+}
+
+#[tokio::test]
+async fn backup_restore_after_recovery_contact_left_organisation() {
+    pretty_env_logger::try_init().unwrap();
+
+    let stack = CoverDropStack::builder(StackProfile::CoverDropOnly)
+        .with_default_journalist_id("generated_test_desk")
+        .build()
+        .await;
+
+    let backup = create_k_of_n_backup(&stack).await;
+
+    // The third recovery contact leaves the organisation after the backup was taken
+    let (departed_contact, _) = &backup.recovery_contacts[2];
+    let delete_form_path = admin::delete_journalist_form(
+        stack.keys_path(),
+        departed_contact,
+        stack.temp_dir_path(),
+        stack.now(),
+    )
+    .await
+    .expect("Create journalist deletion form");
+
+    admin::submit_delete_journalist_form(stack.api_client_uncached(), delete_form_path)
+        .await
+        .expect("Delete journalist submission");
+
+    let public_keys = get_and_verify_public_keys(
+        stack.api_client_uncached(),
+        &stack.keys().anchor_org_pks(),
+        stack.now(),
+    )
+    .await;
+    assert!(public_keys
+        .keys
+        .latest_journalist_msg_pk(departed_contact)
+        .is_none());
+
+    // The remaining two recovery contacts are enough to restore the vault
+    let (in_progress_bundle_path, encrypted_shares) =
+        admin_initiate_restore(&stack, &backup.journalist_identity).await;
+
+    let wrapped_shares = encrypted_shares
+        .iter()
+        .filter_map(|(recipient, encrypted_share)| {
+            let (_, msg_key_pair) = backup
+                .recovery_contacts
+                .iter()
+                .find(|(identity, _)| identity == recipient && identity != departed_contact)?;
+
+            sentinel_restore_try_unwrap_and_wrap_share_step(
+                encrypted_share.clone(),
+                vec![msg_key_pair.clone()],
+                backup.backup_encryption_key.public_key().clone(),
+            )
+            .expect("Failed to unwrap share")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(wrapped_shares.len(), 2);
+
+    let restored_vault_path = admin::backup_complete_restore(
+        &in_progress_bundle_path,
+        &backup.recovery_dir,
+        stack.keys_path(),
+        wrapped_shares,
+        stack.now(),
+    )
+    .await
+    .expect("Admin CLI complete restore");
+
+    assert_restored_vault_opens(&stack, restored_vault_path, &backup.journalist_identity).await;
+}
+
+struct KOfNBackup {
+    journalist_identity: JournalistIdentity,
+    backup_encryption_key: SignedEncryptionKeyPair<BackupMsg>,
+    recovery_contacts: Vec<(
+        JournalistIdentity,
+        SignedEncryptionKeyPair<JournalistMessaging>,
+    )>,
+    recovery_dir: PathBuf,
+}
+
+/// Publish 2-of-3 secret sharing settings using the admin CLI, then create and upload a backup
+/// of the default journalist's vault shared between three newly created recovery contacts.
+async fn create_k_of_n_backup(stack: &CoverDropStack) -> KOfNBackup {
+    let recovery_dir = stack.temp_dir_path().join("backup_recovery");
+    fs::create_dir_all(&recovery_dir).expect("Create backup recovery dir");
+
+    let backup_signing_key = create_test_backup_id_key_pair(stack);
+    backup_signing_key
+        .to_untrusted()
+        .save_to_disk(stack.keys_path())
+        .unwrap();
+
+    let post_backup_signing_pk = PostBackupIdKeyForm::new(
+        backup_signing_key.public_key().to_untrusted(),
+        &stack.keys().org_key_pair,
+        stack.now(),
+    )
+    .expect("Create PostBackupIdKeyForm");
+
+    stack
+        .api_client_uncached()
+        .post_backup_signing_pk(post_backup_signing_pk)
+        .await
+        .expect("Upload backup signing key");
+
+    let backup_encryption_key = create_test_backup_msg_key_pair(stack, backup_signing_key.clone());
+    backup_encryption_key
+        .to_untrusted()
+        .save_to_disk(stack.keys_path())
+        .unwrap();
+
+    let post_backup_encryption_pk = PostBackupMsgKeyForm::new(
+        backup_encryption_key.public_key().to_untrusted(),
+        &backup_signing_key,
+        stack.now(),
+    )
+    .expect("Create PostBackupMsgKeyForm");
+
+    stack
+        .api_client_uncached()
+        .post_backup_encryption_pk(post_backup_encryption_pk)
+        .await
+        .expect("Upload backup encryption key");
+
+    // Sign the settings offline and post them from an online machine
+    let settings_path =
+        admin::sign_backup_secret_sharing_settings(stack.keys_path(), 2, 3, stack.now())
+            .expect("Sign secret sharing settings");
+    admin::post_backup_secret_sharing_settings(
+        stack.api_client_uncached().base_url.clone(),
+        &settings_path,
+    )
+    .await
+    .expect("Post secret sharing settings");
+
+    let public_keys = get_and_verify_public_keys(
+        stack.api_client_uncached(),
+        &stack.keys().anchor_org_pks(),
+        stack.now(),
+    )
+    .await;
+
+    let settings = public_keys.secret_sharing_settings();
+    assert_eq!(settings, SecretSharingSettings::new(2, 3).unwrap());
+
+    let mut recovery_contacts = Vec::new();
+    for display_name in [
+        "Recovery Contact One",
+        "Recovery Contact Two",
+        "Recovery Contact Three",
+    ] {
+        generate_test_journalist(
+            stack.api_client_cached(),
+            stack.keys_path(),
+            stack.temp_dir_path(),
+            stack.now(),
+            stack.trust_anchors(),
+            Some(display_name.into()),
+        )
+        .await;
+
+        let vault_path = stack.temp_dir_path().join(format!(
+            "{}.vault",
+            display_name.to_lowercase().replace(' ', "_")
+        ));
+
+        let vault = JournalistVault::open(&vault_path, MAILBOX_PASSWORD, stack.trust_anchors())
+            .await
+            .expect("Load journalist vault");
+
+        let msg_key_pair = vault
+            .latest_msg_key_pair(stack.now())
+            .await
+            .unwrap()
+            .unwrap();
+
+        recovery_contacts.push((vault.journalist_id().await.unwrap(), msg_key_pair));
+    }
+
+    let journalist_vault = stack.load_static_journalist_vault().await;
+    let journalist_identity = journalist_vault.journalist_id().await.unwrap();
+    let journalist_signing_pair = journalist_vault
+        .latest_id_key_pair(stack.now())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let verified_backup_data = sentinel_create_backup(
+        stack.load_static_journalist_vault_bytes().await,
+        journalist_identity.clone(),
+        journalist_signing_pair.clone(),
+        public_keys.keys.latest_backup_msg_pk().unwrap(),
+        recovery_contacts
+            .iter()
+            .map(|(identity, msg_key_pair)| RecoveryContact {
+                identity: identity.clone(),
+                latest_messaging_key: msg_key_pair.public_key().clone(),
+            })
+            .collect(),
+        settings.k,
+        stack.now(),
+    )
+    .expect("Failed to create backup");
+
+    sentinel_put_backup_data_to_s3(
+        stack.api_client_uncached(),
+        &journalist_signing_pair,
+        verified_backup_data,
+        stack.now(),
+    )
+    .await
+    .expect("Failed to post backup data to s3");
+
+    KOfNBackup {
+        journalist_identity,
+        backup_encryption_key,
+        recovery_contacts,
+        recovery_dir,
+    }
+}
+
+/// Run the first two steps of the admin CLI restore and return the in-progress bundle path and
+/// the encrypted share for each recovery contact.
+async fn admin_initiate_restore(
+    stack: &CoverDropStack,
+    journalist_identity: &JournalistIdentity,
+) -> (PathBuf, Vec<(JournalistIdentity, EncryptedSecretShare)>) {
+    let recovery_dir = stack.temp_dir_path().join("backup_recovery");
+
+    let response_bundle_path = admin::backup_initiate_restore(
+        stack.api_client_uncached().base_url.clone(),
+        stack.s3_client(),
+        &Development,
+        &recovery_dir,
+        journalist_identity,
+    )
+    .await
+    .expect("Admin CLI initiate restore submit");
+
+    let (in_progress_bundle_path, wrapped_shares_paths) = admin::backup_initiate_restore_finalize(
+        &response_bundle_path,
+        stack.keys_path().to_path_buf(),
+        &recovery_dir,
+        stack.now(),
+    )
+    .await
+    .expect("Admin CLI initiate restore finalize");
+
+    (
+        in_progress_bundle_path,
+        load_encrypted_shares(&wrapped_shares_paths),
+    )
+}
+
+/// Load the wrapped shares from disk and extract recipient identity from filename
+fn load_encrypted_shares(paths: &[PathBuf]) -> Vec<(JournalistIdentity, EncryptedSecretShare)> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let share_base64 = fs::read_to_string(path).expect("Read wrapped share file from disk");
+            let encrypted_share = EncryptedSecretShare::from_base64_string(&share_base64).ok()?;
+
+            // Extract journalist ID from filename (format: restore-{timestamp}-share-{num}-{recipient-id}.recovery-share.txt)
+            let filename = path.file_name()?.to_str()?;
+
+            // Split from right to extract recipient-id before .recovery-share.txt extension
+            let journalist_id_str = filename
+                .strip_suffix(".recovery-share.txt")?
+                .rsplit('-')
+                .next()?;
+
+            let journalist_id = JournalistIdentity::new(journalist_id_str).ok()?;
+
+            Some((journalist_id, encrypted_share))
+        })
+        .collect()
+}
+
+async fn assert_restored_vault_opens(
+    stack: &CoverDropStack,
+    restored_vault_path: PathBuf,
+    journalist_identity: &JournalistIdentity,
+) {
+    let restored_vault_bytes =
+        fs::read(restored_vault_path).expect("Load restored journalist vault");
+
+    // Replace the vault file with the restored vault to verify it can be opened
+    stack
+        .save_static_journalist_vault_bytes(restored_vault_bytes)
+        .await;
+
+    let restored_vault = stack.load_static_journalist_vault().await;
+
+    assert_eq!(
+        &restored_vault.journalist_id().await.unwrap(),
+        journalist_identity
+    );
+}
+
 async fn create_recovery_contact_vault_and_return_messaging_keys(
     stack: &CoverDropStack,
 ) -> (
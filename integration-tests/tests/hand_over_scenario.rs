@@ -0,0 +1,215 @@
+use client::commands::{
+    journalist::{
+        dead_drops::load_journalist_dead_drop_messages,
+        messages::{send_journalist_to_user_cover_message, send_journalist_to_user_hand_over},
+    },
+    user::{
+        dead_drops::load_user_dead_drop_messages, messages::send_user_to_journalist_real_message,
+    },
+};
+use integration_tests::{
+    api_wrappers::{get_and_verify_public_keys, get_journalist_dead_drops, get_user_dead_drops},
+    dev_j2u_mixing_config, dev_u2j_mixing_config,
+    stack::{CoverDropStack, StackProfile},
+    utils::send_user_to_journalist_cover_messages,
+};
+use journalist_vault::VaultMessage;
+
+static USER_MESSAGE: &str = "This is a test message from the user to the first journalist";
+static USER_MESSAGE_AFTER_HAND_OVER: &str =
+    "This is a test message from the user to the journalist they were handed over to";
+
+/// Tests a journalist handing a conversation over to another journalist.
+///
+/// The user messages the first journalist, who hands the conversation over to a second
+/// journalist. After pulling their dead drops the user's default recipient is the second
+/// journalist, and a message sent to the default recipient arrives in the second journalist's vault.
+#[tokio::test]
+#[allow(clippy::await_holding_refcell_ref)]
+async fn hand_over_scenario() {
+    pretty_env_logger::try_init().unwrap();
+
+    let stack = CoverDropStack::builder(StackProfile::CoverDropOnly)
+        .with_additional_journalists(1)
+        .build()
+        .await;
+
+    let anchor_org_pks = stack.keys().anchor_org_pks();
+
+    let keys_and_profiles =
+        get_and_verify_public_keys(stack.api_client_cached(), &anchor_org_pks, stack.now()).await;
+
+    let journalist_1_id = stack
+        .load_static_journalist_vault()
+        .await
+        .journalist_id()
+        .await
+        .unwrap();
+
+    let journalist_2_id = stack
+        .load_additional_journalist_vault(1)
+        .await
+        .journalist_id()
+        .await
+        .unwrap();
+
+    //
+    // User sends a message to the first journalist
+    //
+
+    {
+        let mut user_mailbox = stack.mailboxes().user();
+
+        send_user_to_journalist_real_message(
+            stack.messaging_client(),
+            &mut user_mailbox,
+            &keys_and_profiles.keys,
+            &journalist_1_id,
+            USER_MESSAGE,
+        )
+        .await
+        .expect("Send user real message");
+
+        assert_eq!(user_mailbox.default_recipient(), Some(&journalist_1_id));
+
+        send_user_to_journalist_cover_messages(
+            stack.messaging_client(),
+            &keys_and_profiles.keys,
+            dev_u2j_mixing_config().threshold_max - 1,
+        )
+        .await;
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+
+    //
+    // First journalist reads the message and hands the conversation over
+    //
+
+    {
+        let journalist_vault = stack.load_static_journalist_vault().await;
+
+        let dead_drop_list = get_journalist_dead_drops(
+            stack.api_client_cached(),
+            journalist_vault.max_dead_drop_id().await.unwrap(),
+        )
+        .await;
+
+        load_journalist_dead_drop_messages(
+            dead_drop_list,
+            &keys_and_profiles.keys,
+            &journalist_vault,
+            stack.now(),
+        )
+        .await
+        .expect("Save journalist's messages to vault");
+
+        let user_pk = match &journalist_vault.messages().await.unwrap()[0] {
+            VaultMessage::U2J(m) => m.user_pk.clone(),
+            _ => panic!("Expected U2J message"),
+        };
+
+        send_journalist_to_user_hand_over(
+            stack.kinesis_client(),
+            &keys_and_profiles.keys,
+            &journalist_vault,
+            &user_pk,
+            &journalist_2_id,
+            stack.now(),
+        )
+        .await
+        .expect("Send hand over");
+
+        let messages = journalist_vault.messages().await.unwrap();
+        assert_eq!(messages.len(), 2);
+        match &messages[1] {
+            VaultMessage::J2U(m) => assert_eq!(m.hand_over_to.as_ref(), Some(&journalist_2_id)),
+            _ => panic!("Expected J2U message"),
+        }
+
+        for _ in 0..(dev_j2u_mixing_config().threshold_max - 1) {
+            send_journalist_to_user_cover_message(stack.kinesis_client(), &keys_and_profiles.keys)
+                .await
+                .expect("Send journalist cover message")
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+
+    //
+    // User reads the hand over and messages their new default recipient
+    //
+
+    {
+        let mut user_mailbox = stack.mailboxes().user();
+
+        let dead_drop_list =
+            get_user_dead_drops(stack.api_client_cached(), user_mailbox.max_dead_drop_id()).await;
+
+        load_user_dead_drop_messages(
+            &dead_drop_list,
+            &keys_and_profiles.keys,
+            &mut user_mailbox,
+            stack.now(),
+        )
+        .expect("Save users's messages to mailbox");
+
+        assert_eq!(user_mailbox.default_recipient(), Some(&journalist_2_id));
+
+        let default_recipient = user_mailbox.default_recipient().cloned().unwrap();
+
+        send_user_to_journalist_real_message(
+            stack.messaging_client(),
+            &mut user_mailbox,
+            &keys_and_profiles.keys,
+            &default_recipient,
+            USER_MESSAGE_AFTER_HAND_OVER,
+        )
+        .await
+        .expect("Send user real message");
+
+        send_user_to_journalist_cover_messages(
+            stack.messaging_client(),
+            &keys_and_profiles.keys,
+            dev_u2j_mixing_config().threshold_max - 1,
+        )
+        .await;
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+
+    //
+    // Second journalist receives the message
+    //
+
+    {
+        let journalist_vault = stack.load_additional_journalist_vault(1).await;
+
+        let dead_drop_list = get_journalist_dead_drops(
+            stack.api_client_cached(),
+            journalist_vault.max_dead_drop_id().await.unwrap(),
+        )
+        .await;
+
+        load_journalist_dead_drop_messages(
+            dead_drop_list,
+            &keys_and_profiles.keys,
+            &journalist_vault,
+            stack.now(),
+        )
+        .await
+        .expect("Save journalist's messages to vault");
+
+        let messages = journalist_vault.messages().await.unwrap();
+        assert_eq!(messages.len(), 1);
+
+        let message = match &messages[0] {
+            VaultMessage::U2J(m) => m.message.clone(),
+            _ => panic!("Expected U2J message"),
+        };
+
+        assert_eq!(&message, USER_MESSAGE_AFTER_HAND_OVER);
+    }
+
+    assert!(!stack.do_secrets_exist_in_stack().await);
+}
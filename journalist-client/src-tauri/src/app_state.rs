@@ -1,8 +1,10 @@
 use common::{
-    api::api_client::ApiClient,
+    api::{api_client::ApiClient, models::journalist_id::JournalistIdentity},
     clap::Stage,
     client::VerifiedKeysAndJournalistProfiles,
+    crypto::keys::encryption::PublicEncryptionKey,
     generators::NameGenerator,
+    protocol::roles::SupportRecipient,
     task::{RunnerMode, TaskRunner},
     time,
 };
@@ -12,16 +14,15 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use tauri::AppHandle;
 use tokio::{
-    sync::{RwLock, RwLockReadGuard},
+    sync::{OwnedRwLockReadGuard, RwLock, RwLockReadGuard},
     task::JoinHandle,
 };
 use trust_anchors::get_trust_anchors;
 
 use crate::{
     logging::LogReceiver,
-    model::VaultState,
+    model::{EventSink, VaultState},
     notifications::Notifications,
     tasks::{
         AutomatedBackups, BackupManager, CleanUpVault, PullDeadDrops, RefreshPublicInfo,
@@ -30,23 +31,52 @@ use crate::{
 };
 use coverdrop_service::JournalistCoverDropService;
 
+/// A vault which has been unlocked, along with the services and background tasks using it.
+struct OpenVault {
+    journalist_id: JournalistIdentity,
+    /// Path to vault
+    path: PathBuf,
+    /// Handle to the background task runner for this vault, aborted when the vault is closed
+    runner_join_handle: Option<JoinHandle<()>>,
+    /// The users vault - unlocked
+    vault: JournalistVault,
+    api_client: ApiClient,
+    is_soft_locked: bool,
+    coverdrop_service: Arc<JournalistCoverDropService>,
+    public_info: PublicInfo,
+    /// The support key from the profile this vault was opened with, if there is one
+    support_pk: Option<PublicEncryptionKey<SupportRecipient>>,
+}
+
+impl OpenVault {
+    fn state(&self) -> VaultState {
+        VaultState {
+            id: self.journalist_id.to_string(),
+            path: self.path.clone(),
+            is_soft_locked: self.is_soft_locked,
+        }
+    }
+}
+
 // Controls the state of the app.
 #[derive(Default)]
-pub enum AppState {
-    #[default]
-    LoggedOut,
-    /// User has unlocked a vault, selected their API, etc.
-    LoggedIn {
-        /// Path to vault
-        path: PathBuf,
-        /// Handle to background task runner, useful for aborting maybe?
-        _runner_join_handle: Option<JoinHandle<()>>,
-        /// The users vault - unlocked
-        vault: JournalistVault,
-        api_client: ApiClient,
-        is_soft_locked: bool,
-        coverdrop_service: Arc<JournalistCoverDropService>,
-    },
+pub struct AppState {
+    /// Every unlocked vault, in the order they were opened
+    vaults: Vec<OpenVault>,
+    /// The vault shown in the frontend. Commands which act on a single vault use this one.
+    active: Option<JournalistIdentity>,
+}
+
+impl AppState {
+    fn active(&self) -> Option<&OpenVault> {
+        self.open_vault(self.active.as_ref()?)
+    }
+
+    fn open_vault(&self, journalist_id: &JournalistIdentity) -> Option<&OpenVault> {
+        self.vaults
+            .iter()
+            .find(|v| &v.journalist_id == journalist_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -61,41 +91,42 @@ impl PublicInfo {
     pub async fn get(&self) -> RwLockReadGuard<'_, Option<VerifiedKeysAndJournalistProfiles>> {
         self.0.read().await
     }
+
+    pub async fn get_owned(
+        self,
+    ) -> OwnedRwLockReadGuard<Option<VerifiedKeysAndJournalistProfiles>> {
+        self.0.read_owned().await
+    }
 }
 
 pub struct AppStateHandle {
-    pub app_handle: AppHandle,
+    pub events: EventSink,
     pub name_generator: NameGenerator,
-    public_info: PublicInfo,
     pub notifications: Notifications,
     inner: RwLock<AppState>,
     pub logs: LogReceiver,
     no_background_tasks: bool,
-    pub backup_manager: BackupManager,
 }
 
 impl AppStateHandle {
-    pub fn new(
-        app_handle: AppHandle,
-        notifications: Notifications,
-        no_background_tasks: bool,
-    ) -> Self {
+    pub fn new(events: EventSink, notifications: Notifications, no_background_tasks: bool) -> Self {
         Self {
-            app_handle,
+            events,
             name_generator: NameGenerator::default(),
-            public_info: PublicInfo::default(),
             inner: RwLock::new(AppState::default()),
             notifications,
             logs: LogReceiver::default(),
             no_background_tasks,
-            backup_manager: BackupManager::new(),
         }
     }
 
+    /// Unlock a vault and start its background tasks. The vault becomes the active vault,
+    /// any vaults which were already open stay open and keep running their own tasks.
     pub async fn unlock_vault(
         &self,
         stage: Stage,
         api_url: &Url,
+        support_pk: Option<PublicEncryptionKey<SupportRecipient>>,
         path: impl AsRef<Path>,
         password: &str,
     ) -> anyhow::Result<(JournalistVault, ApiClient)> {
@@ -108,6 +139,19 @@ impl AppStateHandle {
 
         tracing::debug!("Vault successfully opened!");
 
+        let journalist_id = vault.journalist_id().await?;
+
+        if self
+            .inner
+            .read()
+            .await
+            .vaults
+            .iter()
+            .any(|v| v.journalist_id == journalist_id)
+        {
+            anyhow::bail!("The vault for {journalist_id} is already open");
+        }
+
         let api_client = ApiClient::new(api_url.clone());
 
         tracing::debug!("Processing setup bundle");
@@ -116,46 +160,55 @@ impl AppStateHandle {
 
         let coverdrop_service = Arc::new(service);
 
+        // Each vault has its own copy of the public info since vaults can be for different stages
+        let public_info = PublicInfo::default();
+        // Failed backups are counted per vault so one vault's successful backup doesn't hide
+        // another vault's failures
+        let backup_manager = BackupManager::new(&journalist_id);
+
         let runner_join_handle = if self.no_background_tasks {
             tracing::info!("Background tasks disabled via --no-background-tasks flag");
             None
         } else {
-            tracing::debug!("Starting background tasks");
+            tracing::debug!("Starting background tasks for {}", journalist_id);
             Some(tokio::task::spawn({
                 // We don't ever want this to run a web server so it should only ever
                 // be RunnerMode::Timer
                 let mut runner = TaskRunner::new(RunnerMode::Timer);
 
                 let refresh_public_info_task =
-                    RefreshPublicInfo::new(&api_client, &vault, &self.public_info);
+                    RefreshPublicInfo::new(&api_client, &vault, &public_info);
                 let sync_public_keys_task =
-                    SyncJournalistProvisioningPublicKeys::new(&vault, &self.public_info);
+                    SyncJournalistProvisioningPublicKeys::new(&vault, &public_info);
                 let pull_dead_drops_task = PullDeadDrops::new(
-                    &self.app_handle,
+                    &self.events,
+                    &journalist_id,
                     &coverdrop_service,
                     &self.notifications,
-                    &self.public_info,
+                    &public_info,
                 );
                 let send_journalist_messages_task = SendJournalistMessages::new(
+                    &journalist_id,
                     &coverdrop_service,
-                    &self.public_info,
-                    &self.app_handle,
+                    &public_info,
+                    &self.events,
                 );
                 let rotate_keys_task = RotateJournalistKeys::new(
-                    &self.backup_manager,
-                    &self.app_handle,
+                    &backup_manager,
+                    &self.events,
+                    &journalist_id,
                     &api_client,
                     &vault,
                     &path,
-                    &self.public_info,
+                    &public_info,
                 );
                 let automated_backups_task = AutomatedBackups::new(
-                    &self.backup_manager,
-                    &self.app_handle,
+                    &backup_manager,
+                    &self.events,
                     &api_client,
                     &vault,
                     &path,
-                    &self.public_info,
+                    &public_info,
                 );
                 let clean_up_vault_task = CleanUpVault::new(&vault);
 
@@ -178,112 +231,241 @@ impl AppStateHandle {
             }))
         };
 
-        self.logs.use_vault(&vault, time::now()).await?;
-
         let mut guard = self.inner.write().await;
 
-        *guard = AppState::LoggedIn {
+        // Logs are written to the first of the open vaults
+        if guard.vaults.is_empty() {
+            self.logs.use_vault(&vault, time::now()).await?;
+        }
+
+        guard.vaults.push(OpenVault {
+            journalist_id: journalist_id.clone(),
             path,
-            _runner_join_handle: runner_join_handle,
+            runner_join_handle,
             vault: vault.clone(),
             api_client: api_client.clone(),
             is_soft_locked: false,
             coverdrop_service: coverdrop_service.clone(),
-        };
+            public_info,
+            support_pk,
+        });
+        guard.active = Some(journalist_id);
 
         Ok((vault, api_client))
     }
 
-    pub async fn vault_state(&self) -> anyhow::Result<Option<VaultState>> {
-        let guard = self.inner.read().await;
+    /// Stop a vault's background tasks and remove it from the app. If it was the active vault
+    /// then the most recently opened of the remaining vaults becomes active.
+    pub async fn close_vault(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> anyhow::Result<Option<VaultState>> {
+        let mut guard = self.inner.write().await;
 
-        if let AppState::LoggedIn {
-            vault,
-            path,
-            is_soft_locked,
-            ..
-        } = &*guard
+        let Some(index) = guard
+            .vaults
+            .iter()
+            .position(|v| &v.journalist_id == journalist_id)
+        else {
+            anyhow::bail!("No open vault for {journalist_id}");
+        };
+
+        let closed = guard.vaults.remove(index);
+        if let Some(runner_join_handle) = closed.runner_join_handle {
+            runner_join_handle.abort();
+        }
+
+        // Logs are written to the first vault opened, so move them to the next oldest vault
+        if index == 0 {
+            let next_vault = guard.vaults.first().map(|v| &v.vault);
+            self.logs.replace_vault(next_vault, time::now()).await?;
+        }
+
+        if guard.active.as_ref() == Some(journalist_id) {
+            let most_recently_opened = guard.vaults.last().map(|v| v.journalist_id.clone());
+            guard.active = most_recently_opened;
+        }
+
+        Ok(guard.active().map(OpenVault::state))
+    }
+
+    /// Make another open vault the one shown in the frontend
+    pub async fn switch_vault(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> anyhow::Result<Option<VaultState>> {
+        let mut guard = self.inner.write().await;
+
+        if !guard
+            .vaults
+            .iter()
+            .any(|v| &v.journalist_id == journalist_id)
         {
-            Ok(Some(VaultState {
-                id: vault.journalist_id().await?.to_string(),
-                path: path.clone(),
-                is_soft_locked: *is_soft_locked,
-            }))
-        } else {
-            Ok(None)
+            anyhow::bail!("No open vault for {journalist_id}");
         }
+
+        guard.active = Some(journalist_id.clone());
+
+        Ok(guard.active().map(OpenVault::state))
     }
 
+    /// The state of the active vault
+    pub async fn vault_state(&self) -> anyhow::Result<Option<VaultState>> {
+        let guard = self.inner.read().await;
+
+        Ok(guard.active().map(OpenVault::state))
+    }
+
+    /// The state of a journalist's vault, `None` if it isn't open
+    pub async fn vault_state_for(&self, journalist_id: &JournalistIdentity) -> Option<VaultState> {
+        let guard = self.inner.read().await;
+
+        guard.open_vault(journalist_id).map(OpenVault::state)
+    }
+
+    /// The state of every open vault, in the order they were opened
+    pub async fn open_vault_states(&self) -> Vec<VaultState> {
+        let guard = self.inner.read().await;
+
+        guard.vaults.iter().map(OpenVault::state).collect()
+    }
+
+    /// The journalist whose vault is active
+    pub async fn journalist_id(&self) -> Option<JournalistIdentity> {
+        let guard = self.inner.read().await;
+
+        guard.active().map(|v| v.journalist_id.clone())
+    }
+
+    /// The active vault
     pub async fn vault(&self) -> Option<JournalistVault> {
         let guard = self.inner.read().await;
 
-        match &*guard {
-            AppState::LoggedOut => None,
-            AppState::LoggedIn { vault, .. } => Some(vault.clone()),
-        }
+        guard.active().map(|v| v.vault.clone())
+    }
+
+    /// A journalist's vault, `None` if it isn't open
+    pub async fn vault_for(&self, journalist_id: &JournalistIdentity) -> Option<JournalistVault> {
+        let guard = self.inner.read().await;
+
+        guard.open_vault(journalist_id).map(|v| v.vault.clone())
+    }
+
+    /// Every open vault which isn't soft locked, along with the journalist it belongs to
+    pub async fn unlocked_vaults(&self) -> Vec<(JournalistIdentity, JournalistVault)> {
+        let guard = self.inner.read().await;
+
+        guard
+            .vaults
+            .iter()
+            .filter(|v| !v.is_soft_locked)
+            .map(|v| (v.journalist_id.clone(), v.vault.clone()))
+            .collect()
     }
 
+    /// Soft lock every open vault, each has to be unlocked again before it can be used
     pub async fn soft_lock_vault(&self) -> anyhow::Result<Option<VaultState>> {
         let mut guard = self.inner.write().await;
 
-        match &mut *guard {
-            AppState::LoggedIn { is_soft_locked, .. } => {
-                *is_soft_locked = true;
-            }
-            AppState::LoggedOut => anyhow::bail!("Not logged in"),
+        if guard.vaults.is_empty() {
+            anyhow::bail!("Not logged in");
+        }
+
+        for open_vault in guard.vaults.iter_mut() {
+            open_vault.is_soft_locked = true;
         }
 
-        drop(guard);
-        self.vault_state().await
+        Ok(guard.active().map(OpenVault::state))
     }
 
+    /// Unlock every soft locked vault which uses the given password. Journalists might use a
+    /// different password for each vault so some may remain locked.
     pub async fn unlock_soft_locked_vault(
         &self,
         password: &str,
     ) -> anyhow::Result<Option<VaultState>> {
         let mut guard = self.inner.write().await;
 
-        match &mut *guard {
-            AppState::LoggedIn {
-                path,
-                is_soft_locked,
-                vault,
-                ..
-            } => {
-                if vault.check_password(path, password).await {
-                    *is_soft_locked = false;
-                }
+        if guard.vaults.is_empty() {
+            anyhow::bail!("Not logged in");
+        }
+
+        for open_vault in guard.vaults.iter_mut() {
+            if open_vault.is_soft_locked
+                && open_vault
+                    .vault
+                    .check_password(&open_vault.path, password)
+                    .await
+            {
+                open_vault.is_soft_locked = false;
             }
-            AppState::LoggedOut => anyhow::bail!("Not logged in"),
         }
 
-        drop(guard);
-        self.vault_state().await
+        Ok(guard.active().map(OpenVault::state))
     }
 
+    /// The API client used by the active vault
     pub async fn api_client(&self) -> Option<ApiClient> {
         let guard = self.inner.read().await;
 
-        match &*guard {
-            AppState::LoggedOut => None,
-            AppState::LoggedIn { api_client, .. } => Some(api_client.clone()),
-        }
+        guard.active().map(|v| v.api_client.clone())
     }
 
+    /// The public info last fetched by the active vault, `None` if no vault is open or the
+    /// active vault hasn't fetched it yet.
     pub async fn public_info(
         &self,
-    ) -> RwLockReadGuard<'_, Option<VerifiedKeysAndJournalistProfiles>> {
-        self.public_info.0.read().await
+    ) -> OwnedRwLockReadGuard<Option<VerifiedKeysAndJournalistProfiles>> {
+        let public_info = {
+            let guard = self.inner.read().await;
+            guard
+                .active()
+                .map(|v| v.public_info.clone())
+                .unwrap_or_default()
+        };
+
+        public_info.get_owned().await
+    }
+
+    /// The public info last fetched by a journalist's vault, `None` if the vault isn't open or
+    /// hasn't fetched it yet.
+    pub async fn public_info_for(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> OwnedRwLockReadGuard<Option<VerifiedKeysAndJournalistProfiles>> {
+        let public_info = {
+            let guard = self.inner.read().await;
+            guard
+                .open_vault(journalist_id)
+                .map(|v| v.public_info.clone())
+                .unwrap_or_default()
+        };
+
+        public_info.get_owned().await
     }
 
+    /// The CoverDrop service for the active vault
     pub async fn coverdrop_service(&self) -> Option<Arc<JournalistCoverDropService>> {
         let guard = self.inner.read().await;
 
-        match &*guard {
-            AppState::LoggedOut => None,
-            AppState::LoggedIn {
-                coverdrop_service, ..
-            } => Some(coverdrop_service.clone()),
-        }
+        guard.active().map(|v| v.coverdrop_service.clone())
+    }
+
+    /// The CoverDrop service for a journalist's vault
+    pub async fn coverdrop_service_for(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> Option<Arc<JournalistCoverDropService>> {
+        let guard = self.inner.read().await;
+
+        guard
+            .open_vault(journalist_id)
+            .map(|v| v.coverdrop_service.clone())
+    }
+
+    pub async fn support_pk(&self) -> Option<PublicEncryptionKey<SupportRecipient>> {
+        let guard = self.inner.read().await;
+
+        guard.active().and_then(|v| v.support_pk.clone())
     }
 }
@@ -0,0 +1,10 @@
+#[cfg(unix)]
+fn main() {
+    journalist_client_lib::run_headless()
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("sentinel-daemon is only supported on Unix");
+    std::process::exit(1);
+}
@@ -1,28 +1,47 @@
 use crate::{
     app_state::AppStateHandle,
     error::{
-        AnyhowSnafu, ApiClientUnavailableSnafu, CommandError, GenericSnafu, JsonSerializeSnafu,
-        VaultLockedSnafu, VaultSnafu,
+        AnyhowSnafu, ApiClientUnavailableSnafu, CommandError, GenericSnafu, IoSnafu,
+        JsonSerializeSnafu, VaultLockedSnafu, VaultSnafu,
     },
     launch_tauri_instance,
     model::TrustedOrganizationPublicKeyAndDigest,
 };
 use chrono::{DateTime, Utc};
 use common::{
-    api::models::untrusted_keys_and_journalist_profiles::UntrustedKeysAndJournalistProfiles,
+    api::models::{
+        journalist_id::JournalistIdentity,
+        untrusted_keys_and_journalist_profiles::UntrustedKeysAndJournalistProfiles,
+    },
     client::JournalistStatus,
     crypto::{human_readable_digest, keys::public_key::PublicKey as _},
+    protocol::log_export::LogScrubber,
     time,
 };
-use journalist_vault::logging::{LogEntry, LoggingSession};
+use journalist_vault::{
+    logging::{LogEntry, LoggingSession},
+    IntegrityReport,
+};
 use snafu::{OptionExt as _, ResultExt as _};
+use std::path::PathBuf;
 use tauri::State;
 
 #[tauri::command]
 pub async fn get_trust_anchor_digests(
     app: State<'_, AppStateHandle>,
 ) -> Result<Vec<TrustedOrganizationPublicKeyAndDigest>, CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_trust_anchor_digests_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_trust_anchor_digests_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<TrustedOrganizationPublicKeyAndDigest>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     let digests = vault
         .org_pks()
@@ -43,9 +62,16 @@ pub async fn get_trust_anchor_digests(
 
 #[tauri::command]
 pub async fn force_rotate_id_pk(app: State<'_, AppStateHandle>) -> Result<(), CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    force_rotate_id_pk_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn force_rotate_id_pk_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<(), CommandError> {
     let coverdrop_service = app
-        .inner()
-        .coverdrop_service()
+        .coverdrop_service_for(journalist_id)
         .await
         .context(VaultLockedSnafu)?;
 
@@ -61,9 +87,16 @@ pub async fn force_rotate_id_pk(app: State<'_, AppStateHandle>) -> Result<(), Co
 
 #[tauri::command]
 pub async fn force_rotate_msg_pk(app: State<'_, AppStateHandle>) -> Result<(), CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    force_rotate_msg_pk_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn force_rotate_msg_pk_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<(), CommandError> {
     let coverdrop_service = app
-        .inner()
-        .coverdrop_service()
+        .coverdrop_service_for(journalist_id)
         .await
         .context(VaultLockedSnafu)?;
 
@@ -81,7 +114,15 @@ pub async fn force_rotate_msg_pk(app: State<'_, AppStateHandle>) -> Result<(), C
 pub async fn get_public_info(
     app: State<'_, AppStateHandle>,
 ) -> Result<Option<UntrustedKeysAndJournalistProfiles>, CommandError> {
-    let public_info = app.public_info().await;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_public_info_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_public_info_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Option<UntrustedKeysAndJournalistProfiles>, CommandError> {
+    let public_info = app.public_info_for(journalist_id).await;
     let public_info = public_info.as_ref();
     // public_info may be None if the initial run of the task hasn't completed
     match public_info {
@@ -131,6 +172,93 @@ pub async fn get_vault_keys(app: State<'_, AppStateHandle>) -> Result<String, Co
     Ok(vault_keys_json)
 }
 
+/// Check the active vault for damage and, if `repair` is set, fix what can be fixed safely
+#[tauri::command]
+pub async fn check_vault_integrity(
+    app: State<'_, AppStateHandle>,
+    repair: bool,
+) -> Result<IntegrityReport, CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    check_vault_integrity_inner(&app, &journalist_id, repair).await
+}
+
+pub(crate) async fn check_vault_integrity_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    repair: bool,
+) -> Result<IntegrityReport, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
+
+    let report = vault
+        .check_integrity(time::now(), repair)
+        .await
+        .context(VaultSnafu {
+            failed_to: "check vault integrity",
+        })?;
+
+    if !report.is_ok() {
+        tracing::warn!("Vault integrity check found problems: {:?}", report);
+    }
+
+    Ok(report)
+}
+
+/// Export the logs written between `from` and `to`, encrypted to the support key of the active
+/// vault's profile. User public keys and message contents are removed unless `scrub` is false.
+#[tauri::command]
+pub async fn export_logs(
+    app: State<'_, AppStateHandle>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    path: PathBuf,
+    scrub: bool,
+) -> Result<(), CommandError> {
+    let support_pk = app.support_pk().await.context(GenericSnafu {
+        ctx: "No support key is configured for this profile",
+    })?;
+
+    let scrubber = if scrub {
+        let mut scrubber = LogScrubber::default();
+
+        // Logs from every open vault end up in the same place so scrub all their users
+        for (_, vault) in app.unlocked_vaults().await {
+            vault
+                .add_to_log_scrubber(&mut scrubber)
+                .await
+                .context(VaultSnafu {
+                    failed_to: "read users and messages for scrubbing logs",
+                })?;
+        }
+
+        Some(scrubber)
+    } else {
+        None
+    };
+
+    let logs = app
+        .logs
+        .export(from, to, scrubber.as_ref(), time::now())
+        .await
+        .context(VaultSnafu {
+            failed_to: "export logs",
+        })?;
+
+    let log_export = logs.to_log_export(&support_pk).context(AnyhowSnafu {
+        failed_to: "encrypt logs",
+    })?;
+
+    std::fs::write(&path, log_export.as_bytes()).context(IoSnafu {
+        failed_to: "write log export",
+    })?;
+
+    tracing::info!("Exported {} log entries", logs.entries.len());
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn update_journalist_status(
     app: State<'_, AppStateHandle>,
@@ -2,13 +2,14 @@ use crate::app_state::AppStateHandle;
 use crate::error::{
     CommandError, GenericSnafu, IoSnafu, PublicInfoUnavailableSnafu, VaultLockedSnafu, VaultSnafu,
 };
-use crate::model::{BackupChecks, VaultState};
+use crate::model::{BackupChecks, BackupSecretSharingSettings, VaultState};
 use common::api::models::journalist_id::JournalistIdentity;
 use common::protocol::backup::sentinel_restore_try_unwrap_and_wrap_share_step;
 use common::protocol::backup_data::EncryptedSecretShare;
 use common::time;
 use journalist_vault::BackupHistoryEntry;
 use snafu::{OptionExt, ResultExt};
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::{fs, process};
 use tauri::State;
@@ -66,18 +67,25 @@ pub async fn get_backup_checks() -> Result<BackupChecks, CommandError> {
 
 #[tauri::command]
 pub async fn perform_backup(app: State<'_, AppStateHandle>) -> Result<(), CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    perform_backup_inner(&app, &journalist_id).await
+}
 
-    let vault_state: VaultState = app
-        .inner()
-        .vault_state()
+pub(crate) async fn perform_backup_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
         .await
-        .context(VaultSnafu {
-            failed_to: "get vault state",
-        })?
-        .context(GenericSnafu {
-            ctx: "vault state is None",
-        })?;
+        .context(VaultLockedSnafu)?;
+
+    let vault_state: VaultState =
+        app.vault_state_for(journalist_id)
+            .await
+            .context(GenericSnafu {
+                ctx: "vault state is None",
+            })?;
 
     let backup_checks_double_check = get_backup_checks().await;
 
@@ -132,7 +140,18 @@ pub async fn eject_backup_volume() -> Result<bool, CommandError> {
 pub async fn get_backup_contacts(
     app: State<'_, AppStateHandle>,
 ) -> Result<Vec<JournalistIdentity>, CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_backup_contacts_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_backup_contacts_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<JournalistIdentity>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
     vault.get_backup_contacts().await.context(VaultSnafu {
         failed_to: "get backup contacts",
     })
@@ -142,18 +161,63 @@ pub async fn get_backup_contacts(
 pub async fn get_backup_history(
     app: State<'_, AppStateHandle>,
 ) -> Result<Vec<BackupHistoryEntry>, CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_backup_history_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_backup_history_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<BackupHistoryEntry>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
     vault.get_backup_history().await.context(VaultSnafu {
         failed_to: "get backup history",
     })
 }
 
+#[tauri::command]
+pub async fn get_backup_secret_sharing_settings(
+    app: State<'_, AppStateHandle>,
+) -> Result<BackupSecretSharingSettings, CommandError> {
+    let public_info = app.public_info().await;
+    let public_info = public_info.as_ref().context(PublicInfoUnavailableSnafu)?;
+
+    let settings = public_info.secret_sharing_settings();
+
+    Ok(BackupSecretSharingSettings {
+        k: settings.k,
+        n: settings.n,
+    })
+}
+
 #[tauri::command]
 pub async fn set_backup_contacts(
     app: State<'_, AppStateHandle>,
     contacts: Vec<JournalistIdentity>,
 ) -> Result<(), CommandError> {
     let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+
+    let public_info = app.public_info().await;
+    let public_info = public_info.as_ref().context(PublicInfoUnavailableSnafu)?;
+
+    let n = usize::from(public_info.secret_sharing_settings().n);
+    if contacts.len() != n {
+        tracing::warn!("Expected {} backup contacts but got {}", n, contacts.len());
+        return Err(GenericSnafu {
+            ctx: "wrong number of backup contacts",
+        }
+        .build());
+    }
+
+    if contacts.iter().collect::<HashSet<_>>().len() != contacts.len() {
+        return Err(GenericSnafu {
+            ctx: "backup contacts must be different people",
+        }
+        .build());
+    }
     vault
         .set_backup_contacts(contacts)
         .await
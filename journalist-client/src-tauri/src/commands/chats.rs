@@ -1,13 +1,15 @@
 use chrono::{DateTime, Utc};
 use common::{
+    api::models::journalist_id::JournalistIdentity,
     client::mailbox::mailbox_message::UserStatus as MailboxUserStatus,
     protocol::{
         constants::MESSAGE_PADDING_LEN,
         journalist::new_encrypted_cover_message_from_journalist_via_covernode, keys::UserPublicKey,
+        safety_number::SafetyNumber,
     },
     time, Error as CommonError, FixedSizeMessageText,
 };
-use journalist_vault::VaultMessage;
+use journalist_vault::{Draft, RetentionPolicy, VaultMessage};
 use snafu::{OptionExt as _, ResultExt};
 use tauri::State;
 
@@ -17,11 +19,14 @@ use crate::{
         AnyhowSnafu, ApiClientUnavailableSnafu, CommandError, CommonSnafu, GenericSnafu,
         PublicInfoUnavailableSnafu, VaultLockedSnafu, VaultSnafu,
     },
-    model::{User, UserStatus},
+    model::{ConversationSafetyNumber, InboxMessage, User, UserStatus},
 };
 
 use crate::model::BackendToFrontendEvent;
 
+/// How much of the space available for a message the text would use. Replies can't be split
+/// into fragments since the mobile apps can't reassemble them, so values over 1.0 mean the
+/// message is too long to send.
 #[tauri::command]
 pub fn check_message_length(message: String) -> Result<f32, CommandError> {
     let padded = FixedSizeMessageText::new(&message);
@@ -42,16 +47,91 @@ pub fn check_message_length(message: String) -> Result<f32, CommandError> {
 
 #[tauri::command]
 pub async fn get_chats(app: State<'_, AppStateHandle>) -> Result<Vec<VaultMessage>, CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_chats_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_chats_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<VaultMessage>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     vault.messages().await.context(VaultSnafu {
         failed_to: "get messages",
     })
 }
 
+/// Messages from every open vault which isn't soft locked, oldest first, so journalists who
+/// have more than one vault open can see all their conversations in one place.
+#[tauri::command]
+pub async fn get_inbox(app: State<'_, AppStateHandle>) -> Result<Vec<InboxMessage>, CommandError> {
+    get_inbox_inner(&app).await
+}
+
+pub(crate) async fn get_inbox_inner(
+    app: &AppStateHandle,
+) -> Result<Vec<InboxMessage>, CommandError> {
+    let mut inbox = vec![];
+
+    for (journalist_id, vault) in app.unlocked_vaults().await {
+        let messages = vault.messages().await.context(VaultSnafu {
+            failed_to: "get messages",
+        })?;
+
+        inbox.extend(messages.into_iter().map(|message| InboxMessage {
+            journalist_id: journalist_id.clone(),
+            message,
+        }));
+    }
+
+    inbox.sort_by_key(|m| m.message.timestamp());
+
+    Ok(inbox)
+}
+
+/// Messages matching a full-text search of message text and user aliases and descriptions
+#[tauri::command]
+pub async fn search_messages(
+    app: State<'_, AppStateHandle>,
+    query: String,
+) -> Result<Vec<VaultMessage>, CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    search_messages_inner(&app, &journalist_id, query).await
+}
+
+pub(crate) async fn search_messages_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    query: String,
+) -> Result<Vec<VaultMessage>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
+
+    vault.search_messages(&query).await.context(VaultSnafu {
+        failed_to: "search messages",
+    })
+}
+
 #[tauri::command]
 pub async fn get_users(app: State<'_, AppStateHandle>) -> Result<Vec<User>, CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_users_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_users_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<User>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     let vault_users = vault.users().await.context(VaultSnafu {
         failed_to: "get users",
@@ -67,6 +147,7 @@ pub async fn get_users(app: State<'_, AppStateHandle>) -> Result<Vec<User>, Comm
                 user.alias,
                 user.description,
                 user.marked_as_unread,
+                user.retention_policy,
             )
         })
         .collect();
@@ -79,7 +160,19 @@ pub async fn mark_as_read(
     app: State<'_, AppStateHandle>,
     reply_key: String,
 ) -> Result<(), CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    mark_as_read_inner(&app, &journalist_id, reply_key).await
+}
+
+pub(crate) async fn mark_as_read_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     tracing::info!("Marking chat {} as read", &reply_key);
 
@@ -97,7 +190,19 @@ pub async fn mark_as_unread(
     app: State<'_, AppStateHandle>,
     reply_key: String,
 ) -> Result<(), CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    mark_as_unread_inner(&app, &journalist_id, reply_key).await
+}
+
+pub(crate) async fn mark_as_unread_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     tracing::info!("Marking chat {} as unread", reply_key);
 
@@ -128,6 +233,36 @@ pub async fn set_custom_expiry(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_safety_number(
+    app: State<'_, AppStateHandle>,
+    reply_key: String,
+) -> Result<ConversationSafetyNumber, CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+
+    let user_pk = user_pk_from_hex(&reply_key)?;
+
+    // Use the published key rather than the vault's latest key since that's what the source sees
+    let public_info = app.public_info_for(&journalist_id).await;
+    let public_info = public_info.as_ref().context(PublicInfoUnavailableSnafu)?;
+
+    let safety_number =
+        SafetyNumber::for_latest_published(&user_pk, &journalist_id, &public_info.keys).context(
+            GenericSnafu {
+                ctx: "No published identity key for journalist",
+            },
+        )?;
+
+    let words = safety_number
+        .words()
+        .map_err(anyhow::Error::from)
+        .context(AnyhowSnafu {
+            failed_to: "render safety number words",
+        })?;
+
+    Ok(ConversationSafetyNumber::new(safety_number.digits(), words))
+}
+
 fn user_pk_from_hex(reply_key: &str) -> Result<UserPublicKey, CommandError> {
     let user_pk = hex::decode(reply_key).ok().context(GenericSnafu {
         ctx: "Failed to decode reply key hex",
@@ -148,7 +283,20 @@ pub async fn update_user_status(
     reply_key: String,
     status: MailboxUserStatus,
 ) -> Result<(), CommandError> {
-    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    update_user_status_inner(&app, &journalist_id, reply_key, status).await
+}
+
+pub(crate) async fn update_user_status_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+    status: MailboxUserStatus,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
 
     let user_pk = user_pk_from_hex(&reply_key)?;
     tracing::info!("Setting user {:?} status to {}", user_pk, status);
@@ -163,6 +311,45 @@ pub async fn update_user_status(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn update_user_retention_policy(
+    app: State<'_, AppStateHandle>,
+    reply_key: String,
+    retention_policy: RetentionPolicy,
+) -> Result<(), CommandError> {
+    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+
+    let user_pk = user_pk_from_hex(&reply_key)?;
+    tracing::info!(
+        "Setting user {:?} retention policy to {:?}",
+        user_pk,
+        retention_policy
+    );
+
+    vault
+        .update_user_retention_policy(&user_pk, retention_policy)
+        .await
+        .context(VaultSnafu {
+            failed_to: "update user retention policy",
+        })?;
+
+    Ok(())
+}
+
+/// The longest, in days, that messages can be kept for before they are deleted
+#[tauri::command]
+pub async fn get_max_message_retention_days(
+    app: State<'_, AppStateHandle>,
+) -> Result<u16, CommandError> {
+    let vault = app.inner().vault().await.context(VaultLockedSnafu)?;
+
+    let limit = vault.message_retention_limit().await.context(VaultSnafu {
+        failed_to: "get message retention limit",
+    })?;
+
+    Ok(limit.max_retention_days)
+}
+
 #[tauri::command]
 pub async fn update_user_alias_and_description(
     app: State<'_, AppStateHandle>,
@@ -189,6 +376,131 @@ pub async fn submit_message(
     app: State<'_, AppStateHandle>,
     reply_key: String,
     message: String,
+    send_after: Option<DateTime<Utc>>,
+) -> Result<(), CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    submit_message_inner(&app, &journalist_id, reply_key, message, send_after).await
+}
+
+pub(crate) async fn submit_message_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+    message: String,
+    send_after: Option<DateTime<Utc>>,
+) -> Result<(), CommandError> {
+    let coverdrop_service =
+        app.coverdrop_service_for(journalist_id)
+            .await
+            .context(GenericSnafu {
+                ctx: "CoverDrop service unavailable",
+            })?;
+
+    let user_pk = user_pk_from_hex(&reply_key)?;
+
+    let public_info = app.public_info_for(journalist_id).await;
+    let public_info = public_info.as_ref().context(PublicInfoUnavailableSnafu)?;
+
+    let queue_length = coverdrop_service
+        .enqueue_j2u_message(public_info, &user_pk, &message, send_after, time::now())
+        .await
+        .context(AnyhowSnafu {
+            failed_to: "enqueue message",
+        })?;
+
+    app.events
+        .emit_outbound_queue_length_event(journalist_id, queue_length)
+        .context(VaultSnafu {
+            failed_to: "emit outbound queue length event",
+        })?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_drafts(app: State<'_, AppStateHandle>) -> Result<Vec<Draft>, CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    get_drafts_inner(&app, &journalist_id).await
+}
+
+pub(crate) async fn get_drafts_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+) -> Result<Vec<Draft>, CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
+
+    vault.drafts().await.context(VaultSnafu {
+        failed_to: "get drafts",
+    })
+}
+
+#[tauri::command]
+pub async fn save_draft(
+    app: State<'_, AppStateHandle>,
+    reply_key: String,
+    message: String,
+    send_after: Option<DateTime<Utc>>,
+) -> Result<(), CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    save_draft_inner(&app, &journalist_id, reply_key, message, send_after).await
+}
+
+pub(crate) async fn save_draft_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+    message: String,
+    send_after: Option<DateTime<Utc>>,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
+
+    let user_pk = user_pk_from_hex(&reply_key)?;
+
+    vault
+        .save_draft(&user_pk, &message, send_after, time::now())
+        .await
+        .context(VaultSnafu {
+            failed_to: "save draft",
+        })
+}
+
+#[tauri::command]
+pub async fn delete_draft(
+    app: State<'_, AppStateHandle>,
+    reply_key: String,
+) -> Result<(), CommandError> {
+    let journalist_id = app.journalist_id().await.context(VaultLockedSnafu)?;
+    delete_draft_inner(&app, &journalist_id, reply_key).await
+}
+
+pub(crate) async fn delete_draft_inner(
+    app: &AppStateHandle,
+    journalist_id: &JournalistIdentity,
+    reply_key: String,
+) -> Result<(), CommandError> {
+    let vault = app
+        .vault_for(journalist_id)
+        .await
+        .context(VaultLockedSnafu)?;
+
+    let user_pk = user_pk_from_hex(&reply_key)?;
+
+    vault.delete_draft(&user_pk).await.context(VaultSnafu {
+        failed_to: "delete draft",
+    })
+}
+
+#[tauri::command]
+pub async fn hand_over_user(
+    app: State<'_, AppStateHandle>,
+    reply_key: String,
+    to: JournalistIdentity,
 ) -> Result<(), CommandError> {
     let coverdrop_service = app
         .inner()
@@ -197,6 +509,11 @@ pub async fn submit_message(
         .context(GenericSnafu {
             ctx: "CoverDrop service unavailable",
         })?;
+    let journalist_id = app
+        .inner()
+        .journalist_id()
+        .await
+        .context(VaultLockedSnafu)?;
 
     let user_pk = user_pk_from_hex(&reply_key)?;
 
@@ -204,14 +521,14 @@ pub async fn submit_message(
     let public_info = public_info.as_ref().context(PublicInfoUnavailableSnafu)?;
 
     let queue_length = coverdrop_service
-        .enqueue_j2u_message(public_info, &user_pk, &message, time::now())
+        .enqueue_j2u_hand_over(public_info, &user_pk, &to, time::now())
         .await
         .context(AnyhowSnafu {
-            failed_to: "enqueue message",
+            failed_to: "enqueue hand over",
         })?;
 
-    app.app_handle
-        .emit_outbound_queue_length_event(queue_length)
+    app.events
+        .emit_outbound_queue_length_event(&journalist_id, queue_length)
         .context(VaultSnafu {
             failed_to: "emit outbound queue length event",
         })?;
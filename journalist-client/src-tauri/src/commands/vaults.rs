@@ -1,4 +1,5 @@
 use common::{
+    api::models::journalist_id::JournalistIdentity,
     clap::Stage,
     crypto::keys::{public_key::PublicKey, untrusted::signing::UntrustedSignedPublicSigningKey},
     protocol::keys::UntrustedOrganizationPublicKey,
@@ -23,6 +24,46 @@ pub async fn get_vault_state(
     })
 }
 
+/// Every open vault, the frontend can switch between them with [switch_vault]
+#[tauri::command]
+pub async fn get_open_vaults(
+    app: State<'_, AppStateHandle>,
+) -> Result<Vec<VaultState>, CommandError> {
+    get_open_vaults_inner(&app).await
+}
+
+pub(crate) async fn get_open_vaults_inner(
+    app: &AppStateHandle,
+) -> Result<Vec<VaultState>, CommandError> {
+    Ok(app.open_vault_states().await)
+}
+
+#[tauri::command]
+pub async fn switch_vault(
+    app: State<'_, AppStateHandle>,
+    journalist_id: JournalistIdentity,
+) -> Result<Option<VaultState>, CommandError> {
+    app.inner()
+        .switch_vault(&journalist_id)
+        .await
+        .context(VaultSnafu {
+            failed_to: "switch vault",
+        })
+}
+
+#[tauri::command]
+pub async fn close_vault(
+    app: State<'_, AppStateHandle>,
+    journalist_id: JournalistIdentity,
+) -> Result<Option<VaultState>, CommandError> {
+    app.inner()
+        .close_vault(&journalist_id)
+        .await
+        .context(VaultSnafu {
+            failed_to: "close vault",
+        })
+}
+
 #[tauri::command]
 pub async fn unlock_vault(
     path: &Path,
@@ -34,6 +75,7 @@ pub async fn unlock_vault(
     let profiles = profiles.inner();
 
     let api_url = profiles.api_url(&stage).context(MissingProfileSnafu)?;
+    let support_pk = profiles.support_pk(&stage).cloned();
 
     let stage = Stage::from_guardian_str(stage.as_str())
         .ok()
@@ -43,7 +85,7 @@ pub async fn unlock_vault(
 
     let (vault, api_client) = app
         .inner()
-        .unlock_vault(stage, api_url, path, password)
+        .unlock_vault(stage, api_url, support_pk, path, password)
         .await
         .context(VaultSnafu {
             failed_to: "unlock vault, is your password correct?",
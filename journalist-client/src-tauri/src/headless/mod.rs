@@ -0,0 +1,167 @@
+//! Sentinel without a window, for desk teams who want a shared always-on instance and for
+//! integration tests which need the real set of background tasks.
+//!
+//! The daemon unlocks its vaults on start up and is then controlled over a Unix socket, see
+//! [server::serve] for the protocol.
+
+use std::{
+    fs,
+    io::Write as _,
+    os::unix::fs::OpenOptionsExt as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use clap::Parser;
+use common::clap::Stage;
+use rand::RngCore as _;
+use reqwest::Url;
+use tracing_subscriber::{layer::SubscriberExt as _, util::SubscriberInitExt as _};
+
+use crate::{
+    app_state::AppStateHandle, logging::JournalistClientLogLayer, model::LoggedEvents,
+    notifications::start_logged_notification_service,
+};
+
+mod rpc;
+mod server;
+
+#[derive(Parser)]
+#[command(name = "sentinel-daemon")]
+#[command(about = "Run Sentinel without a window, controlled through a local socket")]
+struct HeadlessCli {
+    /// The vaults to unlock. Their passwords are read from stdin, one line per vault in the
+    /// same order as the paths.
+    #[arg(long = "vault-path", required = true)]
+    vault_paths: Vec<PathBuf>,
+    /// Read each vault's password from the `.password` file next to it instead of stdin.
+    /// Not allowed in production.
+    #[arg(long)]
+    colocated_passwords: bool,
+    #[arg(long)]
+    stage: Stage,
+    #[arg(long)]
+    api_url: Url,
+    /// Where to create the control socket
+    #[arg(long)]
+    socket_path: PathBuf,
+    /// File containing the token clients must authenticate with.
+    /// A new token is generated and written here if the file does not exist.
+    #[arg(long)]
+    token_path: PathBuf,
+    /// Prevent background tasks from starting when a vault is unlocked
+    #[arg(long)]
+    no_background_tasks: bool,
+}
+
+pub fn run_headless() {
+    let cli = HeadlessCli::parse();
+
+    let runtime = tokio::runtime::Runtime::new().expect("Create tokio runtime");
+
+    let result = read_passwords(&cli).and_then(|passwords| runtime.block_on(run(cli, passwords)));
+
+    if let Err(e) = result {
+        eprintln!("sentinel-daemon failed: {e:?}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: HeadlessCli, passwords: Vec<String>) -> anyhow::Result<()> {
+    let app = Arc::new(AppStateHandle::new(
+        Arc::new(LoggedEvents),
+        start_logged_notification_service(),
+        cli.no_background_tasks,
+    ));
+
+    // Logs are only written to the vault, where they are encrypted, since they can contain user
+    // keys and messages
+    tracing_subscriber::registry()
+        .with(JournalistClientLogLayer::new(app.logs.clone()))
+        .init();
+
+    let token = Arc::new(read_or_create_token(&cli.token_path)?);
+
+    for (vault_path, password) in cli.vault_paths.iter().zip(passwords) {
+        // No support key since logs are read straight from the daemon's host
+        app.unlock_vault(cli.stage, &cli.api_url, None, vault_path, &password)
+            .await?;
+
+        tracing::info!("Unlocked vault {}", vault_path.display());
+    }
+
+    let listener = server::bind(&cli.socket_path)?;
+
+    tokio::select! {
+        result = server::serve(app.clone(), listener, token) => result?,
+        _ = tokio::signal::ctrl_c() => tracing::info!("Shutting down"),
+    }
+
+    let _ = fs::remove_file(&cli.socket_path);
+
+    Ok(())
+}
+
+/// Passwords for each vault, in the same order as the vault paths
+fn read_passwords(cli: &HeadlessCli) -> anyhow::Result<Vec<String>> {
+    if cli.colocated_passwords {
+        anyhow::ensure!(
+            cli.stage != Stage::Production,
+            "Colocated password files can't be used in production"
+        );
+
+        return cli
+            .vault_paths
+            .iter()
+            .map(|vault_path| -> anyhow::Result<String> {
+                let password_path = vault_path.with_extension("password");
+                let password = fs::read_to_string(&password_path)
+                    .with_context(|| format!("Failed to read {}", password_path.display()))?;
+
+                Ok(password.trim().to_string())
+            })
+            .collect();
+    }
+
+    let mut lines = std::io::stdin().lines();
+
+    cli.vault_paths
+        .iter()
+        .map(|vault_path| -> anyhow::Result<String> {
+            let password = lines
+                .next()
+                .with_context(|| format!("No password on stdin for {}", vault_path.display()))??;
+
+            Ok(password.trim().to_string())
+        })
+        .collect()
+}
+
+fn read_or_create_token(token_path: &Path) -> anyhow::Result<String> {
+    if token_path.exists() {
+        let token = fs::read_to_string(token_path)?.trim().to_string();
+        anyhow::ensure!(
+            !token.is_empty(),
+            "Token file {} is empty",
+            token_path.display()
+        );
+
+        return Ok(token);
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(token_path)?
+        .write_all(token.as_bytes())?;
+
+    tracing::info!("Wrote new control token to {}", token_path.display());
+
+    Ok(token)
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Utc};
+use common::{
+    api::models::journalist_id::JournalistIdentity,
+    client::mailbox::mailbox_message::UserStatus as MailboxUserStatus,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    app_state::AppStateHandle,
+    commands::{
+        admin::{
+            check_vault_integrity_inner, force_rotate_id_pk_inner, force_rotate_msg_pk_inner,
+            get_public_info_inner, get_trust_anchor_digests_inner,
+        },
+        backup::{
+            get_backup_checks, get_backup_contacts_inner, get_backup_history_inner,
+            perform_backup_inner,
+        },
+        chats::{
+            delete_draft_inner, get_chats_inner, get_drafts_inner, get_inbox_inner,
+            get_users_inner, mark_as_read_inner, mark_as_unread_inner, save_draft_inner,
+            search_messages_inner, submit_message_inner, update_user_status_inner,
+        },
+        vaults::get_open_vaults_inner,
+    },
+    error::CommandError,
+};
+
+// Error codes from the JSON-RPC 2.0 specification
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+// Codes in the range reserved for implementations
+const COMMAND_FAILED: i64 = -32000;
+const UNAUTHENTICATED: i64 = -32001;
+
+#[derive(Deserialize)]
+pub struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Request, Response> {
+        let request = serde_json::from_str::<Request>(line)
+            .map_err(|e| Response::error(Value::Null, RpcError::new(PARSE_ERROR, e.to_string())))?;
+
+        if request.jsonrpc != "2.0" {
+            return Err(Response::error(
+                request.id,
+                RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+            ));
+        }
+
+        Ok(request)
+    }
+
+    pub fn respond(self, result: Result<Value, RpcError>) -> Response {
+        match result {
+            Ok(result) => Response {
+                jsonrpc: "2.0",
+                id: self.id,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => Response::error(self.id, error),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl Response {
+    fn error(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthenticated() -> Self {
+        Self::new(UNAUTHENTICATED, "Not authenticated")
+    }
+}
+
+impl From<CommandError> for RpcError {
+    fn from(e: CommandError) -> Self {
+        tracing::error!("Command error: {:?}", e);
+        Self::new(COMMAND_FAILED, e.to_string())
+    }
+}
+
+// Parameters use the same camel case names as the arguments the frontend passes to the
+// equivalent Tauri commands. Methods which act on a single vault also take the `journalistId`
+// of the vault, there is no active vault like there is in the frontend.
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticateParams {
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchParams {
+    query: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplyKeyParams {
+    reply_key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserStatusParams {
+    reply_key: String,
+    status: MailboxUserStatus,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageParams {
+    reply_key: String,
+    message: String,
+    #[serde(default)]
+    send_after: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntegrityParams {
+    repair: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalistParams {
+    journalist_id: JournalistIdentity,
+}
+
+pub fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// The journalist whose vault a method acts on, which must be open
+async fn journalist_id(app: &AppStateHandle, p: &Value) -> Result<JournalistIdentity, RpcError> {
+    let JournalistParams { journalist_id } = params(p.clone())?;
+
+    if app.vault_state_for(&journalist_id).await.is_none() {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("No open vault for {journalist_id}"),
+        ));
+    }
+
+    Ok(journalist_id)
+}
+
+fn result<T: Serialize>(result: Result<T, CommandError>) -> Result<Value, RpcError> {
+    let result = result?;
+
+    serde_json::to_value(result).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+}
+
+/// Run the command named by `method`. These are the chat, backup and admin commands Sentinel
+/// offers its frontend, other commands only make sense with a window.
+pub async fn dispatch(app: &AppStateHandle, method: &str, p: Value) -> Result<Value, RpcError> {
+    match method {
+        // Chats
+        "get_inbox" => result(get_inbox_inner(app).await),
+        "get_chats" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_chats_inner(app, &journalist_id).await)
+        }
+        "get_users" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_users_inner(app, &journalist_id).await)
+        }
+        "get_drafts" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_drafts_inner(app, &journalist_id).await)
+        }
+        "search_messages" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let SearchParams { query } = params(p)?;
+            result(search_messages_inner(app, &journalist_id, query).await)
+        }
+        "mark_as_read" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let ReplyKeyParams { reply_key } = params(p)?;
+            result(mark_as_read_inner(app, &journalist_id, reply_key).await)
+        }
+        "mark_as_unread" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let ReplyKeyParams { reply_key } = params(p)?;
+            result(mark_as_unread_inner(app, &journalist_id, reply_key).await)
+        }
+        "update_user_status" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let UserStatusParams { reply_key, status } = params(p)?;
+            result(update_user_status_inner(app, &journalist_id, reply_key, status).await)
+        }
+        "submit_message" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let MessageParams {
+                reply_key,
+                message,
+                send_after,
+            } = params(p)?;
+            result(submit_message_inner(app, &journalist_id, reply_key, message, send_after).await)
+        }
+        "save_draft" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let MessageParams {
+                reply_key,
+                message,
+                send_after,
+            } = params(p)?;
+            result(save_draft_inner(app, &journalist_id, reply_key, message, send_after).await)
+        }
+        "delete_draft" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let ReplyKeyParams { reply_key } = params(p)?;
+            result(delete_draft_inner(app, &journalist_id, reply_key).await)
+        }
+        // Backups
+        "get_backup_checks" => result(get_backup_checks().await),
+        "perform_backup" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(perform_backup_inner(app, &journalist_id).await)
+        }
+        "get_backup_contacts" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_backup_contacts_inner(app, &journalist_id).await)
+        }
+        "get_backup_history" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_backup_history_inner(app, &journalist_id).await)
+        }
+        // Admin
+        "get_public_info" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_public_info_inner(app, &journalist_id).await)
+        }
+        "get_trust_anchor_digests" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(get_trust_anchor_digests_inner(app, &journalist_id).await)
+        }
+        "force_rotate_id_pk" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(force_rotate_id_pk_inner(app, &journalist_id).await)
+        }
+        "force_rotate_msg_pk" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            result(force_rotate_msg_pk_inner(app, &journalist_id).await)
+        }
+        "check_vault_integrity" => {
+            let journalist_id = journalist_id(app, &p).await?;
+            let IntegrityParams { repair } = params(p)?;
+            result(check_vault_integrity_inner(app, &journalist_id, repair).await)
+        }
+        // Vaults
+        "get_open_vaults" => result(get_open_vaults_inner(app).await),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method: {method}"),
+        )),
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    fs, io,
+    os::unix::fs::{DirBuilderExt as _, FileTypeExt as _, PermissionsExt as _},
+    path::Path,
+    sync::Arc,
+};
+
+use rand::RngCore as _;
+
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+use crate::app_state::AppStateHandle;
+
+use super::rpc::{self, AuthenticateParams, Request, RpcError};
+
+/// The longest request line we'll read, including the trailing newline. This is checked before
+/// the connection is authenticated so anyone who can connect can't make us buffer without limit.
+const MAX_REQUEST_LEN: usize = 1024 * 1024;
+
+/// Create the control socket so that only the user running the daemon can connect, the token
+/// protects against other processes running as that user.
+///
+/// The socket is bound inside a directory only we can access and moved into place once its
+/// permissions are set, so there's no point at which anyone else could connect to it. Moving it
+/// also replaces any socket left behind by a previous run, but anything else at `socket_path` is
+/// left alone in case the path was mistyped.
+pub fn bind(socket_path: &Path) -> anyhow::Result<UnixListener> {
+    match fs::symlink_metadata(socket_path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {}
+        Ok(_) => anyhow::bail!(
+            "{} already exists and is not a socket, refusing to replace it",
+            socket_path.display()
+        ),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let parent = match socket_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut suffix = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut suffix);
+    let private_dir = parent.join(format!(".sentinel-daemon-{}", hex::encode(suffix)));
+
+    fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+    let bind_in_private_dir = || -> anyhow::Result<UnixListener> {
+        let private_socket_path = private_dir.join("socket");
+
+        let listener = UnixListener::bind(&private_socket_path)?;
+        fs::set_permissions(&private_socket_path, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&private_socket_path, socket_path)?;
+
+        Ok(listener)
+    };
+    let listener = bind_in_private_dir();
+
+    if let Err(e) = fs::remove_dir(&private_dir) {
+        tracing::warn!("Failed to remove {}: {:?}", private_dir.display(), e);
+    }
+
+    let listener = listener?;
+
+    tracing::info!("Listening on {}", socket_path.display());
+
+    Ok(listener)
+}
+
+/// Accept connections on the control socket until the process is stopped.
+///
+/// Each line sent to the socket is a JSON-RPC 2.0 request and gets a single line response. The
+/// first request on a connection must be `authenticate` with the daemon's token, anything else
+/// is rejected until it has been.
+pub async fn serve(
+    app: Arc<AppStateHandle>,
+    listener: UnixListener,
+    token: Arc<String>,
+) -> anyhow::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+
+        tokio::spawn({
+            let app = app.clone();
+            let token = token.clone();
+
+            async move {
+                if let Err(e) = handle_connection(&app, stream, &token).await {
+                    tracing::warn!("Control connection closed with error: {:?}", e);
+                }
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    app: &AppStateHandle,
+    stream: UnixStream,
+    token: &str,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut buf = vec![];
+
+    let mut is_authenticated = false;
+
+    loop {
+        buf.clear();
+
+        let read = (&mut reader)
+            .take(MAX_REQUEST_LEN as u64)
+            .read_until(b'\n', &mut buf)
+            .await?;
+
+        if read == 0 {
+            break;
+        }
+
+        if read == MAX_REQUEST_LEN && !buf.ends_with(b"\n") {
+            anyhow::bail!("Request is longer than {MAX_REQUEST_LEN} bytes");
+        }
+
+        let line = std::str::from_utf8(&buf)?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match Request::parse(line) {
+            Ok(request) => {
+                let result = if request.method == "authenticate" {
+                    match rpc::params::<AuthenticateParams>(request.params.clone()) {
+                        Ok(params) if tokens_match(&params.token, token) => {
+                            is_authenticated = true;
+                            Ok(serde_json::Value::Null)
+                        }
+                        Ok(_) => {
+                            tracing::warn!("Rejected control connection with an incorrect token");
+                            Err(RpcError::unauthenticated())
+                        }
+                        Err(e) => Err(e),
+                    }
+                } else if is_authenticated {
+                    rpc::dispatch(app, &request.method, request.params.clone()).await
+                } else {
+                    Err(RpcError::unauthenticated())
+                };
+
+                request.respond(result)
+            }
+            Err(response) => response,
+        };
+
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        writer.write_all(&response).await?;
+    }
+
+    Ok(())
+}
+
+/// Compare tokens without leaking how much of the token was correct through timing
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt as _, sync::Arc};
+
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader, Lines},
+        net::{unix::OwnedReadHalf, unix::OwnedWriteHalf, UnixStream},
+    };
+
+    use crate::{
+        app_state::AppStateHandle, model::LoggedEvents,
+        notifications::start_logged_notification_service,
+    };
+
+    use super::{bind, serve, MAX_REQUEST_LEN};
+
+    const TOKEN: &str = "correct-token";
+
+    struct Client {
+        lines: Lines<BufReader<OwnedReadHalf>>,
+        writer: OwnedWriteHalf,
+        next_id: i64,
+    }
+
+    impl Client {
+        async fn call(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": self.next_id,
+                "method": method,
+                "params": params,
+            });
+
+            let mut line = serde_json::to_vec(&request).unwrap();
+            line.push(b'\n');
+            self.writer.write_all(&line).await.unwrap();
+
+            let response = self.lines.next_line().await.unwrap().unwrap();
+            let response = serde_json::from_str::<Value>(&response).unwrap();
+            assert_eq!(response["id"], self.next_id);
+
+            response
+        }
+    }
+
+    async fn start_daemon() -> (tempfile::TempDir, Client) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("sentinel.sock");
+
+        let app = Arc::new(AppStateHandle::new(
+            Arc::new(LoggedEvents),
+            start_logged_notification_service(),
+            true,
+        ));
+
+        let listener = bind(&socket_path).unwrap();
+        tokio::spawn(serve(app, listener, Arc::new(TOKEN.to_string())));
+
+        let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Only the socket is left behind, not the directory it was bound in
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        let (reader, writer) = UnixStream::connect(&socket_path)
+            .await
+            .unwrap()
+            .into_split();
+
+        let client = Client {
+            lines: BufReader::new(reader).lines(),
+            writer,
+            next_id: 0,
+        };
+
+        (temp_dir, client)
+    }
+
+    #[tokio::test]
+    async fn requests_are_rejected_until_authenticated() {
+        let (_temp_dir, mut client) = start_daemon().await;
+
+        let response = client.call("get_open_vaults", json!({})).await;
+        assert_eq!(response["error"]["code"], -32001);
+        assert!(response.get("result").is_none());
+
+        let response = client
+            .call("authenticate", json!({ "token": "wrong-token" }))
+            .await;
+        assert_eq!(response["error"]["code"], -32001);
+
+        // A wrong token doesn't authenticate the connection
+        let response = client.call("get_open_vaults", json!({})).await;
+        assert_eq!(response["error"]["code"], -32001);
+    }
+
+    #[tokio::test]
+    async fn methods_are_dispatched_once_authenticated() {
+        let (_temp_dir, mut client) = start_daemon().await;
+
+        let response = client.call("authenticate", json!({ "token": TOKEN })).await;
+        assert!(response.get("error").is_none());
+
+        let response = client.call("get_open_vaults", json!({})).await;
+        assert_eq!(response["result"], json!([]));
+
+        // Methods which act on a single vault need the journalist whose vault it is
+        let response = client.call("get_chats", json!({})).await;
+        assert_eq!(response["error"]["code"], -32602);
+
+        let response = client
+            .call("get_chats", json!({ "journalistId": "not_open" }))
+            .await;
+        assert_eq!(response["error"]["code"], -32602);
+
+        let response = client.call("switch_vault", json!({})).await;
+        assert_eq!(response["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn overlong_requests_close_the_connection() {
+        let (_temp_dir, mut client) = start_daemon().await;
+
+        // The connection may already be closed before the whole request has been written
+        let _ = client
+            .writer
+            .write_all(&vec![b'a'; MAX_REQUEST_LEN + 1])
+            .await;
+
+        let response = client.lines.next_line().await;
+        assert!(!matches!(response, Ok(Some(_))));
+    }
+
+    #[tokio::test]
+    async fn bind_refuses_to_replace_anything_but_a_socket() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("sentinel.sock");
+
+        fs::write(&socket_path, "not a socket").unwrap();
+
+        assert!(bind(&socket_path).is_err());
+        assert_eq!(fs::read_to_string(&socket_path).unwrap(), "not a socket");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn bind_replaces_a_stale_socket() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("sentinel.sock");
+
+        drop(bind(&socket_path).unwrap());
+        assert!(socket_path.exists());
+
+        bind(&socket_path).unwrap();
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
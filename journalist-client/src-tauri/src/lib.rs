@@ -2,19 +2,21 @@ use app_state::AppStateHandle;
 use clap::Parser as _;
 use commands::{
     admin::{
-        force_rotate_id_pk, force_rotate_msg_pk, get_logging_sessions_timeline, get_logs,
-        get_public_info, get_trust_anchor_digests, get_vault_keys,
+        check_vault_integrity, export_logs, force_rotate_id_pk, force_rotate_msg_pk,
+        get_logging_sessions_timeline, get_logs, get_public_info, get_trust_anchor_digests,
+        get_vault_keys,
     },
     backup::{eject_backup_volume, get_backup_checks, perform_backup},
     chats::{
-        burst_cover_messages, check_message_length, get_chats, get_users, mark_as_read,
-        mark_as_unread, set_custom_expiry, submit_message, update_user_alias_and_description,
-        update_user_status,
+        burst_cover_messages, check_message_length, delete_draft, get_chats, get_drafts, get_inbox,
+        get_max_message_retention_days, get_safety_number, get_users, hand_over_user, mark_as_read,
+        mark_as_unread, save_draft, search_messages, set_custom_expiry, submit_message,
+        update_user_alias_and_description, update_user_retention_policy, update_user_status,
     },
     profiles::get_profiles,
     vaults::{
-        get_colocated_password, get_vault_state, send_notification, soft_lock_vault,
-        unlock_soft_locked_vault, unlock_vault,
+        close_vault, get_colocated_password, get_open_vaults, get_vault_state, send_notification,
+        soft_lock_vault, switch_vault, unlock_soft_locked_vault, unlock_vault,
     },
 };
 use logging::JournalistClientLogLayer;
@@ -22,7 +24,7 @@ use model::Profiles;
 use notifications::start_notification_service;
 use reqwest::Url;
 use std::process::{Child, Command};
-use std::{fs::File, path::Path, str::FromStr, thread};
+use std::{path::Path, str::FromStr, sync::Arc, thread};
 use tauri::{App, Manager as _};
 use tauri_plugin_dialog::{DialogExt as _, MessageDialogKind};
 use tracing_subscriber::{layer::SubscriberExt as _, util::SubscriberInitExt as _};
@@ -32,7 +34,8 @@ use cli::Cli;
 use crate::commands::{
     admin::{fully_exit_app, launch_new_instance, update_journalist_status},
     backup::{
-        get_backup_contacts, get_backup_history, set_backup_contacts, unwrap_backup_secret_share,
+        get_backup_contacts, get_backup_history, get_backup_secret_sharing_settings,
+        set_backup_contacts, unwrap_backup_secret_share,
     },
 };
 
@@ -40,12 +43,17 @@ mod app_state;
 mod cli;
 mod commands;
 mod error;
+#[cfg(unix)]
+mod headless;
 mod logging;
 mod model;
 mod multipass;
 mod notifications;
 mod tasks;
 
+#[cfg(unix)]
+pub use headless::run_headless;
+
 fn fail_setup_with_message(app: &mut App, message: &str) -> Result<(), Box<dyn std::error::Error>> {
     app.dialog()
         .message(message)
@@ -58,8 +66,9 @@ fn fail_setup_with_message(app: &mut App, message: &str) -> Result<(), Box<dyn s
 
 fn handle_profiles(profiles_path: impl AsRef<Path>) -> anyhow::Result<Profiles> {
     let mut profiles = if profiles_path.as_ref().exists() {
-        let profiles_file = File::open(profiles_path.as_ref())?;
-        serde_json::from_reader::<File, Profiles>(profiles_file)?
+        // Read to a string first since keys can only be deserialized from borrowed strings
+        let profiles_json = std::fs::read_to_string(profiles_path.as_ref())?;
+        serde_json::from_str::<Profiles>(&profiles_json)?
     } else {
         Profiles::default()
     };
@@ -119,8 +128,11 @@ fn run_tauri(cli: Cli) {
             let app_name = format!("{} (Secure Messaging)", &app.package_info().name);
 
             let notifications = start_notification_service(app.app_handle(), app_name.clone());
-            let app_state =
-                AppStateHandle::new(app.handle().clone(), notifications, cli.no_background_tasks);
+            let app_state = AppStateHandle::new(
+                Arc::new(app.handle().clone()),
+                notifications,
+                cli.no_background_tasks,
+            );
 
             tracing_subscriber::registry()
                 .with(JournalistClientLogLayer::new(app_state.logs.clone()))
@@ -217,17 +229,27 @@ fn run_tauri(cli: Cli) {
             get_vault_state,
             get_users,
             get_chats,
+            get_inbox,
+            search_messages,
             unlock_vault,
+            get_open_vaults,
+            switch_vault,
+            close_vault,
             get_backup_checks,
             perform_backup,
             eject_backup_volume,
             get_backup_contacts,
+            get_backup_secret_sharing_settings,
             get_backup_history,
             set_backup_contacts,
             unwrap_backup_secret_share,
             get_colocated_password,
             get_profiles,
             submit_message,
+            get_drafts,
+            save_draft,
+            delete_draft,
+            hand_over_user,
             force_rotate_id_pk,
             force_rotate_msg_pk,
             get_public_info,
@@ -235,14 +257,19 @@ fn run_tauri(cli: Cli) {
             check_message_length,
             mark_as_read,
             mark_as_unread,
+            get_safety_number,
             set_custom_expiry,
             update_user_status,
             update_user_alias_and_description,
+            update_user_retention_policy,
+            get_max_message_retention_days,
             get_logging_sessions_timeline,
             get_logs,
+            export_logs,
             burst_cover_messages,
             get_trust_anchor_digests,
             get_vault_keys,
+            check_vault_integrity,
             launch_new_instance,
             send_notification,
             soft_lock_vault,
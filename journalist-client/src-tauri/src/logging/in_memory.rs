@@ -13,9 +13,9 @@ use tracing::Level;
 // That message contains another sender which allows the async task to bundle
 // up the messages and send the message receiver to be reused with the vault
 // based log receiver
-type ShutdownConfirmationData = (Vec<LogEntry>, UnboundedReceiver<LogEntry>);
-type ShutdownConfirmationSender = oneshot::Sender<ShutdownConfirmationData>;
-type ShutdownSender = oneshot::Sender<ShutdownConfirmationSender>;
+pub(super) type ShutdownConfirmationData = (Vec<LogEntry>, UnboundedReceiver<LogEntry>);
+pub(super) type ShutdownConfirmationSender = oneshot::Sender<ShutdownConfirmationData>;
+pub(super) type ShutdownSender = oneshot::Sender<ShutdownConfirmationSender>;
 
 /// An in memory log buffer used for the period before a user opens their vault.
 /// Should only ever contain a few hundred log entries at most with a low append rate.
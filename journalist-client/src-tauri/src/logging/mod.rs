@@ -1,7 +1,10 @@
 use std::{fmt, sync::Arc};
 
 use chrono::{DateTime, Utc};
-use common::time;
+use common::{
+    protocol::log_export::{ExportedLogs, LogScrubber},
+    time,
+};
 use in_memory::InMemoryLogBuffer;
 use journalist_vault::logging::LoggingSession;
 use journalist_vault::{logging::LogEntry, JournalistVault};
@@ -73,6 +76,40 @@ impl LogReceiver {
         Ok(())
     }
 
+    /// Stop writing logs to the vault they're currently written to, for example because it has
+    /// been closed. Logs are moved to `vault` if given, otherwise they're held in memory until
+    /// another vault is opened. Entries which haven't been written yet go to the new target.
+    pub async fn replace_vault(
+        &self,
+        vault: Option<&JournalistVault>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut inner = self.data.write().await;
+
+        let target = &mut inner.target;
+
+        let LogReceiverTarget::Vault(vault_logger) = target else {
+            return Ok(());
+        };
+
+        let (log_entries, rx) = vault_logger.shutdown().await?;
+
+        *target = match vault {
+            Some(vault) => {
+                LogReceiverTarget::Vault(VaultLogger::new(vault.clone(), rx, now).await?)
+            }
+            None => LogReceiverTarget::InMemory(InMemoryLogBuffer::new(rx)),
+        };
+
+        for log_entry in log_entries {
+            if let Err(e) = self.tx.send(log_entry) {
+                eprintln!("Failed to send log entry: {e}");
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn get_sessions_timeline(&self) -> anyhow::Result<Vec<LoggingSession>> {
         let inner = self.data.read().await;
 
@@ -119,6 +156,30 @@ impl LogReceiver {
 
         Ok(entries)
     }
+
+    /// Export the log entries written between `from` and `to` from the vault logs are being
+    /// written to. Logs which are only held in memory are never exported.
+    pub async fn export(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        scrubber: Option<&LogScrubber>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<ExportedLogs> {
+        let inner = self.data.read().await;
+
+        match &inner.target {
+            LogReceiverTarget::InMemory(_) => {
+                anyhow::bail!("Logs can only be exported once a vault has been opened")
+            }
+            LogReceiverTarget::Vault(vault_logger) => {
+                vault_logger
+                    .get_vault()
+                    .export_logs(from, to, scrubber, now)
+                    .await
+            }
+        }
+    }
 }
 
 pub struct JournalistClientLogLayer {
@@ -2,11 +2,18 @@ use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use journalist_vault::{logging::LogEntry, JournalistVault};
-use tokio::{sync::mpsc::UnboundedReceiver, task::JoinHandle, time::interval};
+use tokio::{
+    sync::{mpsc::UnboundedReceiver, oneshot},
+    task::JoinHandle,
+    time::interval,
+};
+
+use super::in_memory::{ShutdownConfirmationData, ShutdownConfirmationSender, ShutdownSender};
 
 pub struct VaultLogger {
     _writer_task_handle: JoinHandle<()>,
     vault: JournalistVault,
+    shutdown_tx: Option<ShutdownSender>,
 }
 
 impl VaultLogger {
@@ -16,6 +23,7 @@ impl VaultLogger {
         now: DateTime<Utc>,
     ) -> anyhow::Result<Self> {
         let session_id = vault.add_session(now).await?;
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<ShutdownConfirmationSender>();
 
         let _writer_task_handle = tokio::task::spawn({
             let write_handle_vault = vault.clone();
@@ -34,6 +42,12 @@ impl VaultLogger {
             async move {
                 loop {
                     tokio::select! {
+                        // Entries which haven't been flushed are handed over with the receiver
+                        // so they're written to whichever target replaces this vault
+                        Ok(confirmation_tx) = &mut shutdown_rx => {
+                            let _ = confirmation_tx.send((log_buf, rx));
+                            break;
+                        }
                         Some(log_entry) = rx.recv() => {
                             let entry_size = log_entry.size();
 
@@ -65,9 +79,26 @@ impl VaultLogger {
         Ok(Self {
             _writer_task_handle,
             vault,
+            shutdown_tx: Some(shutdown_tx),
         })
     }
 
+    /// Stop writing to the vault, returning the entries which haven't been written yet along
+    /// with the receiver so another target can take over
+    pub async fn shutdown(&mut self) -> anyhow::Result<ShutdownConfirmationData> {
+        let (confirmation_tx, confirmation_rx) = oneshot::channel();
+
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            if shutdown_tx.send(confirmation_tx).is_err() {
+                anyhow::bail!("Failed to send shutdown command to vault logger");
+            }
+
+            Ok(confirmation_rx.await?)
+        } else {
+            anyhow::bail!("Vault logging has already been shut down")
+        }
+    }
+
     pub fn get_vault(&self) -> JournalistVault {
         self.vault.clone()
     }
@@ -1,4 +1,6 @@
+use common::api::models::journalist_id::JournalistIdentity;
 use serde::Serialize;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use ts_rs::TS;
 
@@ -45,25 +47,62 @@ struct AlertPayload {
     message: String,
 }
 
+/// Payload for events about a single vault, since several vaults can be open at once
+#[derive(TS, Serialize, Clone)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+struct JournalistEventPayload<T> {
+    journalist_id: JournalistIdentity,
+    value: T,
+}
+
+impl<T> JournalistEventPayload<T> {
+    fn new(journalist_id: &JournalistIdentity, value: T) -> Self {
+        Self {
+            journalist_id: journalist_id.clone(),
+            value,
+        }
+    }
+}
+
 pub trait BackendToFrontendEvent {
-    fn emit_outbound_queue_length_event(&self, length: i64) -> anyhow::Result<()>;
+    fn emit_outbound_queue_length_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+        length: i64,
+    ) -> anyhow::Result<()>;
 
     fn emit_dead_drops_pull_started(&self) -> anyhow::Result<()>;
     fn emit_dead_drops_remaining_event(&self, count: usize) -> anyhow::Result<()>;
-    fn emit_journalist_keys_rotated_event(&self) -> anyhow::Result<()>;
+    fn emit_journalist_keys_rotated_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> anyhow::Result<()>;
     fn emit_automated_backup_started_event(&self) -> anyhow::Result<()>;
     fn emit_automated_backup_completed_event(&self) -> anyhow::Result<()>;
     fn emit_manual_backup_required_event(
         &self,
+        journalist_id: &JournalistIdentity,
         required: Option<BackupAttemptFailureReason>,
     ) -> anyhow::Result<()>;
 
     fn emit_alert_event(&self, level: AlertLevel, message: &str) -> anyhow::Result<()>;
 }
 
+/// Where background tasks and commands send their events. In Sentinel this is the Tauri app
+/// handle, the headless daemon has no frontend so it uses [LoggedEvents].
+pub type EventSink = Arc<dyn BackendToFrontendEvent + Send + Sync>;
+
 impl BackendToFrontendEvent for AppHandle {
-    fn emit_outbound_queue_length_event(&self, length: i64) -> anyhow::Result<()> {
-        self.emit(EventType::OutboundQueueLength.as_str(), length)?;
+    fn emit_outbound_queue_length_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+        length: i64,
+    ) -> anyhow::Result<()> {
+        self.emit(
+            EventType::OutboundQueueLength.as_str(),
+            JournalistEventPayload::new(journalist_id, length),
+        )?;
         Ok(())
     }
 
@@ -77,8 +116,14 @@ impl BackendToFrontendEvent for AppHandle {
         Ok(())
     }
 
-    fn emit_journalist_keys_rotated_event(&self) -> anyhow::Result<()> {
-        self.emit(EventType::JournalistKeysRotated.as_str(), None::<i32>)?;
+    fn emit_journalist_keys_rotated_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> anyhow::Result<()> {
+        self.emit(
+            EventType::JournalistKeysRotated.as_str(),
+            JournalistEventPayload::new(journalist_id, None::<i32>),
+        )?;
         Ok(())
     }
 
@@ -94,9 +139,13 @@ impl BackendToFrontendEvent for AppHandle {
 
     fn emit_manual_backup_required_event(
         &self,
+        journalist_id: &JournalistIdentity,
         required: Option<BackupAttemptFailureReason>,
     ) -> anyhow::Result<()> {
-        self.emit(EventType::ManualBackupRequired.as_str(), required)?;
+        self.emit(
+            EventType::ManualBackupRequired.as_str(),
+            JournalistEventPayload::new(journalist_id, required),
+        )?;
         Ok(())
     }
 
@@ -111,3 +160,64 @@ impl BackendToFrontendEvent for AppHandle {
         Ok(())
     }
 }
+
+/// Writes events to the log instead of sending them to a frontend
+pub struct LoggedEvents;
+
+impl BackendToFrontendEvent for LoggedEvents {
+    fn emit_outbound_queue_length_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+        length: i64,
+    ) -> anyhow::Result<()> {
+        tracing::debug!("Outbound queue length for {}: {}", journalist_id, length);
+        Ok(())
+    }
+
+    fn emit_dead_drops_pull_started(&self) -> anyhow::Result<()> {
+        tracing::debug!("Dead drop pull started");
+        Ok(())
+    }
+
+    fn emit_dead_drops_remaining_event(&self, count: usize) -> anyhow::Result<()> {
+        tracing::debug!("Dead drops remaining: {}", count);
+        Ok(())
+    }
+
+    fn emit_journalist_keys_rotated_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+    ) -> anyhow::Result<()> {
+        tracing::info!("Journalist keys rotated for {}", journalist_id);
+        Ok(())
+    }
+
+    fn emit_automated_backup_started_event(&self) -> anyhow::Result<()> {
+        tracing::info!("Automated backup started");
+        Ok(())
+    }
+
+    fn emit_automated_backup_completed_event(&self) -> anyhow::Result<()> {
+        tracing::info!("Automated backup completed");
+        Ok(())
+    }
+
+    fn emit_manual_backup_required_event(
+        &self,
+        journalist_id: &JournalistIdentity,
+        required: Option<BackupAttemptFailureReason>,
+    ) -> anyhow::Result<()> {
+        if let Some(reason) = required {
+            tracing::warn!("Manual backup required for {}: {:?}", journalist_id, reason);
+        }
+        Ok(())
+    }
+
+    fn emit_alert_event(&self, level: AlertLevel, message: &str) -> anyhow::Result<()> {
+        match level {
+            AlertLevel::Warning => tracing::warn!("{}", message),
+            AlertLevel::Error => tracing::error!("{}", message),
+        }
+        Ok(())
+    }
+}
@@ -10,6 +10,15 @@ pub struct BackupChecks {
     pub maybe_existing_backups: Option<Vec<String>>,
 }
 
+/// The k-of-n secret sharing settings published by the backup admins. Automated backups are
+/// split between `n` recovery contacts and any `k` of them can help restore the vault.
+#[derive(Serialize, TS)]
+#[ts(export)]
+pub struct BackupSecretSharingSettings {
+    pub k: u8,
+    pub n: u8,
+}
+
 #[derive(Clone, Serialize, Debug, PartialEq, TS)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[ts(export)]
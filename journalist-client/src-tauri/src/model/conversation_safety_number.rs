@@ -0,0 +1,16 @@
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConversationSafetyNumber {
+    pub digits: String,
+    pub words: String,
+}
+
+impl ConversationSafetyNumber {
+    pub fn new(digits: String, words: String) -> Self {
+        Self { digits, words }
+    }
+}
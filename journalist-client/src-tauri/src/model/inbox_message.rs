@@ -0,0 +1,13 @@
+use common::api::models::journalist_id::JournalistIdentity;
+use journalist_vault::VaultMessage;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// A message from one of the open vaults, tagged with the journalist whose vault it's from
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct InboxMessage {
+    pub journalist_id: JournalistIdentity,
+    pub message: VaultMessage,
+}
@@ -1,13 +1,17 @@
 mod backend_to_frontend_events;
 mod backup;
+mod conversation_safety_number;
+mod inbox_message;
 mod open_vault_outcome;
 mod profile;
 mod trusted_org_pk_and_digest;
 mod user;
 mod vault_state;
 
-pub use backend_to_frontend_events::{AlertLevel, BackendToFrontendEvent};
-pub use backup::{BackupAttemptFailureReason, BackupChecks};
+pub use backend_to_frontend_events::{AlertLevel, BackendToFrontendEvent, EventSink, LoggedEvents};
+pub use backup::{BackupAttemptFailureReason, BackupChecks, BackupSecretSharingSettings};
+pub use conversation_safety_number::ConversationSafetyNumber;
+pub use inbox_message::InboxMessage;
 pub use open_vault_outcome::OpenVaultOutcome;
 pub use profile::Profiles;
 pub use trusted_org_pk_and_digest::TrustedOrganizationPublicKeyAndDigest;
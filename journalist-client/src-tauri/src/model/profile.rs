@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use common::{crypto::keys::encryption::PublicEncryptionKey, protocol::roles::SupportRecipient};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -10,6 +11,10 @@ use ts_rs::TS;
 pub struct Profile {
     #[ts(as = "String")]
     pub api_url: Url,
+    /// The key exported logs are encrypted to so they can be attached to support tickets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(as = "Option<String>", optional)]
+    pub support_pk: Option<PublicEncryptionKey<SupportRecipient>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, TS)]
@@ -17,11 +22,22 @@ pub struct Profile {
 pub struct Profiles(HashMap<String, Profile>);
 
 impl Profiles {
+    /// Add a profile or update the API URL of an existing one, keeping its other settings
     pub fn insert(&mut self, stage: impl Into<String>, url: Url) {
-        self.0.insert(stage.into(), Profile { api_url: url });
+        self.0
+            .entry(stage.into())
+            .and_modify(|p| p.api_url = url.clone())
+            .or_insert(Profile {
+                api_url: url,
+                support_pk: None,
+            });
     }
 
     pub fn api_url(&self, profile_name: &str) -> Option<&Url> {
         self.0.get(profile_name).map(|p| &p.api_url)
     }
+
+    pub fn support_pk(&self, profile_name: &str) -> Option<&PublicEncryptionKey<SupportRecipient>> {
+        self.0.get(profile_name).and_then(|p| p.support_pk.as_ref())
+    }
 }
@@ -3,6 +3,7 @@ use common::{
     client::mailbox::mailbox_message, crypto::keys::encryption::PublicEncryptionKey,
     generators::NameGenerator,
 };
+use journalist_vault::RetentionPolicy;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -33,6 +34,7 @@ pub struct User {
     alias: Option<String>,
     description: Option<String>,
     marked_as_unread: bool,
+    retention_policy: RetentionPolicy,
 }
 
 impl User {
@@ -43,6 +45,7 @@ impl User {
         alias: Option<String>,
         description: Option<String>,
         marked_as_unread: bool,
+        retention_policy: RetentionPolicy,
     ) -> Self {
         let display_name = name_generator.name_from_bytes(user_pk.key.as_bytes(), 2);
         Self {
@@ -52,6 +55,7 @@ impl User {
             alias,
             description,
             marked_as_unread,
+            retention_policy,
         }
     }
 }
@@ -1,3 +1,4 @@
+use common::api::models::journalist_id::JournalistIdentity;
 use tauri::AppHandle;
 use tauri_plugin_notification::NotificationExt as _;
 use tokio::sync::mpsc::{channel, Sender};
@@ -11,8 +12,13 @@ pub struct NotificationRequest {
 pub struct Notifications(Sender<NotificationRequest>);
 
 impl Notifications {
-    pub async fn send_with_default_title(&self, body: impl Into<String>) {
-        self.send(None, body).await
+    /// Send a notification about one of the open vaults, titled with the journalist's identity
+    pub async fn send_for_journalist(
+        &self,
+        journalist_id: &JournalistIdentity,
+        body: impl Into<String>,
+    ) {
+        self.send(Some(journalist_id.to_string()), body).await
     }
 
     /// Request a notification, if it fails to send then we log an error.
@@ -66,3 +72,20 @@ pub fn start_notification_service(app_handle: &AppHandle, default_title: String)
 
     Notifications(tx)
 }
+
+/// Notifications for the headless daemon, which has no desktop to show them on so they are
+/// written to the log instead. Must be called from within a Tokio runtime.
+pub fn start_logged_notification_service() -> Notifications {
+    let (tx, mut rx) = channel::<NotificationRequest>(100);
+
+    tokio::spawn(async move {
+        while let Some(req) = rx.recv().await {
+            match req.maybe_title {
+                Some(title) => tracing::info!("Notification for {}: {}", title, req.body),
+                None => tracing::info!("Notification: {}", req.body),
+            }
+        }
+    });
+
+    Notifications(tx)
+}
@@ -1,16 +1,15 @@
 use crate::{
     app_state::PublicInfo,
-    model::{AlertLevel, BackendToFrontendEvent, BackupAttemptFailureReason},
+    model::{AlertLevel, BackendToFrontendEvent, BackupAttemptFailureReason, EventSink},
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use common::{
-    api::api_client::ApiClient,
-    backup::constants::BACKUP_DATA_MAX_SIZE_BYTES,
-    protocol::{
-        backup::{sentinel_create_backup, sentinel_put_backup_data_to_s3, RecoveryContact},
-        constants::{SECRET_SHARING_K_VALUE, SECRET_SHARING_N_VALUE},
+    api::{api_client::ApiClient, models::journalist_id::JournalistIdentity},
+    backup::{
+        constants::BACKUP_DATA_MAX_SIZE_BYTES, secret_sharing_settings::SecretSharingSettings,
     },
+    protocol::backup::{sentinel_create_backup, sentinel_put_backup_data_to_s3, RecoveryContact},
     task::Task,
     time,
 };
@@ -21,7 +20,6 @@ use std::{
     fs,
     path::{Path, PathBuf},
 };
-use tauri::AppHandle;
 use tokio::sync::Mutex;
 
 const NUM_FAILED_ATTEMPTS_BEFORE_ALERT: u32 = 5;
@@ -33,19 +31,22 @@ pub struct BackupManager {
     // It will be necessary if we decide to trigger a backup elsewhere, e.g. after a forced key rotation.
     lock: Arc<Mutex<()>>,
     num_failed_attempts: Arc<Mutex<u32>>,
+    /// The journalist whose vault is backed up, included in events sent to the frontend
+    journalist_id: JournalistIdentity,
 }
 
 impl BackupManager {
-    pub fn new() -> Self {
+    pub fn new(journalist_id: &JournalistIdentity) -> Self {
         Self {
             lock: Arc::new(Mutex::new(())),
             num_failed_attempts: Arc::new(Mutex::new(0)),
+            journalist_id: journalist_id.clone(),
         }
     }
 
     async fn increment_failed_attempts(
         &self,
-        app_handle: &AppHandle,
+        events: &EventSink,
         failure_reason: BackupAttemptFailureReason,
     ) -> anyhow::Result<()> {
         let mut failed_attempts = self.num_failed_attempts.lock().await;
@@ -55,7 +56,7 @@ impl BackupManager {
             *failed_attempts
         );
         if *failed_attempts >= NUM_FAILED_ATTEMPTS_BEFORE_ALERT {
-            app_handle.emit_manual_backup_required_event(Some(failure_reason))?;
+            events.emit_manual_backup_required_event(&self.journalist_id, Some(failure_reason))?;
             tracing::warn!(
                 "Automated backup has failed {} times, manual backup required",
                 *failed_attempts
@@ -66,7 +67,7 @@ impl BackupManager {
 
     pub async fn perform_backup(
         &self,
-        app_handle: &AppHandle,
+        events: &EventSink,
         api_client: &ApiClient,
         vault: &JournalistVault,
         vault_path: &Path,
@@ -76,30 +77,30 @@ impl BackupManager {
         // Try to acquire the lock
         if let Ok(_guard) = self.lock.try_lock() {
             // tell frontend we're starting the backup
-            app_handle.emit_automated_backup_started_event()?;
+            events.emit_automated_backup_started_event()?;
 
             let backup_result = self
-                .perform_backup_inner(app_handle, api_client, vault, vault_path, public_info, now)
+                .perform_backup_inner(events, api_client, vault, vault_path, public_info, now)
                 .await;
             match backup_result {
                 Ok(failure_reason) => match failure_reason {
                     None => {
-                        app_handle.emit_manual_backup_required_event(None)?;
+                        events.emit_manual_backup_required_event(&self.journalist_id, None)?;
                         let mut failed_attempts = self.num_failed_attempts.lock().await;
                         *failed_attempts = 0;
                     }
                     Some(reason) => {
-                        self.increment_failed_attempts(app_handle, reason).await?;
+                        self.increment_failed_attempts(events, reason).await?;
                     }
                 },
                 Err(e) => {
                     tracing::error!("Automated backup failed: {:?}", e);
-                    self.increment_failed_attempts(app_handle, BackupAttemptFailureReason::Unknown)
+                    self.increment_failed_attempts(events, BackupAttemptFailureReason::Unknown)
                         .await?;
                 }
             }
             // tell frontend the backup is complete
-            app_handle.emit_automated_backup_completed_event()?;
+            events.emit_automated_backup_completed_event()?;
             Ok(())
         } else {
             tracing::info!("Automated backup already in progress, skipping this run");
@@ -112,7 +113,7 @@ impl BackupManager {
     /// Ok(Some(reason)) if the backup failed for a known reason,
     async fn perform_backup_inner(
         &self,
-        app_handle: &AppHandle,
+        events: &EventSink,
         api_client: &ApiClient,
         vault: &JournalistVault,
         vault_path: &Path,
@@ -136,7 +137,7 @@ impl BackupManager {
         } else if vault_size_bytes
             > (BACKUP_DATA_MAX_SIZE_BYTES * vault_size_warning_threshold_percentage / 100)
         {
-            app_handle.emit_alert_event(
+            events.emit_alert_event(
                 AlertLevel::Warning,
                 "Vault size is approaching maximum backup size.",
             )?;
@@ -157,6 +158,9 @@ impl BackupManager {
                     )
                 })?;
 
+            // The settings are signed by the backup admins so the API cannot weaken them
+            let SecretSharingSettings { k, n } = public_info.secret_sharing_settings();
+
             let recovery_contact_journalist_ids = vault.get_backup_contacts().await?;
             let recovery_contacts = recovery_contact_journalist_ids
                 .iter()
@@ -167,32 +171,33 @@ impl BackupManager {
                         latest_messaging_key: latest_messaging_key.clone(),
                     })
                 })
+                .take(n.into())
                 .collect::<Vec<_>>();
 
             let num_recovery_contacts_selected = recovery_contact_journalist_ids.len();
             let num_recovery_contacts_with_keys = recovery_contacts.len();
-            if num_recovery_contacts_selected < SECRET_SHARING_K_VALUE {
+            if num_recovery_contacts_selected < k.into() {
                 tracing::warn!(
                     "Fewer than {} recovery contacts found when trying to create backup.",
-                    SECRET_SHARING_K_VALUE
+                    k
                 );
                 return Ok(Some(
                     BackupAttemptFailureReason::InsufficientRecoveryContactsSelected,
                 ));
-            } else if num_recovery_contacts_with_keys < SECRET_SHARING_K_VALUE {
+            } else if num_recovery_contacts_with_keys < k.into() {
                 tracing::warn!(
                     "Fewer than {} recovery contacts with valid keys found when trying to create backup.",
-                    SECRET_SHARING_K_VALUE
+                    k
                 );
                 return Ok(Some(
                     BackupAttemptFailureReason::InsufficientRecoveryContactsWithValidKeys,
                 ));
-            } else if num_recovery_contacts_with_keys < SECRET_SHARING_N_VALUE {
+            } else if num_recovery_contacts_with_keys < n.into() {
                 tracing::info!(
                     "Number of recovery contacts with valid keys ({}) is less than the total number of shares to create ({}) but greater than the number required to restore ({}). Continuing with backup.",
                     num_recovery_contacts_with_keys,
-                    SECRET_SHARING_N_VALUE,
-                    SECRET_SHARING_K_VALUE
+                    n,
+                    k
                 );
             } else {
                 tracing::info!(
@@ -201,14 +206,19 @@ impl BackupManager {
                 );
             }
 
-            tracing::info!("Attempting to create automated backup");
+            let recovery_contact_journalist_ids = recovery_contacts
+                .iter()
+                .map(|contact| contact.identity.clone())
+                .collect();
+
+            tracing::info!("Attempting to create {}-of-{} automated backup", k, n);
             let verified_backup_data = sentinel_create_backup(
                 encrypted_vault,
                 journalist_identity,
                 journalist_identity_key.clone(),
                 backup_admin_encryption_key,
                 recovery_contacts,
-                SECRET_SHARING_K_VALUE.try_into().unwrap(),
+                k,
                 now,
             )?;
 
@@ -229,7 +239,7 @@ impl BackupManager {
                 .record_automated_backup(now, recovery_contact_journalist_ids)
                 .await?;
 
-            app_handle.emit_automated_backup_completed_event()?;
+            events.emit_automated_backup_completed_event()?;
         }
 
         tracing::info!("Automated backup complete");
@@ -240,7 +250,7 @@ impl BackupManager {
 
 pub struct AutomatedBackups {
     backup_manager: BackupManager,
-    app_handle: AppHandle,
+    events: EventSink,
     api_client: ApiClient,
     vault: JournalistVault,
     vault_path: PathBuf,
@@ -250,7 +260,7 @@ pub struct AutomatedBackups {
 impl AutomatedBackups {
     pub fn new(
         backup_manager: &BackupManager,
-        app_handle: &AppHandle,
+        events: &EventSink,
         api_client: &ApiClient,
         vault: &JournalistVault,
         vault_path: &Path,
@@ -258,7 +268,7 @@ impl AutomatedBackups {
     ) -> Self {
         Self {
             backup_manager: backup_manager.clone(),
-            app_handle: app_handle.clone(),
+            events: events.clone(),
             api_client: api_client.clone(),
             vault: vault.clone(),
             vault_path: vault_path.to_path_buf(),
@@ -289,7 +299,7 @@ impl Task for AutomatedBackups {
 
         self.backup_manager
             .perform_backup(
-                &self.app_handle,
+                &self.events,
                 &self.api_client,
                 &self.vault,
                 &self.vault_path,
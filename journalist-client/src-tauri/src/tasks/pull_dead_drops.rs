@@ -4,17 +4,18 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use chrono::Duration;
 use common::{
-    client::mailbox::mailbox_message::UserStatus, protocol::keys::UserPublicKey, task::Task, time,
+    api::models::journalist_id::JournalistIdentity, client::mailbox::mailbox_message::UserStatus,
+    protocol::keys::UserPublicKey, task::Task, time,
 };
-use tauri::AppHandle;
 
 use crate::{app_state::PublicInfo, notifications::Notifications};
 use coverdrop_service::JournalistCoverDropService;
 
-use crate::model::BackendToFrontendEvent;
+use crate::model::{BackendToFrontendEvent, EventSink};
 
 pub struct PullDeadDrops {
-    app_handle: AppHandle,
+    events: EventSink,
+    journalist_id: JournalistIdentity,
     coverdrop_service: Arc<JournalistCoverDropService>,
     notifications: Notifications,
     public_info: PublicInfo,
@@ -22,13 +23,15 @@ pub struct PullDeadDrops {
 
 impl PullDeadDrops {
     pub fn new(
-        app_handle: &AppHandle,
+        events: &EventSink,
+        journalist_id: &JournalistIdentity,
         coverdrop_service: &Arc<JournalistCoverDropService>,
         notifications: &Notifications,
         public_info: &PublicInfo,
     ) -> Self {
         Self {
-            app_handle: app_handle.clone(),
+            events: events.clone(),
+            journalist_id: journalist_id.clone(),
             coverdrop_service: coverdrop_service.clone(),
             notifications: notifications.clone(),
             public_info: public_info.clone(),
@@ -50,14 +53,14 @@ impl Task for PullDeadDrops {
             return Ok(());
         };
 
-        self.app_handle.emit_dead_drops_pull_started()?;
+        self.events.emit_dead_drops_pull_started()?;
 
         let decrypted_messages = self
             .coverdrop_service
             .pull_and_decrypt_dead_drops(
                 public_info,
                 Some(move |remaining| {
-                    let _ = self.app_handle.emit_dead_drops_remaining_event(remaining);
+                    let _ = self.events.emit_dead_drops_remaining_event(remaining);
                 }),
                 time::now(),
             )
@@ -78,6 +81,8 @@ impl Task for PullDeadDrops {
 
             let num_messages_from_active_users = decrypted_messages
                 .iter()
+                .filter(|m| m.u2j_message.key_rotation.is_none())
+                .filter(|m| m.u2j_message.read_receipt.is_none())
                 .filter(|m| active_users.contains(&&m.u2j_message.reply_key))
                 .count();
 
@@ -88,8 +93,9 @@ impl Task for PullDeadDrops {
                     format!("Received {} messages", num_messages_from_active_users)
                 };
 
+                // Several vaults can be open at once so say which one the messages are for
                 self.notifications
-                    .send_with_default_title(notification_message)
+                    .send_for_journalist(&self.journalist_id, notification_message)
                     .await;
             }
         }
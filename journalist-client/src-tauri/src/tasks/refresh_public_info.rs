@@ -36,14 +36,35 @@ impl Task for RefreshPublicInfo {
 
         tracing::debug!("Getting public info from API {:?}", self.api_client);
 
-        let public_info = self
+        let mut public_info = self
             .api_client
             .get_public_keys()
             .await?
-            .into_trusted(&anchor_org_pks, time::now());
+            .into_trusted(&anchor_org_pks, time::now())?;
+
+        // Reject the response if it shows a different view of the key transparency log to the
+        // one this vault has seen before
+        let mut known_tree_heads = self.vault.known_tree_heads().await?;
+        public_info
+            .check_tree_head_with_api(&self.api_client, &mut known_tree_heads)
+            .await?;
+        self.vault.set_known_tree_heads(&known_tree_heads).await?;
+
+        // Settings older than the ones the vault has already accepted are ignored, so the API
+        // can't roll backups back to settings the backup admins have replaced
+        public_info.backup_secret_sharing = self
+            .vault
+            .update_backup_secret_sharing_settings(public_info.backup_secret_sharing.as_ref())
+            .await?;
 
         let api_journalist_profiles = public_info.journalist_profiles.clone();
 
+        // Keep the retention limit in the vault so it's enforced even when Sentinel is offline.
+        // It's only changed by a signed limit newer than the one the vault already has.
+        self.vault
+            .update_message_retention_limit(public_info.message_retention_limit.as_ref())
+            .await?;
+
         tracing::debug!("Setting public info");
 
         self.public_info.set(public_info).await;
@@ -3,17 +3,25 @@ use std::{
     sync::Arc,
 };
 
-use crate::{app_state::PublicInfo, model::BackendToFrontendEvent, tasks::BackupManager};
+use crate::{
+    app_state::PublicInfo,
+    model::{BackendToFrontendEvent, EventSink},
+    tasks::BackupManager,
+};
 use async_trait::async_trait;
 use chrono::Duration;
-use common::{api::api_client::ApiClient, task::Task, time};
+use common::{
+    api::{api_client::ApiClient, models::journalist_id::JournalistIdentity},
+    task::Task,
+    time,
+};
 use coverdrop_service::JournalistCoverDropService;
 use journalist_vault::JournalistVault;
-use tauri::AppHandle;
 
 pub struct RotateJournalistKeys {
     backup_manager: BackupManager,
-    app_handle: AppHandle,
+    events: EventSink,
+    journalist_id: JournalistIdentity,
     api_client: ApiClient,
     vault: JournalistVault,
     vault_path: PathBuf,
@@ -24,7 +32,8 @@ pub struct RotateJournalistKeys {
 impl RotateJournalistKeys {
     pub fn new(
         backup_manager: &BackupManager,
-        app_handle: &AppHandle,
+        events: &EventSink,
+        journalist_id: &JournalistIdentity,
         api_client: &ApiClient,
         vault: &JournalistVault,
         vault_path: &Path,
@@ -32,7 +41,8 @@ impl RotateJournalistKeys {
     ) -> Self {
         Self {
             backup_manager: backup_manager.clone(),
-            app_handle: app_handle.clone(),
+            events: events.clone(),
+            journalist_id: journalist_id.clone(),
             api_client: api_client.clone(),
             vault: vault.clone(),
             vault_path: vault_path.to_path_buf(),
@@ -54,12 +64,13 @@ impl Task for RotateJournalistKeys {
         let did_rotate_some_keys = self.coverdrop_service.check_and_rotate_keys(now).await?;
 
         if did_rotate_some_keys {
-            self.app_handle.emit_journalist_keys_rotated_event()?;
+            self.events
+                .emit_journalist_keys_rotated_event(&self.journalist_id)?;
 
             tracing::info!("Rotated journalist keys, performing automated backup");
             self.backup_manager
                 .perform_backup(
-                    &self.app_handle,
+                    &self.events,
                     &self.api_client,
                     &self.vault,
                     &self.vault_path,
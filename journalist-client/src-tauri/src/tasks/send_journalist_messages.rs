@@ -2,30 +2,32 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::Duration;
-use common::{task::Task, time};
+use common::{api::models::journalist_id::JournalistIdentity, task::Task, time};
 use coverdrop_service::JournalistCoverDropService;
-use tauri::AppHandle;
 
 use crate::app_state::PublicInfo;
 
-use crate::model::BackendToFrontendEvent;
+use crate::model::{BackendToFrontendEvent, EventSink};
 
 pub struct SendJournalistMessages {
+    journalist_id: JournalistIdentity,
     coverdrop_service: Arc<JournalistCoverDropService>,
     public_info: PublicInfo,
-    app_handle: AppHandle,
+    events: EventSink,
 }
 
 impl SendJournalistMessages {
     pub fn new(
+        journalist_id: &JournalistIdentity,
         coverdrop_service: &Arc<JournalistCoverDropService>,
         public_info: &PublicInfo,
-        app_handle: &AppHandle,
+        events: &EventSink,
     ) -> Self {
         Self {
+            journalist_id: journalist_id.clone(),
             coverdrop_service: coverdrop_service.clone(),
             public_info: public_info.clone(),
-            app_handle: app_handle.clone(),
+            events: events.clone(),
         }
     }
 }
@@ -52,8 +54,8 @@ impl Task for SendJournalistMessages {
                 .coverdrop_service
                 .dequeue_and_send_j2u_message(&public_info.keys, time::now())
                 .await?;
-            self.app_handle
-                .emit_outbound_queue_length_event(queue_length)?;
+            self.events
+                .emit_outbound_queue_length_event(&self.journalist_id, queue_length)?;
         }
 
         Ok(())
@@ -20,7 +20,13 @@ import { useErrorStore } from "./state/errors";
 import { useMessageStore } from "./state/messages";
 import { applyPalette, ColorMode } from "./styles/palette";
 import { useUserStore } from "./state/users";
-import { getChats, getUsers, markAsUnread } from "./commands/chats";
+import { useDraftStore } from "./state/drafts";
+import {
+  getChats,
+  getDrafts,
+  getUsers,
+  markAsUnread,
+} from "./commands/chats";
 import { MuteToggleModal } from "./components/MuteToggleModal.tsx";
 import { EditUserModal } from "./components/EditUserModal.tsx";
 import { CopyToClipboardModal } from "./components/CopyToClipboardModal.tsx";
@@ -60,6 +66,7 @@ const App = ({
 
   const messageStore = useMessageStore();
   const userStore = useUserStore();
+  const draftStore = useDraftStore();
   const publicInfoStore = usePublicInfoStore();
 
   useEffect(() => {
@@ -225,6 +232,8 @@ const App = ({
     }
 
     fetchUsersAndChats();
+    // Drafts are only loaded once since they're edited locally after that
+    getDrafts().then(draftStore.setDrafts);
     const intervalId = setInterval(fetchUsersAndChats, 5000);
 
     return () => clearInterval(intervalId);
@@ -387,6 +396,7 @@ const App = ({
 
             <ManualBackupModal
               isOpen={isBackupModalOpen}
+              journalistId={vaultState.id}
               vaultPath={vaultState.path}
               setIsBackupModalOpen={setIsBackupModalOpen}
               addCustomToast={addCustomToast}
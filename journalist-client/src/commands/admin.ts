@@ -4,6 +4,7 @@ import { UntrustedKeysAndJournalistProfiles } from "../model/bindings/UntrustedK
 import { invokeWithErrorMessage } from "./invokeWithErrorMessage";
 import { LoggingSession } from "../model/bindings/LoggingSession.ts";
 import { LogEntry } from "../model/bindings/LogEntry.ts";
+import { IntegrityReport } from "../model/bindings/IntegrityReport.ts";
 import { ask } from "@tauri-apps/plugin-dialog";
 
 export const updateJournalistStatus = (
@@ -39,10 +40,28 @@ export const getLoggingSessionsTimeline = (): Promise<LoggingSession[]> => {
   return invokeWithErrorMessage("get_logging_sessions_timeline");
 };
 
+export const exportLogs = (params: {
+  from: Date;
+  to: Date;
+  path: string;
+  scrub?: boolean;
+}): Promise<void> => {
+  return invokeWithErrorMessage("export_logs", {
+    ...params,
+    scrub: params.scrub ?? true,
+  });
+};
+
 export const getVaultKeys = (): Promise<string> => {
   return invokeWithErrorMessage("get_vault_keys");
 };
 
+export const checkVaultIntegrity = (
+  repair: boolean,
+): Promise<IntegrityReport> => {
+  return invokeWithErrorMessage("check_vault_integrity", { repair });
+};
+
 export const getTrustAnchorDigests = (): Promise<
   TrustedOrganizationPublicKeyAndDigest[]
 > => {
@@ -1,5 +1,6 @@
 import { BackupChecks } from "../model/bindings/BackupChecks";
 import { BackupHistoryEntry } from "../model/bindings/BackupHistoryEntry";
+import { BackupSecretSharingSettings } from "../model/bindings/BackupSecretSharingSettings";
 import { JournalistIdentity } from "../model/bindings/JournalistIdentity";
 import { invokeWithErrorMessage } from "./invokeWithErrorMessage";
 
@@ -19,6 +20,11 @@ export const getBackupContacts = (): Promise<JournalistIdentity[]> => {
   return invokeWithErrorMessage("get_backup_contacts");
 };
 
+export const getBackupSecretSharingSettings =
+  (): Promise<BackupSecretSharingSettings> => {
+    return invokeWithErrorMessage("get_backup_secret_sharing_settings");
+  };
+
 export const getBackupHistory = (): Promise<BackupHistoryEntry[]> => {
   return invokeWithErrorMessage("get_backup_history");
 };
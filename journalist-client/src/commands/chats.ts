@@ -1,4 +1,8 @@
+import { ConversationSafetyNumber } from "../model/bindings/ConversationSafetyNumber";
+import { Draft } from "../model/bindings/Draft";
+import { InboxMessage } from "../model/bindings/InboxMessage";
 import { Message } from "../model/bindings/Message";
+import { RetentionPolicy } from "../model/bindings/RetentionPolicy";
 import { User } from "../model/bindings/User";
 import { UserStatus } from "../model/bindings/UserStatus";
 import { invokeWithErrorMessage } from "./invokeWithErrorMessage";
@@ -11,13 +15,46 @@ export const getChats = (): Promise<Message[]> => {
   return invokeWithErrorMessage("get_chats");
 };
 
+export const getInbox = (): Promise<InboxMessage[]> => {
+  return invokeWithErrorMessage("get_inbox");
+};
+
+export const searchMessages = (query: string): Promise<Message[]> => {
+  return invokeWithErrorMessage("search_messages", { query });
+};
+
 export const submitMessage = (
   replyKey: string,
   message: string,
+  sendAfter: string | null = null,
 ): Promise<void> => {
   return invokeWithErrorMessage("submit_message", {
     replyKey,
     message,
+    sendAfter,
+  });
+};
+
+export const getDrafts = (): Promise<Draft[]> => {
+  return invokeWithErrorMessage("get_drafts");
+};
+
+export const saveDraft = async (
+  replyKey: string,
+  message: string,
+  sendAfter: string | null = null,
+): Promise<void> => {
+  await invokeWithErrorMessage("save_draft", { replyKey, message, sendAfter });
+};
+
+export const deleteDraft = async (replyKey: string): Promise<void> => {
+  await invokeWithErrorMessage("delete_draft", { replyKey });
+};
+
+export const handOverUser = (replyKey: string, to: string): Promise<void> => {
+  return invokeWithErrorMessage("hand_over_user", {
+    replyKey,
+    to,
   });
 };
 
@@ -33,6 +70,12 @@ export const markAsUnread = async (replyKey: string): Promise<void> => {
   await invokeWithErrorMessage("mark_as_unread", { replyKey });
 };
 
+export const getSafetyNumber = (
+  replyKey: string,
+): Promise<ConversationSafetyNumber> => {
+  return invokeWithErrorMessage("get_safety_number", { replyKey });
+};
+
 export const setCustomExpiry = async (
   message: Message,
   customExpiry: string | null,
@@ -50,6 +93,20 @@ export const updateUserStatus = async (
   await invokeWithErrorMessage("update_user_status", { replyKey, status });
 };
 
+export const updateUserRetentionPolicy = async (
+  replyKey: string,
+  retentionPolicy: RetentionPolicy,
+): Promise<void> => {
+  await invokeWithErrorMessage("update_user_retention_policy", {
+    replyKey,
+    retentionPolicy,
+  });
+};
+
+export const getMaxMessageRetentionDays = async (): Promise<number> => {
+  return invokeWithErrorMessage("get_max_message_retention_days");
+};
+
 export const updateUserAliasAndDescription = async (
   replyKey: string,
   alias: string,
@@ -29,6 +29,20 @@ export const unlockVault = async (
   }
 };
 
+export const getOpenVaults = (): Promise<VaultState[]> => {
+  return invokeWithErrorMessage("get_open_vaults");
+};
+
+export const switchVault = (
+  journalistId: string,
+): Promise<VaultState | null> => {
+  return invokeWithErrorMessage("switch_vault", { journalistId });
+};
+
+export const closeVault = (journalistId: string): Promise<VaultState | null> => {
+  return invokeWithErrorMessage("close_vault", { journalistId });
+};
+
 export const softLockVault = (): Promise<VaultState | null> => {
   return invokeWithErrorMessage("soft_lock_vault");
 };
@@ -35,6 +35,7 @@ const u2jMessage = {
   normalExpiry: normalExpiry,
   customExpiry: null,
   read: true,
+  isPartial: false,
 } satisfies Partial<Message>;
 
 const j2uMessage = {
@@ -46,6 +47,8 @@ const j2uMessage = {
   customExpiry: null,
   isSent: true,
   sentAt: date,
+  handOverTo: null,
+  isPartial: false,
 } satisfies Partial<Message>;
 
 const mockU2JMessage = (
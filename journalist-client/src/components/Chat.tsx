@@ -5,6 +5,7 @@ import {
   EuiButtonIcon,
   EuiFlexGroup,
   EuiFlexItem,
+  EuiText,
   EuiTextArea,
   useEuiTheme,
 } from "@elastic/eui";
@@ -245,7 +246,16 @@ export const Chat = ({
                         borderRadius: size.s,
                       }}
                     >
-                      {m.message}
+                      {m.type === "journalistToUserMessage" && m.handOverTo
+                        ? `Conversation handed over to ${m.handOverTo}`
+                        : m.message}
+                      {m.isPartial && (
+                        <EuiText size="xs" color="subdued">
+                          <em>
+                            Part of this message has not been received yet
+                          </em>
+                        </EuiText>
+                      )}
                     </div>
                     <PerMessageMenu
                       isOpen={
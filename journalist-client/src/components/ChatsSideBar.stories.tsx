@@ -49,6 +49,7 @@ const u2jMessage = {
   normalExpiry: normalExpiry,
   customExpiry: null,
   read: true,
+  isPartial: false,
 } satisfies Message;
 
 const users = [
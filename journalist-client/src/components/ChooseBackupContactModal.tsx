@@ -11,11 +11,15 @@ import {
 } from "@elastic/eui";
 import { usePublicInfoStore } from "../state/publicInfo";
 import { useEffect, useState } from "react";
-import { getBackupContacts, setBackupContacts } from "../commands/backups";
+import {
+  getBackupContacts,
+  getBackupSecretSharingSettings,
+  setBackupContacts,
+} from "../commands/backups";
+import { BackupSecretSharingSettings } from "../model/bindings/BackupSecretSharingSettings";
 import { JournalistIdentity } from "../model/bindings/JournalistIdentity";
 import { Toast } from "@elastic/eui/src/components/toast/global_toast_list";
 import { SetBackupContactReminderToastBody } from "./SetBackupContactReminderToastBody";
-import { SECRET_SHARING_N_VALUE } from "../constants";
 
 type ChooseBackupContactModalProps = {
   isOpen: boolean;
@@ -34,17 +38,24 @@ export const ChooseBackupContactModal = ({
   openModal,
   closeModal,
 }: ChooseBackupContactModalProps) => {
-  const [selectedBackupContact, setSelectedBackupContact] =
-    useState<JournalistIdentity | null>();
+  const [secretSharingSettings, setSecretSharingSettings] =
+    useState<BackupSecretSharingSettings | null>(null);
+  const [selectedBackupContacts, setSelectedBackupContacts] = useState<
+    JournalistIdentity[]
+  >([]);
   const [
     shouldRequireSettingBackupContact,
     setShouldRequireSettingBackupContact,
   ] = useState<boolean>(false);
 
   const refreshIsSettingBackupContactRequired = () =>
-    getBackupContacts().then((backupContacts: JournalistIdentity[]) => {
+    Promise.all([
+      getBackupContacts(),
+      getBackupSecretSharingSettings(),
+    ]).then(([backupContacts, settings]) => {
+      setSecretSharingSettings(settings);
       setShouldRequireSettingBackupContact((prev) => {
-        const needToSetContact = backupContacts.length < SECRET_SHARING_N_VALUE;
+        const needToSetContact = backupContacts.length < settings.n;
         if (needToSetContact && !prev) {
           const toastId = `backup-contact-${Date.now()}`;
           addCustomToast({
@@ -64,9 +75,7 @@ export const ChooseBackupContactModal = ({
         return needToSetContact;
       });
 
-      if (backupContacts.length > 0) {
-        setSelectedBackupContact(backupContacts[0]);
-      }
+      setSelectedBackupContacts(backupContacts.slice(0, settings.n));
     });
 
   useEffect(() => {
@@ -78,12 +87,26 @@ export const ChooseBackupContactModal = ({
     return () => clearInterval(timer);
   }, []);
 
-  const submitHandler = async () => {
-    await setBackupContacts(
-      selectedBackupContact ? [selectedBackupContact] : [],
+  const n = secretSharingSettings?.n ?? 0;
+  const k = secretSharingSettings?.k ?? 0;
+
+  const selectBackupContact = (index: number, contact: JournalistIdentity) =>
+    setSelectedBackupContacts((prev) => {
+      const next = [...prev];
+      next[index] = contact;
+      return next;
+    });
+
+  const hasChosenAllBackupContacts =
+    n > 0 &&
+    Array.from({ length: n }, (_, i) => selectedBackupContacts[i]).every(
+      (contact) => !!contact,
     );
 
-    setShouldRequireSettingBackupContact(!selectedBackupContact);
+  const submitHandler = async () => {
+    await setBackupContacts(selectedBackupContacts.slice(0, n));
+
+    setShouldRequireSettingBackupContact(false);
     closeModal();
   };
 
@@ -100,39 +123,57 @@ export const ChooseBackupContactModal = ({
     (p) => p.id !== journalistId,
   );
 
-  const backupCandidateOptions = [{ value: "", text: "" }].concat(
-    backupContactCandidates
-      .filter((candidate) => candidate.status === "VISIBLE")
-      .sort((a, b) => (a.sort_name < b.sort_name ? -1 : 1))
-      .map((candidate) => ({
-        value: candidate.id,
-        text: `${candidate.display_name} - ${candidate.description}`,
-      })),
-  );
+  // Each contact can only be chosen once, so hide the ones chosen in the other slots
+  const backupCandidateOptions = (index: number) =>
+    [{ value: "", text: "" }].concat(
+      backupContactCandidates
+        .filter((candidate) => candidate.status === "VISIBLE")
+        .filter(
+          (candidate) =>
+            !selectedBackupContacts.some(
+              (contact, i) => i !== index && contact === candidate.id,
+            ),
+        )
+        .sort((a, b) => (a.sort_name < b.sort_name ? -1 : 1))
+        .map((candidate) => ({
+          value: candidate.id,
+          text: `${candidate.display_name} - ${candidate.description}`,
+        })),
+    );
 
   return (
     isOpen && (
       <EuiModal onClose={closeModal}>
         <EuiModalHeader>
-          <EuiModalHeaderTitle>Choose a backup contact</EuiModalHeaderTitle>
+          <EuiModalHeaderTitle>
+            {n === 1 ? "Choose a backup contact" : "Choose backup contacts"}
+          </EuiModalHeaderTitle>
         </EuiModalHeader>
         <EuiModalBody>
           {shouldRequireSettingBackupContact && (
             <EuiFormRow>
               <EuiCallOut
-                title="Choose a backup contact"
+                title={
+                  n === 1 ? "Choose a backup contact" : "Choose backup contacts"
+                }
                 color="warning"
                 iconType="help"
               >
                 <p>
-                  Please choose a trusted contact to help you recover your
-                  secure message vault in the event of loss or failure.
+                  {n === 1
+                    ? "Please choose a trusted contact"
+                    : `Please choose ${n} trusted contacts`}{" "}
+                  to help you recover your secure message vault in the event of
+                  loss or failure.
                 </p>
                 <p>
-                  This person will be able to help convert a vault backup file
-                  into a live vault for you to use. You should choose someone
-                  who uses Sentinel frequently, and whom you trust to assist if
-                  you should need to access those backups.
+                  {n === 1
+                    ? "This person"
+                    : `Any ${k} of these people together`}{" "}
+                  will be able to help convert a vault backup file into a live
+                  vault for you to use. You should choose people who use
+                  Sentinel frequently, and whom you trust to assist if you
+                  should need to access those backups.
                 </p>
                 <p>
                   Your backup contacts are not able to independently view the
@@ -156,17 +197,22 @@ export const ChooseBackupContactModal = ({
               </p>
             </EuiCallOut>
           ) : (
-            <EuiFormRow label="Select Backup Contact">
-              <EuiSelect
-                options={backupCandidateOptions}
-                onChange={(e) => setSelectedBackupContact(e.target.value)}
-                defaultValue={selectedBackupContact || ""}
-              />
-            </EuiFormRow>
+            Array.from({ length: n }, (_, i) => (
+              <EuiFormRow key={i} label={`Select Backup Contact ${i + 1}`}>
+                <EuiSelect
+                  options={backupCandidateOptions(i)}
+                  onChange={(e) => selectBackupContact(i, e.target.value)}
+                  value={selectedBackupContacts[i] || ""}
+                />
+              </EuiFormRow>
+            ))
           )}
         </EuiModalBody>
         <EuiModalFooter>
-          <EuiButton onClick={submitHandler} disabled={!selectedBackupContact}>
+          <EuiButton
+            onClick={submitHandler}
+            disabled={!hasChosenAllBackupContacts}
+          >
             Submit
           </EuiButton>
         </EuiModalFooter>
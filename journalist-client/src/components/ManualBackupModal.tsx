@@ -22,9 +22,11 @@ import { BackupReminderToastBody } from "./BackupReminderToastBody.tsx";
 import { ask } from "@tauri-apps/plugin-dialog";
 import { listen } from "@tauri-apps/api/event";
 import { BackupAttemptFailureReason } from "../model/bindings/BackupAttemptFailureReason.ts";
+import { JournalistEventPayload } from "../model/bindings/JournalistEventPayload.ts";
 
 type BackupModalProps = {
   isOpen: boolean;
+  journalistId: string;
   vaultPath: string;
   setIsBackupModalOpen: (isOpen: boolean) => void;
   addCustomToast: (toast: Toast) => void;
@@ -33,6 +35,7 @@ type BackupModalProps = {
 
 export const ManualBackupModal = ({
   isOpen,
+  journalistId,
   vaultPath,
   addCustomToast,
   removeCustomToast,
@@ -66,41 +69,45 @@ export const ManualBackupModal = ({
   }, [isBackupRequired]);
 
   // Listen for event from backend indicating a manual backup is required
-  // and show the toast if it is.
+  // and show the toast if it is. Backups are made of the vault being shown
+  // so events about other open vaults are ignored.
   useEffect(() => {
-    const listener = listen<BackupAttemptFailureReason | null>(
-      "manual_backup_required",
-      (event) => {
-        setIsBackupRequired((prev) => {
-          if (event.payload !== null && !prev) {
-            const toastId = `backup-preferred-${Date.now()}`;
-            addCustomToast({
-              id: toastId,
-              title: "Manual backup required",
-              color: "warning",
-              iconType: "warning",
-              onClose: async () => {
-                if (await confirmIgnoringBackupRequired()) {
-                  removeCustomToast(toastId);
-                }
-              },
-              text: (
-                <BackupReminderToastBody
-                  automaticBackupFailureReason={event.payload}
-                  setIsBackupModalOpen={setIsBackupModalOpen}
-                  remove={() => removeCustomToast(toastId)}
-                />
-              ),
-            });
-          }
-          return event.payload !== null;
-        });
-      },
-    );
+    const listener = listen<
+      JournalistEventPayload<BackupAttemptFailureReason | null>
+    >("manual_backup_required", (event) => {
+      if (event.payload.journalistId !== journalistId) {
+        return;
+      }
+      const failureReason = event.payload.value;
+      setIsBackupRequired((prev) => {
+        if (failureReason !== null && !prev) {
+          const toastId = `backup-preferred-${Date.now()}`;
+          addCustomToast({
+            id: toastId,
+            title: "Manual backup required",
+            color: "warning",
+            iconType: "warning",
+            onClose: async () => {
+              if (await confirmIgnoringBackupRequired()) {
+                removeCustomToast(toastId);
+              }
+            },
+            text: (
+              <BackupReminderToastBody
+                automaticBackupFailureReason={failureReason}
+                setIsBackupModalOpen={setIsBackupModalOpen}
+                remove={() => removeCustomToast(toastId)}
+              />
+            ),
+          });
+        }
+        return failureReason !== null;
+      });
+    });
     return () => {
       listener.then((unlisten) => unlisten());
     };
-  }, []);
+  }, [journalistId]);
 
   const [backupChecks, setBackupChecks] = useState<BackupChecks | null>(null);
 
@@ -1,25 +1,47 @@
 import { EventType } from "../model/bindings/EventType.ts";
+import { JournalistEventPayload } from "../model/bindings/JournalistEventPayload.ts";
 import { useEffect, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
 
+type CountPayload = number | null | JournalistEventPayload<number>;
+
 export const useBackendEventListener = (eventName: EventType) => {
   const [data, setData] = useState<{
     remainingCount: number | null;
     startingCount: number;
     lastReceivedAt?: Date;
-  }>({ remainingCount: 0, startingCount: 0 });
+    // Events from each open vault are counted separately and added together
+    countsByJournalist: Record<string, number>;
+  }>({ remainingCount: 0, startingCount: 0, countsByJournalist: {} });
 
   useEffect(() => {
-    const unlistenFnPromise = listen<number | null>(eventName, (event) => {
+    const unlistenFnPromise = listen<CountPayload>(eventName, (event) => {
       console.log("Event received from backend", event);
       setData((prev) => {
+        let countsByJournalist = prev.countsByJournalist;
+        let remainingCount: number | null;
+
+        if (event.payload !== null && typeof event.payload === "object") {
+          countsByJournalist = {
+            ...countsByJournalist,
+            [event.payload.journalistId]: event.payload.value,
+          };
+          remainingCount = Object.values(countsByJournalist).reduce(
+            (total, count) => total + count,
+            0,
+          );
+        } else {
+          remainingCount = event.payload;
+        }
+
         return {
-          remainingCount: event.payload,
+          remainingCount,
           startingCount:
-            event.payload === 0
+            remainingCount === 0
               ? 0
-              : Math.max(event.payload ?? 0, prev.startingCount),
+              : Math.max(remainingCount ?? 0, prev.startingCount),
           lastReceivedAt: new Date(),
+          countsByJournalist,
         };
       });
     });
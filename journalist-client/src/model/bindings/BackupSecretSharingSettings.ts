@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The k-of-n secret sharing settings published by the backup admins. Automated backups are
+ * split between `n` recovery contacts and any `k` of them can help restore the vault.
+ */
+export type BackupSecretSharingSettings = { k: number; n: number };
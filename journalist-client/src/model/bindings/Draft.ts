@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PublicEncryptionKey } from "./PublicEncryptionKey";
+
+/**
+ * A reply to a user which the journalist has written but not yet sent
+ */
+export type Draft = {
+  userPk: PublicEncryptionKey;
+  message: string;
+  /**
+   * When the message should be sent once submitted, or `None` to send it as soon as possible
+   */
+  sendAfter: string | null;
+  updatedAt: string;
+};
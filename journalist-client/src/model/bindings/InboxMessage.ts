@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JournalistIdentity } from "./JournalistIdentity";
+import type { Message } from "./Message";
+
+/**
+ * A message from one of the open vaults, tagged with the journalist whose vault it's from
+ */
+export type InboxMessage = {
+  journalistId: JournalistIdentity;
+  message: Message;
+};
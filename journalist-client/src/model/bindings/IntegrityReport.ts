@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { KeyProblem } from "./KeyProblem";
+
+/**
+ * The problems found by [`crate::JournalistVault::check_integrity`]
+ */
+export type IntegrityReport = {
+  /**
+   * Problems reported by SQLite's `integrity_check` pragma
+   */
+  databaseErrors: Array<string>;
+  /**
+   * Provisioning public keys which don't verify against any trust anchor
+   */
+  unverifiedProvisioningPks: Array<KeyProblem>;
+  /**
+   * Published ID key pairs which don't verify against their provisioning public key
+   */
+  unverifiedIdKeyPairs: Array<KeyProblem>;
+  /**
+   * Published messaging key pairs which don't verify against their ID key pair
+   */
+  unverifiedMsgKeyPairs: Array<KeyProblem>;
+  /**
+   * Candidate ID key pairs which were never promoted, for example because Sentinel
+   * stopped part way through a rotation
+   */
+  staleCandidateIdKeyPairs: Array<KeyProblem>;
+  /**
+   * Candidate messaging key pairs which can no longer be promoted
+   */
+  staleCandidateMsgKeyPairs: Array<KeyProblem>;
+  /**
+   * Queued messages which aren't read receipts and don't belong to a message in the vault
+   */
+  orphanedQueueMessageIds: Array<bigint>;
+  /**
+   * True if the repairable problems in this report have been fixed
+   */
+  repaired: boolean;
+};
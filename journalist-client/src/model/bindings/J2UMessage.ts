@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JournalistIdentity } from "./JournalistIdentity";
 import type { PublicEncryptionKey } from "./PublicEncryptionKey";
 
 export type J2UMessage = {
@@ -9,4 +10,21 @@ export type J2UMessage = {
   sentAt: string;
   normalExpiry: string;
   customExpiry: string | null;
+  /**
+   * Set when this message handed the conversation over to another journalist or desk
+   * rather than containing text written by the journalist.
+   */
+  handOverTo: JournalistIdentity | null;
+  /**
+   * Set when this message was split into fragments and some are missing from the vault
+   */
+  isPartial: boolean;
+  /**
+   * Set when the user has sent a read receipt for this message
+   */
+  readByUser: boolean;
+  /**
+   * Set when the message is waiting in the outbound queue until a scheduled time
+   */
+  sendAfter: string | null;
 };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JournalistIdentity } from "./JournalistIdentity";
+
+/**
+ * Payload for events about a single vault, since several vaults can be open at once
+ */
+export type JournalistEventPayload<T> = {
+  journalistId: JournalistIdentity;
+  value: T;
+};
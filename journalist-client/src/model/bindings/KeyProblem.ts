@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stored key which failed one of the integrity checks
+ */
+export type KeyProblem = {
+  /**
+   * The row id of the key in its table
+   */
+  id: bigint;
+  reason: string;
+};
@@ -1,3 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Profile = { apiUrl: string };
+export type Profile = {
+  apiUrl: string;
+  /**
+   * The key exported logs are encrypted to so they can be attached to support tickets
+   */
+  supportPk?: string;
+};
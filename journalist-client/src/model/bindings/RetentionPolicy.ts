@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long the messages in a conversation are kept before they are removed when the vault is
+ * cleaned up. Whatever the policy, messages are never kept for longer than the organization's
+ * message retention limit.
+ */
+export type RetentionPolicy =
+  | { type: "INHERIT" }
+  | { type: "EXTEND"; days: number }
+  | { type: "KEEP_UNTIL_UNMUTED" };
@@ -9,4 +9,8 @@ export type U2JMessage = {
   normalExpiry: string;
   customExpiry: string | null;
   read: boolean;
+  /**
+   * Set when this message was split into fragments and some have not been received yet
+   */
+  isPartial: boolean;
 };
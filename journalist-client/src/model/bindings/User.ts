@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RetentionPolicy } from "./RetentionPolicy";
 import type { UserStatus } from "./UserStatus";
 
 export type User = {
@@ -9,4 +10,5 @@ export type User = {
   alias: string | null;
   description: string | null;
   markedAsUnread: boolean;
+  retentionPolicy: RetentionPolicy;
 };
@@ -1,23 +1,54 @@
 import * as _ from "radash";
 import { create } from "zustand";
+import { deleteDraft, saveDraft } from "../commands/chats";
+import { Draft } from "../model/bindings/Draft";
+
+// Avoid writing to the vault on every key press
+const SAVE_DRAFT_DELAY_MS = 1000;
+
+const pendingSaves: Record<string, ReturnType<typeof _.debounce>> = {};
+
+const persistDraft = (userReplyKey: string, draft: string) => {
+  if (!pendingSaves[userReplyKey]) {
+    pendingSaves[userReplyKey] = _.debounce(
+      { delay: SAVE_DRAFT_DELAY_MS },
+      (draft: string) =>
+        draft.length === 0
+          ? deleteDraft(userReplyKey)
+          : saveDraft(userReplyKey, draft),
+    );
+  }
+  pendingSaves[userReplyKey](draft);
+};
 
 type DraftState = {
   drafts: Record<string, string>;
+  setDrafts: (drafts: Draft[]) => void;
   setDraft: (userReplyKey: string, draft: string) => void;
   clearDraft: (userReplyKey: string) => void;
 };
 
 export const useDraftStore = create<DraftState>((set) => ({
   drafts: {},
-  setDraft: (userReplyKey: string, draft: string) =>
+  setDrafts: (drafts: Draft[]) =>
+    set(() => ({
+      drafts: Object.fromEntries(drafts.map((d) => [d.userPk, d.message])),
+    })),
+  setDraft: (userReplyKey: string, draft: string) => {
+    persistDraft(userReplyKey, draft);
     set((state) => ({
       drafts: {
         ...state.drafts,
         [userReplyKey]: draft,
       },
-    })),
-  clearDraft: (userReplyKey: string) =>
+    }));
+  },
+  // The vault removes the draft when the message is submitted so we only need
+  // to make sure a pending save doesn't bring it back
+  clearDraft: (userReplyKey: string) => {
+    pendingSaves[userReplyKey]?.cancel();
     set((state) => ({
       drafts: _.omit(state.drafts, [userReplyKey]),
-    })),
+    }));
+  },
 }));
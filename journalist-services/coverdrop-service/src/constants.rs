@@ -1,4 +1,16 @@
 use std::time::Duration;
 
+use common::protocol::constants::{
+    COVERNODE_MSG_KEY_ROTATE_AFTER, COVERNODE_MSG_KEY_VALID_DURATION,
+};
+
 pub const JOURNALIST_ID_KEY_POLL_ITERATIONS: u64 = 60;
 pub const JOURNALIST_ID_KEY_POLL_SLEEP_DURATION: Duration = Duration::from_secs(1);
+
+/// Scheduled messages are encrypted when they are enqueued. The latest CoverNode key always has
+/// at least this long left before it expires, so messages scheduled within this delay are
+/// normally sent without being encrypted again. If the journalist's client isn't running when a
+/// message is due it is encrypted again to the latest keys when it is dequeued.
+pub const MAX_SCHEDULED_SEND_DELAY: chrono::Duration = COVERNODE_MSG_KEY_VALID_DURATION
+    .checked_sub(&COVERNODE_MSG_KEY_ROTATE_AFTER)
+    .unwrap();
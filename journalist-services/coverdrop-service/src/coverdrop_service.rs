@@ -1,28 +1,44 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use common::{
     api::{
-        api_client::ApiClient, forms::RotateJournalistIdPublicKeyFormForm,
-        models::messages::user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId,
+        api_client::ApiClient,
+        forms::RotateJournalistIdPublicKeyFormForm,
+        models::{
+            dead_drops::UserToJournalistDeadDrop, journalist_id::JournalistIdentity,
+            messages::user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId,
+        },
     },
     client::VerifiedKeysAndJournalistProfiles,
     epoch::Epoch,
     identity_api::forms::post_rotate_journalist_id::RotateJournalistIdPublicKeyForm,
     protocol::{
         constants::{JOURNALIST_ID_KEY_ROTATE_AFTER, JOURNALIST_MSG_KEY_ROTATE_AFTER},
-        covernode::verify_user_to_journalist_dead_drop_list,
+        covernode::{covernode_msg_pks_not_valid_after, verify_user_to_journalist_dead_drop_list},
         journalist::{
+            encrypt_hand_over_from_journalist_to_user_via_covernode,
             encrypt_real_message_from_journalist_to_user_via_covernode,
             get_decrypted_journalist_dead_drop_message,
             new_encrypted_cover_message_from_journalist_via_covernode,
         },
-        keys::{OrganizationPublicKeyFamilyList, UserPublicKey},
+        keys::{
+            CoverNodeMessagingPublicKey, JournalistMessagingKeyPair,
+            OrganizationPublicKeyFamilyList, UserPublicKey,
+        },
     },
     FixedSizeMessageText,
 };
-use journalist_vault::{JournalistVault, User};
+use journalist_vault::{
+    EncryptedJournalistToCoverNodeMessageWithId, JournalistVault, QueuedJ2UMessage, User,
+};
+use rayon::prelude::*;
 
-use crate::constants::{JOURNALIST_ID_KEY_POLL_ITERATIONS, JOURNALIST_ID_KEY_POLL_SLEEP_DURATION};
+use crate::constants::{
+    JOURNALIST_ID_KEY_POLL_ITERATIONS, JOURNALIST_ID_KEY_POLL_SLEEP_DURATION,
+    MAX_SCHEDULED_SEND_DELAY,
+};
 
 pub enum ProcessVaultSetupBundleResult {
     AlreadyRegistered,
@@ -42,11 +58,13 @@ impl JournalistCoverDropService {
         }
     }
 
-    /// Pull dead drops from the API, verify them, decrypt messages, and store in vault.
-    /// Returns the list of decrypted messages.
+    /// Pull dead drops from the API a page at a time, verify them, decrypt messages, and store
+    /// them in the vault. The max dead drop ID is updated after each page so an interrupted pull
+    /// picks up where it left off. Returns the list of decrypted messages.
     ///
-    /// The optional `on_progress` callback is called after each dead drop is processed,
-    /// with the number of remaining dead drops to process.
+    /// The optional `on_progress` callback is called when a page is fetched and after each dead
+    /// drop is processed, with the number of dead drops left to process in the current page.
+    /// The total isn't known up front since dead drops are only fetched a page at a time.
     pub async fn pull_and_decrypt_dead_drops(
         &self,
         public_info: &VerifiedKeysAndJournalistProfiles,
@@ -60,100 +78,90 @@ impl JournalistCoverDropService {
         };
         let keys = &public_info.keys;
 
-        let ids_greater_than = self.vault.max_dead_drop_id().await?;
+        let mut ids_greater_than = self.vault.max_dead_drop_id().await?;
 
         tracing::info!(
             "Pulling dead drops with ID greater than {}",
             ids_greater_than
         );
 
-        let dead_drop_list = self
-            .api_client
-            .pull_all_journalist_dead_drops(ids_greater_than)
-            .await?;
+        let journalist_msg_key_pairs = Arc::new(
+            self.vault
+                .msg_key_pairs_for_decryption(now)
+                .await?
+                .collect::<Vec<_>>(),
+        );
 
-        let maybe_max_dead_drop_id = dead_drop_list
-            .dead_drops
-            .iter()
-            .max_by_key(|d| d.id)
-            .map(|d| d.id);
-        let Some(max_dead_drop_id) = maybe_max_dead_drop_id else {
-            tracing::info!("No dead drops in dead drop list");
-            maybe_invoke_on_progress(0);
-            return Ok(Vec::new());
-        };
+        let covernode_msg_pks = Arc::new(
+            keys.covernode_msg_pk_iter()
+                .map(|(_, msg_pk)| msg_pk.clone())
+                .collect::<Vec<_>>(),
+        );
 
-        // TODO should we return early if verified dead drops < total dead drops?
-        // see https://github.com/guardian/coverdrop-internal/issues/3643
-        let dead_drops = verify_user_to_journalist_dead_drop_list(keys, dead_drop_list, now);
+        let mut decrypted_messages = vec![];
 
-        tracing::info!("Found {} dead drops", dead_drops.len());
+        loop {
+            let dead_drop_list = self
+                .api_client
+                .pull_journalist_dead_drops(ids_greater_than, None)
+                .await?;
 
-        let total_dead_drops_to_process = dead_drops.len();
+            let Some(max_dead_drop_id) = dead_drop_list.dead_drops.iter().map(|d| d.id).max()
+            else {
+                tracing::info!("No more dead drops in dead drop list");
+                break;
+            };
 
-        // Notify progress with initial count
-        maybe_invoke_on_progress(total_dead_drops_to_process);
+            // TODO should we return early if verified dead drops < total dead drops?
+            // see https://github.com/guardian/coverdrop-internal/issues/3643
+            let dead_drops = verify_user_to_journalist_dead_drop_list(keys, dead_drop_list, now);
 
-        // find the max epoch in the list of dead drops to make sure that the public info epoch is high enough to decrypt
-        let Some(max_dead_drop_epoch) = dead_drops.iter().max_by_key(|d| d.epoch).map(|d| d.epoch)
-        else {
-            // this check is redundant but necessary to turn the epoch into Some
-            tracing::info!("No dead drops in dead drop list");
-            // Callback was already called above with 0 since total_dead_drops_to_process would be 0
-            return Ok(Vec::new());
-        };
+            tracing::info!(
+                "Found {} dead drops with IDs up to {}",
+                dead_drops.len(),
+                max_dead_drop_id
+            );
 
-        if public_info.max_epoch < max_dead_drop_epoch {
-            tracing::info!("Max epoch of public key hierarchy {} is less than the max dead drop epoch {}. Returning early.", public_info.max_epoch, max_dead_drop_epoch);
-            maybe_invoke_on_progress(0);
-            return Ok(Vec::new());
-        } else {
-            tracing::info!("Max epoch of public key hierarchy {} is greater than or equal to the max dead drop epoch {}. Attempting to decrypt.", public_info.max_epoch, max_dead_drop_epoch);
-        }
+            // Make sure that the public info epoch is high enough to decrypt this page. Earlier
+            // pages have already been stored so we stop here and retry this page next time.
+            if let Some(max_dead_drop_epoch) = dead_drops.iter().map(|d| d.epoch).max() {
+                if public_info.max_epoch < max_dead_drop_epoch {
+                    tracing::info!("Max epoch of public key hierarchy {} is less than the max dead drop epoch {}. Returning early.", public_info.max_epoch, max_dead_drop_epoch);
+                    break;
+                }
+            }
 
-        let journalist_msg_key_pairs = self
-            .vault
-            .msg_key_pairs_for_decryption(now)
-            .await?
-            .collect::<Vec<_>>();
+            let page_len = dead_drops.len();
+            maybe_invoke_on_progress(page_len);
 
-        let covernode_msg_pks = keys
-            .covernode_msg_pk_iter()
-            .map(|(_, msg_pk)| msg_pk)
-            .collect::<Vec<_>>();
+            let mut page_messages = vec![];
 
-        let decrypted_messages: Vec<UserToJournalistMessageWithDeadDropId> = dead_drops
-            .iter()
-            .enumerate()
-            .flat_map(|(index, dead_drop)| {
-                let processed_messages = dead_drop
-                    .data
-                    .messages
-                    .iter()
-                    .filter_map(|encrypted_message| {
-                        get_decrypted_journalist_dead_drop_message(
-                            &covernode_msg_pks,
-                            &journalist_msg_key_pairs,
-                            encrypted_message,
-                            dead_drop.id,
-                        )
-                    })
-                    .collect::<Vec<_>>();
-
-                // Notify progress after processing each dead drop
-                maybe_invoke_on_progress(total_dead_drops_to_process - index - 1);
-
-                processed_messages
-            })
-            .collect();
+            for (index, dead_drop) in dead_drops.into_iter().enumerate() {
+                page_messages.extend(
+                    decrypt_dead_drop(
+                        covernode_msg_pks.clone(),
+                        journalist_msg_key_pairs.clone(),
+                        dead_drop,
+                    )
+                    .await?,
+                );
 
-        self.vault
-            .add_messages_from_user_to_journalist_and_update_max_dead_drop_id(
-                &decrypted_messages,
-                max_dead_drop_id,
-                now,
-            )
-            .await?;
+                maybe_invoke_on_progress(page_len - index - 1);
+            }
+
+            self.vault
+                .add_messages_from_user_to_journalist_and_update_max_dead_drop_id(
+                    &page_messages,
+                    max_dead_drop_id,
+                    now,
+                )
+                .await?;
+
+            decrypted_messages.extend(page_messages);
+            ids_greater_than = max_dead_drop_id;
+        }
+
+        maybe_invoke_on_progress(0);
 
         Ok(decrypted_messages)
     }
@@ -401,15 +409,25 @@ impl JournalistCoverDropService {
         Ok(did_rotate_some_keys)
     }
 
-    /// Encrypt a message and enqueue it for sending to a user.
+    /// Encrypt a message and enqueue it for sending to a user. Messages which are too long for
+    /// a single slot are rejected, since the mobile apps can't reassemble fragments. If
+    /// `send_after` is set the message is not sent before that time.
     /// Returns the queue length after enqueuing.
     pub async fn enqueue_j2u_message(
         &self,
         keys: &VerifiedKeysAndJournalistProfiles,
         user_pk: &UserPublicKey,
         message: &str,
+        send_after: Option<DateTime<Utc>>,
         now: DateTime<Utc>,
     ) -> Result<i64> {
+        if send_after.is_some_and(|send_after| send_after > now + MAX_SCHEDULED_SEND_DELAY) {
+            anyhow::bail!(
+                "Messages can't be scheduled more than {} days in advance",
+                MAX_SCHEDULED_SEND_DELAY.num_days()
+            );
+        }
+
         let unencrypted_message = FixedSizeMessageText::new(message)?;
 
         let latest_journalist_msg_key_pair = self
@@ -427,10 +445,59 @@ impl JournalistCoverDropService {
 
         let queue_length = self
             .vault
-            .add_message_from_journalist_to_user_and_enqueue(
+            .add_messages_from_journalist_to_user_and_enqueue(
                 user_pk,
-                &unencrypted_message,
+                vec![(unencrypted_message, encrypted_message)],
+                send_after,
+                covernode_msg_pks_not_valid_after(&keys.keys)?,
+                now,
+            )
+            .await?;
+
+        Ok(queue_length)
+    }
+
+    /// Hand the conversation with a user over to another journalist or desk, the user's client
+    /// will send future messages to them. Returns the queue length after enqueuing.
+    pub async fn enqueue_j2u_hand_over(
+        &self,
+        keys: &VerifiedKeysAndJournalistProfiles,
+        user_pk: &UserPublicKey,
+        to: &JournalistIdentity,
+        now: DateTime<Utc>,
+    ) -> Result<i64> {
+        if *to == self.vault.journalist_id().await? {
+            anyhow::bail!("Cannot hand a conversation over to yourself");
+        }
+
+        if !keys
+            .journalist_profiles
+            .iter()
+            .any(|profile| profile.id == *to)
+        {
+            anyhow::bail!("No journalist profile found for {}", to);
+        }
+
+        let latest_journalist_msg_key_pair = self
+            .vault
+            .latest_msg_key_pair(now)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No messaging keys in vault"))?;
+
+        let encrypted_message = encrypt_hand_over_from_journalist_to_user_via_covernode(
+            &keys.keys,
+            user_pk,
+            &latest_journalist_msg_key_pair,
+            to,
+        )?;
+
+        let queue_length = self
+            .vault
+            .add_hand_over_from_journalist_to_user_and_enqueue(
+                user_pk,
+                to,
                 encrypted_message,
+                covernode_msg_pks_not_valid_after(&keys.keys)?,
                 now,
             )
             .await?;
@@ -438,8 +505,81 @@ impl JournalistCoverDropService {
         Ok(queue_length)
     }
 
+    /// Get the oldest message in the queue which is due to be sent. Scheduled messages can wait
+    /// in the queue until after the CoverNode keys they were encrypted to have expired, so those
+    /// messages are encrypted again to the latest keys before being returned.
+    pub async fn head_queue_message(
+        &self,
+        keys: &OrganizationPublicKeyFamilyList,
+        now: DateTime<Utc>,
+    ) -> Result<Option<EncryptedJournalistToCoverNodeMessageWithId>> {
+        let Some(mut message) = self.vault.head_queue_message(now).await? else {
+            return Ok(None);
+        };
+
+        if message
+            .covernode_msg_keys_not_valid_after
+            .is_none_or(|not_valid_after| not_valid_after > now)
+        {
+            return Ok(Some(message));
+        }
+
+        tracing::info!(
+            "CoverNode keys for queued message {} have expired, encrypting it again",
+            message.id
+        );
+
+        let Some(queued_message) = self.vault.queued_j2u_message(message.id).await? else {
+            // Read receipts aren't stored in the conversation so there's nothing to encrypt again.
+            // Sending them would only waste a slot since the CoverNode can't decrypt them.
+            let queue_length = self.vault.delete_queue_message(message.id).await?;
+            anyhow::bail!(
+                "Dropped queued message {} because the CoverNode keys it was encrypted to have expired, {} messages remain in the queue",
+                message.id,
+                queue_length
+            );
+        };
+
+        let not_valid_after = covernode_msg_pks_not_valid_after(keys)?;
+        if not_valid_after <= now {
+            anyhow::bail!("No valid CoverNode messaging keys to encrypt queued messages to");
+        }
+
+        let latest_journalist_msg_key_pair = self
+            .vault
+            .latest_msg_key_pair(now)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No messaging keys in vault"))?;
+
+        message.message = match queued_message {
+            QueuedJ2UMessage::Message { user_pk, message } => {
+                encrypt_real_message_from_journalist_to_user_via_covernode(
+                    keys,
+                    &user_pk,
+                    &latest_journalist_msg_key_pair,
+                    &message,
+                )?
+            }
+            QueuedJ2UMessage::HandOver { user_pk, to } => {
+                encrypt_hand_over_from_journalist_to_user_via_covernode(
+                    keys,
+                    &user_pk,
+                    &latest_journalist_msg_key_pair,
+                    &to,
+                )?
+            }
+        };
+
+        self.vault
+            .replace_queue_message(message.id, message.message.clone(), not_valid_after)
+            .await?;
+        message.covernode_msg_keys_not_valid_after = Some(not_valid_after);
+
+        Ok(Some(message))
+    }
+
     /// Dequeue a j2u message for sending to a user, and send it to the API.
-    /// If there are no messages to send, create and send a cover message.
+    /// If there are no messages due to be sent, create and send a cover message.
     /// Returns the queue length after dequeuing.
     pub async fn dequeue_and_send_j2u_message(
         &self,
@@ -450,7 +590,15 @@ impl JournalistCoverDropService {
             anyhow::bail!("No ID key pair found in vault");
         };
 
-        if let Ok(Some(message)) = self.vault.head_queue_message().await {
+        let head_queue_message = self
+            .head_queue_message(keys, now)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to get message from vault queue: {:?}", e);
+                None
+            });
+
+        if let Some(message) = head_queue_message {
             tracing::debug!("Found message in vault queue");
 
             self.api_client
@@ -463,7 +611,7 @@ impl JournalistCoverDropService {
 
             Ok(queue_length)
         } else {
-            tracing::debug!("No message in vault queue, creating and sending cover message");
+            tracing::debug!("No message due in vault queue, creating and sending cover message");
 
             let message = new_encrypted_cover_message_from_journalist_via_covernode(keys)?;
 
@@ -473,7 +621,10 @@ impl JournalistCoverDropService {
 
             tracing::debug!("Posting message was successful");
 
-            Ok(0)
+            // Messages scheduled for later are still waiting in the queue
+            let queue_length = self.vault.queue_length().await?;
+
+            Ok(queue_length)
         }
     }
 
@@ -607,3 +758,33 @@ impl JournalistCoverDropService {
         Ok(ProcessVaultSetupBundleResult::SuccessfullyProcessedBundle)
     }
 }
+
+/// Trial decrypt every message in a dead drop against every combination of CoverNode and
+/// journalist messaging keys. This is CPU bound so the messages are spread across the rayon
+/// thread pool, off the async runtime.
+async fn decrypt_dead_drop(
+    covernode_msg_pks: Arc<Vec<CoverNodeMessagingPublicKey>>,
+    journalist_msg_key_pairs: Arc<Vec<JournalistMessagingKeyPair>>,
+    dead_drop: UserToJournalistDeadDrop,
+) -> Result<Vec<UserToJournalistMessageWithDeadDropId>> {
+    let messages = tokio::task::spawn_blocking(move || {
+        let covernode_msg_pks = covernode_msg_pks.iter().collect::<Vec<_>>();
+
+        dead_drop
+            .data
+            .messages
+            .par_iter()
+            .filter_map(|encrypted_message| {
+                get_decrypted_journalist_dead_drop_message(
+                    &covernode_msg_pks,
+                    &journalist_msg_key_pairs,
+                    encrypted_message,
+                    dead_drop.id,
+                )
+            })
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    Ok(messages)
+}
@@ -0,0 +1,155 @@
+use chrono::{Duration, Utc};
+use common::{
+    api::{
+        api_client::ApiClient,
+        models::{
+            journalist_id::JournalistIdentity,
+            messages::{
+                journalist_to_covernode_message::JournalistToCoverNodeMessage,
+                read_receipt::ReadReceipt,
+            },
+        },
+    },
+    client::VerifiedKeysAndJournalistProfiles,
+    epoch::Epoch,
+    protocol::{
+        covernode::decrypt_journalist_message,
+        journalist::encrypt_read_receipt_from_journalist_to_user_via_covernode,
+        keys::test::generate_protocol_keys,
+    },
+};
+use coverdrop_service::JournalistCoverDropService;
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+
+#[tokio::test]
+async fn scheduled_message_is_encrypted_again_once_covernode_keys_expire() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+    let keys = generate_protocol_keys(now);
+
+    let vault = JournalistVault::create(
+        &db_path,
+        "test_password",
+        &JournalistIdentity::new("Hello").unwrap(),
+        std::slice::from_ref(&keys.journalist_provisioning_pk),
+        now,
+        vec![keys.org_pk.clone().into_anchor()],
+    )
+    .await
+    .expect("Create journalist vault");
+
+    vault
+        .insert_registered_id_key_pair(1, &keys.journalist_id_key_pair, now, now, Epoch(0))
+        .await
+        .unwrap();
+    let msg_key_pair = vault
+        .get_or_create_candidate_msg_key_pair(now)
+        .await
+        .unwrap();
+    vault
+        .promote_candidate_msg_key_pair(&msg_key_pair, Epoch(1))
+        .await
+        .unwrap();
+
+    let api_client = ApiClient::new("http://localhost".parse().unwrap());
+    let service = JournalistCoverDropService::new(&api_client, &vault);
+
+    let keys_and_profiles = VerifiedKeysAndJournalistProfiles {
+        journalist_profiles: vec![],
+        default_journalist_id: None,
+        keys: keys.hierarchy,
+        max_epoch: Epoch(1),
+        key_transparency: None,
+        backup_secret_sharing: None,
+        message_retention_limit: None,
+    };
+
+    let send_after = now + Duration::days(6);
+    service
+        .enqueue_j2u_message(
+            &keys_and_profiles,
+            &keys.user_pk,
+            "Hello",
+            Some(send_after),
+            now,
+        )
+        .await
+        .unwrap();
+
+    // While the CoverNode key is valid the message is sent as it was encrypted
+    let queued = service
+        .head_queue_message(&keys_and_profiles.keys, send_after)
+        .await
+        .unwrap()
+        .expect("Scheduled message is due");
+    assert_eq!(
+        queued.covernode_msg_keys_not_valid_after,
+        Some(keys.covernode_msg_pk.not_valid_after)
+    );
+    assert!(decrypt_journalist_message(&keys.covernode_msg_key_pair, &queued.message).is_ok());
+
+    // The message wasn't sent before the CoverNode key expired, by which time the CoverNode
+    // has rotated its keys and the journalist has a new messaging key
+    let later = keys.covernode_msg_pk.not_valid_after + Duration::hours(1);
+    let later_keys = generate_protocol_keys(later);
+
+    let msg_key_pair = vault
+        .get_or_create_candidate_msg_key_pair(later)
+        .await
+        .unwrap();
+    vault
+        .promote_candidate_msg_key_pair(&msg_key_pair, Epoch(2))
+        .await
+        .unwrap();
+
+    let reencrypted = service
+        .head_queue_message(&later_keys.hierarchy, later)
+        .await
+        .unwrap()
+        .expect("Scheduled message is still due");
+
+    assert_eq!(reencrypted.id, queued.id);
+    assert_eq!(
+        reencrypted.covernode_msg_keys_not_valid_after,
+        Some(later_keys.covernode_msg_pk.not_valid_after)
+    );
+    assert!(
+        decrypt_journalist_message(&keys.covernode_msg_key_pair, &reencrypted.message).is_err()
+    );
+    assert!(matches!(
+        decrypt_journalist_message(&later_keys.covernode_msg_key_pair, &reencrypted.message),
+        Ok(JournalistToCoverNodeMessage::Real { .. })
+    ));
+
+    // The re-encrypted message replaces the original in the vault
+    let stored = vault.head_queue_message(later).await.unwrap().unwrap();
+    assert_eq!(stored.message, reencrypted.message);
+
+    vault.delete_queue_message(queued.id).await.unwrap();
+
+    // Read receipts can't be encrypted again so they're dropped rather than sent undecryptable
+    let read_receipt = encrypt_read_receipt_from_journalist_to_user_via_covernode(
+        &keys_and_profiles.keys,
+        &keys.user_pk,
+        &msg_key_pair,
+        ReadReceipt::new(vec![]),
+    )
+    .unwrap();
+    vault
+        .enqueue_read_receipt_from_journalist_to_user(
+            read_receipt,
+            keys.covernode_msg_pk.not_valid_after,
+        )
+        .await
+        .unwrap();
+
+    assert!(service
+        .head_queue_message(&later_keys.hierarchy, later)
+        .await
+        .is_err());
+    assert_eq!(vault.queue_length().await.unwrap(), 0);
+}
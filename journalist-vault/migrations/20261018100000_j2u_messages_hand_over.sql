@@ -0,0 +1,2 @@
+ALTER TABLE j2u_messages
+    ADD COLUMN hand_over_to TEXT; -- Journalist ID the conversation was handed over to, or NULL for a regular message
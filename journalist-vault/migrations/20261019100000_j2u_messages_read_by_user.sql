@@ -0,0 +1,2 @@
+ALTER TABLE j2u_messages
+    ADD COLUMN read_by_user INTEGER NOT NULL DEFAULT 0; -- Set once the user has sent a read receipt for this message
@@ -0,0 +1,23 @@
+-- Full-text search over messages and the aliases and descriptions of users.
+--
+-- Message text is stored compressed so SQLite can't index it itself. Rows are added to
+-- `message_search` when messages are inserted and removed when they are deleted, see
+-- `message_queries`. Messages which were already in the vault are indexed when it is opened.
+CREATE VIRTUAL TABLE message_search USING fts5(
+    text,
+    message_id UNINDEXED,    -- The id of the message in either u2j_messages or j2u_messages
+    is_from_user UNINDEXED,  -- 1 if the message is in u2j_messages, 0 if in j2u_messages
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE user_search USING fts5(
+    alias,
+    description,
+    user_pk UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO user_search (alias, description, user_pk)
+SELECT alias, description, user_pk
+FROM users
+WHERE alias IS NOT NULL OR description IS NOT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE users
+    ADD COLUMN retention_policy TEXT NOT NULL DEFAULT 'INHERIT'
+    CHECK (retention_policy IN ('INHERIT', 'EXTEND', 'KEEP_UNTIL_UNMUTED'));
+
+ALTER TABLE users
+    ADD COLUMN retention_days INTEGER; -- Only set when retention_policy is 'EXTEND'
+
+ALTER TABLE vault_info
+    ADD COLUMN max_message_retention_days INTEGER; -- NULL until a limit has been published by the admins
@@ -0,0 +1,12 @@
+-- A single unsent reply per conversation, kept so journalists can prepare messages offline
+CREATE TABLE drafts (
+    user_pk     BLOB PRIMARY KEY NOT NULL,
+    message     TEXT NOT NULL,
+    send_after  TEXT,          -- ISO formatted date, NULL to send as soon as possible
+    updated_at  TEXT NOT NULL, -- ISO formatted date
+    FOREIGN KEY(user_pk) REFERENCES users(user_pk) ON DELETE CASCADE
+);
+
+-- Queued messages are not sent before this time, NULL for messages which can be sent immediately
+ALTER TABLE outbound_queue
+    ADD COLUMN send_after TEXT;
@@ -0,0 +1,14 @@
+-- Read receipts are queued without a matching row in j2u_messages. Marking them lets the
+-- integrity check tell them apart from queue entries whose message has been lost.
+ALTER TABLE outbound_queue
+    ADD COLUMN is_read_receipt INTEGER NOT NULL DEFAULT 0;
+
+-- Read receipts queued before this migration have no j2u_messages row. There's no way to tell
+-- them apart from lost messages, so treat them all as read receipts rather than letting the
+-- integrity check delete them.
+UPDATE outbound_queue
+SET is_read_receipt = 1
+WHERE NOT EXISTS (
+    SELECT 1 FROM j2u_messages
+    WHERE j2u_messages.outbound_queue_id = outbound_queue.id
+);
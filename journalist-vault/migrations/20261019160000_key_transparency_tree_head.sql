@@ -0,0 +1,2 @@
+ALTER TABLE vault_info
+    ADD COLUMN latest_tree_head_json TEXT; -- NULL until the API has served a key transparency proof
@@ -0,0 +1,2 @@
+ALTER TABLE vault_info
+    ADD COLUMN backup_secret_sharing_settings_json TEXT; -- NULL until the backup admins have published settings
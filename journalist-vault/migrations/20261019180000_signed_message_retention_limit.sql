@@ -0,0 +1,3 @@
+-- The signed limit is kept so a limit served by the API is only accepted if it is newer
+ALTER TABLE vault_info
+    ADD COLUMN message_retention_limit_json TEXT; -- NULL until a limit has been published by the admins
@@ -0,0 +1,5 @@
+-- When the first of the CoverNode messaging keys a queued message was encrypted to expires.
+-- Messages still in the queue after this time must be encrypted again before being sent.
+-- NULL for messages queued before this was recorded.
+ALTER TABLE outbound_queue
+    ADD COLUMN covernode_msg_keys_not_valid_after TEXT;
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use common::{
+    crypto::keys::encryption::traits::PublicEncryptionKey, protocol::keys::UserPublicKey,
+};
+use serde::Serialize;
+use sqlx::SqliteConnection;
+use ts_rs::TS;
+
+/// A reply to a user which the journalist has written but not yet sent
+#[derive(Clone, Debug, TS, Serialize)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct Draft {
+    pub user_pk: UserPublicKey,
+    pub message: String,
+    /// When the message should be sent once submitted, or `None` to send it as soon as possible
+    pub send_after: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub(crate) async fn drafts(conn: &mut SqliteConnection) -> anyhow::Result<Vec<Draft>> {
+    let drafts = sqlx::query!(
+        r#"
+            SELECT
+                user_pk    AS "user_pk: Vec<u8>",
+                message    AS "message: String",
+                send_after AS "send_after: DateTime<Utc>",
+                updated_at AS "updated_at: DateTime<Utc>"
+            FROM drafts
+            ORDER BY updated_at DESC
+        "#
+    )
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(|row| {
+        let user_pk = UserPublicKey::from_bytes(&row.user_pk)?;
+
+        Ok(Draft {
+            user_pk,
+            message: row.message,
+            send_after: row.send_after,
+            updated_at: row.updated_at,
+        })
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(drafts)
+}
+
+/// Save the draft reply to a user, replacing any previous draft for the same user
+pub(crate) async fn upsert_draft(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+    message: &str,
+    send_after: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    sqlx::query!(
+        r#"
+            INSERT INTO drafts
+                (user_pk, message, send_after, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(user_pk) DO UPDATE SET
+                message = excluded.message,
+                send_after = excluded.send_after,
+                updated_at = excluded.updated_at
+        "#,
+        user_pk_bytes,
+        message,
+        send_after,
+        now
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn delete_draft(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+) -> anyhow::Result<()> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    sqlx::query!(r#"DELETE FROM drafts WHERE user_pk = ?1"#, user_pk_bytes)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use common::{crypto::keys::encryption::UnsignedEncryptionKeyPair, protocol::roles::User};
+    use sqlx::{pool::PoolConnection, Sqlite};
+
+    use crate::user_queries;
+
+    use super::{delete_draft, drafts, upsert_draft};
+
+    #[sqlx::test]
+    async fn test_drafts_round_trip(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        user_queries::add_user(&mut conn, &user_pk, now)
+            .await
+            .unwrap();
+
+        upsert_draft(&mut conn, &user_pk, "first attempt", None, now)
+            .await
+            .unwrap();
+
+        let send_after = now + Duration::hours(3);
+        upsert_draft(
+            &mut conn,
+            &user_pk,
+            "second attempt",
+            Some(send_after),
+            now + Duration::minutes(1),
+        )
+        .await
+        .unwrap();
+
+        let saved = drafts(&mut conn).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].user_pk, user_pk);
+        assert_eq!(saved[0].message, "second attempt");
+        assert_eq!(saved[0].send_after, Some(send_after));
+
+        delete_draft(&mut conn, &user_pk).await.unwrap();
+        assert!(drafts(&mut conn).await.unwrap().is_empty());
+
+        Ok(())
+    }
+}
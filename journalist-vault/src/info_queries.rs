@@ -1,4 +1,9 @@
-use common::api::models::{dead_drops::DeadDropId, journalist_id::JournalistIdentity};
+use common::{
+    api::models::{dead_drops::DeadDropId, journalist_id::JournalistIdentity},
+    backup::secret_sharing_settings::SignedSecretSharingSettings,
+    key_transparency::TreeHead,
+    system::message_retention::{MessageRetentionLimit, SignedMessageRetentionLimit},
+};
 use sqlx::SqliteConnection;
 
 pub(crate) async fn create_initial_info(
@@ -59,3 +64,144 @@ pub(crate) async fn set_max_dead_drop_id(
 
     Ok(())
 }
+
+/// The message retention limit last published by the admins, or the default if the vault has
+/// never seen one
+pub(crate) async fn message_retention_limit(
+    conn: &mut SqliteConnection,
+) -> anyhow::Result<MessageRetentionLimit> {
+    let row = sqlx::query!(
+        r#"
+            SELECT
+                max_message_retention_days AS "max_message_retention_days: i64"
+            FROM vault_info
+        "#
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let Some(days) = row.max_message_retention_days else {
+        return Ok(MessageRetentionLimit::default());
+    };
+
+    MessageRetentionLimit::new(u16::try_from(days)?)
+}
+
+/// The newest signed message retention limit the vault has accepted
+pub(crate) async fn signed_message_retention_limit(
+    conn: &mut SqliteConnection,
+) -> anyhow::Result<Option<SignedMessageRetentionLimit>> {
+    let row = sqlx::query!(
+        r#"
+            SELECT
+                message_retention_limit_json
+            FROM vault_info
+        "#
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let limit = row
+        .message_retention_limit_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?;
+
+    Ok(limit)
+}
+
+pub(crate) async fn set_message_retention_limit(
+    conn: &mut SqliteConnection,
+    signed_limit: &SignedMessageRetentionLimit,
+) -> anyhow::Result<()> {
+    let limit_json = serde_json::to_string(signed_limit)?;
+
+    sqlx::query!(
+        r#"
+            UPDATE vault_info
+            SET max_message_retention_days = ?1,
+                message_retention_limit_json = ?2
+        "#,
+        signed_limit.limit.max_retention_days,
+        limit_json
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The newest key transparency tree head the vault has accepted
+pub(crate) async fn latest_tree_head(
+    conn: &mut SqliteConnection,
+) -> anyhow::Result<Option<TreeHead>> {
+    let row = sqlx::query!(
+        r#"
+            SELECT
+                latest_tree_head_json
+            FROM vault_info
+        "#
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let tree_head = row
+        .latest_tree_head_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?;
+
+    Ok(tree_head)
+}
+
+pub(crate) async fn set_latest_tree_head(
+    conn: &mut SqliteConnection,
+    tree_head: &TreeHead,
+) -> anyhow::Result<()> {
+    let tree_head_json = serde_json::to_string(tree_head)?;
+
+    sqlx::query!(
+        "UPDATE vault_info SET latest_tree_head_json = ?1",
+        tree_head_json
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// The newest signed secret sharing settings the vault has accepted
+pub(crate) async fn backup_secret_sharing_settings(
+    conn: &mut SqliteConnection,
+) -> anyhow::Result<Option<SignedSecretSharingSettings>> {
+    let row = sqlx::query!(
+        r#"
+            SELECT
+                backup_secret_sharing_settings_json
+            FROM vault_info
+        "#
+    )
+    .fetch_one(conn)
+    .await?;
+
+    let settings = row
+        .backup_secret_sharing_settings_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?;
+
+    Ok(settings)
+}
+
+pub(crate) async fn set_backup_secret_sharing_settings(
+    conn: &mut SqliteConnection,
+    settings: &SignedSecretSharingSettings,
+) -> anyhow::Result<()> {
+    let settings_json = serde_json::to_string(settings)?;
+
+    sqlx::query!(
+        "UPDATE vault_info SET backup_secret_sharing_settings_json = ?1",
+        settings_json
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use common::protocol::{
+    constants::JOURNALIST_ID_KEY_ROTATE_AFTER,
+    keys::{
+        verify_journalist_provisioning_pk, AnchorOrganizationPublicKeys, JournalistIdKeyPair,
+        JournalistProvisioningPublicKey, UntrustedJournalistIdKeyPair,
+        UntrustedJournalistMessagingKeyPair, UntrustedJournalistProvisioningPublicKey,
+    },
+};
+use serde::Serialize;
+use sqlx::SqliteConnection;
+use ts_rs::TS;
+
+use crate::message_queries;
+
+/// A stored key which failed one of the integrity checks
+#[derive(Clone, Debug, TS, Serialize)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct KeyProblem {
+    /// The row id of the key in its table
+    pub id: i64,
+    pub reason: String,
+}
+
+/// The problems found by [`crate::JournalistVault::check_integrity`]
+#[derive(Clone, Debug, Default, TS, Serialize)]
+#[ts(export, rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// Problems reported by SQLite's `integrity_check` pragma
+    pub database_errors: Vec<String>,
+    /// Provisioning public keys which don't verify against any trust anchor
+    pub unverified_provisioning_pks: Vec<KeyProblem>,
+    /// Published ID key pairs which don't verify against their provisioning public key
+    pub unverified_id_key_pairs: Vec<KeyProblem>,
+    /// Published messaging key pairs which don't verify against their ID key pair
+    pub unverified_msg_key_pairs: Vec<KeyProblem>,
+    /// Candidate ID key pairs which were never promoted, for example because Sentinel
+    /// stopped part way through a rotation
+    pub stale_candidate_id_key_pairs: Vec<KeyProblem>,
+    /// Candidate messaging key pairs which can no longer be promoted
+    pub stale_candidate_msg_key_pairs: Vec<KeyProblem>,
+    /// Queued messages which aren't read receipts and don't belong to a message in the vault
+    pub orphaned_queue_message_ids: Vec<i64>,
+    /// True if the repairable problems in this report have been fixed
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.database_errors.is_empty()
+            && self.unverified_provisioning_pks.is_empty()
+            && self.unverified_id_key_pairs.is_empty()
+            && self.unverified_msg_key_pairs.is_empty()
+            && self.stale_candidate_id_key_pairs.is_empty()
+            && self.stale_candidate_msg_key_pairs.is_empty()
+            && self.orphaned_queue_message_ids.is_empty()
+    }
+}
+
+pub(crate) async fn check_integrity(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    trust_anchors: AnchorOrganizationPublicKeys,
+) -> anyhow::Result<IntegrityReport> {
+    // `integrity_check` returns a single "ok" row when no problems are found
+    let database_errors = sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .filter(|row| row != "ok")
+        .collect();
+
+    let mut report = IntegrityReport {
+        database_errors,
+        ..Default::default()
+    };
+
+    let provisioning_pks =
+        check_provisioning_pks(&mut *conn, now, trust_anchors, &mut report).await?;
+    let id_key_pairs = check_id_key_pairs(&mut *conn, now, &provisioning_pks, &mut report).await?;
+    check_msg_key_pairs(&mut *conn, now, &id_key_pairs, &mut report).await?;
+    check_candidate_id_key_pairs(&mut *conn, now, &mut report).await?;
+
+    report.orphaned_queue_message_ids = sqlx::query_scalar!(
+        r#"
+            SELECT id AS "id: i64"
+            FROM outbound_queue
+            WHERE is_read_receipt = 0
+            AND NOT EXISTS (
+                SELECT 1 FROM j2u_messages
+                WHERE j2u_messages.outbound_queue_id = outbound_queue.id
+            )
+            ORDER BY id
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(report)
+}
+
+/// Fix the problems in `report` which can be fixed without losing anything the journalist needs.
+///
+/// Keys which fail verification are left alone since an out of date set of trust anchors
+/// would make perfectly good keys look broken, and deleting a messaging key would make
+/// messages encrypted to it unreadable.
+pub(crate) async fn repair(
+    conn: &mut SqliteConnection,
+    report: &IntegrityReport,
+) -> anyhow::Result<()> {
+    for id in &report.orphaned_queue_message_ids {
+        message_queries::delete_queue_message(&mut *conn, *id).await?;
+    }
+
+    for candidate in &report.stale_candidate_id_key_pairs {
+        sqlx::query!(
+            r#"DELETE FROM candidate_journalist_id_key_pair WHERE id = ?1"#,
+            candidate.id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for candidate in &report.stale_candidate_msg_key_pairs {
+        sqlx::query!(
+            r#"DELETE FROM journalist_msg_key_pairs WHERE id = ?1 AND epoch IS NULL"#,
+            candidate.id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn check_provisioning_pks(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    trust_anchors: AnchorOrganizationPublicKeys,
+    report: &mut IntegrityReport,
+) -> anyhow::Result<HashMap<i64, JournalistProvisioningPublicKey>> {
+    let org_pks_from_trust_anchors = trust_anchors.into_non_anchors();
+
+    let rows = sqlx::query!(
+        r#"
+            SELECT
+                id      AS "id: i64",
+                pk_json AS "pk_json: String"
+            FROM journalist_provisioning_pks
+            ORDER BY id
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut verified = HashMap::new();
+
+    for row in rows {
+        let provisioning_pk =
+            match serde_json::from_str::<UntrustedJournalistProvisioningPublicKey>(&row.pk_json) {
+                Ok(provisioning_pk) => provisioning_pk,
+                Err(e) => {
+                    report.unverified_provisioning_pks.push(KeyProblem {
+                        id: row.id,
+                        reason: format!("Could not parse key: {e}"),
+                    });
+                    continue;
+                }
+            };
+
+        match org_pks_from_trust_anchors.iter().find_map(|org_pk| {
+            verify_journalist_provisioning_pk(&provisioning_pk, org_pk, now).ok()
+        }) {
+            Some(provisioning_pk) => {
+                verified.insert(row.id, provisioning_pk);
+            }
+            None => report.unverified_provisioning_pks.push(KeyProblem {
+                id: row.id,
+                reason: "Does not verify against any trust anchor".to_string(),
+            }),
+        }
+    }
+
+    Ok(verified)
+}
+
+async fn check_id_key_pairs(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    provisioning_pks: &HashMap<i64, JournalistProvisioningPublicKey>,
+    report: &mut IntegrityReport,
+) -> anyhow::Result<HashMap<i64, JournalistIdKeyPair>> {
+    let rows = sqlx::query!(
+        r#"
+            SELECT
+                id                 AS "id: i64",
+                provisioning_pk_id AS "provisioning_pk_id: i64",
+                key_pair_json      AS "key_pair_json: String"
+            FROM journalist_id_key_pairs
+            ORDER BY id
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut verified = HashMap::new();
+
+    for row in rows {
+        let Some(provisioning_pk) = provisioning_pks.get(&row.provisioning_pk_id) else {
+            report.unverified_id_key_pairs.push(KeyProblem {
+                id: row.id,
+                reason: format!(
+                    "Parent provisioning key {} is missing or could not be verified",
+                    row.provisioning_pk_id
+                ),
+            });
+            continue;
+        };
+
+        let id_key_pair = serde_json::from_str::<UntrustedJournalistIdKeyPair>(&row.key_pair_json)
+            .map_err(anyhow::Error::from)
+            .and_then(|id_key_pair| id_key_pair.to_trusted(provisioning_pk, now));
+
+        match id_key_pair {
+            Ok(id_key_pair) => {
+                verified.insert(row.id, id_key_pair);
+            }
+            Err(e) => report.unverified_id_key_pairs.push(KeyProblem {
+                id: row.id,
+                reason: format!("Does not verify against provisioning key: {e}"),
+            }),
+        }
+    }
+
+    Ok(verified)
+}
+
+/// Published messaging keys which fail verification are reported as unverified. Candidates which
+/// fail verification can never be promoted so they are reported as stale, as are all but the
+/// newest candidate.
+async fn check_msg_key_pairs(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    id_key_pairs: &HashMap<i64, JournalistIdKeyPair>,
+    report: &mut IntegrityReport,
+) -> anyhow::Result<()> {
+    let rows = sqlx::query!(
+        r#"
+            SELECT
+                id             AS "id: i64",
+                id_key_pair_id AS "id_key_pair_id: i64",
+                key_pair_json  AS "key_pair_json: String",
+                epoch IS NULL  AS "is_candidate: bool"
+            FROM journalist_msg_key_pairs
+            ORDER BY added_at DESC, id DESC
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut seen_candidate = false;
+
+    for row in rows {
+        let verification = match id_key_pairs.get(&row.id_key_pair_id) {
+            Some(id_key_pair) => {
+                serde_json::from_str::<UntrustedJournalistMessagingKeyPair>(&row.key_pair_json)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|msg_key_pair| msg_key_pair.to_trusted(id_key_pair.public_key(), now))
+                    .map(|_| ())
+                    .map_err(|e| format!("Does not verify against ID key: {e}"))
+            }
+            None => Err(format!(
+                "Parent ID key {} is missing or could not be verified",
+                row.id_key_pair_id
+            )),
+        };
+
+        if row.is_candidate {
+            let reason = match verification {
+                Err(reason) => Some(reason),
+                Ok(()) if seen_candidate => Some("A newer candidate exists".to_string()),
+                Ok(()) => None,
+            };
+            seen_candidate = true;
+
+            if let Some(reason) = reason {
+                report
+                    .stale_candidate_msg_key_pairs
+                    .push(KeyProblem { id: row.id, reason });
+            }
+        } else if let Err(reason) = verification {
+            report
+                .unverified_msg_key_pairs
+                .push(KeyProblem { id: row.id, reason });
+        }
+    }
+
+    Ok(())
+}
+
+/// A candidate ID key pair is normally promoted within a few minutes of being created. One which
+/// is older than the rotation period, or isn't the newest candidate, is left over from a failed
+/// rotation.
+async fn check_candidate_id_key_pairs(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    report: &mut IntegrityReport,
+) -> anyhow::Result<()> {
+    let rows = sqlx::query!(
+        r#"
+            SELECT
+                id       AS "id: i64",
+                added_at AS "added_at: DateTime<Utc>"
+            FROM candidate_journalist_id_key_pair
+            ORDER BY added_at DESC, id DESC
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let reason = if index > 0 {
+            "A newer candidate exists".to_string()
+        } else if row.added_at + JOURNALIST_ID_KEY_ROTATE_AFTER < now {
+            format!("Created at {} and never published", row.added_at)
+        } else {
+            continue;
+        };
+
+        report
+            .stale_candidate_id_key_pairs
+            .push(KeyProblem { id: row.id, reason });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use common::{
+        api::models::messages::journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage,
+        crypto::keys::{encryption::UnsignedEncryptionKeyPair, signing::UnsignedSigningKeyPair},
+        epoch::Epoch,
+        protocol::{
+            constants::{
+                JOURNALIST_ID_KEY_VALID_DURATION, JOURNALIST_PROVISIONING_KEY_VALID_DURATION,
+                JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN,
+            },
+            keys::{anchor_org_pk, generate_organization_key_pair, AnchorOrganizationPublicKeys},
+            roles::User,
+        },
+    };
+    use sqlx::{pool::PoolConnection, Sqlite};
+
+    use crate::{
+        id_key_queries::{insert_candidate_id_key_pair, insert_registered_id_key_pair},
+        message_queries::{enqueue_message, enqueue_read_receipt, get_queue_length},
+        provisioning_key_queries::insert_journalist_provisioning_pk,
+        user_queries,
+    };
+
+    use super::{check_integrity, repair};
+
+    #[sqlx::test]
+    async fn test_check_and_repair_integrity(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let org_key_pair = generate_organization_key_pair(now);
+        let anchor_org_pk = anchor_org_pk(
+            &org_key_pair.to_public_key().to_untrusted().to_tofu_anchor(),
+            now,
+        )
+        .unwrap();
+        let trust_anchors = AnchorOrganizationPublicKeys::new(vec![anchor_org_pk]);
+
+        // A provisioning key from our organization and one from an organization we don't trust
+        let provisioning_key_pair = UnsignedSigningKeyPair::generate().to_signed_key_pair(
+            &org_key_pair,
+            now + JOURNALIST_PROVISIONING_KEY_VALID_DURATION,
+        );
+        insert_journalist_provisioning_pk(&mut conn, &provisioning_key_pair.to_public_key(), now)
+            .await
+            .unwrap();
+
+        let untrusted_provisioning_key_pair = UnsignedSigningKeyPair::generate()
+            .to_signed_key_pair(
+                &generate_organization_key_pair(now),
+                now + JOURNALIST_PROVISIONING_KEY_VALID_DURATION,
+            );
+        insert_journalist_provisioning_pk(
+            &mut conn,
+            &untrusted_provisioning_key_pair.to_public_key(),
+            now,
+        )
+        .await
+        .unwrap();
+
+        let id_key_pair = UnsignedSigningKeyPair::generate().to_signed_key_pair(
+            &provisioning_key_pair,
+            now + JOURNALIST_ID_KEY_VALID_DURATION,
+        );
+        insert_registered_id_key_pair(&mut conn, 1, &id_key_pair, now, now, Epoch(0))
+            .await
+            .unwrap();
+
+        // A rotation which never finished
+        insert_candidate_id_key_pair(
+            &mut conn,
+            &UnsignedSigningKeyPair::generate(),
+            now - Duration::weeks(5),
+        )
+        .await
+        .unwrap();
+
+        let message = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
+            [1; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN].to_vec(),
+        );
+        enqueue_read_receipt(&mut conn, message.clone(), now)
+            .await
+            .unwrap();
+        let orphaned_queue_id = enqueue_message(&mut conn, message, None, now)
+            .await
+            .unwrap();
+
+        let user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        user_queries::add_user(&mut conn, &user_pk, now)
+            .await
+            .unwrap();
+
+        let report = check_integrity(&mut conn, now, trust_anchors.clone())
+            .await
+            .unwrap();
+
+        assert!(report.database_errors.is_empty());
+        assert_eq!(report.unverified_provisioning_pks.len(), 1);
+        assert_eq!(report.unverified_provisioning_pks[0].id, 2);
+        assert!(report.unverified_id_key_pairs.is_empty());
+        assert_eq!(report.stale_candidate_id_key_pairs.len(), 1);
+        assert_eq!(report.orphaned_queue_message_ids, vec![orphaned_queue_id]);
+        assert!(!report.is_ok());
+
+        repair(&mut conn, &report).await.unwrap();
+
+        let report = check_integrity(&mut conn, now, trust_anchors)
+            .await
+            .unwrap();
+
+        // Keys which don't verify are only reported, never deleted
+        assert_eq!(report.unverified_provisioning_pks.len(), 1);
+        assert!(report.stale_candidate_id_key_pairs.is_empty());
+        assert!(report.orphaned_queue_message_ids.is_empty());
+
+        // The read receipt is still waiting to be sent
+        assert_eq!(get_queue_length(&mut conn).await.unwrap(), 1);
+
+        // Users whose messages have all been removed by retention cleanup are kept
+        let user_pks = user_queries::user_pks(&mut conn)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(user_pks, vec![user_pk]);
+
+        Ok(())
+    }
+}
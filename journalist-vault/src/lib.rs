@@ -1,11 +1,14 @@
 mod backup_queries;
+mod draft_queries;
 mod id_key_queries;
 mod info_queries;
+mod integrity_check;
 pub mod key_rows;
 pub mod logging;
 mod message_queries;
 mod msg_key_queries;
 pub mod provisioning_key_queries;
+mod retention_policy;
 #[cfg(test)]
 mod test_vault_clean_up;
 mod user_queries;
@@ -33,36 +36,47 @@ use common::{
             journalist_id::JournalistIdentity,
             messages::{
                 journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage,
+                read_receipt::ReadReceipt,
                 user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId,
             },
         },
     },
     argon2_sqlcipher::Argon2SqlCipher,
+    backup::secret_sharing_settings::SignedSecretSharingSettings,
     client::mailbox::mailbox_message::UserStatus,
     crypto::keys::{
+        encryption::PublicEncryptionKey,
         public_key::PublicKey,
         signing::{SignedPublicSigningKey, UnsignedSigningKeyPair},
     },
     epoch::Epoch,
     identity_api::models::UntrustedJournalistIdPublicKeyWithEpoch,
+    key_transparency::KnownTreeHeads,
     protocol::{
         constants::MESSAGE_VALID_FOR_DURATION,
+        evidence_bundle::{EvidenceBundle, ExportedConversation, ExportedMessage},
         keys::{
-            generate_journalist_messaging_key_pair, verify_journalist_provisioning_pk,
-            AnchorOrganizationPublicKey, AnchorOrganizationPublicKeys, JournalistIdKeyPair,
-            JournalistMessagingKeyPair, JournalistProvisioningPublicKey, LatestKey,
-            UnregisteredJournalistIdKeyPair, UserPublicKey,
+            generate_journalist_messaging_key_pair, verify_journalist_id_pk,
+            verify_journalist_provisioning_pk, AnchorOrganizationPublicKey,
+            AnchorOrganizationPublicKeys, JournalistIdKeyPair, JournalistMessagingKeyPair,
+            JournalistProvisioningPublicKey, LatestKey, UnregisteredJournalistIdKeyPair,
+            UserPublicKey,
         },
-        roles::JournalistProvisioning,
+        log_export::{ExportedLogEntry, ExportedLogs, LogScrubber},
+        roles::{EvidenceRecipient, JournalistProvisioning},
     },
+    system::message_retention::{MessageRetentionLimit, SignedMessageRetentionLimit},
     FixedSizeMessageText,
 };
+pub use draft_queries::Draft;
 use id_key_queries::{candidate_id_key_pair, insert_candidate_id_key_pair};
+pub use integrity_check::{IntegrityReport, KeyProblem};
 use logging::LogEntry;
 use msg_key_queries::{
     candidate_msg_key_pair, insert_candidate_msg_key_pair,
     promote_candidate_msg_key_pair_to_published,
 };
+pub use retention_policy::RetentionPolicy;
 use sqlx::Acquire;
 use sqlx::SqlitePool;
 
@@ -74,6 +88,21 @@ pub type QueuedMessageId = i64;
 pub struct EncryptedJournalistToCoverNodeMessageWithId {
     pub id: QueuedMessageId,
     pub message: EncryptedJournalistToCoverNodeMessage,
+    /// When the first of the CoverNode keys the message was encrypted to expires, if known
+    pub covernode_msg_keys_not_valid_after: Option<DateTime<Utc>>,
+}
+
+/// The unencrypted contents of a message in the outbound queue, used to encrypt it again if
+/// the CoverNode keys it was encrypted to expire before it is sent.
+pub enum QueuedJ2UMessage {
+    Message {
+        user_pk: UserPublicKey,
+        message: FixedSizeMessageText,
+    },
+    HandOver {
+        user_pk: UserPublicKey,
+        to: JournalistIdentity,
+    },
 }
 
 /// Some journalist vault functions can optionally replace an existing item. For example,
@@ -94,6 +123,7 @@ pub struct User {
     pub description: Option<String>,
     pub status: UserStatus,
     pub marked_as_unread: bool,
+    pub retention_policy: RetentionPolicy,
 }
 
 #[derive(Clone)]
@@ -155,6 +185,10 @@ impl JournalistVault {
         let mut conn = pool.acquire().await?;
         let _ = info_queries::journalist_id(&mut conn).await?;
 
+        let mut tx = conn.begin().await?;
+        message_queries::index_unindexed_messages(&mut tx).await?;
+        tx.commit().await?;
+
         Ok(Self {
             pool,
             trust_anchors,
@@ -214,6 +248,74 @@ impl JournalistVault {
         logging::select_log_entries(&mut conn, min_level, search_term, before, limit, offset).await
     }
 
+    /// Add the users in this vault, and the contents of their messages and drafts, to a
+    /// scrubber so they can be removed from exported logs
+    pub async fn add_to_log_scrubber(&self, scrubber: &mut LogScrubber) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+
+        for user_pk in user_queries::user_pks(&mut conn).await? {
+            scrubber.add_user(&user_pk);
+        }
+
+        for message in message_queries::messages(&mut conn).await? {
+            match message {
+                VaultMessage::U2J(m) => scrubber.add_message(&m.message),
+                VaultMessage::J2U(m) => scrubber.add_message(&m.message),
+            }
+        }
+
+        for draft in draft_queries::drafts(&mut conn).await? {
+            scrubber.add_message(&draft.message);
+        }
+
+        Ok(())
+    }
+
+    /// Export the log entries written between `from` and `to`. If a scrubber is provided each
+    /// entry is passed through it first.
+    pub async fn export_logs(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        scrubber: Option<&LogScrubber>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<ExportedLogs> {
+        let mut conn = self.pool.acquire().await?;
+
+        let journalist_identity = info_queries::journalist_id(&mut conn).await?;
+
+        let entries = logging::select_log_entries_between(&mut conn, from, to)
+            .await?
+            .into_iter()
+            .map(|entry| {
+                let (target, message) = match scrubber {
+                    Some(scrubber) => (
+                        scrubber.scrub(&entry.target),
+                        scrubber.scrub(&entry.message),
+                    ),
+                    None => (entry.target, entry.message),
+                };
+
+                ExportedLogEntry {
+                    session_id: entry.session_id.unwrap_or_default(),
+                    timestamp: entry.timestamp,
+                    level: entry.level,
+                    target,
+                    message,
+                }
+            })
+            .collect();
+
+        Ok(ExportedLogs {
+            journalist_identity,
+            exported_at: now,
+            from,
+            to,
+            scrubbed: scrubber.is_some(),
+            entries,
+        })
+    }
+
     //
     // Info
     //
@@ -339,6 +441,26 @@ impl JournalistVault {
         let mut tx = self.pool.begin().await?;
 
         for message in messages {
+            if let Some(key_rotation) = &message.u2j_message.key_rotation {
+                user_queries::rotate_user_key(
+                    &mut tx,
+                    &key_rotation.previous_reply_key,
+                    &message.u2j_message.reply_key,
+                )
+                .await?;
+                continue;
+            }
+
+            if let Some(read_receipt) = &message.u2j_message.read_receipt {
+                message_queries::mark_j2u_messages_read_by_user(
+                    &mut tx,
+                    &message.u2j_message.reply_key,
+                    read_receipt,
+                )
+                .await?;
+                continue;
+            }
+
             // insert into users table if not already present
             user_queries::add_user(&mut tx, &message.u2j_message.reply_key, now).await?;
 
@@ -369,6 +491,33 @@ impl JournalistVault {
         // we'd be passing in the public key hierarchy.
         unencrypted_message: &FixedSizeMessageText,
         encrypted_message: EncryptedJournalistToCoverNodeMessage,
+        covernode_msg_keys_not_valid_after: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<i64> {
+        self.add_messages_from_journalist_to_user_and_enqueue(
+            user_pk,
+            vec![(unencrypted_message.clone(), encrypted_message)],
+            None,
+            covernode_msg_keys_not_valid_after,
+            now,
+        )
+        .await
+    }
+
+    /// Add the fragments of a long message to the user's conversation and the outbound queue
+    /// in a single transaction, so either all fragments are sent or none are.
+    ///
+    /// If `send_after` is set the fragments stay in the queue until then. Any draft reply to the
+    /// user is removed since it has now been submitted.
+    ///
+    /// `covernode_msg_keys_not_valid_after` is when the first of the CoverNode messaging keys the
+    /// fragments were encrypted to expires, see [`Self::head_queue_message`].
+    pub async fn add_messages_from_journalist_to_user_and_enqueue(
+        &self,
+        user_pk: &UserPublicKey,
+        messages: Vec<(FixedSizeMessageText, EncryptedJournalistToCoverNodeMessage)>,
+        send_after: Option<DateTime<Utc>>,
+        covernode_msg_keys_not_valid_after: DateTime<Utc>,
         now: DateTime<Utc>,
     ) -> anyhow::Result<i64> {
         let mut tx = self.pool.begin().await?;
@@ -376,15 +525,58 @@ impl JournalistVault {
         // insert into users table if not already present
         user_queries::add_user(&mut tx, user_pk, now).await?;
 
-        let queue_id = message_queries::enqueue_message(&mut tx, encrypted_message).await?;
-        message_queries::add_j2u_message(
+        // Scheduled messages are shown in the conversation, and expire, from when they're due
+        let sent_at = send_after.map_or(now, |send_after| send_after.max(now));
+
+        for (unencrypted_message, encrypted_message) in messages {
+            let queue_id = message_queries::enqueue_message(
+                &mut tx,
+                encrypted_message,
+                send_after,
+                covernode_msg_keys_not_valid_after,
+            )
+            .await?;
+            message_queries::add_j2u_message(
+                &mut tx,
+                user_pk,
+                &unencrypted_message,
+                sent_at,
+                Some(queue_id),
+            )
+            .await?;
+        }
+
+        draft_queries::delete_draft(&mut tx, user_pk).await?;
+
+        let queue_length = message_queries::get_queue_length(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(queue_length)
+    }
+
+    /// Hand the conversation with a user over to another journalist or desk. The hand over
+    /// message is placed in the outbound queue and recorded in the user's conversation.
+    pub async fn add_hand_over_from_journalist_to_user_and_enqueue(
+        &self,
+        user_pk: &UserPublicKey,
+        to: &JournalistIdentity,
+        encrypted_message: EncryptedJournalistToCoverNodeMessage,
+        covernode_msg_keys_not_valid_after: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        user_queries::add_user(&mut tx, user_pk, now).await?;
+
+        let queue_id = message_queries::enqueue_message(
             &mut tx,
-            user_pk,
-            unencrypted_message,
-            now,
-            Some(queue_id),
+            encrypted_message,
+            None,
+            covernode_msg_keys_not_valid_after,
         )
         .await?;
+        message_queries::add_j2u_hand_over(&mut tx, user_pk, to, now, Some(queue_id)).await?;
 
         let queue_length = message_queries::get_queue_length(&mut tx).await?;
 
@@ -393,12 +585,86 @@ impl JournalistVault {
         Ok(queue_length)
     }
 
-    /// Get the oldest message in a journalist's outbound queue
+    /// A read receipt acknowledging the most recent messages received from a user
+    pub async fn read_receipt_for_user(
+        &self,
+        user_pk: &UserPublicKey,
+    ) -> anyhow::Result<ReadReceipt> {
+        let mut conn = self.pool.acquire().await?;
+
+        let messages = message_queries::u2j_message_texts(&mut conn, user_pk).await?;
+
+        Ok(ReadReceipt::for_messages(&messages))
+    }
+
+    /// Place a read receipt in the outbound queue. Read receipts aren't part of the
+    /// conversation so nothing else is recorded. Returns the new queue length.
+    pub async fn enqueue_read_receipt_from_journalist_to_user(
+        &self,
+        encrypted_message: EncryptedJournalistToCoverNodeMessage,
+        covernode_msg_keys_not_valid_after: DateTime<Utc>,
+    ) -> anyhow::Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        message_queries::enqueue_read_receipt(
+            &mut tx,
+            encrypted_message,
+            covernode_msg_keys_not_valid_after,
+        )
+        .await?;
+
+        let queue_length = message_queries::get_queue_length(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(queue_length)
+    }
+
+    /// Get the oldest message in a journalist's outbound queue which is due to be sent.
+    ///
+    /// Messages can wait in the queue for longer than the CoverNode keys they were encrypted to
+    /// are valid, so callers must check `covernode_msg_keys_not_valid_after` and use
+    /// [`Self::replace_queue_message`] to encrypt expired messages again before sending them.
     pub async fn head_queue_message(
         &self,
+        now: DateTime<Utc>,
     ) -> anyhow::Result<Option<EncryptedJournalistToCoverNodeMessageWithId>> {
         let mut conn = self.pool.acquire().await?;
-        message_queries::peek_head_queue_message(&mut conn).await
+        message_queries::peek_head_queue_message(&mut conn, now).await
+    }
+
+    /// The unencrypted contents of a queued message. Returns `None` for read receipts, which
+    /// aren't stored in the conversation.
+    pub async fn queued_j2u_message(
+        &self,
+        id: QueuedMessageId,
+    ) -> anyhow::Result<Option<QueuedJ2UMessage>> {
+        let mut conn = self.pool.acquire().await?;
+        message_queries::queued_j2u_message(&mut conn, id).await
+    }
+
+    /// Replace a queued message with one encrypted to newer CoverNode keys, keeping its place
+    /// in the queue
+    pub async fn replace_queue_message(
+        &self,
+        id: QueuedMessageId,
+        encrypted_message: EncryptedJournalistToCoverNodeMessage,
+        covernode_msg_keys_not_valid_after: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        message_queries::replace_queue_message(
+            &mut conn,
+            id,
+            encrypted_message,
+            covernode_msg_keys_not_valid_after,
+        )
+        .await
+    }
+
+    /// The number of messages in the outbound queue, including those scheduled for later
+    pub async fn queue_length(&self) -> anyhow::Result<i64> {
+        let mut conn = self.pool.acquire().await?;
+        message_queries::get_queue_length(&mut conn).await
     }
 
     /// Delete a message from the outbound queue and returns the new queue length.
@@ -415,6 +681,35 @@ impl JournalistVault {
         Ok(new_queue_length)
     }
 
+    /// Unsent replies, most recently edited first
+    pub async fn drafts(&self) -> anyhow::Result<Vec<Draft>> {
+        let mut conn = self.pool.acquire().await?;
+        draft_queries::drafts(&mut conn).await
+    }
+
+    /// Save the draft reply to a user, replacing any previous draft
+    pub async fn save_draft(
+        &self,
+        user_pk: &UserPublicKey,
+        message: &str,
+        send_after: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        user_queries::add_user(&mut tx, user_pk, now).await?;
+        draft_queries::upsert_draft(&mut tx, user_pk, message, send_after, now).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_draft(&self, user_pk: &UserPublicKey) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        draft_queries::delete_draft(&mut conn, user_pk).await
+    }
+
     pub async fn mark_as_read(&self, user_pk: &UserPublicKey) -> anyhow::Result<()> {
         let mut tx = self.pool.begin().await?;
         message_queries::mark_as_read(&mut tx, user_pk).await?;
@@ -443,6 +738,83 @@ impl JournalistVault {
         message_queries::messages(&mut conn).await
     }
 
+    /// Search the text of messages and the aliases and descriptions given to users. Returns the
+    /// matching messages, and every message from matching users, oldest first.
+    pub async fn search_messages(&self, query: &str) -> anyhow::Result<Vec<VaultMessage>> {
+        let mut conn = self.pool.acquire().await?;
+
+        message_queries::search_messages(&mut conn, query).await
+    }
+
+    /// Export every message exchanged with a user, along with their alias and description,
+    /// signed with our latest identity key and encrypted to the recipient's key. The provisioning
+    /// key which signed the identity key is included so the bundle can be verified offline.
+    pub async fn export_conversation(
+        &self,
+        user_pk: &UserPublicKey,
+        recipient_pk: &PublicEncryptionKey<EvidenceRecipient>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<EvidenceBundle> {
+        let Some(id_key_pair) = self.latest_id_key_pair(now).await? else {
+            anyhow::bail!("No identity key in journalist vault");
+        };
+
+        let untrusted_id_pk = id_key_pair.public_key().to_untrusted();
+        let Some(provisioning_pk) =
+            self.provisioning_pks(now)
+                .await?
+                .into_iter()
+                .find(|provisioning_pk| {
+                    verify_journalist_id_pk(&untrusted_id_pk, provisioning_pk, now).is_ok()
+                })
+        else {
+            anyhow::bail!("No provisioning key in journalist vault for the latest identity key");
+        };
+
+        let mut conn = self.pool.acquire().await?;
+
+        let journalist_identity = info_queries::journalist_id(&mut conn).await?;
+
+        let Some(user) = user_queries::users(&mut conn)
+            .await?
+            .into_iter()
+            .find(|user| &user.user_pk == user_pk)
+        else {
+            anyhow::bail!("User not found in journalist vault");
+        };
+
+        let messages = message_queries::messages(&mut conn)
+            .await?
+            .into_iter()
+            .filter(|message| message.user_pk() == user_pk)
+            .map(|message| match message {
+                VaultMessage::U2J(m) => ExportedMessage {
+                    from_user: true,
+                    text: m.message,
+                    timestamp: m.received_at,
+                    hand_over_to: None,
+                },
+                VaultMessage::J2U(m) => ExportedMessage {
+                    from_user: false,
+                    text: m.message,
+                    timestamp: m.sent_at,
+                    hand_over_to: m.hand_over_to,
+                },
+            })
+            .collect();
+
+        let conversation = ExportedConversation {
+            journalist_identity,
+            user_pk: user.user_pk,
+            alias: user.alias,
+            description: user.description,
+            exported_at: now,
+            messages,
+        };
+
+        conversation.to_evidence_bundle(&id_key_pair, &provisioning_pk, recipient_pk)
+    }
+
     pub async fn update_user_status(
         &self,
         user_pk: &UserPublicKey,
@@ -453,6 +825,29 @@ impl JournalistVault {
         user_queries::update_user_status(&mut conn, user_pk, status).await
     }
 
+    /// Set how long the messages exchanged with a user are kept for. Extending retention beyond
+    /// the organization's message retention limit is rejected.
+    pub async fn update_user_retention_policy(
+        &self,
+        user_pk: &UserPublicKey,
+        retention_policy: RetentionPolicy,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+
+        if let RetentionPolicy::Extend { days } = retention_policy {
+            let limit = info_queries::message_retention_limit(&mut conn).await?;
+
+            if days > limit.max_retention_days {
+                anyhow::bail!(
+                    "Messages can be kept for at most {} days, but got {days}",
+                    limit.max_retention_days
+                );
+            }
+        }
+
+        user_queries::update_user_retention_policy(&mut conn, user_pk, retention_policy).await
+    }
+
     //
     // Keys
     //
@@ -664,6 +1059,102 @@ impl JournalistVault {
         info_queries::set_max_dead_drop_id(&mut conn, max_dead_drop_id).await
     }
 
+    /// The longest messages can be kept for, as last published by the admins
+    pub async fn message_retention_limit(&self) -> anyhow::Result<MessageRetentionLimit> {
+        let mut conn = self.pool.acquire().await?;
+        info_queries::message_retention_limit(&mut conn).await
+    }
+
+    /// Store a verified message retention limit from the API so it is still enforced when the
+    /// vault is opened offline. The limit is only changed by a signed limit newer than the one
+    /// the vault has already accepted, so the API can't roll back or remove the limit.
+    pub async fn update_message_retention_limit(
+        &self,
+        signed_limit: Option<&SignedMessageRetentionLimit>,
+    ) -> anyhow::Result<()> {
+        let Some(signed_limit) = signed_limit else {
+            return Ok(());
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        let current = info_queries::signed_message_retention_limit(&mut tx).await?;
+
+        if signed_limit.supersedes(current.as_ref()) {
+            info_queries::set_message_retention_limit(&mut tx, signed_limit).await?;
+        } else {
+            tracing::warn!(
+                "Ignoring message retention limit created at {}, which is not newer than the limit in the vault",
+                signed_limit.created_at
+            );
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// The newest backup secret sharing settings the vault has accepted
+    pub async fn backup_secret_sharing_settings(
+        &self,
+    ) -> anyhow::Result<Option<SignedSecretSharingSettings>> {
+        let mut conn = self.pool.acquire().await?;
+        info_queries::backup_secret_sharing_settings(&mut conn).await
+    }
+
+    /// Store verified secret sharing settings from the API unless the vault has already accepted
+    /// newer ones, so the API can't roll backups back to settings the admins have replaced.
+    /// Returns the settings which should be used.
+    pub async fn update_backup_secret_sharing_settings(
+        &self,
+        settings: Option<&SignedSecretSharingSettings>,
+    ) -> anyhow::Result<Option<SignedSecretSharingSettings>> {
+        let mut tx = self.pool.begin().await?;
+
+        let current = info_queries::backup_secret_sharing_settings(&mut tx).await?;
+
+        let latest = match settings {
+            Some(settings) if settings.supersedes(current.as_ref()) => {
+                info_queries::set_backup_secret_sharing_settings(&mut tx, settings).await?;
+                Some(settings.clone())
+            }
+            Some(settings) => {
+                tracing::warn!(
+                    "Ignoring secret sharing settings created at {}, which are older than the settings in the vault",
+                    settings.created_at
+                );
+                current
+            }
+            None => current,
+        };
+
+        tx.commit().await?;
+
+        Ok(latest)
+    }
+
+    /// The key transparency tree heads the next public keys response is checked against
+    pub async fn known_tree_heads(&self) -> anyhow::Result<KnownTreeHeads> {
+        let mut conn = self.pool.acquire().await?;
+        let latest = info_queries::latest_tree_head(&mut conn).await?;
+
+        Ok(KnownTreeHeads::from_latest(latest))
+    }
+
+    /// Remember the newest tree head so a different view of the key transparency log is noticed
+    /// even after the vault is reopened
+    pub async fn set_known_tree_heads(
+        &self,
+        known_tree_heads: &KnownTreeHeads,
+    ) -> anyhow::Result<()> {
+        let Some(latest) = known_tree_heads.latest() else {
+            return Ok(());
+        };
+
+        let mut conn = self.pool.acquire().await?;
+        info_queries::set_latest_tree_head(&mut conn, latest).await
+    }
+
     /// Takes an iterator of journalist provisioning keys and inserts any that aren't already in the vault
     /// after verifying them with trust anchors.
     pub async fn sync_journalist_provisioning_pks(
@@ -875,16 +1366,26 @@ impl JournalistVault {
 
     /// - Delete expired id and msg key pairs
     /// - Delete expired provisioning public keys
-    /// - Remove messages that are more than MESSAGE_VALID_FOR_DURATION old
+    /// - Remove messages that are older than their user's retention policy allows, by default
+    ///   MESSAGE_VALID_FOR_DURATION, and never older than the message retention limit
     /// - Delete old logs
     pub async fn clean_up(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
         let message_deletion_duration = MESSAGE_VALID_FOR_DURATION;
 
         let mut tx = self.pool.begin().await?;
 
-        message_queries::delete_messages_before(&mut tx, now, message_deletion_duration)
-            .await
-            .context("delete old messages")?;
+        let max_message_retention = info_queries::message_retention_limit(&mut tx)
+            .await?
+            .max_retention();
+
+        message_queries::delete_messages_before(
+            &mut tx,
+            now,
+            message_deletion_duration,
+            max_message_retention,
+        )
+        .await
+        .context("delete old messages")?;
 
         // Delete expired keys
         msg_key_queries::delete_expired_msg_key_pairs(&mut tx, now)
@@ -912,6 +1413,37 @@ impl JournalistVault {
         Ok(())
     }
 
+    /// Check the vault for damage left behind by a crash, for example during a key rotation
+    /// or migration. This runs SQLite's integrity check, verifies every stored key against its
+    /// parent and looks for orphaned queue entries.
+    ///
+    /// If `repair` is set the problems which can be fixed safely are fixed in the same
+    /// transaction. Nothing is repaired if SQLite reports the database itself is damaged.
+    pub async fn check_integrity(
+        &self,
+        now: DateTime<Utc>,
+        repair: bool,
+    ) -> anyhow::Result<IntegrityReport> {
+        let trust_anchors = self.trust_anchors()?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let mut report = integrity_check::check_integrity(&mut tx, now, trust_anchors).await?;
+
+        if repair {
+            if report.database_errors.is_empty() {
+                integrity_check::repair(&mut tx, &report).await?;
+                report.repaired = true;
+            } else {
+                tracing::warn!("Not repairing vault since SQLite reported it is damaged");
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
     //
     // Backups
     //
@@ -203,6 +203,48 @@ pub async fn select_log_entries(
     Ok(entries)
 }
 
+/// Every log entry written between `from` and `to`, oldest first
+pub async fn select_log_entries_between(
+    conn: &mut SqliteConnection,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> anyhow::Result<Vec<LogEntry>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            ROWID AS "id: i64",
+            timestamp AS "timestamp: DateTime<Utc>",
+            level AS "level: String",
+            target AS "target: String",
+            message AS "message: String",
+            session_id AS "session_id: i64"
+        FROM log_entries
+        WHERE timestamp >= $1 AND timestamp <= $2
+        ORDER BY timestamp ASC
+        "#,
+        from,
+        to
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let entries = rows
+        .into_iter()
+        .map(|row| {
+            LogEntry::new(
+                row.id,
+                row.timestamp,
+                row.level,
+                row.target,
+                row.message,
+                Some(row.session_id),
+            )
+        })
+        .collect();
+
+    Ok(entries)
+}
+
 /// Delete all logging sessions and their associated log entries that are older than 2 weeks
 /// and retain only the most recent 15k DEBUG and the most recent 5k TRACE (i.e. roughly 4MB in total)
 pub async fn delete_old_logs(
@@ -1,18 +1,25 @@
 use crate::{
+    user_queries,
     vault_message::{J2UMessage, U2JMessage, VaultMessage},
-    EncryptedJournalistToCoverNodeMessageWithId,
+    EncryptedJournalistToCoverNodeMessageWithId, QueuedJ2UMessage,
 };
 use chrono::{DateTime, Duration, Utc};
 use common::{
     api::models::{
         dead_drops::DeadDropId,
-        messages::journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage,
+        journalist_id::JournalistIdentity,
+        messages::{
+            journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage,
+            read_receipt::ReadReceipt,
+        },
     },
     crypto::keys::encryption::traits::PublicEncryptionKey,
+    message_fragments::{reassemble, MessageFragment},
     protocol::keys::UserPublicKey,
     FixedSizeMessageText,
 };
 use sqlx::SqliteConnection;
+use std::collections::HashSet;
 
 pub(crate) async fn add_u2j_message(
     conn: &mut SqliteConnection,
@@ -36,17 +43,22 @@ pub(crate) async fn add_u2j_message(
         received_at,
         dead_drop_id
     )
-    .fetch_one(conn)
+    .fetch_one(&mut *conn)
     .await?;
 
-    Ok(VaultMessage::U2J(U2JMessage::new(
+    let message = VaultMessage::U2J(U2JMessage::new(
         message_id,
         user_pk.clone(),
         message.clone(),
         received_at,
         None,
         false,
-    )?))
+    )?);
+
+    let (message_id, is_from_user) = message_key(&message);
+    index_message_text(conn, message_id, is_from_user, message.message()).await?;
+
+    Ok(message)
 }
 
 pub(crate) async fn add_j2u_message(
@@ -71,20 +83,160 @@ pub(crate) async fn add_j2u_message(
         sent_at,
         outbound_queue_id
     )
-    .fetch_one(conn)
+    .fetch_one(&mut *conn)
     .await?;
 
-    Ok(VaultMessage::J2U(J2UMessage::new(
+    let message = VaultMessage::J2U(J2UMessage::new(
         message_id,
         user_pk.clone(),
         message.clone(),
         false,
         sent_at,
         None,
-    )?))
+        None,
+        false,
+        None,
+    )?);
+
+    let (message_id, is_from_user) = message_key(&message);
+    index_message_text(conn, message_id, is_from_user, message.message()).await?;
+
+    Ok(message)
+}
+
+/// Record that the conversation with a user has been handed over to another journalist.
+/// Hand overs don't contain any text so an empty message is stored alongside the new recipient.
+pub(crate) async fn add_j2u_hand_over(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+    to: &JournalistIdentity,
+    sent_at: DateTime<Utc>,
+    outbound_queue_id: Option<i64>,
+) -> anyhow::Result<VaultMessage> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    let message = FixedSizeMessageText::new("")?;
+    let message_bytes = message.as_bytes();
+
+    let hand_over_to: &str = to;
+
+    let message_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO j2u_messages
+            (user_pk, message, sent_at, outbound_queue_id, hand_over_to)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        RETURNING id"#,
+        user_pk_bytes,
+        message_bytes,
+        sent_at,
+        outbound_queue_id,
+        hand_over_to
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let message = VaultMessage::J2U(J2UMessage::new(
+        message_id,
+        user_pk.clone(),
+        message,
+        false,
+        sent_at,
+        None,
+        Some(to.clone()),
+        false,
+        None,
+    )?);
+
+    let (message_id, is_from_user) = message_key(&message);
+    index_message_text(conn, message_id, is_from_user, message.message()).await?;
+
+    Ok(message)
+}
+
+/// Add the text of a newly inserted message to the search index. Fragments of long messages are
+/// indexed without their header so each fragment can be found by the words it contains.
+async fn index_message_text(
+    conn: &mut SqliteConnection,
+    message_id: i64,
+    is_from_user: bool,
+    text: &str,
+) -> anyhow::Result<()> {
+    let text = MessageFragment::parse(text).map_or(text, |fragment| fragment.text);
+
+    sqlx::query!(
+        r#"
+            INSERT INTO message_search (text, message_id, is_from_user)
+            VALUES (?1, ?2, ?3)
+        "#,
+        text,
+        message_id,
+        is_from_user
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Message ids are only unique within the u2j or j2u table
+fn message_key(message: &VaultMessage) -> (i64, bool) {
+    match message {
+        VaultMessage::U2J(message) => (message.id, true),
+        VaultMessage::J2U(message) => (message.id, false),
+    }
+}
+
+/// Identifies the message a fragment belongs to, or `None` if the row holds a whole message
+fn fragment_key(message: &VaultMessage) -> Option<(Vec<u8>, bool, u32)> {
+    MessageFragment::parse(message.message()).map(|fragment| {
+        (
+            message.user_pk().as_bytes().to_vec(),
+            matches!(message, VaultMessage::U2J(_)),
+            fragment.message_id,
+        )
+    })
+}
+
+/// Index any messages which are missing from the search index, such as those received before
+/// the index was added to the vault.
+pub(crate) async fn index_unindexed_messages(conn: &mut SqliteConnection) -> anyhow::Result<()> {
+    let unindexed = sqlx::query!(
+        r#"
+            SELECT
+                id      AS "id: i64",
+                message AS "message: Vec<u8>",
+                TRUE    AS "is_from_user: bool"
+            FROM u2j_messages
+            WHERE id NOT IN (SELECT message_id FROM message_search WHERE is_from_user = 1)
+            UNION ALL
+            SELECT
+                id,
+                message,
+                FALSE
+            FROM j2u_messages
+            WHERE id NOT IN (SELECT message_id FROM message_search WHERE is_from_user = 0)
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in unindexed {
+        let text = FixedSizeMessageText::from_vec_unchecked(row.message).to_string()?;
+
+        index_message_text(conn, row.id, row.is_from_user, &text).await?;
+    }
+
+    Ok(())
 }
 
 pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<VaultMessage>> {
+    let rows = message_rows(conn).await?;
+
+    Ok(reassemble_messages(rows))
+}
+
+/// Every message row in the vault, oldest first, with long messages still split into fragments
+async fn message_rows(conn: &mut SqliteConnection) -> anyhow::Result<Vec<VaultMessage>> {
     let messages = sqlx::query!(
         r#"
             WITH messages AS (
@@ -96,7 +248,9 @@ pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<
                     custom_expiry,
                     read,
                     TRUE AS is_from_user,
-                    NULL AS outbound_queue_id
+                    NULL AS outbound_queue_id,
+                    NULL AS hand_over_to,
+                    NULL AS read_by_user
                 FROM u2j_messages
                 UNION ALL
                 SELECT
@@ -107,7 +261,9 @@ pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<
                     custom_expiry,
                     NULL AS read,
                     FALSE AS is_from_user,
-                    outbound_queue_id
+                    outbound_queue_id,
+                    hand_over_to,
+                    read_by_user
                 FROM j2u_messages
             )
             SELECT
@@ -118,7 +274,10 @@ pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<
                 m.timestamp                            AS "timestamp: DateTime<Utc>",
                 m.custom_expiry                        AS "custom_expiry: DateTime<Utc>",
                 m.read                                 AS "read: bool",
-                oq.message IS NULL                     AS "is_sent: bool"
+                m.hand_over_to                         AS "hand_over_to: JournalistIdentity",
+                m.read_by_user                         AS "read_by_user: bool",
+                oq.message IS NULL                     AS "is_sent: bool",
+                oq.send_after                          AS "send_after: DateTime<Utc>"
             FROM messages m
             LEFT JOIN outbound_queue oq
                 ON oq.id = m.outbound_queue_id
@@ -152,6 +311,9 @@ pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<
                     row.is_sent,
                     row.timestamp,
                     row.custom_expiry,
+                    row.hand_over_to,
+                    row.read_by_user.unwrap_or(false),
+                    row.send_after,
                 )
                 .expect("Initialize j2u message"),
             ))
@@ -163,6 +325,102 @@ pub(crate) async fn messages(conn: &mut SqliteConnection) -> anyhow::Result<Vec<
     Ok(messages)
 }
 
+/// Long messages are split into fragments which are stored as separate rows
+fn reassemble_messages(messages: Vec<VaultMessage>) -> Vec<VaultMessage> {
+    reassemble(
+        messages,
+        |m| {
+            (
+                m.user_pk().as_bytes().to_vec(),
+                matches!(m, VaultMessage::U2J(_)),
+            )
+        },
+        VaultMessage::message,
+    )
+    .into_iter()
+    .map(|r| {
+        let is_partial = r.is_partial();
+        r.message.with_reassembled_text(r.text, is_partial)
+    })
+    .collect()
+}
+
+/// Messages whose text matches every word in `query`, along with every message in conversations
+/// with users whose alias or description matches. Words match by prefix and ignore case and
+/// diacritics. Matches in one fragment of a long message return the whole message.
+pub(crate) async fn search_messages(
+    conn: &mut SqliteConnection,
+    query: &str,
+) -> anyhow::Result<Vec<VaultMessage>> {
+    let Some(query) = fts_query(query) else {
+        return Ok(vec![]);
+    };
+
+    let matching_messages = sqlx::query!(
+        r#"
+            SELECT
+                message_id   AS "message_id!: i64",
+                is_from_user AS "is_from_user!: bool"
+            FROM message_search
+            WHERE message_search MATCH ?1
+        "#,
+        query
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| (row.message_id, row.is_from_user))
+    .collect::<HashSet<_>>();
+
+    let matching_users = sqlx::query_scalar!(
+        r#"
+            SELECT user_pk AS "user_pk!: Vec<u8>"
+            FROM user_search
+            WHERE user_search MATCH ?1
+        "#,
+        query
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect::<HashSet<_>>();
+
+    let rows = message_rows(conn).await?;
+
+    // Keep every fragment of a long message if any one of them matches, so it can be reassembled
+    let matching_fragments = rows
+        .iter()
+        .filter(|message| matching_messages.contains(&message_key(message)))
+        .filter_map(fragment_key)
+        .collect::<HashSet<_>>();
+
+    let rows = rows
+        .into_iter()
+        .filter(|message| {
+            matching_messages.contains(&message_key(message))
+                || matching_users.contains(&message.user_pk().as_bytes()[..])
+                || fragment_key(message).is_some_and(|key| matching_fragments.contains(&key))
+        })
+        .collect();
+
+    Ok(reassemble_messages(rows))
+}
+
+/// Turn what a journalist typed into an FTS5 query. Each word is quoted so punctuation can't be
+/// parsed as query syntax, and matched as a prefix so results appear while typing.
+fn fts_query(query: &str) -> Option<String> {
+    let words = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
 pub(crate) async fn mark_as_read(
     conn: &mut SqliteConnection,
     user_pk: &UserPublicKey,
@@ -184,36 +442,124 @@ pub(crate) async fn mark_as_read(
     Ok(())
 }
 
+/// The text of every message received from a user, oldest first, used to build read receipts
+pub(crate) async fn u2j_message_texts(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+) -> anyhow::Result<Vec<FixedSizeMessageText>> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    let messages = sqlx::query_scalar!(
+        r#"
+            SELECT message AS "message: Vec<u8>"
+            FROM u2j_messages
+            WHERE user_pk = ?1
+            ORDER BY received_at ASC, id ASC
+        "#,
+        user_pk_bytes
+    )
+    .fetch_all(conn)
+    .await?
+    .into_iter()
+    .map(FixedSizeMessageText::from_vec_unchecked)
+    .collect();
+
+    Ok(messages)
+}
+
+/// Mark the messages sent to a user which are acknowledged by a read receipt from that user
+pub(crate) async fn mark_j2u_messages_read_by_user(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+    read_receipt: &ReadReceipt,
+) -> anyhow::Result<()> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    // Read receipts identify messages by a hash of their text, which SQLite can't compute
+    let unread = sqlx::query!(
+        r#"
+            SELECT
+                id      AS "id: i64",
+                message AS "message: Vec<u8>"
+            FROM j2u_messages
+            WHERE user_pk = ?1 AND read_by_user = 0
+        "#,
+        user_pk_bytes
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in unread {
+        let message = FixedSizeMessageText::from_vec_unchecked(row.message);
+
+        if read_receipt.acknowledges(&message) {
+            sqlx::query!(
+                r#"
+                    UPDATE j2u_messages
+                    SET read_by_user = 1
+                    WHERE id = ?1
+                "#,
+                row.id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The rows which store a message, a long message is split into fragments which are each stored
+/// as a row. Only the first fragment of a reassembled message carries its ID.
+fn fragment_row_keys(rows: &[VaultMessage], message: &VaultMessage) -> Vec<(i64, bool)> {
+    let key = message_key(message);
+
+    let Some(fragment) = rows
+        .iter()
+        .find(|row| message_key(row) == key)
+        .and_then(fragment_key)
+    else {
+        return vec![key];
+    };
+
+    rows.iter()
+        .filter(|row| fragment_key(row).as_ref() == Some(&fragment))
+        .map(message_key)
+        .collect()
+}
+
+/// Set the custom expiry of a message, including every fragment of a long message
 pub(crate) async fn set_custom_expiry(
     conn: &mut SqliteConnection,
     message: &VaultMessage,
     custom_expiry: Option<DateTime<Utc>>,
 ) -> anyhow::Result<()> {
-    match message {
-        VaultMessage::J2U(message) => {
+    let rows = message_rows(conn).await?;
+
+    for (id, is_from_user) in fragment_row_keys(&rows, message) {
+        if is_from_user {
             sqlx::query!(
                 r#"
-        UPDATE j2u_messages
+        UPDATE u2j_messages
         SET custom_expiry = ?1
         WHERE id = ?2
         "#,
                 custom_expiry,
-                message.id
+                id
             )
-            .execute(conn)
+            .execute(&mut *conn)
             .await?;
-        }
-        VaultMessage::U2J(message) => {
+        } else {
             sqlx::query!(
                 r#"
-        UPDATE u2j_messages
+        UPDATE j2u_messages
         SET custom_expiry = ?1
         WHERE id = ?2
         "#,
                 custom_expiry,
-                message.id
+                id
             )
-            .execute(conn)
+            .execute(&mut *conn)
             .await?;
         }
     }
@@ -229,20 +575,50 @@ pub(crate) async fn get_queue_length(conn: &mut SqliteConnection) -> anyhow::Res
     Ok(queue_length)
 }
 
-/// Adds a new messages to the FIFO outbound queue.
+/// Adds a new messages to the FIFO outbound queue. If `send_after` is set the message is held
+/// in the queue until that time. `covernode_msg_keys_not_valid_after` is when the first of the
+/// CoverNode keys the message was encrypted to expires.
 pub(crate) async fn enqueue_message(
     conn: &mut SqliteConnection,
     message: EncryptedJournalistToCoverNodeMessage,
+    send_after: Option<DateTime<Utc>>,
+    covernode_msg_keys_not_valid_after: DateTime<Utc>,
 ) -> anyhow::Result<i64> {
     let message = message.as_bytes();
 
     let queue_id = sqlx::query_scalar!(
         r#"
         INSERT INTO outbound_queue
-            (message)
-        VALUES (?1)
+            (message, send_after, covernode_msg_keys_not_valid_after)
+        VALUES (?1, ?2, ?3)
         RETURNING id"#,
-        message
+        message,
+        send_after,
+        covernode_msg_keys_not_valid_after
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(queue_id)
+}
+
+/// Adds a read receipt to the FIFO outbound queue. Read receipts have no matching row in
+/// `j2u_messages` so they are marked to stop the integrity check treating them as orphans.
+pub(crate) async fn enqueue_read_receipt(
+    conn: &mut SqliteConnection,
+    message: EncryptedJournalistToCoverNodeMessage,
+    covernode_msg_keys_not_valid_after: DateTime<Utc>,
+) -> anyhow::Result<i64> {
+    let message = message.as_bytes();
+
+    let queue_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO outbound_queue
+            (message, is_read_receipt, covernode_msg_keys_not_valid_after)
+        VALUES (?1, 1, ?2)
+        RETURNING id"#,
+        message,
+        covernode_msg_keys_not_valid_after
     )
     .fetch_one(conn)
     .await?;
@@ -251,28 +627,92 @@ pub(crate) async fn enqueue_message(
 }
 
 /// Returns, but does not remove, the front-most (i.e. oldest message) from the FIFO outbound queue.
+/// Messages scheduled to be sent after `now` are skipped.
 pub(crate) async fn peek_head_queue_message(
     conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
 ) -> anyhow::Result<Option<EncryptedJournalistToCoverNodeMessageWithId>> {
     let maybe_message = sqlx::query!(
         r#"
         SELECT
-            id       AS "id: i64",
-            message  AS "bytes: Vec<u8>"
+            id                                  AS "id: i64",
+            message                             AS "bytes: Vec<u8>",
+            covernode_msg_keys_not_valid_after  AS "covernode_msg_keys_not_valid_after: DateTime<Utc>"
         FROM outbound_queue
+        WHERE send_after IS NULL OR send_after <= ?1
         ORDER BY id ASC
-        LIMIT 1"#
+        LIMIT 1"#,
+        now
     )
     .fetch_optional(conn)
     .await?
     .map(|row| EncryptedJournalistToCoverNodeMessageWithId {
         id: row.id,
         message: EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(row.bytes),
+        covernode_msg_keys_not_valid_after: row.covernode_msg_keys_not_valid_after,
+    });
+
+    Ok(maybe_message)
+}
+
+/// The conversation message which was placed in the outbound queue with the given queue ID.
+/// Read receipts aren't part of the conversation so none is returned for them.
+pub(crate) async fn queued_j2u_message(
+    conn: &mut SqliteConnection,
+    queue_id: i64,
+) -> anyhow::Result<Option<QueuedJ2UMessage>> {
+    let maybe_message = sqlx::query!(
+        r#"
+        SELECT
+            user_pk       AS "user_pk: Vec<u8>",
+            message       AS "message: Vec<u8>",
+            hand_over_to  AS "hand_over_to: JournalistIdentity"
+        FROM j2u_messages
+        WHERE outbound_queue_id = ?1"#,
+        queue_id
+    )
+    .fetch_optional(conn)
+    .await?
+    .map(|row| {
+        let user_pk = UserPublicKey::from_bytes(&row.user_pk)
+            .expect("Parse user_pk into byte array in journalist vault");
+
+        match row.hand_over_to {
+            Some(to) => QueuedJ2UMessage::HandOver { user_pk, to },
+            None => QueuedJ2UMessage::Message {
+                user_pk,
+                message: FixedSizeMessageText::from_vec_unchecked(row.message),
+            },
+        }
     });
 
     Ok(maybe_message)
 }
 
+/// Replace the encrypted contents of a queued message, keeping its place in the queue
+pub(crate) async fn replace_queue_message(
+    conn: &mut SqliteConnection,
+    id: i64,
+    message: EncryptedJournalistToCoverNodeMessage,
+    covernode_msg_keys_not_valid_after: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let message = message.as_bytes();
+
+    sqlx::query!(
+        r#"
+        UPDATE outbound_queue
+        SET message = ?1, covernode_msg_keys_not_valid_after = ?2
+        WHERE id = ?3"#,
+        message,
+        covernode_msg_keys_not_valid_after,
+        id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
 /// Deletes the message with the given [id] (see [EncryptedJournalistToCoverNodeMessageWithId]) from the FIFO outbound
 /// queue.
 pub(crate) async fn delete_queue_message(
@@ -286,70 +726,196 @@ pub(crate) async fn delete_queue_message(
     Ok(())
 }
 
-pub(crate) async fn delete_messages_before(
+/// Delete a single message row and its entry in the search index
+async fn delete_message_row(
     conn: &mut SqliteConnection,
-    now: DateTime<Utc>,
-    message_deletion_duration: Duration,
+    id: i64,
+    is_from_user: bool,
 ) -> anyhow::Result<()> {
-    let deletion_cutoff = now - message_deletion_duration;
     sqlx::query!(
         r#"
-        DELETE FROM u2j_messages
-        WHERE (received_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2;"#,
-        deletion_cutoff,
-        now
+            DELETE FROM message_search
+            WHERE is_from_user = ?1 AND message_id = ?2
+        "#,
+        is_from_user,
+        id
     )
     .execute(&mut *conn)
     .await?;
 
-    sqlx::query!(
-        r#"
-        DELETE FROM j2u_messages
-        WHERE (sent_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2;"#,
-        deletion_cutoff,
-        now
-    )
-    .execute(conn)
-    .await?;
+    if is_from_user {
+        sqlx::query!(r#"DELETE FROM u2j_messages WHERE id = ?1"#, id)
+            .execute(conn)
+            .await?;
+    } else {
+        sqlx::query!(r#"DELETE FROM j2u_messages WHERE id = ?1"#, id)
+            .execute(conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Delete messages which have passed their custom expiry or, if they don't have one, are older
+/// than the retention policy of the user they were exchanged with allows.
+pub(crate) async fn delete_messages_before(
+    conn: &mut SqliteConnection,
+    now: DateTime<Utc>,
+    message_deletion_duration: Duration,
+    max_message_retention: Duration,
+) -> anyhow::Result<()> {
+    let rows = message_rows(conn).await?;
+
+    for user in user_queries::users(&mut *conn).await? {
+        let retention = user.retention_policy.retention(
+            user.status,
+            message_deletion_duration,
+            max_message_retention,
+        );
+        let deletion_cutoff = now - retention;
+        let user_pk_bytes = &user.user_pk.as_bytes()[..];
+
+        // A long message is deleted as a whole once any of its fragments is due to be deleted,
+        // rather than leaving the rest of the message behind
+        let is_due = |row: &VaultMessage| match row {
+            VaultMessage::U2J(m) => m
+                .custom_expiry
+                .map_or(m.received_at < deletion_cutoff, |expiry| expiry < now),
+            VaultMessage::J2U(m) => m
+                .custom_expiry
+                .map_or(m.sent_at < deletion_cutoff, |expiry| expiry < now),
+        };
+
+        let user_rows = rows
+            .iter()
+            .filter(|row| row.user_pk().as_bytes() == user.user_pk.as_bytes())
+            .collect::<Vec<_>>();
+
+        let due_fragments = user_rows
+            .iter()
+            .filter(|row| is_due(row))
+            .filter_map(|row| fragment_key(row))
+            .collect::<HashSet<_>>();
+
+        for row in user_rows {
+            if fragment_key(row).is_some_and(|key| due_fragments.contains(&key)) {
+                let (id, is_from_user) = message_key(row);
+                delete_message_row(conn, id, is_from_user).await?;
+            }
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM message_search
+            WHERE is_from_user = 1 AND message_id IN (
+                SELECT id FROM u2j_messages
+                WHERE user_pk = ?3
+                    AND ((received_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2)
+            );"#,
+            deletion_cutoff,
+            now,
+            user_pk_bytes
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM message_search
+            WHERE is_from_user = 0 AND message_id IN (
+                SELECT id FROM j2u_messages
+                WHERE user_pk = ?3
+                    AND ((sent_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2)
+            );"#,
+            deletion_cutoff,
+            now,
+            user_pk_bytes
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM u2j_messages
+            WHERE user_pk = ?3
+                AND ((received_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2);"#,
+            deletion_cutoff,
+            now,
+            user_pk_bytes
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM j2u_messages
+            WHERE user_pk = ?3
+                AND ((sent_at < ?1 AND custom_expiry IS NULL) OR custom_expiry < ?2);"#,
+            deletion_cutoff,
+            now,
+            user_pk_bytes
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    // FTS5 only marks deleted rows until its segments are merged, so merge them now rather than
+    // leave the text of deleted messages in the index
+    sqlx::query!(r#"INSERT INTO message_search (message_search) VALUES ('optimize')"#)
+        .execute(conn)
+        .await?;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
+    use crate::draft_queries::{drafts, upsert_draft};
     use crate::message_queries::{
-        add_j2u_message, add_u2j_message, delete_messages_before, delete_queue_message,
-        enqueue_message, messages, peek_head_queue_message, set_custom_expiry,
+        add_j2u_hand_over, add_j2u_message, add_u2j_message, delete_messages_before,
+        delete_queue_message, enqueue_message, mark_j2u_messages_read_by_user, message_rows,
+        messages, peek_head_queue_message, search_messages, set_custom_expiry, u2j_message_texts,
     };
-    use crate::user_queries::add_user;
-    use crate::VaultMessage;
-    use chrono::{DateTime, Utc};
+    use crate::user_queries::{
+        add_user, rotate_user_key, update_user_alias_and_description, update_user_retention_policy,
+        update_user_status, users,
+    };
+    use crate::{RetentionPolicy, VaultMessage};
+    use chrono::{DateTime, Duration, Utc};
+    use common::api::models::journalist_id::JournalistIdentity;
     use common::api::models::messages::journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage;
+    use common::api::models::messages::read_receipt::ReadReceipt;
+    use common::client::mailbox::mailbox_message::UserStatus;
+    use common::crypto::keys::encryption::traits::PublicEncryptionKey;
     use common::crypto::keys::encryption::UnsignedEncryptionKeyPair;
+    use common::message_fragments::split_into_fragments;
     use common::protocol::constants::JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN;
+    use common::protocol::keys::UserPublicKey;
     use common::protocol::roles::User;
     use common::FixedSizeMessageText;
     use itertools::Itertools;
     use sqlx::pool::PoolConnection;
-    use sqlx::Sqlite;
+    use sqlx::{Sqlite, SqliteConnection};
 
     #[sqlx::test]
     async fn test_message_queue_order(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
         let message_1 = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
             [1; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN].to_vec(),
         );
-        enqueue_message(&mut conn, message_1.clone())
+        enqueue_message(&mut conn, message_1.clone(), None, now)
             .await
             .expect("Add first message");
 
         let message_2 = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
             [2; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN].to_vec(),
         );
-        enqueue_message(&mut conn, message_2.clone())
+        enqueue_message(&mut conn, message_2.clone(), None, now)
             .await
             .expect("Add second message");
 
-        let oldest_message = peek_head_queue_message(&mut conn)
+        let oldest_message = peek_head_queue_message(&mut conn, now)
             .await
             .expect("Get message")
             .expect("A message to be returned");
@@ -358,7 +924,7 @@ mod test {
             .await
             .expect("Delete message");
 
-        let oldest_message = peek_head_queue_message(&mut conn)
+        let oldest_message = peek_head_queue_message(&mut conn, now)
             .await
             .expect("Get message")
             .expect("A message to be returned");
@@ -367,7 +933,7 @@ mod test {
             .await
             .expect("Delete message");
 
-        let oldest_message = peek_head_queue_message(&mut conn)
+        let oldest_message = peek_head_queue_message(&mut conn, now)
             .await
             .expect("Get message");
         assert!(oldest_message.is_none());
@@ -377,6 +943,51 @@ mod test {
 
     const ONE_HOUR: chrono::Duration = chrono::Duration::hours(1);
 
+    #[sqlx::test]
+    async fn test_scheduled_messages_wait_in_queue(
+        mut conn: PoolConnection<Sqlite>,
+    ) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+        let send_after = now + Duration::hours(2);
+
+        let scheduled_message = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
+            [1; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN].to_vec(),
+        );
+        enqueue_message(&mut conn, scheduled_message.clone(), Some(send_after), now)
+            .await
+            .expect("Add scheduled message");
+
+        let immediate_message = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
+            [2; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN].to_vec(),
+        );
+        enqueue_message(&mut conn, immediate_message.clone(), None, now)
+            .await
+            .expect("Add immediate message");
+
+        // The scheduled message is older but isn't due yet
+        let head = peek_head_queue_message(&mut conn, now)
+            .await
+            .expect("Get message")
+            .expect("A message to be returned");
+        assert_eq!(immediate_message, head.message);
+        delete_queue_message(&mut conn, head.id)
+            .await
+            .expect("Delete message");
+
+        let head = peek_head_queue_message(&mut conn, now)
+            .await
+            .expect("Get message");
+        assert!(head.is_none());
+
+        let head = peek_head_queue_message(&mut conn, send_after)
+            .await
+            .expect("Get message")
+            .expect("A message to be returned");
+        assert_eq!(scheduled_message, head.message);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn test_delete_message_before(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
         let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
@@ -488,9 +1099,14 @@ mod test {
             "There should be 8 messages before any deletion"
         );
 
-        delete_messages_before(&mut conn, now, message_deletion_duration)
-            .await
-            .unwrap();
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            message_deletion_duration,
+        )
+        .await
+        .unwrap();
 
         let messages_after_first_deletion = messages(&mut conn).await.unwrap();
         assert_eq!(
@@ -511,9 +1127,14 @@ mod test {
             "The messages with id 2 and 4 should remain in both tables"
         );
 
-        delete_messages_before(&mut conn, now, message_deletion_duration)
-            .await
-            .unwrap();
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            message_deletion_duration,
+        )
+        .await
+        .unwrap();
 
         let messages_after_second_deletion = messages(&mut conn).await.unwrap();
         assert_eq!(
@@ -529,9 +1150,14 @@ mod test {
             .await
             .unwrap();
 
-        delete_messages_before(&mut conn, now, message_deletion_duration)
-            .await
-            .unwrap();
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            message_deletion_duration,
+        )
+        .await
+        .unwrap();
 
         let messages_after_clearing_some_custom_expiry_and_running_final_deletion =
             messages(&mut conn).await.unwrap();
@@ -554,4 +1180,495 @@ mod test {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn test_delete_messages_respects_retention_policy(
+        mut conn: PoolConnection<Sqlite>,
+    ) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+        let message_deletion_duration = chrono::Duration::days(14);
+        let max_message_retention = chrono::Duration::days(60);
+
+        let message = FixedSizeMessageText::new("test message").unwrap();
+
+        let inherit_user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        let extend_user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        let extend_too_far_user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+        let muted_user_pk = UnsignedEncryptionKeyPair::<User>::generate()
+            .public_key()
+            .clone();
+
+        for (user_pk, retention_policy) in [
+            (&inherit_user_pk, RetentionPolicy::Inherit),
+            (&extend_user_pk, RetentionPolicy::Extend { days: 30 }),
+            (
+                &extend_too_far_user_pk,
+                RetentionPolicy::Extend { days: 365 },
+            ),
+            (&muted_user_pk, RetentionPolicy::KeepUntilUnmuted),
+        ] {
+            add_user(&mut conn, user_pk, now).await.unwrap();
+            update_user_retention_policy(&mut conn, user_pk, retention_policy)
+                .await
+                .unwrap();
+
+            for age_in_days in [20, 45, 90] {
+                add_u2j_message(
+                    &mut conn,
+                    user_pk,
+                    &message,
+                    now - chrono::Duration::days(age_in_days),
+                    1,
+                )
+                .await
+                .unwrap();
+            }
+        }
+        update_user_status(&mut conn, &muted_user_pk, UserStatus::Muted)
+            .await
+            .unwrap();
+
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            max_message_retention,
+        )
+        .await
+        .unwrap();
+
+        let remaining_messages = |messages: &[VaultMessage], user_pk: &UserPublicKey| {
+            messages
+                .iter()
+                .filter(|message| message.user_pk() == user_pk)
+                .count()
+        };
+
+        let remaining = messages(&mut conn).await.unwrap();
+        assert_eq!(remaining_messages(&remaining, &inherit_user_pk), 0);
+        assert_eq!(remaining_messages(&remaining, &extend_user_pk), 1);
+        assert_eq!(
+            remaining_messages(&remaining, &extend_too_far_user_pk),
+            2,
+            "Extended retention should be capped by the maximum"
+        );
+        assert_eq!(remaining_messages(&remaining, &muted_user_pk), 2);
+
+        // Once unmuted the default retention applies again
+        update_user_status(&mut conn, &muted_user_pk, UserStatus::Active)
+            .await
+            .unwrap();
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            max_message_retention,
+        )
+        .await
+        .unwrap();
+
+        let remaining = messages(&mut conn).await.unwrap();
+        assert_eq!(remaining_messages(&remaining, &muted_user_pk), 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_hand_over_round_trip(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let user_pk = user_key_pair.public_key();
+        add_user(&mut conn, user_pk, now)
+            .await
+            .expect("test user added to DB");
+
+        let message = FixedSizeMessageText::new("test message").unwrap();
+        add_j2u_message(&mut conn, user_pk, &message, now, None)
+            .await
+            .expect("Add j2u message");
+
+        let to = JournalistIdentity::new("desk").unwrap();
+        add_j2u_hand_over(&mut conn, user_pk, &to, now + ONE_HOUR, None)
+            .await
+            .expect("Add hand over");
+
+        let hand_over_to = messages(&mut conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|msg| match msg {
+                VaultMessage::J2U(msg) => msg.hand_over_to,
+                VaultMessage::U2J(_) => panic!("Expected J2U message"),
+            })
+            .collect_vec();
+
+        assert_eq!(hand_over_to, vec![None, Some(to)]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_read_receipts(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let user_pk = user_key_pair.public_key();
+        add_user(&mut conn, user_pk, now)
+            .await
+            .expect("test user added to DB");
+
+        let read = FixedSizeMessageText::new("read").unwrap();
+        let unread = FixedSizeMessageText::new("unread").unwrap();
+        for message in [&read, &unread] {
+            add_j2u_message(&mut conn, user_pk, message, now, None)
+                .await
+                .expect("Add j2u message");
+        }
+
+        let received = FixedSizeMessageText::new("received").unwrap();
+        add_u2j_message(&mut conn, user_pk, &received, now, 1)
+            .await
+            .expect("Add u2j message");
+
+        assert_eq!(
+            u2j_message_texts(&mut conn, user_pk).await.unwrap(),
+            vec![received]
+        );
+
+        mark_j2u_messages_read_by_user(&mut conn, user_pk, &ReadReceipt::for_messages([&read]))
+            .await
+            .expect("Mark messages as read");
+
+        let read_by_user = messages(&mut conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                VaultMessage::J2U(msg) => Some((msg.message, msg.read_by_user)),
+                VaultMessage::U2J(_) => None,
+            })
+            .collect_vec();
+
+        assert_eq!(
+            read_by_user,
+            vec![("read".to_string(), true), ("unread".to_string(), false)]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_search_messages(mut conn: PoolConnection<Sqlite>) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let source_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let source_pk = source_key_pair.public_key();
+        let other_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let other_pk = other_key_pair.public_key();
+
+        for user_pk in [source_pk, other_pk] {
+            add_user(&mut conn, user_pk, now)
+                .await
+                .expect("test user added to DB");
+        }
+
+        let tip = FixedSizeMessageText::new("A tip about the Mayor's café").unwrap();
+        add_u2j_message(&mut conn, source_pk, &tip, now, 1)
+            .await
+            .expect("Add u2j message");
+
+        let reply = FixedSizeMessageText::new("Thanks for the tip").unwrap();
+        add_j2u_message(&mut conn, source_pk, &reply, now + ONE_HOUR, None)
+            .await
+            .expect("Add j2u message");
+
+        let unrelated = FixedSizeMessageText::new("Something else entirely").unwrap();
+        add_u2j_message(&mut conn, other_pk, &unrelated, now, 2)
+            .await
+            .expect("Add u2j message");
+
+        async fn search(conn: &mut SqliteConnection, query: &str) -> Vec<String> {
+            search_messages(conn, query)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|msg| msg.message().to_string())
+                .collect_vec()
+        }
+
+        assert_eq!(
+            search(&mut conn, "tip").await,
+            vec!["A tip about the Mayor's café", "Thanks for the tip"]
+        );
+        // Case, diacritics and unfinished words are ignored
+        assert_eq!(
+            search(&mut conn, "MAY cafe").await,
+            vec!["A tip about the Mayor's café"]
+        );
+        // Punctuation is not parsed as query syntax
+        assert_eq!(
+            search(&mut conn, "\"mayor's\" -").await,
+            vec!["A tip about the Mayor's café"]
+        );
+        assert_eq!(search(&mut conn, "   ").await, Vec::<String>::new());
+
+        update_user_alias_and_description(
+            &mut conn,
+            other_pk,
+            "Whistleblower",
+            "Works at the port",
+        )
+        .await
+        .expect("Update alias and description");
+
+        assert_eq!(
+            search(&mut conn, "port").await,
+            vec!["Something else entirely"]
+        );
+
+        delete_messages_before(
+            &mut conn,
+            now + ONE_HOUR * 24 * 30,
+            chrono::Duration::days(14),
+            chrono::Duration::days(14),
+        )
+        .await
+        .expect("Delete messages");
+
+        assert_eq!(search(&mut conn, "tip").await, Vec::<String>::new());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_fragmented_messages_are_reassembled(
+        mut conn: PoolConnection<Sqlite>,
+    ) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let complete_user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let complete_user_pk = complete_user_key_pair.public_key();
+        let partial_user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let partial_user_pk = partial_user_key_pair.public_key();
+
+        for user_pk in [complete_user_pk, partial_user_pk] {
+            add_user(&mut conn, user_pk, now)
+                .await
+                .expect("test user added to DB");
+        }
+
+        // Random hex doesn't compress well so this needs several fragments
+        let text = (0..50)
+            .map(|_| {
+                let key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+                hex::encode(key_pair.public_key().as_bytes())
+            })
+            .collect::<String>();
+
+        let fragments = split_into_fragments(&text).unwrap();
+        assert!(fragments.len() > 2);
+
+        for (i, fragment) in fragments.iter().enumerate() {
+            let received_at = now + chrono::Duration::seconds(i as i64);
+
+            add_u2j_message(&mut conn, complete_user_pk, fragment, received_at, 1)
+                .await
+                .expect("Add u2j fragment");
+
+            if i != 1 {
+                add_u2j_message(&mut conn, partial_user_pk, fragment, received_at, 1)
+                    .await
+                    .expect("Add u2j fragment");
+            }
+        }
+
+        let reassembled = messages(&mut conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|msg| match msg {
+                VaultMessage::U2J(msg) => (msg.user_pk, msg.message, msg.is_partial),
+                VaultMessage::J2U(_) => panic!("Expected U2J message"),
+            })
+            .collect_vec();
+
+        assert_eq!(reassembled.len(), 2);
+
+        let complete = reassembled
+            .iter()
+            .find(|(user_pk, _, _)| user_pk == complete_user_pk)
+            .unwrap();
+        assert_eq!(complete.1, text);
+        assert!(!complete.2);
+
+        let partial = reassembled
+            .iter()
+            .find(|(user_pk, _, _)| user_pk == partial_user_pk)
+            .unwrap();
+        assert_ne!(partial.1, text);
+        assert!(partial.2);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_fragmented_messages_expire_together(
+        mut conn: PoolConnection<Sqlite>,
+    ) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+        let message_deletion_duration = Duration::days(14);
+
+        let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let user_pk = user_key_pair.public_key();
+        add_user(&mut conn, user_pk, now)
+            .await
+            .expect("test user added to DB");
+
+        let text = (0..50)
+            .map(|_| {
+                let key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+                hex::encode(key_pair.public_key().as_bytes())
+            })
+            .collect::<String>();
+
+        let fragments = split_into_fragments(&text).unwrap();
+        assert!(fragments.len() > 2);
+
+        // Only the first fragment was received before the deletion cutoff
+        for (i, fragment) in fragments.iter().enumerate() {
+            let received_at = if i == 0 {
+                now - Duration::days(15)
+            } else {
+                now - Duration::days(1) + Duration::seconds(i as i64)
+            };
+
+            add_u2j_message(&mut conn, user_pk, fragment, received_at, 1)
+                .await
+                .expect("Add u2j fragment");
+        }
+
+        let reassembled = messages(&mut conn).await.unwrap();
+        assert_eq!(reassembled.len(), 1);
+
+        let custom_expiry = Some(now + Duration::days(1));
+        set_custom_expiry(&mut conn, &reassembled[0], custom_expiry)
+            .await
+            .unwrap();
+
+        let rows = message_rows(&mut conn).await.unwrap();
+        assert_eq!(rows.len(), fragments.len());
+        assert!(rows.iter().all(|row| match row {
+            VaultMessage::U2J(m) => m.custom_expiry == custom_expiry,
+            VaultMessage::J2U(_) => false,
+        }));
+
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            message_deletion_duration,
+        )
+        .await
+        .unwrap();
+
+        let remaining = messages(&mut conn).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].message(), text);
+
+        set_custom_expiry(&mut conn, &remaining[0], None)
+            .await
+            .unwrap();
+
+        delete_messages_before(
+            &mut conn,
+            now,
+            message_deletion_duration,
+            message_deletion_duration,
+        )
+        .await
+        .unwrap();
+
+        assert!(message_rows(&mut conn).await.unwrap().is_empty());
+        assert!(search_messages(&mut conn, "a").await.unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_rotate_user_key_moves_conversation(
+        mut conn: PoolConnection<Sqlite>,
+    ) -> sqlx::Result<()> {
+        let now: DateTime<Utc> = "2025-07-28T10:30:00Z".parse().unwrap();
+
+        let previous_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let previous_pk = previous_key_pair.public_key();
+        let new_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+        let new_pk = new_key_pair.public_key();
+
+        add_user(&mut conn, previous_pk, now)
+            .await
+            .expect("test user added to DB");
+
+        let tip = FixedSizeMessageText::new("A tip about the harbour").unwrap();
+        add_u2j_message(&mut conn, previous_pk, &tip, now, 1)
+            .await
+            .expect("Add u2j message");
+
+        let reply = FixedSizeMessageText::new("Thanks, tell me more").unwrap();
+        add_j2u_message(&mut conn, previous_pk, &reply, now + ONE_HOUR, None)
+            .await
+            .expect("Add j2u message");
+
+        update_user_alias_and_description(&mut conn, previous_pk, "Dock worker", "Night shift")
+            .await
+            .expect("Update alias and description");
+
+        upsert_draft(&mut conn, previous_pk, "Which ship?", None, now)
+            .await
+            .expect("Save draft");
+
+        rotate_user_key(&mut conn, previous_pk, new_pk)
+            .await
+            .expect("Rotate user key");
+
+        let message_user_pks = messages(&mut conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|msg| match msg {
+                VaultMessage::U2J(msg) => msg.user_pk,
+                VaultMessage::J2U(msg) => msg.user_pk,
+            })
+            .collect_vec();
+        assert_eq!(message_user_pks, vec![new_pk.clone(), new_pk.clone()]);
+
+        let users = users(&mut conn).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(&users[0].user_pk, new_pk);
+        assert_eq!(users[0].alias.as_deref(), Some("Dock worker"));
+
+        let drafts = drafts(&mut conn).await.unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(&drafts[0].user_pk, new_pk);
+        assert_eq!(drafts[0].message, "Which ship?");
+
+        // Both the message text and the alias are still searchable under the new key
+        for query in ["harbour", "dock"] {
+            let found = search_messages(&mut conn, query).await.unwrap();
+            assert!(!found.is_empty());
+            assert!(found.iter().all(|msg| match msg {
+                VaultMessage::U2J(msg) => &msg.user_pk == new_pk,
+                VaultMessage::J2U(msg) => &msg.user_pk == new_pk,
+            }));
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,102 @@
+use chrono::Duration;
+use common::client::mailbox::mailbox_message::UserStatus;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How long the messages in a conversation are kept before they are removed when the vault is
+/// cleaned up. Whatever the policy, messages are never kept for longer than the organization's
+/// message retention limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "type")]
+#[ts(export)]
+pub enum RetentionPolicy {
+    /// Keep messages for the default `MESSAGE_VALID_FOR_DURATION`
+    Inherit,
+    /// Keep messages for the given number of days
+    Extend { days: u16 },
+    /// Keep messages for as long as the user is muted, once they are unmuted the messages are
+    /// kept for the default duration again
+    KeepUntilUnmuted,
+}
+
+impl RetentionPolicy {
+    pub(crate) fn from_row(policy: &str, days: Option<i64>) -> anyhow::Result<Self> {
+        match (policy, days) {
+            ("INHERIT", _) => Ok(Self::Inherit),
+            ("EXTEND", Some(days)) => Ok(Self::Extend {
+                days: u16::try_from(days)?,
+            }),
+            ("KEEP_UNTIL_UNMUTED", _) => Ok(Self::KeepUntilUnmuted),
+            _ => anyhow::bail!("Invalid retention policy {policy} with {days:?} days"),
+        }
+    }
+
+    pub(crate) fn to_row(self) -> (&'static str, Option<i64>) {
+        match self {
+            Self::Inherit => ("INHERIT", None),
+            Self::Extend { days } => ("EXTEND", Some(days.into())),
+            Self::KeepUntilUnmuted => ("KEEP_UNTIL_UNMUTED", None),
+        }
+    }
+
+    /// How long messages from a user with this policy and status are kept for
+    pub fn retention(
+        &self,
+        status: UserStatus,
+        default_retention: Duration,
+        max_retention: Duration,
+    ) -> Duration {
+        match (self, status) {
+            (Self::Extend { days }, _) => Duration::days((*days).into()).min(max_retention),
+            (Self::KeepUntilUnmuted, UserStatus::Muted) => max_retention,
+            (Self::Inherit, _) | (Self::KeepUntilUnmuted, UserStatus::Active) => default_retention,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: Duration = Duration::days(14);
+    const MAX: Duration = Duration::days(90);
+
+    #[test]
+    fn retention_is_capped_by_the_limit() {
+        let extend = RetentionPolicy::Extend { days: 30 };
+        assert_eq!(
+            extend.retention(UserStatus::Active, DEFAULT, MAX),
+            Duration::days(30)
+        );
+
+        let extend_too_far = RetentionPolicy::Extend { days: 365 };
+        assert_eq!(
+            extend_too_far.retention(UserStatus::Active, DEFAULT, MAX),
+            MAX
+        );
+    }
+
+    #[test]
+    fn keep_until_unmuted_only_applies_while_muted() {
+        let policy = RetentionPolicy::KeepUntilUnmuted;
+
+        assert_eq!(policy.retention(UserStatus::Muted, DEFAULT, MAX), MAX);
+        assert_eq!(policy.retention(UserStatus::Active, DEFAULT, MAX), DEFAULT);
+    }
+
+    #[test]
+    fn row_round_trip() -> anyhow::Result<()> {
+        for policy in [
+            RetentionPolicy::Inherit,
+            RetentionPolicy::Extend { days: 60 },
+            RetentionPolicy::KeepUntilUnmuted,
+        ] {
+            let (name, days) = policy.to_row();
+            assert_eq!(RetentionPolicy::from_row(name, days)?, policy);
+        }
+
+        assert!(RetentionPolicy::from_row("EXTEND", None).is_err());
+
+        Ok(())
+    }
+}
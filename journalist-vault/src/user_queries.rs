@@ -1,4 +1,4 @@
-use crate::User;
+use crate::{RetentionPolicy, User};
 use chrono::{DateTime, Utc};
 use common::{
     client::mailbox::mailbox_message::UserStatus,
@@ -31,7 +31,9 @@ pub(crate) async fn users(conn: &mut SqliteConnection) -> anyhow::Result<Vec<Use
                 alias AS "alias: String",
                 description AS "description: String",
                 status AS "status: UserStatus",
-                marked_as_unread AS "marked_as_unread: bool"
+                marked_as_unread AS "marked_as_unread: bool",
+                retention_policy AS "retention_policy: String",
+                retention_days AS "retention_days: i64"
             FROM users
         "#
     )
@@ -40,12 +42,15 @@ pub(crate) async fn users(conn: &mut SqliteConnection) -> anyhow::Result<Vec<Use
     .into_iter()
     .map(|row| {
         let user_pk = UserPublicKey::from_bytes(&row.user_pk)?;
+        let retention_policy =
+            RetentionPolicy::from_row(&row.retention_policy, row.retention_days)?;
         Ok(User {
             user_pk,
             status: row.status,
             alias: row.alias,
             description: row.description,
             marked_as_unread: row.marked_as_unread,
+            retention_policy,
         })
     })
     .collect::<anyhow::Result<Vec<_>>>()?;
@@ -76,6 +81,73 @@ pub(crate) async fn add_user(
     Ok(())
 }
 
+/// Move a user's conversation from their previous public key to their new one, keeping the
+/// status, alias and description the journalist gave them. Does nothing if the previous key is
+/// not known to this vault.
+pub(crate) async fn rotate_user_key(
+    conn: &mut SqliteConnection,
+    previous_user_pk: &UserPublicKey,
+    new_user_pk: &UserPublicKey,
+) -> anyhow::Result<()> {
+    let previous_user_pk_bytes = &previous_user_pk.as_bytes()[..];
+    let new_user_pk_bytes = &new_user_pk.as_bytes()[..];
+
+    // The new key might already be known if a message sent with it arrived before the rotation
+    sqlx::query!(
+        r#"
+            INSERT INTO users
+                (user_pk, status, status_updated_at, alias, description, marked_as_unread,
+                    retention_policy, retention_days)
+            SELECT ?1, status, status_updated_at, alias, description, marked_as_unread,
+                retention_policy, retention_days
+            FROM users
+            WHERE user_pk = ?2
+            ON CONFLICT(user_pk) DO NOTHING
+        "#,
+        new_user_pk_bytes,
+        previous_user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"UPDATE u2j_messages SET user_pk = ?1 WHERE user_pk = ?2"#,
+        new_user_pk_bytes,
+        previous_user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"UPDATE j2u_messages SET user_pk = ?1 WHERE user_pk = ?2"#,
+        new_user_pk_bytes,
+        previous_user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // If a draft was somehow written to the new key already, keep the one from the conversation
+    sqlx::query!(
+        r#"UPDATE OR REPLACE drafts SET user_pk = ?1 WHERE user_pk = ?2"#,
+        new_user_pk_bytes,
+        previous_user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"DELETE FROM users WHERE user_pk = ?1"#,
+        previous_user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    update_user_search(conn, previous_user_pk).await?;
+    update_user_search(conn, new_user_pk).await?;
+
+    Ok(())
+}
+
 pub(crate) async fn update_user_status(
     conn: &mut SqliteConnection,
     user_pk: &UserPublicKey,
@@ -98,6 +170,37 @@ pub(crate) async fn update_user_status(
     Ok(())
 }
 
+pub(crate) async fn update_user_retention_policy(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+    retention_policy: RetentionPolicy,
+) -> anyhow::Result<()> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+    let (retention_policy, retention_days) = retention_policy.to_row();
+
+    let query = sqlx::query!(
+        r#"
+            UPDATE users
+            SET retention_policy = ?1,
+            retention_days = ?2
+            WHERE user_pk = ?3"#,
+        retention_policy,
+        retention_days,
+        user_pk_bytes
+    )
+    .execute(conn)
+    .await?;
+
+    if query.rows_affected() == 0 {
+        anyhow::bail!(
+            "Failed to update retention policy for user: {}",
+            hex::encode(user_pk_bytes)
+        );
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn mark_as_read(
     conn: &mut SqliteConnection,
     user_pk: &UserPublicKey,
@@ -148,7 +251,7 @@ pub(crate) async fn update_user_alias_and_description(
         description,
         user_pk_bytes
     )
-    .execute(conn)
+    .execute(&mut *conn)
     .await?;
 
     if query.rows_affected() == 0 {
@@ -158,5 +261,37 @@ pub(crate) async fn update_user_alias_and_description(
         ));
     }
 
+    update_user_search(conn, user_pk).await?;
+
+    Ok(())
+}
+
+/// Replace the search index entry for a user with their current alias and description, or
+/// remove it if the user no longer exists.
+async fn update_user_search(
+    conn: &mut SqliteConnection,
+    user_pk: &UserPublicKey,
+) -> anyhow::Result<()> {
+    let user_pk_bytes = &user_pk.as_bytes()[..];
+
+    sqlx::query!(
+        r#"DELETE FROM user_search WHERE user_pk = ?1"#,
+        user_pk_bytes
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            INSERT INTO user_search (alias, description, user_pk)
+            SELECT alias, description, user_pk
+            FROM users
+            WHERE user_pk = ?1 AND (alias IS NOT NULL OR description IS NOT NULL)
+        "#,
+        user_pk_bytes
+    )
+    .execute(conn)
+    .await?;
+
     Ok(())
 }
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use common::{
+    api::models::journalist_id::JournalistIdentity,
     protocol::{constants::MESSAGE_VALID_FOR_DURATION, keys::UserPublicKey},
     FixedSizeMessageText,
 };
@@ -17,6 +18,8 @@ pub struct U2JMessage {
     pub normal_expiry: DateTime<Utc>,
     pub custom_expiry: Option<DateTime<Utc>>,
     pub read: bool,
+    /// Set when this message was split into fragments and some have not been received yet
+    pub is_partial: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize, TS)]
@@ -30,6 +33,15 @@ pub struct J2UMessage {
     pub sent_at: DateTime<Utc>,
     pub normal_expiry: DateTime<Utc>,
     pub custom_expiry: Option<DateTime<Utc>>,
+    /// Set when this message handed the conversation over to another journalist or desk
+    /// rather than containing text written by the journalist.
+    pub hand_over_to: Option<JournalistIdentity>,
+    /// Set when this message was split into fragments and some are missing from the vault
+    pub is_partial: bool,
+    /// Set when the user has sent a read receipt for this message
+    pub read_by_user: bool,
+    /// Set when the message is waiting in the outbound queue until a scheduled time
+    pub send_after: Option<DateTime<Utc>>,
 }
 
 impl U2JMessage {
@@ -50,6 +62,7 @@ impl U2JMessage {
             normal_expiry: received_at + MESSAGE_VALID_FOR_DURATION,
             custom_expiry,
             read,
+            is_partial: false,
         })
     }
 }
@@ -63,6 +76,9 @@ impl J2UMessage {
         is_sent: bool,
         sent_at: DateTime<Utc>,
         custom_expiry: Option<DateTime<Utc>>,
+        hand_over_to: Option<JournalistIdentity>,
+        read_by_user: bool,
+        send_after: Option<DateTime<Utc>>,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             id,
@@ -72,6 +88,10 @@ impl J2UMessage {
             sent_at,
             normal_expiry: sent_at + MESSAGE_VALID_FOR_DURATION,
             custom_expiry,
+            hand_over_to,
+            is_partial: false,
+            read_by_user,
+            send_after,
         })
     }
 }
@@ -86,3 +106,43 @@ pub enum VaultMessage {
     #[serde(rename = "journalistToUserMessage")]
     J2U(J2UMessage),
 }
+
+impl VaultMessage {
+    pub fn user_pk(&self) -> &UserPublicKey {
+        match self {
+            VaultMessage::U2J(m) => &m.user_pk,
+            VaultMessage::J2U(m) => &m.user_pk,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            VaultMessage::U2J(m) => &m.message,
+            VaultMessage::J2U(m) => &m.message,
+        }
+    }
+
+    /// When the message was received from, or sent to, the user
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            VaultMessage::U2J(m) => m.received_at,
+            VaultMessage::J2U(m) => m.sent_at,
+        }
+    }
+
+    /// Replace the text of a message with the text of all the fragments it was split into
+    pub(crate) fn with_reassembled_text(mut self, text: String, is_partial: bool) -> Self {
+        match &mut self {
+            VaultMessage::U2J(m) => {
+                m.message = text;
+                m.is_partial = is_partial;
+            }
+            VaultMessage::J2U(m) => {
+                m.message = text;
+                m.is_partial = is_partial;
+            }
+        }
+
+        self
+    }
+}
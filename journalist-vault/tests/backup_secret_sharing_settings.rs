@@ -0,0 +1,86 @@
+use chrono::{Duration, Utc};
+use common::{
+    api::models::journalist_id::JournalistIdentity,
+    backup::{
+        keys::generate_backup_id_key_pair,
+        secret_sharing_settings::{SecretSharingSettings, SignedSecretSharingSettings},
+    },
+    protocol::keys::{generate_journalist_provisioning_key_pair, generate_organization_key_pair},
+};
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+
+#[tokio::test]
+async fn older_secret_sharing_settings_are_ignored() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+
+    let journalist_id = JournalistIdentity::new("Hello").unwrap();
+    let org_key_pair = generate_organization_key_pair(now);
+    let trust_anchors = vec![org_key_pair.public_key().clone().into_anchor()];
+
+    let journalist_provisioning_key_pair =
+        generate_journalist_provisioning_key_pair(&org_key_pair, now);
+    let backup_id_key_pair = generate_backup_id_key_pair(&org_key_pair, now);
+
+    let older = SignedSecretSharingSettings::new(
+        SecretSharingSettings::new(2, 3).unwrap(),
+        &backup_id_key_pair,
+        now - Duration::days(1),
+    );
+    let newer = SignedSecretSharingSettings::new(
+        SecretSharingSettings::new(3, 4).unwrap(),
+        &backup_id_key_pair,
+        now,
+    );
+
+    {
+        let journalist_provisioning_pks =
+            vec![journalist_provisioning_key_pair.public_key().clone()];
+
+        let vault = JournalistVault::create(
+            &db_path,
+            "test_password",
+            &journalist_id,
+            &journalist_provisioning_pks,
+            now,
+            trust_anchors.clone(),
+        )
+        .await
+        .expect("Create journalist vault");
+
+        assert!(vault
+            .backup_secret_sharing_settings()
+            .await
+            .unwrap()
+            .is_none());
+
+        let latest = vault
+            .update_backup_secret_sharing_settings(Some(&newer))
+            .await
+            .unwrap();
+        assert_eq!(latest.map(|signed| signed.settings), Some(newer.settings));
+    }
+
+    let vault = JournalistVault::open(&db_path, "test_password", trust_anchors)
+        .await
+        .expect("Load journalist vault");
+
+    // The API serving older settings, or none at all, doesn't replace the newer settings
+    for settings in [Some(&older), None] {
+        let latest = vault
+            .update_backup_secret_sharing_settings(settings)
+            .await
+            .unwrap();
+        assert_eq!(latest.map(|signed| signed.settings), Some(newer.settings));
+    }
+
+    let stored = vault.backup_secret_sharing_settings().await.unwrap();
+    assert_eq!(
+        stored.map(|signed| signed.created_at),
+        Some(newer.created_at)
+    );
+}
@@ -0,0 +1,151 @@
+use chrono::{Duration, Utc};
+use common::{
+    api::models::{
+        journalist_id::JournalistIdentity,
+        messages::{
+            journalist_to_covernode_message::EncryptedJournalistToCoverNodeMessage,
+            user_to_journalist_message::UserToJournalistMessage,
+            user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId,
+        },
+    },
+    crypto::keys::encryption::UnsignedEncryptionKeyPair,
+    epoch::Epoch,
+    protocol::{
+        constants::JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN,
+        keys::{
+            generate_journalist_id_key_pair, generate_journalist_provisioning_key_pair,
+            generate_organization_key_pair,
+        },
+        roles::{EvidenceRecipient, User},
+    },
+    FixedSizeMessageText,
+};
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+
+#[tokio::test]
+async fn exported_conversation_can_be_verified_by_recipient() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+    let journalist_id = JournalistIdentity::new("Hello").unwrap();
+
+    let org_key_pair = generate_organization_key_pair(now);
+    let trust_anchors = vec![org_key_pair.public_key().clone().into_anchor()];
+
+    let journalist_provisioning_key_pair =
+        generate_journalist_provisioning_key_pair(&org_key_pair, now);
+    let journalist_id_key_pair =
+        generate_journalist_id_key_pair(&journalist_provisioning_key_pair, now);
+
+    let journalist_provisioning_pks = vec![journalist_provisioning_key_pair.public_key().clone()];
+
+    let vault = JournalistVault::create(
+        &db_path,
+        "test_password",
+        &journalist_id,
+        &journalist_provisioning_pks,
+        now,
+        trust_anchors.clone(),
+    )
+    .await
+    .expect("Create journalist vault");
+
+    // The vault was created with a single provisioning key
+    vault
+        .insert_registered_id_key_pair(1, &journalist_id_key_pair, now, now, Epoch(0))
+        .await
+        .expect("Insert identity key");
+
+    let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+    let user_pk = user_key_pair.public_key();
+    let other_user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+
+    let u2j_messages = [
+        (user_pk, "I have documents"),
+        (other_user_key_pair.public_key(), "Hi"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (from, text))| UserToJournalistMessageWithDeadDropId {
+        u2j_message: UserToJournalistMessage::new(
+            FixedSizeMessageText::new(text).unwrap(),
+            from.clone(),
+        ),
+        dead_drop_id: i as i32 + 1,
+    })
+    .collect::<Vec<_>>();
+
+    vault
+        .add_messages_from_user_to_journalist_and_update_max_dead_drop_id(&u2j_messages, 2, now)
+        .await
+        .unwrap();
+
+    let reply = FixedSizeMessageText::new("Please send them").unwrap();
+    let encrypted_reply = EncryptedJournalistToCoverNodeMessage::from_vec_unchecked(
+        vec![0; JOURNALIST_TO_COVERNODE_ENCRYPTED_MESSAGE_LEN],
+    );
+    vault
+        .add_messages_from_journalist_to_user_and_enqueue(
+            user_pk,
+            vec![(reply, encrypted_reply)],
+            None,
+            now + Duration::weeks(1),
+            now + Duration::seconds(1),
+        )
+        .await
+        .unwrap();
+
+    vault
+        .update_user_alias_and_description(user_pk, "Whistleblower", "Works in the mayor's office")
+        .await
+        .unwrap();
+
+    let recipient_key_pair = UnsignedEncryptionKeyPair::<EvidenceRecipient>::generate();
+
+    let bundle = vault
+        .export_conversation(user_pk, recipient_key_pair.public_key(), now)
+        .await
+        .expect("Export conversation");
+
+    // The bundle is checked against the organization key at the time it was exported, so it can
+    // still be verified long after the journalist's keys have expired
+    let conversation = bundle
+        .decrypt(&recipient_key_pair)
+        .unwrap()
+        .to_verified(&trust_anchors, now + Duration::days(3650))
+        .expect("Verify conversation");
+
+    assert_eq!(conversation.journalist_identity, journalist_id);
+    assert_eq!(&conversation.user_pk, user_pk);
+    assert_eq!(conversation.alias.as_deref(), Some("Whistleblower"));
+    assert_eq!(
+        conversation.description.as_deref(),
+        Some("Works in the mayor's office")
+    );
+    assert_eq!(conversation.exported_at, now);
+
+    // Only messages exchanged with this user are exported
+    let messages = conversation
+        .messages
+        .iter()
+        .map(|message| (message.from_user, message.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![(true, "I have documents"), (false, "Please send them")]
+    );
+
+    // A recipient who doesn't trust the organization rejects the bundle
+    let other_org_key_pair = generate_organization_key_pair(now);
+    assert!(bundle
+        .decrypt(&recipient_key_pair)
+        .unwrap()
+        .to_verified(
+            &[other_org_key_pair.public_key().clone().into_anchor()],
+            now
+        )
+        .is_err());
+}
@@ -0,0 +1,75 @@
+use chrono::{TimeZone as _, Utc};
+use common::{
+    api::models::journalist_id::JournalistIdentity,
+    clap::Stage,
+    key_transparency::{
+        merkle::{leaf_hash, root},
+        TreeHead,
+    },
+    protocol::keys::{generate_journalist_provisioning_key_pair, generate_organization_key_pair},
+};
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+use trust_anchors::get_trust_anchors;
+
+#[tokio::test]
+async fn latest_tree_head_survives_reopening_vault() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+
+    let journalist_id = JournalistIdentity::new("Hello").unwrap();
+    let org_key_pair = generate_organization_key_pair(now);
+
+    let journalist_provisioning_key_pair =
+        generate_journalist_provisioning_key_pair(&org_key_pair, now);
+
+    let trust_anchors = get_trust_anchors(&Stage::Development, now).expect("loaded trust anchors");
+
+    let leaves: Vec<_> = (0..4_u8).map(|i| leaf_hash(&[i])).collect();
+    let tree_head = TreeHead::new(4, root(&leaves), Utc.timestamp_opt(1_000, 0).unwrap());
+
+    {
+        let journalist_provisioning_pks =
+            vec![journalist_provisioning_key_pair.public_key().clone()];
+
+        let vault = JournalistVault::create(
+            &db_path,
+            "test_password",
+            &journalist_id,
+            &journalist_provisioning_pks,
+            now,
+            trust_anchors.clone(),
+        )
+        .await
+        .expect("Create journalist vault");
+
+        let mut known_tree_heads = vault.known_tree_heads().await.expect("Get tree heads");
+        assert!(known_tree_heads.latest().is_none());
+
+        known_tree_heads
+            .check_and_insert(&tree_head, &[])
+            .expect("Accept first tree head");
+        vault
+            .set_known_tree_heads(&known_tree_heads)
+            .await
+            .expect("Set tree heads");
+    }
+
+    let vault = JournalistVault::open(&db_path, "test_password", trust_anchors)
+        .await
+        .expect("Load journalist vault");
+
+    let mut known_tree_heads = vault.known_tree_heads().await.expect("Get tree heads");
+    assert_eq!(known_tree_heads.latest(), Some(&tree_head));
+
+    // A different tree of the same size is a fork of the log
+    let mut forked = leaves.clone();
+    forked[0] = leaf_hash(b"substituted key");
+
+    assert!(known_tree_heads
+        .check_and_insert(&TreeHead::new(4, root(&forked), now), &[])
+        .is_err());
+}
@@ -0,0 +1,81 @@
+use chrono::{Duration, Utc};
+use common::{
+    api::models::journalist_id::JournalistIdentity,
+    protocol::keys::{generate_journalist_provisioning_key_pair, generate_organization_key_pair},
+    system::{
+        keys::generate_admin_key_pair,
+        message_retention::{MessageRetentionLimit, SignedMessageRetentionLimit},
+    },
+};
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+
+#[tokio::test]
+async fn message_retention_limit_is_only_replaced_by_newer_limits() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+
+    let journalist_id = JournalistIdentity::new("Hello").unwrap();
+    let org_key_pair = generate_organization_key_pair(now);
+    let trust_anchors = vec![org_key_pair.public_key().clone().into_anchor()];
+
+    let journalist_provisioning_key_pair =
+        generate_journalist_provisioning_key_pair(&org_key_pair, now);
+    let admin_key_pair = generate_admin_key_pair(&org_key_pair, now);
+
+    let older = SignedMessageRetentionLimit::new(
+        MessageRetentionLimit::new(30).unwrap(),
+        &admin_key_pair,
+        now - Duration::days(1),
+    );
+    let newer = SignedMessageRetentionLimit::new(
+        MessageRetentionLimit::new(90).unwrap(),
+        &admin_key_pair,
+        now,
+    );
+
+    {
+        let journalist_provisioning_pks =
+            vec![journalist_provisioning_key_pair.public_key().clone()];
+
+        let vault = JournalistVault::create(
+            &db_path,
+            "test_password",
+            &journalist_id,
+            &journalist_provisioning_pks,
+            now,
+            trust_anchors.clone(),
+        )
+        .await
+        .expect("Create journalist vault");
+
+        // A response without a signed limit leaves the default in place
+        vault.update_message_retention_limit(None).await.unwrap();
+        assert_eq!(
+            vault.message_retention_limit().await.unwrap(),
+            MessageRetentionLimit::default()
+        );
+
+        vault
+            .update_message_retention_limit(Some(&newer))
+            .await
+            .unwrap();
+        assert_eq!(vault.message_retention_limit().await.unwrap(), newer.limit);
+    }
+
+    let vault = JournalistVault::open(&db_path, "test_password", trust_anchors)
+        .await
+        .expect("Load journalist vault");
+
+    // Neither an older limit nor a missing one replaces the limit the vault has accepted
+    for signed_limit in [Some(&older), None] {
+        vault
+            .update_message_retention_limit(signed_limit)
+            .await
+            .unwrap();
+        assert_eq!(vault.message_retention_limit().await.unwrap(), newer.limit);
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::Utc;
+use common::{
+    api::models::{
+        dead_drops::DeadDropId,
+        journalist_id::JournalistIdentity,
+        messages::{
+            user_to_journalist_message::UserToJournalistMessage,
+            user_to_journalist_message_with_dead_drop_id::UserToJournalistMessageWithDeadDropId,
+        },
+    },
+    crypto::keys::encryption::UnsignedEncryptionKeyPair,
+    protocol::{
+        keys::{generate_journalist_provisioning_key_pair, generate_organization_key_pair},
+        roles::User,
+    },
+    FixedSizeMessageText,
+};
+use journalist_vault::JournalistVault;
+use tempfile::tempdir_in;
+
+const PAGE_SIZE: usize = 2;
+
+/// Store the next page of dead drops after the vault's max dead drop ID, the same way the
+/// CoverDrop service does when pulling dead drops. Returns false when there are none left.
+async fn pull_next_page(
+    vault: &JournalistVault,
+    dead_drops: &[UserToJournalistMessageWithDeadDropId],
+) -> bool {
+    let ids_greater_than = vault.max_dead_drop_id().await.unwrap();
+
+    let page = dead_drops
+        .iter()
+        .filter(|message| message.dead_drop_id > ids_greater_than)
+        .take(PAGE_SIZE)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let Some(max_dead_drop_id) = page.iter().map(|message| message.dead_drop_id).max() else {
+        return false;
+    };
+
+    vault
+        .add_messages_from_user_to_journalist_and_update_max_dead_drop_id(
+            &page,
+            max_dead_drop_id,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+    true
+}
+
+#[tokio::test]
+async fn resuming_interrupted_pull_continues_from_last_committed_page() {
+    let temp_dir = tempdir_in(std::env::current_dir().unwrap()).unwrap();
+    let mut db_path = temp_dir.path().to_owned();
+    db_path.push("test.db");
+
+    let now = Utc::now();
+    let journalist_id = JournalistIdentity::new("Hello").unwrap();
+
+    let org_key_pair = generate_organization_key_pair(now);
+    let trust_anchors = vec![org_key_pair.public_key().clone().into_anchor()];
+
+    let journalist_provisioning_key_pair =
+        generate_journalist_provisioning_key_pair(&org_key_pair, now);
+
+    let user_key_pair = UnsignedEncryptionKeyPair::<User>::generate();
+
+    let dead_drops = (1..=5)
+        .map(
+            |dead_drop_id: DeadDropId| UserToJournalistMessageWithDeadDropId {
+                u2j_message: UserToJournalistMessage::new(
+                    FixedSizeMessageText::new(&format!("Message {dead_drop_id}")).unwrap(),
+                    user_key_pair.public_key().clone(),
+                ),
+                dead_drop_id,
+            },
+        )
+        .collect::<Vec<_>>();
+
+    {
+        let journalist_provisioning_pks =
+            vec![journalist_provisioning_key_pair.public_key().clone()];
+
+        let vault = JournalistVault::create(
+            &db_path,
+            "test_password",
+            &journalist_id,
+            &journalist_provisioning_pks,
+            now,
+            trust_anchors.clone(),
+        )
+        .await
+        .expect("Create journalist vault");
+
+        assert!(pull_next_page(&vault, &dead_drops).await);
+
+        // The second page is being processed when the vault is closed, without being committed
+        let mut tx = vault.pool.begin().await.unwrap();
+        sqlx::query("UPDATE vault_info SET max_dead_drop_id = 4")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+    }
+
+    let vault = JournalistVault::open(&db_path, "test_password", trust_anchors)
+        .await
+        .expect("Load journalist vault");
+
+    assert_eq!(vault.max_dead_drop_id().await.unwrap(), 2);
+
+    while pull_next_page(&vault, &dead_drops).await {}
+
+    assert_eq!(vault.max_dead_drop_id().await.unwrap(), 5);
+
+    let mut messages = vault
+        .messages()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| message.message().to_owned())
+        .collect::<Vec<_>>();
+    messages.sort();
+
+    // Every message is stored exactly once, none are skipped or duplicated by resuming
+    assert_eq!(
+        messages,
+        (1..=5)
+            .map(|dead_drop_id| format!("Message {dead_drop_id}"))
+            .collect::<Vec<_>>()
+    );
+}
@@ -71,7 +71,7 @@ async fn main() -> anyhow::Result<()> {
     let keys_and_profiles = api_client
         .get_public_keys()
         .await?
-        .into_trusted(&anchor_org_pks, time::now());
+        .into_trusted(&anchor_org_pks, time::now())?;
 
     let keys = keys_and_profiles.keys;
 
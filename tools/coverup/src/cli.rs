@@ -373,6 +373,34 @@ pub enum JournalistVaultCommand {
         #[clap(long)]
         stage: Stage,
     },
+    /// Generate the key pair that Sentinel encrypts exported logs to
+    GenerateSupportKeyPair {
+        #[clap(long)]
+        output_directory: PathBuf,
+    },
+    /// Decrypt and print logs exported from Sentinel for a support ticket
+    ReadLogExport {
+        #[clap(long)]
+        export_path: PathBuf,
+        /// The support key pair the logs were encrypted to
+        #[clap(long)]
+        key_pair_path: PathBuf,
+    },
+    /// Check a journalist vault for damage such as keys which don't verify against their
+    /// parents and orphaned queue entries. Prints a JSON report.
+    CheckIntegrity {
+        #[clap(long)]
+        vault_path: PathBuf,
+        #[clap(long)]
+        password: Option<String>,
+        #[clap(long, conflicts_with = "password")]
+        password_path: Option<PathBuf>,
+        #[clap(long)]
+        stage: Stage,
+        /// Fix the problems which can be fixed safely
+        #[clap(long)]
+        repair: bool,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -7,8 +7,10 @@ pub mod identity_api_commands;
 mod list_files;
 pub mod production_commands;
 pub mod staging_commands;
+mod support_logs;
 
 pub use back_up::back_up;
 pub use copy_file::copy_file;
 pub use data_copier_shell::data_copier_shell;
 pub use list_files::list_files;
+pub use support_logs::{generate_support_key_pair, read_log_export};
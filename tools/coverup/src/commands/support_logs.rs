@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use anyhow::Context;
+use common::{
+    crypto::keys::{
+        encryption::UnsignedEncryptionKeyPair, public_key::PublicKey as _,
+        serde::StorableKeyMaterial, untrusted::encryption::UntrustedUnsignedEncryptionKeyPair,
+    },
+    protocol::{log_export::EncryptedLogExport, roles::SupportRecipient},
+};
+
+/// Generate the key pair Sentinel encrypts exported logs to. The public key should be added to
+/// the `supportPk` field of each Sentinel profile.
+pub fn generate_support_key_pair(output_directory: impl AsRef<Path>) -> anyhow::Result<()> {
+    let key_pair = UnsignedEncryptionKeyPair::<SupportRecipient>::generate();
+
+    let key_pair_path = key_pair.to_untrusted().save_to_disk(&output_directory)?;
+
+    println!("Key pair: {}", key_pair_path.display());
+    println!("Public key: {}", key_pair.public_key().public_key_hex());
+
+    Ok(())
+}
+
+/// Decrypt logs exported from Sentinel and print them
+pub fn read_log_export(
+    export_path: impl AsRef<Path>,
+    key_pair_path: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let key_pair =
+        UntrustedUnsignedEncryptionKeyPair::<SupportRecipient>::load_from_file(key_pair_path)?
+            .to_trusted();
+
+    let bytes = std::fs::read(export_path).context("Failed to read log export")?;
+    let logs = EncryptedLogExport::from_vec_unchecked(bytes).decrypt(&key_pair)?;
+
+    println!("Journalist: {}", logs.journalist_identity);
+    println!("From: {}", logs.from);
+    println!("To: {}", logs.to);
+    println!("Exported at: {}", logs.exported_at);
+    if !logs.scrubbed {
+        println!("Contains user public keys and message contents");
+    }
+    println!();
+
+    for entry in &logs.entries {
+        println!(
+            "[{}] #{} {} {}: {}",
+            entry.timestamp, entry.session_id, entry.level, entry.target, entry.message
+        );
+    }
+
+    Ok(())
+}
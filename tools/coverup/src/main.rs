@@ -15,7 +15,8 @@ use commands::development_commands::{copy_all_images_to_multipass, copy_image_to
 use commands::staging_commands::minio_tunnel;
 use commands::{
     back_up, copy_file, covernode_commands, data_copier_shell, development_commands,
-    identity_api_commands, list_files, production_commands, staging_commands,
+    generate_support_key_pair, identity_api_commands, list_files, production_commands,
+    read_log_export, staging_commands,
 };
 use common::argon2_sqlcipher::Argon2SqlCipher;
 use common::clap::{validate_password_from_args, Stage};
@@ -480,6 +481,34 @@ async fn main() -> anyhow::Result<()> {
 
                 println!("OK");
             }
+            JournalistVaultCommand::GenerateSupportKeyPair { output_directory } => {
+                generate_support_key_pair(output_directory)?;
+            }
+            JournalistVaultCommand::ReadLogExport {
+                export_path,
+                key_pair_path,
+            } => {
+                read_log_export(export_path, key_pair_path)?;
+            }
+            JournalistVaultCommand::CheckIntegrity {
+                vault_path,
+                password,
+                password_path,
+                stage,
+                repair,
+            } => {
+                let password = validate_password_from_args(password, password_path)?;
+                let trust_anchors = get_trust_anchors(&stage, time::now())?;
+                let vault = JournalistVault::open(&vault_path, &password, trust_anchors).await?;
+
+                let report = vault.check_integrity(time::now(), repair).await?;
+
+                println!("{}", serde_json::to_string_pretty(&report)?);
+
+                if !report.is_ok() && !report.repaired {
+                    anyhow::bail!("Vault integrity check found problems");
+                }
+            }
             // TODO: delete https://github.com/guardian/coverdrop-internal/issues/3100
             JournalistVaultCommand::MigrateHexArgon2Database {
                 vault_path,
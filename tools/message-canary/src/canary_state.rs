@@ -138,7 +138,7 @@ impl CanaryState {
     ) -> anyhow::Result<VerifiedKeysAndJournalistProfiles> {
         let keys_and_profiles = self.api_client.get_public_keys().await?;
 
-        Ok(keys_and_profiles.into_trusted(&self.trust_anchors, now))
+        Ok(keys_and_profiles.into_trusted(&self.trust_anchors, now)?)
     }
 
     pub async fn get_users(&self) -> &[User] {
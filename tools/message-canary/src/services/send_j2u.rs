@@ -65,7 +65,7 @@ pub async fn send_j2u(canary_state: CanaryState, mph_j2u: u32) -> anyhow::Result
         );
 
         coverdrop_service
-            .enqueue_j2u_message(&keys_and_profiles, user_pk, j2u_message.as_str(), now)
+            .enqueue_j2u_message(&keys_and_profiles, user_pk, j2u_message.as_str(), None, now)
             .await?;
         coverdrop_service
             .dequeue_and_send_j2u_message(&keys_and_profiles.keys, now)